@@ -0,0 +1,206 @@
+use std::{collections::{HashMap, VecDeque}, hash::Hash};
+
+/// A set of abstract values ordered by precision, from [bottom](Lattice::bottom) (no information yet)
+/// to the least precise value
+pub trait Lattice : Clone+PartialEq{
+    /// The value of a point the analysis hasn't reached
+    fn bottom() -> Self;
+
+    /// The least precise value covering both *self* and *other*, used where control flow merges
+    fn join(&self, other:&Self) -> Self;
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+/// The sign of a number
+pub enum Sign{
+    Bottom,
+    Negative,
+    Zero,
+    Positive,
+
+    /// Any sign
+    Top
+}
+
+impl Sign{
+    /// The sign of a known number
+    pub fn of(value:i64) -> Self{
+        match value.signum() {
+            -1 => Sign::Negative,
+            0 => Sign::Zero,
+            _ => Sign::Positive
+        }
+    }
+}
+
+impl Lattice for Sign{
+    fn bottom() -> Self{ Sign::Bottom }
+
+    fn join(&self, other:&Self) -> Self{
+        match (self, other) {
+            (Sign::Bottom, s) | (s, Sign::Bottom) => *s,
+            (a, b) if a == b => *a,
+            _ => Sign::Top
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+/// Whether a value is known at compile time
+pub enum Constant<V>{
+    Bottom,
+
+    /// Always this value
+    Value(V),
+
+    /// Several values are possible
+    Top
+}
+
+impl<V:Clone+PartialEq> Lattice for Constant<V>{
+    fn bottom() -> Self{ Constant::Bottom }
+
+    fn join(&self, other:&Self) -> Self{
+        match (self, other) {
+            (Constant::Bottom, c) | (c, Constant::Bottom) => c.clone(),
+            (a, b) if a == b => a.clone(),
+            _ => Constant::Top
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+/// Whether a value may be null, or a variable may be uninitialized
+pub enum Nullability{
+    Bottom,
+
+    /// Always null, or never initialized
+    Null,
+
+    /// Never null, or always initialized
+    NonNull,
+
+    /// Null on some paths only
+    MaybeNull
+}
+
+impl Lattice for Nullability{
+    fn bottom() -> Self{ Nullability::Bottom }
+
+    fn join(&self, other:&Self) -> Self{
+        match (self, other) {
+            (Nullability::Bottom, n) | (n, Nullability::Bottom) => *n,
+            (a, b) if a == b => *a,
+            _ => Nullability::MaybeNull
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+/// Abstract values of variables, missing variables are at [bottom](Lattice::bottom)
+pub struct Env<K:Eq+Hash+Clone, V:Lattice>{
+    values: HashMap<K, V>
+}
+
+impl<K:Eq+Hash+Clone, V:Lattice> Env<K, V>{
+    pub fn new() -> Self{ Env { values: HashMap::new() } }
+
+    /// Returns the value of *variable*
+    pub fn get(&self, variable:&K) -> V{
+        self.values.get(variable).cloned().unwrap_or_else(V::bottom)
+    }
+
+    /// Sets the value of *variable*
+    pub fn set(&mut self, variable:K, value:V){
+        if value == V::bottom() { self.values.remove(&variable); }
+        else { self.values.insert(variable, value); }
+    }
+
+    /// Sets the value of *variable*, builder style
+    pub fn with(mut self, variable:K, value:V) -> Self{
+        self.set(variable, value);
+        self
+    }
+}
+
+impl<K:Eq+Hash+Clone, V:Lattice> Default for Env<K, V>{
+    fn default() -> Self { Self::new() }
+}
+
+impl<K:Eq+Hash+Clone, V:Lattice> Lattice for Env<K, V>{
+    fn bottom() -> Self{ Env::new() }
+
+    fn join(&self, other:&Self) -> Self{
+        let mut joined = self.clone();
+
+        for (variable, value) in &other.values{
+            joined.set(variable.clone(), self.get(variable).join(value));
+        }
+
+        joined
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+/// A control flow graph, each node usually being a statement or a basic block
+pub struct Cfg<N>{
+    pub nodes: Vec<N>,
+
+    /// Edges between nodes, as (from, to) indices
+    pub edges: Vec<(usize, usize)>,
+
+    /// Index of the node execution starts at
+    pub entry: usize
+}
+
+impl<N> Cfg<N>{
+    pub fn new(nodes:Vec<N>, entry:usize) -> Self{ Cfg { nodes, edges: vec![], entry } }
+
+    /// Adds an edge from node *from* to node *to*, builder style
+    pub fn with_edge(mut self, from:usize, to:usize) -> Self{
+        self.edges.push((from, to));
+        self
+    }
+
+    /// Iterates over the nodes execution may continue to after node *node*
+    pub fn successors(&self, node:usize) -> impl Iterator<Item = usize> + '_{
+        self.edges.iter().filter(move |(from, _)| *from == node).map(|(_, to)| *to)
+    }
+
+    /// Computes the abstract state at the start of every node, forward from the entry
+    /// 
+    /// The states are joined where control flow merges and propagated until nothing changes,
+    /// which terminates as long as the [lattice](Lattice) has no infinite ascending chain
+    /// 
+    /// initial: The state at the entry
+    /// 
+    /// transfer: The state after a node, given the state before it
+    pub fn analyze<S:Lattice>(&self, initial:S, transfer:impl Fn(&N, &S) -> S) -> Vec<S>{
+        let mut states = vec![S::bottom(); self.nodes.len()];
+        let mut reached = vec![false; self.nodes.len()];
+        let mut worklist = VecDeque::new();
+
+        if self.entry < self.nodes.len(){
+            states[self.entry] = initial;
+            reached[self.entry] = true;
+            worklist.push_back(self.entry);
+        }
+
+        while let Some(node) = worklist.pop_front(){
+            let after = transfer(&self.nodes[node], &states[node]);
+
+            for next in self.successors(node){
+                let joined = states[next].join(&after);
+
+                // A node is visited at least once even if its state stays at bottom
+                if joined != states[next] || !reached[next]{
+                    states[next] = joined;
+                    reached[next] = true;
+                    if !worklist.contains(&next) { worklist.push_back(next); }
+                }
+            }
+        }
+
+        states
+    }
+}
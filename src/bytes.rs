@@ -0,0 +1,140 @@
+use std::{fmt::Display, error::Error};
+
+use crate::{lexer::{Location, Token, TokenKind}, regex::Regex, Reportable};
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+/// A token extracted from bytes by a [ByteLexer]
+pub struct ByteToken<Kind:TokenKind>{
+    /// Offset of the first byte of the token
+    pub offset: usize,
+
+    pub kind: Kind,
+
+    /// The bytes matched for this token
+    pub bytes: Vec<u8>
+}
+
+impl<Kind:TokenKind> ByteToken<Kind>{
+    /// Converts this token into a [Token] so it can be [parsed](crate::parser::Parser)
+    /// 
+    /// The offset becomes the column of the location, the bytes are decoded as UTF-8 with replacement characters
+    pub fn to_token(&self, file:&str) -> Token<Kind>{
+        Token::new(
+            Location { file: file.to_string(), line: 0, column: self.offset },
+            self.kind, String::from_utf8_lossy(&self.bytes).into_owned()
+        )
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+/// Error type of the [ByteLexer], a byte no node matches
+pub struct ByteLexingError{
+    /// Offset of the byte
+    pub offset: usize,
+
+    pub byte: u8
+}
+
+impl Reportable for ByteLexingError{
+    fn message(&self) -> String{ format!("Failed to parse token at byte {} ({:#04x})", self.offset, self.byte) }
+
+    fn location(&self) -> Option<Location>{ None }
+}
+
+impl Display for ByteLexingError{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.report())
+    }
+}
+
+impl Error for ByteLexingError{}
+
+/// The equivalent of a [Lexer](crate::lexer::Lexer) over bytes, for DSLs embedded in binary formats or ASCII protocols
+/// 
+/// # Exemples
+/// ```rust
+/// use crate::neoglot_lib::{bytes::*, lexer::TokenKind, regex::*};
+/// 
+/// #[derive(PartialEq, PartialOrd, Eq, Copy, Clone, Debug, Hash)]
+/// enum TokenType{ Command, Length }
+/// 
+/// impl Symbol for TokenType{}
+/// impl TokenKind for TokenType{}
+/// 
+/// let lexer = ByteLexer::new()
+///     .with(Regex::new().then(RegexElement::Set(b'A', b'Z', Quantifier::OneOrMany)), TokenType::Command)
+///     .with(Regex::new().then(RegexElement::Item(0xff, Quantifier::Exactly(1))).then(RegexElement::Set(0x00, 0xff, Quantifier::Exactly(1))), TokenType::Length)
+///     .with_skipped(b" \r\n");
+/// 
+/// let tokens = lexer.tokenize(b"GET \xff\x10\r\n").unwrap();
+/// 
+/// assert_eq!(tokens, vec![
+///     ByteToken{ offset: 0, kind: TokenType::Command, bytes: b"GET".to_vec() },
+///     ByteToken{ offset: 4, kind: TokenType::Length, bytes: vec![0xff, 0x10] }
+/// ]);
+/// ```
+#[derive(Debug, Clone)]
+pub struct ByteLexer<Kind:TokenKind>{
+    /// The matching sequences and the kind of tokens they make
+    nodes: Vec<(Regex<u8>, Kind)>,
+
+    /// Bytes skipped between tokens
+    skipped: Vec<u8>
+}
+
+impl<Kind:TokenKind> Default for ByteLexer<Kind>{
+    fn default() -> Self { Self::new() }
+}
+
+impl<Kind:TokenKind> ByteLexer<Kind>{
+    pub fn new() -> Self{ ByteLexer { nodes: vec![], skipped: vec![] } }
+
+    /// Adds a matching sequence for a kind of tokens
+    pub fn register(&mut self, regex:Regex<u8>, kind:Kind){
+        self.nodes.push((regex, kind));
+    }
+
+    /// Adds a matching sequence for a kind of tokens, builder style
+    pub fn with(mut self, regex:Regex<u8>, kind:Kind) -> Self{
+        self.register(regex, kind);
+        self
+    }
+
+    /// Sets the bytes silently skipped between tokens, builder style
+    /// 
+    /// By default every byte must be part of a token
+    pub fn with_skipped(mut self, bytes:&[u8]) -> Self{
+        self.skipped = bytes.to_vec();
+        self
+    }
+
+    /// Extracts the [tokens](ByteToken) from bytes
+    /// 
+    /// At each offset the first registered sequence that matches makes the token
+    pub fn tokenize(&self, content:&[u8]) -> Result<Vec<ByteToken<Kind>>, Vec<ByteLexingError>>{
+        let mut tokens = vec![];
+        let mut errors = vec![];
+        let mut offset = 0;
+
+        while offset < content.len(){
+            let rest = &content[offset..];
+            let token = self.nodes.iter().find_map(|(regex, kind)| {
+                let (matched, _) = regex.split_first(rest);
+                (!matched.is_empty()).then(|| ByteToken { offset, kind: *kind, bytes: matched.to_vec() })
+            });
+
+            match token {
+                Some(token) => {
+                    offset += token.bytes.len();
+                    tokens.push(token);
+                },
+                None => {
+                    if !self.skipped.contains(&rest[0]) { errors.push(ByteLexingError { offset, byte: rest[0] }); }
+                    offset += 1;
+                }
+            }
+        }
+
+        if errors.is_empty() { Ok(tokens) } else { Err(errors) }
+    }
+}
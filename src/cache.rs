@@ -0,0 +1,380 @@
+use std::{collections::HashMap, fs, hash::Hasher, io, path::PathBuf};
+
+use crate::{lexer::{Lexer, LexingError, LexingResult, Location, Origin, Token, TokenKind}, parser::AST};
+
+/// Identifies the binary token format
+const MAGIC:&[u8; 4] = b"NGTK";
+
+/// Identifies the binary AST format
+const AST_MAGIC:&[u8; 4] = b"NGAS";
+
+/// Version of the binary token and AST formats
+const VERSION:u8 = 3;
+
+/// The token origins, stored as their position
+const ORIGINS:[Origin; 4] = [Origin::Written, Origin::Recovered, Origin::Expanded, Origin::Layout];
+
+#[derive(Debug, Clone, Copy)]
+/// A 64 bits FNV-1a [Hasher], whose output is stable across runs and compiler versions
+/// unlike [DefaultHasher](std::collections::hash_map::DefaultHasher)
+pub struct StableHasher{
+    hash: u64
+}
+
+impl StableHasher{
+    pub fn new() -> Self{ StableHasher { hash: 0xcbf29ce484222325 } }
+}
+
+impl Default for StableHasher{
+    fn default() -> Self { Self::new() }
+}
+
+impl Hasher for StableHasher{
+    fn finish(&self) -> u64{ self.hash }
+
+    fn write(&mut self, bytes:&[u8]){
+        for byte in bytes{
+            self.hash = (self.hash ^ *byte as u64).wrapping_mul(0x100000001b3);
+        }
+    }
+}
+
+/// Hashes the content of a file to key its cached tokens
+/// 
+/// This is a 64 bits FNV-1a hash, stable across runs and platforms
+pub fn content_hash(content:&str) -> u64{
+    let mut hasher = StableHasher::new();
+    hasher.write(content.as_bytes());
+    hasher.finish()
+}
+
+/// Serializes [tokens](Token) into a compact binary form
+/// 
+/// Literals are stored once each, and the file of the first token is used for all of them.
+/// The [origin](Origin) and the [normalized form](Token::normalized) of each token are kept
+/// 
+/// Kinds are stored as their position in *kinds*, returns None if a kind is missing from it
+/// 
+/// key: Usually the [content_hash] of the file the tokens come from
+pub fn encode_tokens<T:TokenKind>(tokens:&[Token<T>], kinds:&[T], key:u64) -> Option<Vec<u8>>{
+    let mut literals:Vec<&str> = vec![];
+    let mut literal_ids:HashMap<&str, u32> = HashMap::new();
+    let mut body = vec![];
+
+    for token in tokens{
+        let kind = kinds.iter().position(|k| *k == token.kind)? as u32;
+        let mut literal_id = |literal| *literal_ids.entry(literal).or_insert_with(|| {
+            literals.push(literal);
+            literals.len() as u32 - 1
+        });
+
+        // Tokens that weren't normalized are marked with the largest id
+        let literal = literal_id(&token.literal);
+        let normalized = token.normalized.as_deref().map_or(u32::MAX, literal_id);
+
+        for value in [kind, literal, normalized, token.location.line as u32, token.location.column as u32]{
+            body.extend_from_slice(&value.to_le_bytes());
+        }
+
+        body.push(ORIGINS.iter().position(|origin| *origin == token.origin)? as u8);
+    }
+
+    let mut bytes = MAGIC.to_vec();
+    bytes.push(VERSION);
+    bytes.extend_from_slice(&key.to_le_bytes());
+
+    let file = tokens.first().map_or("", |t| t.location.file.as_str());
+    write_str(&mut bytes, file);
+
+    bytes.extend_from_slice(&(literals.len() as u32).to_le_bytes());
+    for literal in literals{ write_str(&mut bytes, literal); }
+
+    bytes.extend_from_slice(&(tokens.len() as u32).to_le_bytes());
+    bytes.extend(body);
+
+    Some(bytes)
+}
+
+/// Deserializes [tokens](Token) written by [encode_tokens]
+/// 
+/// Returns None if the data is corrupted, written with another key, or refers to a kind out of *kinds*
+pub fn decode_tokens<T:TokenKind>(bytes:&[u8], kinds:&[T], key:u64) -> Option<Vec<Token<T>>>{
+    let mut reader = Reader { bytes };
+
+    if reader.take(4)? != MAGIC || reader.take(1)?[0] != VERSION { return None; }
+    if u64::from_le_bytes(reader.take(8)?.try_into().ok()?) != key { return None; }
+
+    let file = reader.string()?;
+
+    let literals = (0..reader.u32()?).map(|_| reader.string()).collect::<Option<Vec<String>>>()?;
+
+    let count = reader.u32()?;
+    // A token takes 21 bytes, a corrupted count must not reserve more than the data can hold
+    let mut tokens = Vec::with_capacity((count as usize).min(reader.bytes.len() / 21));
+
+    for _ in 0..count{
+        let kind = *kinds.get(reader.u32()? as usize)?;
+        let literal = literals.get(reader.u32()? as usize)?.clone();
+        let normalized = match reader.u32()? {
+            u32::MAX => None,
+            id => Some(literals.get(id as usize)?.clone())
+        };
+        let line = reader.u32()? as usize;
+        let column = reader.u32()? as usize;
+        let origin = *ORIGINS.get(reader.take(1)?[0] as usize)?;
+
+        tokens.push(Token { location: Location { file: file.clone(), line, column }, kind, literal, origin, normalized });
+    }
+
+    if !reader.bytes.is_empty(){ return None; }
+
+    Some(tokens)
+}
+
+/// Serializes a forest of [ASTs](AST) into a compact binary form, each node as its kind and number of children in preorder
+/// 
+/// Kinds are stored as their position in *kinds*, returns None if a kind is missing from it
+/// 
+/// key: Usually the [content_hash] of the file the forest is parsed from
+pub fn encode_forest<T:TokenKind>(forest:&[AST<T>], kinds:&[T], key:u64) -> Option<Vec<u8>>{
+    let mut bytes = AST_MAGIC.to_vec();
+    bytes.push(VERSION);
+    bytes.extend_from_slice(&key.to_le_bytes());
+    bytes.extend_from_slice(&(forest.len() as u32).to_le_bytes());
+
+    for ast in forest.iter().flat_map(AST::iter){
+        let kind = kinds.iter().position(|k| *k == ast.kind)? as u32;
+
+        for value in [kind, ast.children.len() as u32]{
+            bytes.extend_from_slice(&value.to_le_bytes());
+        }
+    }
+
+    Some(bytes)
+}
+
+/// Deserializes a forest of [ASTs](AST) written by [encode_forest], without recursing so that deep trees fit in the stack
+/// 
+/// Returns None if the data is corrupted, written with another key, or refers to a kind out of *kinds*
+pub fn decode_forest<T:TokenKind>(bytes:&[u8], kinds:&[T], key:u64) -> Option<Vec<AST<T>>>{
+    let mut reader = Reader { bytes };
+
+    if reader.take(4)? != AST_MAGIC || reader.take(1)?[0] != VERSION { return None; }
+    if u64::from_le_bytes(reader.take(8)?.try_into().ok()?) != key { return None; }
+
+    let count = reader.u32()? as usize;
+    let mut forest = vec![];
+
+    // The nodes still missing children, with the number they miss
+    let mut open:Vec<(AST<T>, u32)> = vec![];
+
+    while forest.len() < count{
+        let kind = *kinds.get(reader.u32()? as usize)?;
+        open.push((AST { kind, children: vec![] }, reader.u32()?));
+
+        while let Some((_, 0)) = open.last(){
+            let (ast, _) = open.pop()?;
+
+            match open.last_mut() {
+                Some((parent, missing)) => {
+                    parent.children.push(ast);
+                    *missing -= 1;
+                },
+                None => forest.push(ast)
+            }
+        }
+    }
+
+    if !reader.bytes.is_empty(){ return None; }
+
+    Some(forest)
+}
+
+// Writes a string prefixed by its length
+fn write_str(bytes:&mut Vec<u8>, text:&str){
+    bytes.extend_from_slice(&(text.len() as u32).to_le_bytes());
+    bytes.extend_from_slice(text.as_bytes());
+}
+
+// Reads the binary token format
+struct Reader<'a>{
+    bytes: &'a [u8]
+}
+
+impl Reader<'_>{
+    fn take(&mut self, n:usize) -> Option<&[u8]>{
+        if self.bytes.len() < n { return None; }
+
+        let (taken, rest) = self.bytes.split_at(n);
+        self.bytes = rest;
+        Some(taken)
+    }
+
+    fn u32(&mut self) -> Option<u32>{
+        Some(u32::from_le_bytes(self.take(4)?.try_into().ok()?))
+    }
+
+    fn string(&mut self) -> Option<String>{
+        let len = self.u32()? as usize;
+        String::from_utf8(self.take(len)?.to_vec()).ok()
+    }
+}
+
+#[derive(Debug)]
+/// Why a file couldn't be [parsed](TokenCache::parse_file)
+pub enum ParseFailure<E>{
+    Lexing(Vec<LexingError>),
+
+    /// The error returned by the parsing
+    Parsing(E)
+}
+
+/// The tokens and the forest of a file, see [TokenCache::parse_file]
+pub type ParsedFile<T, E> = Result<(Vec<Token<T>>, Vec<AST<T>>), ParseFailure<E>>;
+
+#[derive(Debug, Clone)]
+/// A directory of cached [tokens](Token) and [ASTs](AST), keyed by the hash of the files they come from
+/// 
+/// An entry is only read back for the exact content it was written for, so editing a file invalidates it.
+/// Changing the lexer or the parser doesn't, bump the [version](TokenCache::with_version) of the cache instead
+pub struct TokenCache{
+    /// Where the cache files are
+    dir: PathBuf,
+
+    /// Mixed into the keys, so that entries written by another version are never read
+    version: u64
+}
+
+impl TokenCache{
+    pub fn new(dir:impl Into<PathBuf>) -> Self{ TokenCache { dir: dir.into(), version: 0 } }
+
+    /// Sets the version of the language the entries are written for, builder style
+    /// 
+    /// The entries written for another version are ignored
+    pub fn with_version(mut self, version:u64) -> Self{
+        self.version = version;
+        self
+    }
+
+    // The key of a file content
+    fn key(&self, content:&str) -> u64{
+        let mut hasher = StableHasher::new();
+        hasher.write(&self.version.to_le_bytes());
+        hasher.write(content.as_bytes());
+        hasher.finish()
+    }
+
+    // The cache file of a file content
+    fn entry(&self, key:u64, extension:&str) -> PathBuf{ self.dir.join(format!("{key:016x}.{extension}")) }
+
+    /// Returns the cached tokens of *content*, or None if they are not cached
+    pub fn load<T:TokenKind>(&self, content:&str, kinds:&[T]) -> Option<Vec<Token<T>>>{
+        let key = self.key(content);
+        decode_tokens(&fs::read(self.entry(key, "tokens")).ok()?, kinds, key)
+    }
+
+    /// Caches the tokens of *content*
+    pub fn store<T:TokenKind>(&self, content:&str, tokens:&[Token<T>], kinds:&[T]) -> io::Result<()>{
+        let key = self.key(content);
+        let bytes = encode_tokens(tokens, kinds, key)
+            .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "token kind missing from the kinds table"))?;
+
+        fs::create_dir_all(&self.dir)?;
+        fs::write(self.entry(key, "tokens"), bytes)
+    }
+
+    /// Returns the cached forest of *content*, or None if it is not cached
+    pub fn load_forest<T:TokenKind>(&self, content:&str, kinds:&[T]) -> Option<Vec<AST<T>>>{
+        let key = self.key(content);
+        decode_forest(&fs::read(self.entry(key, "ast")).ok()?, kinds, key)
+    }
+
+    /// Caches the forest parsed from *content*
+    pub fn store_forest<T:TokenKind>(&self, content:&str, forest:&[AST<T>], kinds:&[T]) -> io::Result<()>{
+        let key = self.key(content);
+        let bytes = encode_forest(forest, kinds, key)
+            .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "AST kind missing from the kinds table"))?;
+
+        fs::create_dir_all(&self.dir)?;
+        fs::write(self.entry(key, "ast"), bytes)
+    }
+
+    /// Extracts the [tokens](Token) from a file, skipping the lexing if the file is unchanged since it was cached
+    /// 
+    /// Successful lexing results are cached, failing to write the cache is not an error
+    pub fn tokenize_file<T:TokenKind>(&self, lexer:&Lexer<T>, path:&str, kinds:&[T]) -> LexingResult<T>{
+        let Ok(content) = fs::read_to_string(path) else { return lexer.tokenize_file(path); };
+        self.tokenize_content(lexer, content, path, kinds)
+    }
+
+    // Extracts the tokens from the content of a file, see tokenize_file
+    fn tokenize_content<T:TokenKind>(&self, lexer:&Lexer<T>, content:String, path:&str, kinds:&[T]) -> LexingResult<T>{
+        if let Some(tokens) = self.load(&content, kinds).filter(|t| t.first().is_none_or(|t| t.location.file == path)){
+            return LexingResult::Ok(tokens);
+        }
+
+        let result = lexer.tokenize_content(content.clone(), path);
+        if let LexingResult::Ok(tokens) = &result{
+            let _ = self.store(&content, tokens, kinds);
+        }
+
+        result
+    }
+
+    /// Extracts the [tokens](Token) from a file and *parse*s them into a forest,
+    /// skipping both if the file is unchanged since they were cached
+    /// 
+    /// Like the tokens, successful parsing results are cached and failing to write the cache is not an error
+    /// 
+    /// # Exemples
+    /// ```rust
+    /// use crate::neoglot_lib::{cache::TokenCache, lexer::*, parser::AST, regex::*};
+    /// 
+    /// #[derive(PartialEq, PartialOrd, Eq, Copy, Clone, Debug, Hash)]
+    /// enum TokenType{ Number }
+    /// 
+    /// impl Symbol for TokenType{}
+    /// impl TokenKind for TokenType{}
+    /// 
+    /// let lexer = Lexer::new().with(LexerNode::new(Regex::new().then(RegexElement::Set('0', '9', Quantifier::OneOrMany)), TokenType::Number));
+    /// let dir = std::env::temp_dir().join(format!("neoglot-parse-{}", std::process::id()));
+    /// let cache = TokenCache::new(&dir);
+    /// 
+    /// let path = dir.join("numbers.ng");
+    /// std::fs::create_dir_all(&dir).unwrap();
+    /// std::fs::write(&path, "1 2 3").unwrap();
+    /// 
+    /// let parse = |tokens:&[Token<TokenType>]| Ok::<_, ()>(tokens.iter().map(|t| AST { kind: t.kind, children: vec![] }).collect());
+    /// let (tokens, forest) = cache.parse_file(&lexer, path.to_str().unwrap(), &[TokenType::Number], parse).unwrap();
+    /// assert_eq!((tokens.len(), forest.len()), (3, 3));
+    /// 
+    /// // Unchanged, so the parsing is skipped
+    /// let cached = cache.parse_file(&lexer, path.to_str().unwrap(), &[TokenType::Number], |_| Err(()));
+    /// assert_eq!(cached.unwrap().1, forest);
+    /// 
+    /// std::fs::remove_dir_all(dir).unwrap();
+    /// ```
+    pub fn parse_file<T:TokenKind, E>(&self, lexer:&Lexer<T>, path:&str, kinds:&[T], parse:impl FnOnce(&[Token<T>]) -> Result<Vec<AST<T>>, E>) -> ParsedFile<T, E>{
+        let content = fs::read_to_string(path);
+        let result = match &content {
+            Ok(content) => self.tokenize_content(lexer, content.clone(), path, kinds),
+            Err(_) => lexer.tokenize_file(path)
+        };
+
+        let tokens = match result {
+            LexingResult::Ok(tokens) => tokens,
+            LexingResult::Err(errors) => return Err(ParseFailure::Lexing(errors))
+        };
+
+        if let Some(forest) = content.as_ref().ok().and_then(|content| self.load_forest(content, kinds)){
+            return Ok((tokens, forest));
+        }
+
+        let forest = parse(&tokens).map_err(ParseFailure::Parsing)?;
+        if let Ok(content) = &content{
+            let _ = self.store_forest(content, &forest, kinds);
+        }
+
+        Ok((tokens, forest))
+    }
+}
@@ -0,0 +1,133 @@
+use std::collections::HashMap;
+
+use crate::{lexer::{Lexer, LexingError, LexingResult, Location, Token, TokenKind, TokenLike}, source::Source};
+
+#[derive(Debug, Clone, Default)]
+/// The [sources](Source) compact tokens point into, each known by a small id
+/// 
+/// # Exemples
+/// ```rust
+/// use crate::neoglot_lib::{compact::*, lexer::*, regex::*, source::Source};
+/// 
+/// #[derive(PartialEq, PartialOrd, Eq, Copy, Clone, Debug, Hash)]
+/// enum TokenType{ Number }
+/// 
+/// impl Symbol for TokenType{}
+/// impl TokenKind for TokenType{}
+/// 
+/// let lexer = Lexer::new().with(LexerNode::new(Regex::new().then(RegexElement::Set('0', '9', Quantifier::OneOrMany)), TokenType::Number));
+/// 
+/// let mut files = Files::new();
+/// let id = files.add(Source::new("main", "12\n 345".to_string()));
+/// 
+/// let tokens = files.tokenize(&lexer, id).unwrap();
+/// 
+/// assert_eq!(tokens[1], CompactToken{ file: id, offset: 4, length: 3, kind: TokenType::Number });
+/// assert_eq!(tokens[1].literal(&files), Some("345"));
+/// assert_eq!(tokens[1].location(&files), Some(Location{ file: "main".to_string(), line: 1, column: 1 }));
+/// ```
+pub struct Files{
+    sources: Vec<Source>,
+
+    /// The id of each path
+    ids: HashMap<String, u32>
+}
+
+impl Files{
+    pub fn new() -> Self{ Files { sources: vec![], ids: HashMap::new() } }
+
+    /// Adds a source and returns its id
+    /// 
+    /// A source with the same path as a known one replaces it and takes its id
+    pub fn add(&mut self, source:Source) -> u32{
+        if let Some(id) = self.ids.get(source.path()){
+            self.sources[*id as usize] = source;
+            return *id;
+        }
+
+        let id = self.sources.len() as u32;
+        self.ids.insert(source.path().to_string(), id);
+        self.sources.push(source);
+        id
+    }
+
+    /// The id of the source at *path*, or None
+    pub fn id(&self, path:&str) -> Option<u32>{ self.ids.get(path).copied() }
+
+    /// The source with the id *id*, or None
+    pub fn get(&self, id:u32) -> Option<&Source>{ self.sources.get(id as usize) }
+
+    /// Converts a [Token] to a [CompactToken]
+    /// 
+    /// Returns None if its file is unknown or its literal isn't the text at its location,
+    /// as for [normalized](Lexer::with_nfc) or [generated](Location::generated) tokens
+    pub fn compact<K:TokenKind>(&self, token:&Token<K>) -> Option<CompactToken<K>>{
+        let file = self.id(&token.location.file)?;
+        let offset = self.get(file)?.location_to_offset(&token.location)?;
+        let length = token.literal.len();
+
+        if self.get(file)?.content().get(offset..offset + length)? != token.literal { return None; }
+
+        Some(CompactToken { file, offset: u32::try_from(offset).ok()?, length: u32::try_from(length).ok()?, kind: token.kind })
+    }
+
+    /// Tokenizes the source with the id *file* into [CompactTokens](CompactToken)
+    /// 
+    /// The tokens are converted as they are lexed, see [compact](Files::compact),
+    /// the ones that can't be are reported as errors
+    pub fn tokenize<K:TokenKind>(&self, lexer:&Lexer<K>, file:u32) -> Result<Vec<CompactToken<K>>, Vec<LexingError>>{
+        let Some(source) = self.get(file) else { return Err(vec![]); };
+
+        match lexer.tokenize_content(source.content().to_string(), source.path()) {
+            LexingResult::Ok(tokens) => tokens.iter()
+                .map(|t| self.compact(t).ok_or_else(|| LexingError::new(t.location.clone())))
+                .collect::<Result<Vec<_>, LexingError>>()
+                .map_err(|e| vec![e]),
+            LexingResult::Err(errors) => Err(errors)
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+/// A [token](Token) stored as the place of its literal in one of the [Files]
+/// 
+/// It holds no text so it takes about four times less memory than a [Token],
+/// the literal and [location](Location) are read back from the [Files] when needed
+pub struct CompactToken<K:TokenKind>{
+    /// The id of the source, see [Files::add]
+    pub file: u32,
+
+    /// The offset of the literal in the source, in bytes
+    pub offset: u32,
+
+    /// The length of the literal, in bytes
+    pub length: u32,
+
+    pub kind: K
+}
+
+impl<K:TokenKind> CompactToken<K>{
+    /// The literal of the token, or None if it is out of its source
+    pub fn literal<'f>(&self, files:&'f Files) -> Option<&'f str>{
+        files.get(self.file)?.content().get(self.offset as usize..(self.offset + self.length) as usize)
+    }
+
+    /// The location of the token, or None if it is out of its source
+    pub fn location(&self, files:&Files) -> Option<Location>{
+        files.get(self.file)?.offset_to_location(self.offset as usize)
+    }
+
+    /// Returns true if *next* starts at the byte where this token ends, see [Token::touches]
+    pub fn touches(&self, next:&CompactToken<K>) -> bool{
+        self.file == next.file && self.offset + self.length == next.offset
+    }
+
+    /// Converts back to a [Token]
+    pub fn to_token(&self, files:&Files) -> Option<Token<K>>{
+        Some(Token::new(self.location(files)?, self.kind, self.literal(files)?.to_string()))
+    }
+}
+
+impl<K:TokenKind> TokenLike<K> for CompactToken<K>{
+    fn kind(&self) -> K{ self.kind }
+}
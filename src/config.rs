@@ -0,0 +1,285 @@
+use std::{collections::{BTreeMap, BTreeSet}, error::Error, fmt::Display, fs, io, path::PathBuf, time::Duration};
+
+use crate::{lexer::{Lexer, Location, TokenKind}, limits::Limits, parser::Parser, warnings::{Level, WarningCategories}, ReportStyle, Reportable};
+
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+/// The options of the [Lexer], see [Lexer::with_config]
+pub struct LexerOptions{
+    /// Whether identifiers are normalized to NFC, see `Lexer::with_nfc`
+    pub nfc: bool,
+
+    /// Bounds on the token lengths and input sizes
+    pub limits: Limits
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+/// How diagnostics are rendered, see [ReportStyle]
+pub struct DiagnosticSettings{
+    /// Paths inside this directory are shown relative to it
+    pub root: Option<PathBuf>,
+
+    /// Whether paths are hyperlinks to the files
+    pub hyperlinks: bool
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// Every setting of a language implementation in one place
+/// 
+/// A driver holds one configuration and hands it to each stage,
+/// as [Lexer::with_config] and [Parser::with_config], instead of calling their setters one by one
+/// 
+/// A configuration is written and read as `key = value` lines, see [parse](LanguageConfig::parse)
+/// 
+/// # Exemples
+/// ```rust
+/// use crate::neoglot_lib::{config::LanguageConfig, limits::Limits};
+/// 
+/// let config = LanguageConfig::new()
+///     .with_parser_limits(Limits::new().with_max_depth(64))
+///     .with_feature("generics");
+/// 
+/// let text = config.to_string();
+/// assert_eq!(text, "parser.max_depth = 64\nfeatures = generics\n");
+/// assert_eq!(LanguageConfig::parse(&text, "lang.cfg"), Ok(config));
+/// ```
+pub struct LanguageConfig{
+    pub lexer: LexerOptions,
+
+    /// Bounds on the parsing work
+    pub parser: Limits,
+
+    pub diagnostics: DiagnosticSettings,
+
+    /// The optional language features turned on
+    pub features: BTreeSet<String>,
+
+    /// The levels the user chose for warning categories, see [WarningCategories]
+    pub warnings: BTreeMap<String, Level>
+}
+
+impl Default for LanguageConfig{
+    fn default() -> Self { Self::new() }
+}
+
+impl LanguageConfig{
+    pub fn new() -> Self{
+        LanguageConfig {
+            lexer: LexerOptions::default(),
+            parser: Limits::new(),
+            diagnostics: DiagnosticSettings::default(),
+            features: BTreeSet::new(),
+            warnings: BTreeMap::new()
+        }
+    }
+
+    /// Sets the lexer options, builder style
+    pub fn with_lexer(mut self, lexer:LexerOptions) -> Self{
+        self.lexer = lexer;
+        self
+    }
+
+    /// Sets the parser limits, builder style
+    pub fn with_parser_limits(mut self, limits:Limits) -> Self{
+        self.parser = limits;
+        self
+    }
+
+    /// Sets the diagnostic settings, builder style
+    pub fn with_diagnostics(mut self, diagnostics:DiagnosticSettings) -> Self{
+        self.diagnostics = diagnostics;
+        self
+    }
+
+    /// Turns a language feature on, builder style
+    pub fn with_feature(mut self, feature:&str) -> Self{
+        self.features.insert(feature.to_string());
+        self
+    }
+
+    /// Returns true if the language feature *feature* is on
+    pub fn has_feature(&self, feature:&str) -> bool{ self.features.contains(feature) }
+
+    /// Sets the level of the warning category *category*, builder style
+    pub fn with_warning_level(mut self, category:&str, level:Level) -> Self{
+        self.warnings.insert(category.to_string(), level);
+        self
+    }
+
+    /// Overrides the levels of *categories* with the ones of the configuration
+    pub fn warning_categories(&self, categories:WarningCategories) -> WarningCategories{
+        categories.with_levels(&self.warnings)
+    }
+
+    /// The style of the reports following the diagnostic settings
+    pub fn report_style(&self) -> ReportStyle{
+        let style = match &self.diagnostics.root {
+            Some(root) => ReportStyle::new().with_root(root),
+            None => ReportStyle::new()
+        };
+
+        if self.diagnostics.hyperlinks { style.with_hyperlinks() } else { style }
+    }
+
+    /// Reads a configuration from `key = value` lines, as written by [to_string](ToString::to_string)
+    /// 
+    /// Empty lines and lines starting with `#` are skipped, missing keys keep their default value.
+    /// The keys are `lexer.nfc`, `diagnostics.root`, `diagnostics.hyperlinks`, `features` as a comma separated list,
+    /// `warnings.` followed by a warning category with `allow`, `warn` or `deny`,
+    /// and the [limits](Limits) `max_depth`, `max_tokens`, `time_budget_ms`, `max_token_length`, `max_input_size`, `max_stack` and `max_repetitions`
+    /// prefixed by `lexer.` or `parser.`
+    /// 
+    /// *path* is only used to locate the errors
+    pub fn parse(content:&str, path:&str) -> Result<Self, ConfigError>{
+        let mut config = LanguageConfig::new();
+
+        for (line, text) in crate::source::lines(content).enumerate(){
+            let text = text.trim();
+            if text.is_empty() || text.starts_with('#') { continue; }
+
+            let location = Location { file: path.to_string(), line, column: 0 };
+            let Some((key, value)) = text.split_once('=') else { return Err(ConfigError::Syntax(location)); };
+            let (key, value) = (key.trim(), value.trim());
+
+            let invalid = || ConfigError::InvalidValue { key: key.to_string(), value: value.to_string(), location: location.clone() };
+            let flag = || value.parse::<bool>().map_err(|_| invalid());
+            let number = || value.parse::<usize>().map(Some).map_err(|_| invalid());
+
+            let (section, limit) = key.split_once('.').unwrap_or(("", key));
+            let limits = match section {
+                "lexer" => Some(&mut config.lexer.limits),
+                "parser" => Some(&mut config.parser),
+                _ => None
+            };
+
+            match (key, limits, limit) {
+                ("lexer.nfc", _, _) => config.lexer.nfc = flag()?,
+                ("diagnostics.root", _, _) => config.diagnostics.root = Some(PathBuf::from(value)),
+                ("diagnostics.hyperlinks", _, _) => config.diagnostics.hyperlinks = flag()?,
+                ("features", _, _) => config.features = value.split(',').map(str::trim).filter(|f| !f.is_empty()).map(str::to_string).collect(),
+                (_, None, category) if section == "warnings" && !category.is_empty() => { config.warnings.insert(category.to_string(), Level::parse(value).ok_or_else(invalid)?); },
+
+                (_, Some(limits), "max_depth") => limits.max_depth = number()?,
+                (_, Some(limits), "max_tokens") => limits.max_tokens = number()?,
+                (_, Some(limits), "time_budget_ms") => limits.time_budget = number()?.map(|ms| Duration::from_millis(ms as u64)),
+                (_, Some(limits), "max_token_length") => limits.max_token_length = number()?,
+                (_, Some(limits), "max_input_size") => limits.max_input_size = number()?,
+                (_, Some(limits), "max_stack") => limits.max_stack = number()?,
+                (_, Some(limits), "max_repetitions") => limits.max_repetitions = number()?,
+
+                _ => return Err(ConfigError::UnknownKey { key: key.to_string(), location })
+            }
+        }
+
+        Ok(config)
+    }
+
+    /// Reads a configuration from a file, see [parse](LanguageConfig::parse)
+    pub fn from_file(path:&str) -> io::Result<Result<Self, ConfigError>>{
+        Ok(Self::parse(&fs::read_to_string(path)?, path))
+    }
+}
+
+// Writes the set limits under a prefix
+fn write_limits(f: &mut std::fmt::Formatter<'_>, prefix:&str, limits:&Limits) -> std::fmt::Result{
+    let entries = [
+        ("max_depth", limits.max_depth),
+        ("max_tokens", limits.max_tokens),
+        ("time_budget_ms", limits.time_budget.map(|budget| budget.as_millis() as usize)),
+        ("max_token_length", limits.max_token_length),
+        ("max_input_size", limits.max_input_size),
+        ("max_stack", limits.max_stack),
+        ("max_repetitions", limits.max_repetitions)
+    ];
+
+    for (key, value) in entries{
+        if let Some(value) = value { writeln!(f, "{prefix}.{key} = {value}")?; }
+    }
+
+    Ok(())
+}
+
+/// Writes the settings that differ from the defaults as `key = value` lines, see [LanguageConfig::parse]
+/// 
+/// Time budgets are written in whole milliseconds
+impl Display for LanguageConfig{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.lexer.nfc { writeln!(f, "lexer.nfc = true")?; }
+        write_limits(f, "lexer", &self.lexer.limits)?;
+        write_limits(f, "parser", &self.parser)?;
+
+        if let Some(root) = &self.diagnostics.root { writeln!(f, "diagnostics.root = {}", root.display())?; }
+        if self.diagnostics.hyperlinks { writeln!(f, "diagnostics.hyperlinks = true")?; }
+
+        if !self.features.is_empty() {
+            writeln!(f, "features = {}", self.features.iter().cloned().collect::<Vec<String>>().join(", "))?;
+        }
+
+        for (category, level) in &self.warnings{ writeln!(f, "warnings.{category} = {level}")?; }
+
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// A line of a configuration that couldn't be read, see [LanguageConfig::parse]
+pub enum ConfigError{
+    /// The line isn't a `key = value` pair
+    Syntax(Location),
+
+    UnknownKey{
+        key: String,
+        location: Location
+    },
+
+    /// The value doesn't fit the key, as a word for a number
+    InvalidValue{
+        key: String,
+        value: String,
+        location: Location
+    }
+}
+
+impl Reportable for ConfigError{
+    fn message(&self) -> String{
+        match self {
+            ConfigError::Syntax(_) => String::from("Expected a `key = value` pair"),
+            ConfigError::UnknownKey { key, .. } => format!("Unknown configuration key `{key}`"),
+            ConfigError::InvalidValue { key, value, .. } => format!("Invalid value `{value}` for `{key}`")
+        }
+    }
+
+    fn location(&self) -> Option<Location>{
+        match self {
+            ConfigError::Syntax(location) | ConfigError::UnknownKey { location, .. } | ConfigError::InvalidValue { location, .. } => Some(location.clone())
+        }
+    }
+}
+
+impl Display for ConfigError{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.report())
+    }
+}
+
+impl Error for ConfigError{}
+
+impl<Kind:TokenKind> Lexer<Kind>{
+    /// Applies the lexer options of *config*, builder style
+    /// 
+    /// NFC normalization is only applied with the `unicode` feature
+    pub fn with_config(self, config:&LanguageConfig) -> Self{
+        let lexer = self.with_limits(config.lexer.limits);
+
+        #[cfg(feature = "unicode")]
+        let lexer = if config.lexer.nfc { lexer.with_nfc() } else { lexer };
+
+        lexer
+    }
+}
+
+impl<T:TokenKind> Parser<'_, T>{
+    /// Applies the parser limits of *config*, builder style
+    pub fn with_config(self, config:&LanguageConfig) -> Self{
+        self.with_limits(config.parser)
+    }
+}
@@ -0,0 +1,185 @@
+use std::{collections::HashMap, fmt::Display, error::Error};
+
+use crate::{lexer::{Location, Span, Token, TokenKind}, Reportable};
+
+#[derive(Debug, Clone, PartialEq)]
+/// Error type of the escape sequences decoding
+/// 
+/// Each error holds the span of the faulty escape sequence
+pub enum EscapeError{
+    /// An escape sequence that isn't known, as `\q`
+    UnknownEscape(Span),
+
+    /// A `\x` or `\u` escape without valid digits, or whose value isn't a character
+    InvalidCodePoint(Span),
+
+    /// A backslash ends the text
+    Unfinished(Span)
+}
+
+impl Reportable for EscapeError{
+    fn message(&self) -> String{
+        match self {
+            EscapeError::UnknownEscape(_) => String::from("Unknown escape sequence"),
+            EscapeError::InvalidCodePoint(_) => String::from("Invalid character code in escape sequence"),
+            EscapeError::Unfinished(_) => String::from("Unfinished escape sequence")
+        }
+    }
+
+    fn location(&self) -> Option<Location>{
+        match self {
+            EscapeError::UnknownEscape(span)
+            | EscapeError::InvalidCodePoint(span)
+            | EscapeError::Unfinished(span) => Some(span.start.clone())
+        }
+    }
+}
+
+impl Display for EscapeError{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.report())
+    }
+}
+
+impl Error for EscapeError{}
+
+#[derive(Debug, Clone, PartialEq)]
+/// Decodes the escape sequences of string literals
+/// 
+/// By default `\n`, `\r`, `\t`, `\0`, `\\`, `\'` and `\"` are known
+/// 
+/// # Exemples
+/// ```rust
+/// use crate::neoglot_lib::{escapes::*, lexer::*};
+/// 
+/// let escapes = Escapes::new().with_hex().with_unicode();
+/// let start = Location{ file: "main".to_string(), line: 2, column: 10 };
+/// 
+/// assert_eq!(escapes.decode(r"a\tb\x41\u{e9}", &start), Ok("a\tbAé".to_string()));
+/// 
+/// let errors = escapes.decode(r"a\qb", &start).unwrap_err();
+/// assert_eq!(errors, vec![EscapeError::UnknownEscape(Span::new(
+///     Location{ file: "main".to_string(), line: 2, column: 11 },
+///     Location{ file: "main".to_string(), line: 2, column: 13 }
+/// ))]);
+/// ```
+pub struct Escapes{
+    /// The character following the backslash and the one it stands for
+    simple: HashMap<char, char>,
+
+    /// Whether `\xNN` escapes are known
+    hex: bool,
+
+    /// Whether `\u{NNNN}` escapes are known
+    unicode: bool
+}
+
+impl Default for Escapes{
+    fn default() -> Self { Self::new() }
+}
+
+impl Escapes{
+    pub fn new() -> Self{
+        Escapes {
+            simple: HashMap::from([('n', '\n'), ('r', '\r'), ('t', '\t'), ('0', '\0'), ('\\', '\\'), ('\'', '\''), ('"', '"')]),
+            hex: false,
+            unicode: false
+        }
+    }
+
+    /// Makes `\escape` stand for *value*, builder style
+    pub fn with_escape(mut self, escape:char, value:char) -> Self{
+        self.simple.insert(escape, value);
+        self
+    }
+
+    /// Knows the `\xNN` escapes, two hexadecimal digits up to `7F`, builder style
+    pub fn with_hex(mut self) -> Self{
+        self.hex = true;
+        self
+    }
+
+    /// Knows the `\u{NNNN}` escapes, one to six hexadecimal digits, builder style
+    pub fn with_unicode(mut self) -> Self{
+        self.unicode = true;
+        self
+    }
+
+    /// Decodes the literal of a string token, without its first and last characters which are the quotes
+    pub fn decode_token<Kind:TokenKind>(&self, token:&Token<Kind>) -> Result<String, Vec<EscapeError>>{
+        let mut chars = token.literal.chars();
+        chars.next();
+        chars.next_back();
+
+        self.decode(chars.as_str(), &Location { column: token.location.column + 1, ..token.location.clone() })
+    }
+
+    /// Decodes the escape sequences of *raw*, the text of a literal starting at *location*
+    /// 
+    /// Every invalid escape sequence is reported
+    pub fn decode(&self, raw:&str, location:&Location) -> Result<String, Vec<EscapeError>>{
+        let chars = raw.chars().collect::<Vec<char>>();
+        let span = |start:usize, end:usize| Span::new(
+            Location { column: location.column + start, ..location.clone() },
+            Location { column: location.column + end, ..location.clone() }
+        );
+
+        let mut value = String::new();
+        let mut errors = vec![];
+        let mut i = 0;
+
+        while i < chars.len(){
+            if chars[i] != '\\'{
+                value.push(chars[i]);
+                i += 1;
+                continue;
+            }
+
+            let start = i;
+            let Some(escape) = chars.get(i+1) else {
+                errors.push(EscapeError::Unfinished(span(start, start + 1)));
+                break;
+            };
+
+            i += 2;
+
+            if let Some(c) = self.simple.get(escape){
+                value.push(*c);
+            }
+            else if *escape == 'x' && self.hex{
+                let digits = chars[i..].iter().take(2).take_while(|c| c.is_ascii_hexdigit()).collect::<String>();
+                i += digits.chars().count();
+
+                match u8::from_str_radix(&digits, 16) {
+                    Ok(code) if digits.len() == 2 && code <= 0x7f => value.push(char::from(code)),
+                    _ => errors.push(EscapeError::InvalidCodePoint(span(start, i)))
+                }
+            }
+            else if *escape == 'u' && self.unicode{
+                let code = match chars.get(i) {
+                    Some('{') => {
+                        let digits = chars[i+1..].iter().take_while(|c| c.is_ascii_hexdigit()).collect::<String>();
+                        let closed = chars.get(i + 1 + digits.chars().count()) == Some(&'}');
+                        i += 1 + digits.chars().count() + usize::from(closed);
+
+                        u32::from_str_radix(&digits, 16).ok()
+                            .filter(|_| closed && (1..=6).contains(&digits.len()))
+                            .and_then(char::from_u32)
+                    },
+                    _ => None
+                };
+
+                match code {
+                    Some(c) => value.push(c),
+                    None => errors.push(EscapeError::InvalidCodePoint(span(start, i)))
+                }
+            }
+            else{
+                errors.push(EscapeError::UnknownEscape(span(start, i)));
+            }
+        }
+
+        if errors.is_empty() { Ok(value) }
+        else { Err(errors) }
+    }
+}
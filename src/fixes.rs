@@ -0,0 +1,149 @@
+use crate::{lexer::{Location, Span, Token, TokenKind}, source::Source, suite::Diagnostic};
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+/// A replacement of a [span](Span) of a source by a text
+/// 
+/// Insertions are edits of an empty span, deletions are edits with an empty replacement
+pub struct Edit{
+    pub span: Span,
+    pub replacement: String
+}
+
+impl Edit{
+    pub fn new(span:Span, replacement:&str) -> Self{ Edit { span, replacement: replacement.to_string() } }
+
+    /// Inserts *text* at *location*
+    pub fn insert(location:Location, text:&str) -> Self{ Self::new(Span::new(location.clone(), location), text) }
+
+    /// Removes the text of *span*
+    pub fn delete(span:Span) -> Self{ Self::new(span, "") }
+
+    /// Replaces the text of a sequence of [tokens](Token), as the ones an [AST](crate::parser::AST) was parsed from,
+    /// or None if there are none
+    pub fn replace_tokens<Kind:TokenKind>(tokens:&[Token<Kind>], replacement:&str) -> Option<Self>{
+        Some(Self::new(Span::covering(tokens)?, replacement))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+/// A fix-it suggested by a [diagnostic](Diagnostic), its edits are applied all together or not at all
+pub struct Fix{
+    /// What the fix does, as `Remove the unused variable`
+    pub message: String,
+
+    pub edits: Vec<Edit>
+}
+
+impl Fix{
+    pub fn new(message:&str) -> Self{ Fix { message: message.to_string(), edits: vec![] } }
+
+    /// Adds *edit* to the fix, builder style
+    pub fn with_edit(mut self, edit:Edit) -> Self{
+        self.edits.push(edit);
+        self
+    }
+
+    /// The byte ranges of the edits in *source*, in source order,
+    /// or None if an edit is in another file, out of it or reversed
+    fn ranges<'f>(&'f self, source:&Source) -> Option<Vec<(usize, usize, &'f str)>>{
+        let mut ranges = self.edits.iter().map(|edit| {
+            if edit.span.start.file != source.path() || edit.span.end.file != source.path() { return None; }
+
+            let start = source.location_to_offset(&edit.span.start)?;
+            let end = source.location_to_offset(&edit.span.end)?;
+            (start <= end).then_some((start, end, edit.replacement.as_str()))
+        }).collect::<Option<Vec<(usize, usize, &str)>>>()?;
+
+        ranges.sort_by_key(|(start, end, _)| (*start, *end));
+        Some(ranges)
+    }
+}
+
+/// Returns true if two byte ranges can't both be edited: they share a byte, or they are insertions at the same offset
+fn conflicting(a:(usize, usize), b:(usize, usize)) -> bool{
+    (a.0 < b.1 && b.0 < a.1) || (a.0 == a.1 && b.0 == b.1 && a.0 == b.0)
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// A source rewritten by [apply], with the fixes applied and the ones left out
+pub struct FixedSource{
+    /// The rewritten content of the source
+    pub content: String,
+
+    /// The fixes applied, in the order they were given
+    pub applied: Vec<Fix>,
+
+    /// The fixes overlapping an applied one, or with an edit outside of the source, in the order they were given
+    pub skipped: Vec<Fix>
+}
+
+impl FixedSource{
+    /// Returns true if the source was left as it was
+    pub fn unchanged(&self) -> bool{ self.applied.is_empty() }
+}
+
+/// Applies all the *fixes* to *source* in one pass and returns the rewritten content
+/// 
+/// The fixes are kept in the order they are given: a fix is skipped when one of its edits overlaps an edit
+/// of a fix kept before it, so running it again on the rewritten source applies the next ones
+/// 
+/// # Exemples
+/// ```rust
+/// use crate::neoglot_lib::{fixes::*, lexer::{Location, Span}, source::Source};
+/// 
+/// let source = Source::new("main.lang", String::from("let x = 1;\nlet y = x;;"));
+/// let at = |line, column| Location { file: String::from("main.lang"), line, column };
+/// 
+/// let fixed = apply(&source, [
+///     Fix::new("Remove the extra semicolon").with_edit(Edit::delete(Span::new(at(1, 10), at(1, 11)))),
+///     Fix::new("Rename y to z").with_edit(Edit::new(Span::new(at(1, 4), at(1, 5)), "z")),
+///     Fix::new("Rename y to w").with_edit(Edit::new(Span::new(at(1, 4), at(1, 5)), "w")),
+///     Fix::new("Make x mutable").with_edit(Edit::insert(at(0, 4), "mut "))
+/// ]);
+/// 
+/// assert_eq!(fixed.content, "let mut x = 1;\nlet z = x;");
+/// assert_eq!(fixed.applied.len(), 3);
+/// assert_eq!(fixed.skipped[0].message, "Rename y to w");
+/// ```
+pub fn apply(source:&Source, fixes:impl IntoIterator<Item = Fix>) -> FixedSource{
+    let mut edits:Vec<(usize, usize, String)> = vec![];
+    let (mut applied, mut skipped) = (vec![], vec![]);
+
+    for fix in fixes{
+        let accepted = fix.ranges(source).filter(|ranges| {
+            ranges.windows(2).all(|pair| !conflicting((pair[0].0, pair[0].1), (pair[1].0, pair[1].1)))
+                && ranges.iter().all(|(start, end, _)| edits.iter().all(|edit| !conflicting((*start, *end), (edit.0, edit.1))))
+        });
+
+        match accepted {
+            Some(ranges) => {
+                edits.extend(ranges.into_iter().map(|(start, end, replacement)| (start, end, replacement.to_string())));
+                applied.push(fix);
+            },
+            None => skipped.push(fix)
+        }
+    }
+
+    edits.sort_by_key(|(start, end, _)| (*start, *end));
+
+    let text = source.content();
+    let mut content = String::with_capacity(text.len());
+    let mut copied = 0;
+
+    for (start, end, replacement) in edits{
+        content.push_str(&text[copied..start]);
+        content.push_str(&replacement);
+        copied = end;
+    }
+    content.push_str(&text[copied..]);
+
+    FixedSource { content, applied, skipped }
+}
+
+/// [Applies](apply) the fixes of the *diagnostics* emitted on *source*, the core of a `fix` command
+/// 
+/// The diagnostics are usually the ones a [pipeline](crate::pipeline::Pipeline) gathered for the file,
+/// the fixes of the ones emitted on other files are skipped
+pub fn apply_diagnostics<'d>(source:&Source, diagnostics:impl IntoIterator<Item = &'d Diagnostic>) -> FixedSource{
+    apply(source, diagnostics.into_iter().flat_map(|diagnostic| diagnostic.fixes.iter().cloned()))
+}
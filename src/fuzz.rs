@@ -0,0 +1,171 @@
+use crate::{lexer::{Location, Origin, Token, TokenKind}, parser::ParserNode};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// A small random number generator (SplitMix64), so that a seed gives the same programs on every platform and version
+pub struct Rng{
+    state: u64
+}
+
+impl Rng{
+    pub fn new(seed:u64) -> Self{ Rng { state: seed } }
+
+    /// The next random number
+    pub fn next_u64(&mut self) -> u64{
+        self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// A random number below *bound*, 0 if *bound* is 0
+    pub fn below(&mut self, bound:usize) -> usize{
+        if bound == 0 { return 0; }
+        (self.next_u64() % bound as u64) as usize
+    }
+
+    /// A random item of *items*, None if there is none
+    pub fn pick<'a, I>(&mut self, items:&'a [I]) -> Option<&'a I>{
+        if items.is_empty() { return None; }
+        items.get(self.below(items.len()))
+    }
+}
+
+/// Generates random programs valid under the [nodes](ParserNode) of a parser, to test it against other parsers
+/// 
+/// Each statement of a program is a sequence of tokens [generated](crate::regex::Regex::generate) by the regex of a random node.
+/// The same seed always gives the same programs
+/// 
+/// # Exemples
+/// ```rust
+/// use crate::neoglot_lib::{fuzz::ProgramGenerator, lexer::*, parser::*, regex::*};
+/// 
+/// #[derive(PartialEq, PartialOrd, Eq, Hash, Debug, Copy, Clone)]
+/// enum Kind{ Let, Name, Number }
+/// 
+/// impl Symbol for Kind{}
+/// impl TokenKind for Kind{}
+/// 
+/// let nodes = vec![Box::new(ParserNode{
+///     regex: Regex::new()
+///         .then(RegexElement::Item(Kind::Let, Quantifier::Exactly(1)))
+///         .then(RegexElement::Set(Kind::Name, Kind::Number, Quantifier::OneOrMany)),
+///     parser: Box::new(|_| Ok(AST{ kind: Kind::Let, children: vec![] }))
+/// })];
+/// 
+/// let mut generator = ProgramGenerator::new(7).with_kinds(&[Kind::Let, Kind::Name, Kind::Number]);
+/// let program = generator.program(&nodes, 3);
+/// 
+/// assert_eq!(program.iter().filter(|t| t.kind == Kind::Let).count(), 3);
+/// assert_eq!(ProgramGenerator::new(7).with_kinds(&[Kind::Let, Kind::Name, Kind::Number]).program(&nodes, 3), program);
+/// ```
+pub struct ProgramGenerator<T:TokenKind>{
+    rng: Rng,
+
+    /// The kinds the tokens are drawn from, for the elements other than single kinds
+    kinds: Vec<T>,
+
+    /// How many times more than needed the unbounded quantifiers may repeat
+    max_repeat: usize,
+
+    /// Gives the literal of a token of a kind
+    literal: fn(T) -> String
+}
+
+impl<T:TokenKind> ProgramGenerator<T>{
+    pub fn new(seed:u64) -> Self{
+        ProgramGenerator { rng: Rng::new(seed), kinds: vec![], max_repeat: 3, literal: |kind| format!("{kind:?}") }
+    }
+
+    /// Sets the kinds the tokens are drawn from, builder style
+    /// 
+    /// Only the kinds of single kind elements are generated without them
+    pub fn with_kinds(mut self, kinds:&[T]) -> Self{
+        self.kinds = kinds.to_vec();
+        self
+    }
+
+    /// Bounds how many times more than needed the unbounded quantifiers repeat, 3 by default, builder style
+    pub fn with_max_repeat(mut self, max_repeat:usize) -> Self{
+        self.max_repeat = max_repeat;
+        self
+    }
+
+    /// Sets how the literals of the tokens are written, their kind's debug form by default, builder style
+    pub fn with_literals(mut self, literal:fn(T) -> String) -> Self{
+        self.literal = literal;
+        self
+    }
+
+    /// The kinds of a statement matched by a random node of *nodes*
+    /// 
+    /// Returns None if no node can be matched with the [kinds](ProgramGenerator::with_kinds)
+    pub fn statement(&mut self, nodes:&[Box<ParserNode<T>>]) -> Option<Vec<T>>{
+        let mut candidates = nodes.iter().collect::<Vec<&Box<ParserNode<T>>>>();
+
+        // The nodes that can't be matched are dropped until one is
+        while !candidates.is_empty(){
+            let i = self.rng.below(candidates.len());
+
+            match candidates[i].regex.generate(&mut self.rng, &self.kinds, self.max_repeat) {
+                Some(kinds) if !kinds.is_empty() => return Some(kinds),
+                _ => { candidates.remove(i); }
+            }
+        }
+
+        None
+    }
+
+    /// A program of at most *statements* [statements](ProgramGenerator::statement), fewer if no node can be matched
+    /// 
+    /// The tokens are [expanded](Origin::Expanded) ones at [generated](Location::generated) locations,
+    /// one column after the end of the previous token
+    pub fn program(&mut self, nodes:&[Box<ParserNode<T>>], statements:usize) -> Vec<Token<T>>{
+        let mut tokens = vec![];
+        let mut column = 0;
+
+        for _ in 0..statements{
+            let Some(kinds) = self.statement(nodes) else { break };
+
+            for kind in kinds{
+                let literal = (self.literal)(kind);
+                let location = Location::generated("fuzz", column);
+                column += literal.chars().count() + 1;
+
+                tokens.push(Token::new(location, kind, literal).with_origin(Origin::Expanded));
+            }
+        }
+
+        tokens
+    }
+
+    /// An endless iterator over [programs](ProgramGenerator::program) of at most *statements* statements
+    pub fn programs<'g>(&'g mut self, nodes:&'g [Box<ParserNode<T>>], statements:usize) -> impl Iterator<Item = Vec<Token<T>>> + 'g{
+        std::iter::repeat_with(move || self.program(nodes, statements))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+/// A program on which two parsers disagree, see [differential]
+pub struct Disagreement<T:TokenKind, R>{
+    /// The position of the program among the ones compared
+    pub index: usize,
+
+    pub program: Vec<Token<T>>,
+
+    /// What each parser gave
+    pub left: R,
+    pub right: R
+}
+
+/// Runs two parsers on every program and returns the programs on which their results differ
+/// 
+/// The parsers are usually two backends of the same grammar, as a hand-written recursive descent and a table driven one,
+/// run on the [programs](ProgramGenerator::programs) of a generator
+pub fn differential<T:TokenKind, R:PartialEq>(programs:impl IntoIterator<Item = Vec<Token<T>>>, left:impl Fn(&[Token<T>]) -> R, right:impl Fn(&[Token<T>]) -> R) -> Vec<Disagreement<T, R>>{
+    programs.into_iter().enumerate().filter_map(|(index, program)| {
+        let (left, right) = (left(&program), right(&program));
+        (left != right).then_some(Disagreement { index, program, left, right })
+    }).collect()
+}
@@ -0,0 +1,144 @@
+use std::{collections::{HashMap, HashSet}, fmt::Display, error::Error};
+
+use crate::{lexer::{Location, Token, TokenKind}, Reportable};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+/// The writing system a character belongs to
+pub enum Script{
+    /// Digits, punctuation and other characters shared by every script
+    Common,
+    Latin,
+    Greek,
+    Cyrillic,
+
+    /// Any other script
+    Other
+}
+
+/// Returns the [Script] of a character
+/// 
+/// Only the scripts commonly mistaken for Latin are told apart
+pub fn script_of(c:char) -> Script{
+    match c {
+        'a'..='z' | 'A'..='Z' | 'ª' | 'º' | '\u{c0}'..='\u{24f}' | '\u{1e00}'..='\u{1eff}' if c.is_alphabetic() => Script::Latin,
+        '\u{370}'..='\u{3ff}' | '\u{1f00}'..='\u{1fff}' if c.is_alphabetic() => Script::Greek,
+        '\u{400}'..='\u{52f}' => Script::Cyrillic,
+        c if c.is_ascii() || !c.is_alphabetic() => Script::Common,
+        _ => Script::Other
+    }
+}
+
+// Latin look-alike of a character, if it has one
+fn prototype(c:char) -> Option<char>{
+    let latin = match c {
+        // Cyrillic
+        'а' => 'a', 'е' => 'e', 'о' => 'o', 'р' => 'p', 'с' => 'c', 'у' => 'y', 'х' => 'x',
+        'і' => 'i', 'ј' => 'j', 'ѕ' => 's', 'ԁ' => 'd', 'ԛ' => 'q', 'ԝ' => 'w', 'һ' => 'h',
+        'А' => 'A', 'В' => 'B', 'Е' => 'E', 'К' => 'K', 'М' => 'M', 'Н' => 'H', 'О' => 'O',
+        'Р' => 'P', 'С' => 'C', 'Т' => 'T', 'Х' => 'X', 'Ѕ' => 'S', 'І' => 'l', 'Ј' => 'J',
+
+        // Greek
+        'α' => 'a', 'ο' => 'o', 'ν' => 'v', 'ρ' => 'p', 'ι' => 'i', 'κ' => 'k',
+        'Α' => 'A', 'Β' => 'B', 'Ε' => 'E', 'Ζ' => 'Z', 'Η' => 'H', 'Ι' => 'l', 'Κ' => 'K',
+        'Μ' => 'M', 'Ν' => 'N', 'Ο' => 'O', 'Ρ' => 'P', 'Τ' => 'T', 'Υ' => 'Y', 'Χ' => 'X',
+
+        // Latin
+        '0' => 'O', '1' => 'l', 'I' => 'l',
+        _ => return None
+    };
+
+    Some(latin)
+}
+
+/// Returns the skeleton of an identifier:
+/// two identifiers with the same skeleton are likely to be confused when read
+pub fn skeleton(identifier:&str) -> String{
+    identifier.chars().map(|c| prototype(c).unwrap_or(c)).collect()
+}
+
+#[derive(Debug, Clone, PartialEq)]
+/// A suspicious identifier found by [check_identifiers]
+pub enum IdentifierWarning{
+    /// The identifier mixes letters of several [scripts](Script)
+    MixedScript{
+        identifier: String,
+        scripts: Vec<Script>,
+        location: Location
+    },
+
+    /// The identifier looks like another one, declared earlier
+    Confusable{
+        identifier: String,
+        other: String,
+        location: Location
+    }
+}
+
+impl Reportable for IdentifierWarning{
+    fn message(&self) -> String{
+        match self {
+            IdentifierWarning::MixedScript { identifier, scripts, .. } => format!("Identifier `{identifier}` mixes the scripts {scripts:?}"),
+            IdentifierWarning::Confusable { identifier, other, .. } => format!("Identifier `{identifier}` can be confused with `{other}`")
+        }
+    }
+
+    fn location(&self) -> Option<Location>{
+        match self {
+            IdentifierWarning::MixedScript { location, .. } | IdentifierWarning::Confusable { location, .. } => Some(location.clone())
+        }
+    }
+}
+
+impl Display for IdentifierWarning{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.report())
+    }
+}
+
+impl Error for IdentifierWarning{}
+
+/// Looks for mixed-script identifiers and for distinct identifiers that look the same
+/// 
+/// Each identifier is reported once, at its first occurence
+/// 
+/// is_identifier: Tells which [tokens](Token) are identifiers
+pub fn check_identifiers<T:TokenKind>(tokens:&[Token<T>], is_identifier:impl Fn(&Token<T>) -> bool) -> Vec<IdentifierWarning>{
+    let mut warnings = vec![];
+    let mut skeletons:HashMap<String, &str> = HashMap::new();
+    let mut seen = HashSet::new();
+
+    for token in tokens.iter().filter(|t| is_identifier(t)){
+        let identifier = token.literal.as_str();
+        if !seen.insert(identifier) { continue; }
+
+        match skeletons.get(&skeleton(identifier)) {
+            Some(other) => {
+                warnings.push(IdentifierWarning::Confusable {
+                    identifier: identifier.to_string(), other: other.to_string(), location: token.location.clone()
+                });
+                continue;
+            },
+            None => { skeletons.insert(skeleton(identifier), identifier); }
+        }
+
+        let mut scripts = identifier.chars().map(script_of).filter(|s| *s != Script::Common).collect::<Vec<Script>>();
+        scripts.sort();
+        scripts.dedup();
+
+        if scripts.len() > 1{
+            warnings.push(IdentifierWarning::MixedScript {
+                identifier: identifier.to_string(), scripts, location: token.location.clone()
+            });
+        }
+    }
+
+    warnings
+}
+
+/// Normalizes an identifier to its NFC form, so that identifiers written with
+/// precomposed or combining characters are equal
+#[cfg(feature = "unicode")]
+pub fn normalize(identifier:&str) -> String{
+    use unicode_normalization::UnicodeNormalization;
+    identifier.nfc().collect()
+}
@@ -1,9 +1,11 @@
-use std::{fmt::Display, error::Error, fs};
+use std::{fmt::Display, error::Error, fs, collections::{HashMap, HashSet}};
 
-use crate::{regex::{Regex, self}, build_report};
+use crate::{identifiers::{self, IdentifierWarning}, limits::{Limit, Limits}, regex::{Regex, self}, source, Reportable};
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 /// The location of a [token](Token) in a file
+/// 
+/// Locations are ordered by file, then line, then column
 pub struct Location {
     pub file: String,
     pub line: usize,
@@ -13,13 +15,384 @@ pub struct Location {
 impl Location{
     pub fn line(&mut self, l:usize){ self.line = l; }
     pub fn column(&mut self, col:usize){ self.column = col; }
+
+    /// A location in code generated by *origin* rather than read from a file
+    /// 
+    /// Its file is `<generated origin>`
+    pub fn generated(origin:&str, column:usize) -> Self{
+        Location { file: format!("<generated {origin}>"), line: 0, column }
+    }
+
+    /// Returns true if this location was made by [generated](Location::generated)
+    pub fn is_generated(&self) -> bool{
+        self.file.starts_with("<generated ") && self.file.ends_with('>')
+    }
+}
+
+/// Formats as `file:line:column`
+impl Display for Location{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}:{}:{}", self.file, self.line, self.column)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+/// A range of a file, from *start* (included) to *end* (excluded)
+pub struct Span {
+    pub start: Location,
+    pub end: Location
+}
+
+impl Span{
+    pub fn new(start:Location, end:Location) -> Self{ Span { start, end } }
+
+    /// Returns true if *location* is inside this span
+    pub fn contains(&self, location:&Location) -> bool{
+        self.start.file == location.file && self.start <= *location && *location < self.end
+    }
+
+    /// Returns true if *other* is entirely inside this span
+    pub fn contains_span(&self, other:&Span) -> bool{
+        self.start.file == other.start.file && self.start <= other.start && other.end <= self.end
+    }
+
+    /// Returns true if this span and *other* share at least one position
+    pub fn overlaps(&self, other:&Span) -> bool{
+        self.start.file == other.start.file && self.start < other.end && other.start < self.end
+    }
+
+    /// Returns the smallest span covering both this span and *other*
+    /// 
+    /// Both spans are expected to be in the same file
+    pub fn merge(&self, other:&Span) -> Span{
+        Span::new(self.start.clone().min(other.start.clone()), self.end.clone().max(other.end.clone()))
+    }
+
+    /// Returns the positions shared by this span and *other*, or None if they don't overlap
+    pub fn intersect(&self, other:&Span) -> Option<Span>{
+        if !self.overlaps(other) { return None; }
+
+        Some(Span::new(self.start.clone().max(other.start.clone()), self.end.clone().min(other.end.clone())))
+    }
+
+    /// Returns the span covering a sequence of [tokens](Token), or None if there are none
+    pub fn covering<Kind:TokenKind>(tokens:&[Token<Kind>]) -> Option<Span>{
+        let (first, last) = tokens.first().zip(tokens.last())?;
+        Some(first.span().merge(&last.span()))
+    }
+}
+
+/// Formats as `file:line:column-line:column`
+impl Display for Span{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}-{}:{}", self.start, self.end.line, self.end.column)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+/// A value along with the [span](Span) of source it was built from
+pub struct Spanned<V>{
+    pub value: V,
+    pub span: Span
+}
+
+impl<V> Spanned<V>{
+    pub fn new(value:V, span:Span) -> Self{ Spanned { value, span } }
+
+    /// Transforms the value, keeping the span
+    pub fn map<U>(self, f:impl FnOnce(V) -> U) -> Spanned<U>{
+        Spanned { value: f(self.value), span: self.span }
+    }
+
+    /// Borrows the value, keeping the span
+    pub fn as_ref(&self) -> Spanned<&V>{
+        Spanned { value: &self.value, span: self.span.clone() }
+    }
+
+    /// Combines this value with *other*, the result covers both spans
+    pub fn merge<W, U>(self, other:Spanned<W>, f:impl FnOnce(V, W) -> U) -> Spanned<U>{
+        let span = self.span.merge(&other.span);
+        Spanned { value: f(self.value, other.value), span }
+    }
+}
+
+/// Formats as `value @ span`
+impl<V:Display> Display for Spanned<V>{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} @ {}", self.value, self.span)
+    }
+}
+
+/// A value standing for a [token](Token), as a [Token] itself or a [CompactToken](crate::compact::CompactToken)
+/// 
+/// Helpers only looking at the kinds of the tokens, as [find_matching](crate::parser::find_matching),
+/// work over any of them
+pub trait TokenLike<Kind:TokenKind>{
+    /// The kind of the token
+    fn kind(&self) -> Kind;
+}
+
+impl<Kind:TokenKind> TokenLike<Kind> for Token<Kind>{
+    fn kind(&self) -> Kind{ self.kind }
 }
 
 /// A trait representing the type of a [token](Token) (integer, float, keword...)
-pub trait TokenKind : Copy+regex::Symbol{}
+pub trait TokenKind : Copy+regex::Symbol{
+    /// A small number identifying this kind, used to store it compactly in a [KindSet]
+    /// 
+    /// Fieldless enums can simply return `Some(*self as usize)`
+    fn kind_index(&self) -> Option<usize>{ None }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+/// The broad category of a [token kind](TokenKind), see [TokenCategory::category]
+pub enum Category{
+    Keyword,
+    Operator,
+    Literal,
+    Trivia,
+
+    /// Any kind outside the other categories, as identifiers
+    Other
+}
+
+impl Category{
+    /// The lowercase name of the category, None for [Other](Category::Other)
+    pub fn name(&self) -> Option<&'static str>{
+        match self {
+            Category::Keyword => Some("keyword"),
+            Category::Operator => Some("operator"),
+            Category::Literal => Some("literal"),
+            Category::Trivia => Some("trivia"),
+            Category::Other => None
+        }
+    }
+}
+
+/// Broad categories of a [token kind](TokenKind), for utilities that don't know the kinds of a language
+/// as [without_trivia], [highlights] and [message_with_categories](crate::parser::ParsingError::message_with_categories)
+/// 
+/// Every query returns false unless implemented
+/// 
+/// # Exemples
+/// ```rust
+/// use crate::neoglot_lib::{lexer::*, regex::*};
+/// 
+/// #[derive(PartialEq, PartialOrd, Eq, Copy, Clone, Debug, Hash)]
+/// enum TokenType{ If, Plus, Number, Space }
+/// 
+/// impl Symbol for TokenType{}
+/// impl TokenKind for TokenType{}
+/// impl TokenCategory for TokenType{
+///     fn is_keyword(&self) -> bool{ *self == TokenType::If }
+///     fn is_trivia(&self) -> bool{ *self == TokenType::Space }
+/// }
+/// 
+/// assert_eq!(TokenType::If.category(), Category::Keyword);
+/// assert_eq!(TokenType::Plus.category(), Category::Other);
+/// ```
+pub trait TokenCategory : TokenKind{
+    fn is_keyword(&self) -> bool{ false }
+
+    fn is_operator(&self) -> bool{ false }
+
+    fn is_literal(&self) -> bool{ false }
+
+    /// Returns true for the kinds without meaning for the parser, as whitespaces and comments
+    fn is_trivia(&self) -> bool{ false }
+
+    /// The first category of the kind, in the order of the queries
+    fn category(&self) -> Category{
+        if self.is_keyword() { Category::Keyword }
+        else if self.is_operator() { Category::Operator }
+        else if self.is_literal() { Category::Literal }
+        else if self.is_trivia() { Category::Trivia }
+        else { Category::Other }
+    }
+}
+
+/// The tokens whose kind isn't [trivia](TokenCategory::is_trivia), in order
+pub fn without_trivia<Kind:TokenCategory, T:TokenLike<Kind>+Clone>(tokens:&[T]) -> Vec<T>{
+    tokens.iter().filter(|t| !t.kind().is_trivia()).cloned().collect()
+}
+
+/// The [span](Span) and [category](Category) of each token to highlight, in order
+/// 
+/// The tokens of the [Other](Category::Other) category aren't highlighted
+pub fn highlights<Kind:TokenCategory>(tokens:&[Token<Kind>]) -> Vec<(Span, Category)>{
+    tokens.iter()
+        .filter(|t| !t.is_synthetic())
+        .map(|t| (t.span(), t.kind.category()))
+        .filter(|(_, category)| *category != Category::Other)
+        .collect()
+}
+
+#[derive(Debug, Clone, PartialEq)]
+/// A set of [token kinds](TokenKind) with fast membership tests
+/// 
+/// Kinds with a [kind_index](TokenKind::kind_index) below 128 are stored in a bitset,
+/// the others in a [HashSet]
+/// 
+/// # Exemples
+/// ```rust
+/// use crate::neoglot_lib::{lexer::*, regex::*};
+/// 
+/// #[derive(PartialEq, PartialOrd, Eq, Copy, Clone, Debug, Hash)]
+/// enum TokenType{
+///     Plus, Minus, Star, Slash
+/// }
+/// 
+/// impl Symbol for TokenType{}
+/// impl TokenKind for TokenType{
+///     fn kind_index(&self) -> Option<usize>{ Some(*self as usize) }
+/// }
+/// 
+/// let additive = KindSet::of(&[TokenType::Plus, TokenType::Minus]);
+/// 
+/// assert!(additive.contains(TokenType::Minus));
+/// assert!(!additive.contains(TokenType::Star));
+/// ```
+pub struct KindSet<T:TokenKind>{
+    /// Kinds with a small index
+    bits: u128,
+
+    /// Every other kind
+    others: HashSet<T>
+}
+
+impl<T:TokenKind> Default for KindSet<T>{
+    fn default() -> Self { Self::new() }
+}
+
+impl<T:TokenKind> KindSet<T>{
+    /// Creates an empty set
+    pub fn new() -> Self{ KindSet { bits: 0, others: HashSet::new() } }
+
+    /// Creates a set holding *kinds*
+    pub fn of(kinds:&[T]) -> Self{
+        let mut set = Self::new();
+        for kind in kinds{ set.insert(*kind); }
+
+        set
+    }
+
+    /// Adds *kind* to the set
+    pub fn insert(&mut self, kind:T){
+        match kind.kind_index(){
+            Some(i) if i < 128 => self.bits |= 1 << i,
+            _ => { self.others.insert(kind); }
+        }
+    }
+
+    /// Removes *kind* from the set
+    pub fn remove(&mut self, kind:T){
+        match kind.kind_index(){
+            Some(i) if i < 128 => self.bits &= !(1 << i),
+            _ => { self.others.remove(&kind); }
+        }
+    }
+
+    /// Returns true if *kind* is in the set
+    pub fn contains(&self, kind:T) -> bool{
+        match kind.kind_index(){
+            Some(i) if i < 128 => self.bits & (1 << i) != 0,
+            _ => self.others.contains(&kind)
+        }
+    }
+
+    /// The number of kinds in the set
+    pub fn len(&self) -> usize{ self.bits.count_ones() as usize + self.others.len() }
+
+    /// Returns true if the set holds no kind
+    pub fn is_empty(&self) -> bool{ self.len() == 0 }
+}
+
+impl<T:TokenKind> FromIterator<T> for KindSet<T>{
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut set = Self::new();
+        for kind in iter{ set.insert(kind); }
+
+        set
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// Human-readable information on a [token kind](TokenKind)
+pub struct KindInfo{
+    /// A friendly name, as "arithmetic operator"
+    pub name: String,
+
+    /// A longer explanation, for generated documentation
+    pub description: Option<String>
+}
+
+#[derive(Debug, Clone, PartialEq)]
+/// Friendly names of [token kinds](TokenKind), used by error messages and tooling instead of their debug strings
+pub struct KindNames<T:TokenKind>{
+    infos: HashMap<T, KindInfo>
+}
+
+impl<T:TokenKind> Default for KindNames<T>{
+    fn default() -> Self { Self::new() }
+}
+
+impl<T:TokenKind> KindNames<T>{
+    pub fn new() -> Self{ KindNames { infos: HashMap::new() } }
+
+    /// Names *kind*, replacing its previous information
+    pub fn describe(&mut self, kind:T, name:&str, description:Option<&str>){
+        self.infos.insert(kind, KindInfo { name: name.to_string(), description: description.map(str::to_string) });
+    }
+
+    /// Names *kind*, builder style
+    pub fn with(mut self, kind:T, name:&str) -> Self{
+        self.describe(kind, name, None);
+        self
+    }
+
+    /// Names and describes *kind*, builder style
+    pub fn with_description(mut self, kind:T, name:&str, description:&str) -> Self{
+        self.describe(kind, name, Some(description));
+        self
+    }
+
+    /// Returns the information on *kind*, if it was described
+    pub fn info(&self, kind:T) -> Option<&KindInfo>{ self.infos.get(&kind) }
+
+    /// Returns the friendly name of *kind*, or its debug string if it has none
+    pub fn name(&self, kind:T) -> String{
+        self.info(kind).map_or_else(|| format!("{kind:?}"), |info| info.name.clone())
+    }
+
+    /// Iterates over the described kinds, in no particular order
+    pub fn iter(&self) -> impl Iterator<Item = (&T, &KindInfo)>{ self.infos.iter() }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+/// Where a [token](Token) comes from
+/// 
+/// Tokens that were not written by the user are synthetic,
+/// formatters can skip them and diagnostics should not underline them
+pub enum Origin{
+    /// Written by the user in a source file
+    #[default]
+    Written,
+
+    /// Inserted by error recovery in place of a missing token
+    Recovered,
+
+    /// Produced by a macro expansion or a code generator, see [Quote](crate::quote::Quote)
+    Expanded,
+
+    /// Produced by the lexer for the layout of the code, as the [end of input](Lexer::with_eof) or indentation tokens
+    Layout
+}
 
 #[derive(Debug, PartialEq, Clone)]
+#[non_exhaustive]
 /// A token is a lexical unit produced by a [Lexer]
+/// 
+/// Built with [new](Token::new) and the `with_*` methods, as fields may be added
 pub struct Token<TokenKind> {
     /// Where the token is in a file
     pub location: Location,
@@ -28,7 +401,78 @@ pub struct Token<TokenKind> {
     pub kind: TokenKind,
 
     /// The value held by the token
-    pub literal: String
+    pub literal: String,
+
+    /// Whether the token was written by the user or synthesized
+    pub origin: Origin,
+
+    /// The form later phases compare, if it differs from the literal, see [normalized](Token::normalized)
+    pub normalized: Option<String>
+}
+
+impl<Kind:TokenKind> Token<Kind>{
+    /// A token [written](Origin::Written) by the user, with nothing [normalized](Token::normalized)
+    pub fn new(location:Location, kind:Kind, literal:String) -> Self{
+        Token { location, kind, literal, origin: Origin::Written, normalized: None }
+    }
+
+    /// Sets where the token comes from, builder style
+    pub fn with_origin(mut self, origin:Origin) -> Self{
+        self.origin = origin;
+        self
+    }
+
+    /// Sets the form later phases compare instead of the literal, builder style
+    pub fn with_normalized(mut self, normalized:String) -> Self{
+        self.normalized = Some(normalized);
+        self
+    }
+
+    /// The [span](Span) covered by the literal of this token
+    /// 
+    /// A literal over several lines, as a block comment, ends on its last line
+    pub fn span(&self) -> Span{
+        let mut end = self.location.clone();
+
+        match source::line_breaks(&self.literal).last() {
+            Some((offset, ending)) => {
+                let last_line = offset + if ending == source::LineEnding::CrLf { 2 } else { 1 };
+
+                end.line(end.line + source::line_breaks(&self.literal).count());
+                end.column(self.literal[last_line..].chars().count());
+            },
+            None => end.column(end.column + self.literal.chars().count())
+        }
+
+        Span::new(self.location.clone(), end)
+    }
+
+    /// The literal of this token along with its [span](Span)
+    pub fn spanned(&self) -> Spanned<&str>{ Spanned::new(&self.literal, self.span()) }
+
+    /// The form of the literal later phases compare, as the lowercase spelling of a keyword
+    /// in a case-insensitive language, see [Lexer::with_keyword_normalization]
+    /// 
+    /// It is the literal itself for the tokens that weren't normalized
+    pub fn normalized(&self) -> &str{ self.normalized.as_deref().unwrap_or(&self.literal) }
+
+    /// Returns true if the token wasn't [written](Origin::Written) by the user
+    pub fn is_synthetic(&self) -> bool{ self.origin != Origin::Written }
+
+    /// Returns true if *next* starts right where this token ends, with no whitespace or comment in between
+    /// 
+    /// As in `a -1` where `-` touches `1` but not `a`
+    pub fn touches(&self, next:&Token<Kind>) -> bool{
+        let end = self.span().end;
+        end.file == next.location.file && end.line == next.location.line && end.column == next.location.column
+    }
+}
+
+/// Formats as `kind "literal" @ file:line:column`
+impl<Kind:TokenKind> Display for Token<Kind>{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?} {:?} @ {}", self.kind, self.literal, self.location)
+    }
 }
 
 /// A LexerNode match a set of characters into one type of [token](Token)
@@ -56,7 +500,7 @@ pub struct Token<TokenKind> {
 /// let candidate1 = "25+ world".chars().collect::<Vec<char>>();
 /// let candidate2 = "#test".chars().collect::<Vec<char>>();
 /// 
-/// let result1:(&[char], Option<Token<TokenType>>) = (&['+', ' ', 'w', 'o', 'r', 'l', 'd'], Some(Token{ location: location.clone(), kind:TokenType::UInt, literal: "25".to_string() }));
+/// let result1:(&[char], Option<Token<TokenType>>) = (&['+', ' ', 'w', 'o', 'r', 'l', 'd'], Some(Token::new(location.clone(), TokenType::UInt, "25".to_string())));
 /// 
 /// let result2:(&[char], Option<Token<TokenType>>) = (&['#', 't', 'e', 's', 't'], None);
 /// 
@@ -65,6 +509,7 @@ pub struct Token<TokenKind> {
 /// assert_eq!(uint_node.tokenize(&candidate2, &location), result2);
 /// 
 /// ```
+#[derive(Debug, Clone)]
 pub struct LexerNode<Kind:TokenKind> {
     /// The matching sequence
     regex: Regex<char>,
@@ -75,32 +520,78 @@ pub struct LexerNode<Kind:TokenKind> {
 }
 
 impl<Kind:TokenKind> LexerNode<Kind>{
-    pub fn new<'a>(regex: Regex<char>, kind:Kind) -> Self{ LexerNode{ regex, kind} }
+    pub fn new(regex: Regex<char>, kind:Kind) -> Self{ LexerNode{ regex, kind} }
+
+    /// The kind of the tokens made by this node
+    pub fn kind(&self) -> Kind{ self.kind }
 
     /// This function tries to construct the first token that match the matching sequence
     /// 
     /// It returns the rest of the unread characters and the [token](Token) that was found which can be [None] if no [token](Token) was found
     pub fn tokenize<'a>(&self, c:&'a [char], location: &Location) -> (&'a [char], Option<Token<Kind>>){
-        let (matched, others) = self.regex.split_first(c);
+        self.tokenize_within(c, c.len(), location)
+    }
+
+    // Tokenizes like tokenize, reading at most *length* characters while the assertions see all of them
+    fn tokenize_within<'a>(&self, c:&'a [char], length:usize, location: &Location) -> (&'a [char], Option<Token<Kind>>){
+        let (matched, others) = self.regex.split_first_within(c, length).unwrap_or((&[], c));
         let token = if matched.is_empty() { None } else {
             let literal = matched.iter().collect::<String>();
-            Some(Token{ location: location.clone(), kind: self.kind, literal})
+            Some(Token::new(location.clone(), self.kind, literal))
         };
 
         (others, token)
     }
 }
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
 /// Error type for the lexing process
+/// 
+/// Built with [new](LexingError::new) and the `with_*` methods, as fields may be added
 pub struct LexingError{
-    pub location: Location
+    pub location: Location,
+
+    /// The exceeded [limit](Limits), if the error comes from one rather than from an unknown token
+    pub limit: Option<Limit>,
+
+    /// The [names](Regex::with_name) of the nodes whose regex [may start](Regex::may_start) with the symbol there, in order
+    pub expected: Vec<String>
+}
+
+impl LexingError{
+    /// An unknown token at *location*
+    pub fn new(location:Location) -> Self{ LexingError { location, limit: None, expected: vec![] } }
+
+    /// Sets the exceeded [limit](Limits), builder style
+    pub fn with_limit(mut self, limit:Limit) -> Self{
+        self.limit = Some(limit);
+        self
+    }
+
+    /// Sets the names of the expected nodes, builder style
+    pub fn with_expected(mut self, expected:Vec<String>) -> Self{
+        self.expected = expected;
+        self
+    }
+}
+
+impl Reportable for LexingError{
+    fn message(&self) -> String{
+        match (self.limit, self.expected.split_last()) {
+            (Some(limit), _) => limit.to_string(),
+            (None, None) => String::from("Failed to parse token"),
+            (None, Some((last, []))) => format!("Failed to parse token, expected {last}"),
+            (None, Some((last, others))) => format!("Failed to parse token, expected {} or {last}", others.join(", "))
+        }
+    }
+
+    fn location(&self) -> Option<Location>{ Some(self.location.clone()) }
 }
 
 impl Display for LexingError{
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let msg = build_report("Failed to parse token", self.location.clone());
-        f.write_str(&msg)
+        f.write_str(&self.report())
     }
 }
 
@@ -112,8 +603,101 @@ pub enum LexingResult<T:TokenKind>{
     Ok(Vec<Token<T>>)
 }
 
+/// Iterator over the content of a [LexingResult]
+/// 
+/// Yields every [token](Token) wrapped in [Ok] or every [error](LexingError) wrapped in [Err]
+pub enum LexingResultIter<T:TokenKind>{
+    Ok(std::vec::IntoIter<Token<T>>),
+    Err(std::vec::IntoIter<LexingError>)
+}
+
+impl<T:TokenKind> Iterator for LexingResultIter<T>{
+    type Item = Result<Token<T>, LexingError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self {
+            LexingResultIter::Ok(tokens) => tokens.next().map(Ok),
+            LexingResultIter::Err(errors) => errors.next().map(Err)
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        match self {
+            LexingResultIter::Ok(tokens) => tokens.size_hint(),
+            LexingResultIter::Err(errors) => errors.size_hint()
+        }
+    }
+}
+
+impl<T:TokenKind> IntoIterator for LexingResult<T>{
+    type Item = Result<Token<T>, LexingError>;
+    type IntoIter = LexingResultIter<T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        match self {
+            LexingResult::Ok(tokens) => LexingResultIter::Ok(tokens.into_iter()),
+            LexingResult::Err(errors) => LexingResultIter::Err(errors.into_iter())
+        }
+    }
+}
+
+impl<T:TokenKind> FromIterator<Token<T>> for LexingResult<T>{
+    fn from_iter<I: IntoIterator<Item = Token<T>>>(iter: I) -> Self {
+        LexingResult::Ok(iter.into_iter().collect())
+    }
+}
+
+/// Collects the [tokens](Token), or every [error](LexingError) if there is at least one
+impl<T:TokenKind> FromIterator<Result<Token<T>, LexingError>> for LexingResult<T>{
+    fn from_iter<I: IntoIterator<Item = Result<Token<T>, LexingError>>>(iter: I) -> Self {
+        let mut tokens = vec![];
+        let mut errors = vec![];
+
+        for item in iter{
+            match item {
+                Ok(token) => tokens.push(token),
+                Err(e) => errors.push(e)
+            }
+        }
+
+        if !errors.is_empty(){ LexingResult::Err(errors) }
+        else { LexingResult::Ok(tokens) }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+/// A [LexerNode] that never produces tokens because earlier nodes always match first
+pub struct LexerConflict<Kind:TokenKind>{
+    /// Position of the shadowed node in the [Lexer]
+    pub node: usize,
+
+    /// Kind of the shadowed node
+    pub kind: Kind,
+
+    /// Inputs of the shadowed node, with the position and kind of the node that lexes them instead
+    pub examples: Vec<(String, usize, Kind)>
+}
+
+impl<Kind:TokenKind> Display for LexerConflict<Kind>{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Node #{} ({:?}) can never match:", self.node, self.kind)?;
+
+        for (input, node, kind) in &self.examples{
+            write!(f, " {input:?} is lexed by node #{node} ({kind:?});")?;
+        }
+
+        Ok(())
+    }
+}
+
 /// The Lexer performs a lexical analysis on characters and extract the [tokens](Token)
 /// 
+/// # Resolution order
+/// At each position the [nodes](LexerNode) are tried in the order they were registered,
+/// the first one matching at least one character makes the token and the next ones aren't tried,
+/// even if they would match a longer input. The nodes can be reordered with [move_node](Lexer::move_node),
+/// [check](Lexer::check) finds the nodes that never win
+/// 
 /// # Exemples
 /// ```rust
 /// use crate::neoglot_lib::{lexer::*, regex::*};
@@ -147,15 +731,17 @@ pub enum LexingResult<T:TokenKind>{
 /// match result{
 ///     LexingResult::Ok(tokens) =>{
 ///         assert_eq!(tokens, vec![
-///             Token{ location: location.clone(), kind:TokenType::UInt, literal:String::from("10") },
+///             Token::new(location.clone(), TokenType::UInt, String::from("10")),
 ///             
-///             Token{ location: Location{ file: String::new(), line:0, column:3 },
-///                 kind: TokenType::Plus, literal:String::from("+")
-///             },
+///             Token::new(
+///                 Location{ file: String::new(), line:0, column:3 },
+///                 TokenType::Plus, String::from("+")
+///             ),
 ///             
-///             Token{ location: Location{ file: String::new(), line:0, column:7 },
-///                 kind: TokenType::UInt, literal: String::from("25")
-///             }
+///             Token::new(
+///                 Location{ file: String::new(), line:0, column:7 },
+///                 TokenType::UInt, String::from("25")
+///             )
 ///         ]);
 ///     },
 /// 
@@ -163,18 +749,203 @@ pub enum LexingResult<T:TokenKind>{
 /// }
 /// 
 /// ```
+#[derive(Debug, Clone)]
 pub struct Lexer<Kind:TokenKind>{
-    nodes: Vec<LexerNode<Kind>>
+    nodes: Vec<LexerNode<Kind>>,
+
+    /// Kinds of the identifier tokens
+    identifiers: KindSet<Kind>,
+
+    /// Whether identifiers are normalized to NFC
+    #[cfg(feature = "unicode")]
+    nfc: bool,
+
+    /// Kinds of the keyword tokens, see [with_normalized_keywords](Lexer::with_normalized_keywords)
+    keywords: KindSet<Kind>,
+
+    /// How the literals of the keywords are normalized, if they are
+    normalize: Option<fn(&str) -> String>,
+
+    /// Kind of the token appended at the end of the input, if any
+    eof: Option<Kind>,
+
+    /// Friendly names of the token kinds
+    names: KindNames<Kind>,
+
+    /// Bounds on the token lengths and input sizes
+    limits: Limits
+}
+
+impl<Kind: TokenKind> Default for Lexer<Kind>{
+    fn default() -> Self { Self::new() }
 }
 
 impl<Kind: TokenKind> Lexer<Kind>{
-    pub fn new() -> Self {Lexer { nodes: vec![] }}
+    pub fn new() -> Self {
+        Lexer {
+            nodes: vec![],
+            identifiers: KindSet::new(),
+            #[cfg(feature = "unicode")]
+            nfc: false,
+            keywords: KindSet::new(),
+            normalize: None,
+            eof: None,
+            names: KindNames::new(),
+            limits: Limits::new()
+        }
+    }
 
     /// Adds a [LexerNode] to this Lexer
     pub fn register(&mut self, node: LexerNode<Kind>) {
         self.nodes.push(node);
     }
 
+    /// Adds a [LexerNode] to this Lexer, builder style
+    pub fn with(mut self, node: LexerNode<Kind>) -> Self{
+        self.register(node);
+        self
+    }
+
+    /// The [nodes](LexerNode) of this Lexer, in the order they are tried
+    pub fn nodes(&self) -> &[LexerNode<Kind>]{ &self.nodes }
+
+    /// The position of the first node making tokens of *kind*, if any
+    pub fn position(&self, kind:Kind) -> Option<usize>{ self.nodes.iter().position(|node| node.kind == kind) }
+
+    /// Inserts a [LexerNode] at *index*, before the nodes from *index* on,
+    /// or at the end if *index* is past it
+    pub fn insert_node(&mut self, index:usize, node: LexerNode<Kind>){
+        self.nodes.insert(index.min(self.nodes.len()), node);
+    }
+
+    /// Removes the node at *index* and returns it, None if there is none
+    pub fn remove_node(&mut self, index:usize) -> Option<LexerNode<Kind>>{
+        (index < self.nodes.len()).then(|| self.nodes.remove(index))
+    }
+
+    /// Moves the node at *from* to *to*, shifting the nodes in between
+    /// 
+    /// Returns false and does nothing if either position is out of the nodes
+    pub fn move_node(&mut self, from:usize, to:usize) -> bool{
+        if from >= self.nodes.len() || to >= self.nodes.len() { return false; }
+
+        let node = self.nodes.remove(from);
+        self.nodes.insert(to, node);
+        true
+    }
+
+    /// Gives a friendly name and an optional description to a kind of tokens, builder style
+    pub fn with_kind_name(mut self, kind:Kind, name:&str, description:Option<&str>) -> Self{
+        self.names.describe(kind, name, description);
+        self
+    }
+
+    /// The friendly names of the token kinds
+    pub fn names(&self) -> &KindNames<Kind>{ &self.names }
+
+    /// Iterates over the kinds of tokens this Lexer may produce, in registration order
+    pub fn kinds(&self) -> impl Iterator<Item = Kind> + '_{
+        self.nodes.iter().map(|node| node.kind)
+    }
+
+    /// Marks the kinds of the identifier tokens, builder style
+    pub fn with_identifiers(mut self, kinds:&[Kind]) -> Self{
+        for kind in kinds{ self.identifiers.insert(*kind); }
+        self
+    }
+
+    /// Normalizes the identifiers to NFC while lexing, builder style
+    /// 
+    /// The literal of a normalized identifier may differ from the source text
+    #[cfg(feature = "unicode")]
+    pub fn with_nfc(mut self) -> Self{
+        self.nfc = true;
+        self
+    }
+
+    /// Gives the tokens of the kinds *kinds* the [normalized](Token::normalized) form *normalize* returns, builder style
+    /// 
+    /// The literals keep the user's spelling for the diagnostics,
+    /// so that case-insensitive languages can compare `SELECT` and `select` through their lowercase form
+    /// 
+    /// # Exemples
+    /// ```rust
+    /// use crate::neoglot_lib::{lexer::*, regex::*};
+    /// 
+    /// #[derive(PartialEq, PartialOrd, Eq, Copy, Clone, Debug, Hash)]
+    /// enum TokenType{ Select }
+    /// 
+    /// impl Symbol for TokenType{}
+    /// impl TokenKind for TokenType{}
+    /// 
+    /// let select = "select".chars().fold(Regex::new(), |regex, c| regex.then(RegexElement::Item(c, Quantifier::Exactly(1))));
+    /// let lexer = Lexer::new()
+    ///     .with(LexerNode::new(select.case_insensitive(true), TokenType::Select))
+    ///     .with_normalized_keywords(&[TokenType::Select], str::to_lowercase);
+    /// 
+    /// let LexingResult::Ok(tokens) = lexer.tokenize_content("SeLeCt".to_string(), "main") else { panic!() };
+    /// 
+    /// assert_eq!(tokens[0].literal, "SeLeCt");
+    /// assert_eq!(tokens[0].normalized(), "select");
+    /// ```
+    pub fn with_normalized_keywords(mut self, kinds:&[Kind], normalize:fn(&str) -> String) -> Self{
+        for kind in kinds{ self.keywords.insert(*kind); }
+        self.normalize = Some(normalize);
+        self
+    }
+
+    /// Appends a token of kind *kind* at the end of every input, builder style
+    /// 
+    /// The token has an empty literal and the location right after the last character,
+    /// so that grammars can match the end of input explicitly and report errors there
+    pub fn with_eof(mut self, kind:Kind) -> Self{
+        self.eof = Some(kind);
+        self
+    }
+
+    /// Bounds the inputs, builder style
+    /// 
+    /// Inputs larger than the input size limit are rejected without being lexed,
+    /// tokens longer than the token length limit are reported with the rest of their line skipped.
+    /// Nodes read at most one symbol more than that limit, so long tokens cost no more than short ones,
+    /// while their assertions see the whole line
+    pub fn with_limits(mut self, limits:Limits) -> Self{
+        self.limits = limits;
+        self
+    }
+
+    /// Looks for mixed-script and confusable identifiers, see [check_identifiers](identifiers::check_identifiers)
+    pub fn check_identifiers(&self, tokens:&[Token<Kind>]) -> Vec<IdentifierWarning>{
+        identifiers::check_identifiers(tokens, |t| self.identifiers.contains(t.kind))
+    }
+
+    /// Finds the [nodes](LexerNode) shadowed by earlier ones
+    /// 
+    /// A node is reported when every input its regex accepts starts with an input of an earlier node, which is tried first,
+    /// see [is_shadowed_by](Regex::is_shadowed_by). The nodes whose regexes can't be [compiled](Regex::try_compile),
+    /// as the ones with assertions, are left out of the comparisons.
+    /// Each conflict comes with a sample of the inputs of the node and the earlier nodes lexing them
+    pub fn check(&self) -> Vec<LexerConflict<Kind>>{
+        const SAMPLES:usize = 32;
+
+        let comparable = self.nodes.iter().map(|node| node.regex.try_compile().is_ok()).collect::<Vec<bool>>();
+
+        self.nodes.iter().enumerate().filter(|(i, _)| comparable[*i]).filter_map(|(i, node)| {
+            let earlier = self.nodes[..i].iter().zip(&comparable).filter(|(_, comparable)| **comparable).map(|(earlier, _)| &earlier.regex);
+            if !node.regex.is_shadowed_by(earlier).unwrap_or(false) { return None; }
+
+            let examples = node.regex.examples(SAMPLES).into_iter().filter(|input| !input.is_empty()).filter_map(|input| {
+                let chars = input.chars().collect::<Vec<char>>();
+                let (winner, earlier) = self.nodes[..i].iter().enumerate()
+                    .find(|(_, earlier)| !earlier.regex.split_first(&chars).0.is_empty())?;
+
+                Some((input, winner, earlier.kind))
+            }).collect();
+
+            Some(LexerConflict { node: i, kind: node.kind, examples })
+        }).collect()
+    }
+
     /// Extracts the [tokens](Token) from a [String]
     /// 
     /// content: The source [String] to extract the [tokens](Token) from
@@ -186,27 +957,77 @@ impl<Kind: TokenKind> Lexer<Kind>{
 
         let mut errors:Vec<LexingError> = vec![];
 
-        for line_content in content.lines() {
+        if let Err(limit) = self.limits.check_input_size(content.len()){
+            return LexingResult::Err(vec![LexingError::new(location).with_limit(limit)]);
+        }
+
+        for line_content in source::lines(&content) {
             let mut stream = line_content.chars().collect::<Vec<char>>();
 
             while !stream.is_empty(){
                 let mut matched = false;
+
+                // The nodes only read one symbol more than the longest token allowed, their assertions see the whole line
+                let window = self.limits.max_token_length.map_or(stream.len(), |max| stream.len().min(max + 1));
+                let truncated = window < stream.len();
+
                 for node in &self.nodes{
-                    let (others, result) = node.tokenize(&stream, &location);
-                    
+                    let (others, result) = node.tokenize_within(&stream, window, &location);
+
+                    // A token filling the window, or a pattern still going at its end, is too long.
+                    // Where it would end isn't known, so the rest of the line is skipped
+                    let too_long = match &result {
+                        Some(token) => self.limits.check_token_length(token.literal.chars().count()).err(),
+                        None if truncated && node.regex.longest_prefix_within(&stream, window).is_ok_and(|(prefix, _)| prefix == window) => self.limits.check_token_length(window).err(),
+                        None => None
+                    };
+
+                    if let Some(limit) = too_long{
+                        errors.push(LexingError::new(location.clone()).with_limit(limit));
+                        location.column(location.column + stream.len());
+                        stream.clear();
+                        matched = true;
+                        break;
+                    }
+
                     // If a token was found, add it to the list
                     // and updates location to the start of the next token
 
                     if let Some(token) = result{
-                        location.column(location.column + token.literal.len());
+                        location.column(location.column + token.literal.chars().count());
+                        let others = others.to_vec();
+
+                        #[cfg(feature = "unicode")]
+                        let token = if self.nfc && self.identifiers.contains(token.kind) {
+                            Token { literal: identifiers::normalize(&token.literal), ..token }
+                        } else { token };
+
+                        let token = match self.normalize.filter(|_| self.keywords.contains(token.kind)) {
+                            Some(normalize) => match normalize(&token.literal) {
+                                normalized if normalized == token.literal => token,
+                                normalized => token.with_normalized(normalized)
+                            },
+                            None => token
+                        };
+
                         tokens.push(token);
-                        stream = others.to_vec();
+                        stream = others;
                         matched = true;
+
+                        // The next token is looked for from the first node again
+                        break;
                     }
                 }
 
                 if !matched{
-                    if !stream[0].is_whitespace(){ errors.push(LexingError { location: location.clone() }) }
+                    if !stream[0].is_whitespace(){
+                        let expected = self.nodes.iter()
+                            .filter(|node| node.regex.may_start(&stream[0]))
+                            .filter_map(|node| node.regex.name().map(String::from))
+                            .collect();
+
+                        errors.push(LexingError::new(location.clone()).with_expected(expected));
+                    }
 
                     stream.remove(0);
                     location.column(location.column +1);
@@ -235,6 +1056,16 @@ impl<Kind: TokenKind> Lexer<Kind>{
 
         }
 
+        if let Some(kind) = self.eof{
+            // Right after the last character, which ends the last line unless it is a line break
+            if !content.is_empty() && !content.ends_with(['\n', '\r']){
+                location.line(location.line - 1);
+                location.column(source::lines(&content).last().map_or(0, |l| l.chars().count()));
+            }
+
+            tokens.push(Token::new(location, kind, String::new()).with_origin(Origin::Layout));
+        }
+
         if !errors.is_empty(){ LexingResult::Err(errors) }
         else { LexingResult::Ok(tokens) }
 
@@ -244,11 +1075,17 @@ impl<Kind: TokenKind> Lexer<Kind>{
     /// 
     /// path: The path to the file to extract the [tokens](Token) from
     pub fn tokenize_file(&self, path: &str) -> LexingResult<Kind>{
-        let content = fs::read_to_string(path);
         let location = Location { file: path.to_string(), line: 0, column: 0 };
 
+        // Large files are rejected before being read
+        if let Some(Err(limit)) = fs::metadata(path).ok().map(|m| self.limits.check_input_size(m.len() as usize)){
+            return LexingResult::Err(vec![LexingError::new(location).with_limit(limit)]);
+        }
+
+        let content = fs::read_to_string(path);
+
         // Could not read the file
-        if content.is_err() { return LexingResult::Err(vec![LexingError { location }]) }
+        if content.is_err() { return LexingResult::Err(vec![LexingError::new(location)]) }
 
         self.tokenize_content(content.unwrap(), path)
 
@@ -1,6 +1,9 @@
 //! Neoglot is a library helping creating your own programming language.
 
+use std::path::{Path, PathBuf};
+
 use lexer::Location;
+use source::SourceManager;
 
 /// A module for building abstract regular expressions
 /// 
@@ -12,34 +15,229 @@ pub mod regex;
 /// Extract tokens from files
 pub mod lexer;
 
+/// Byte lexing module
+/// 
+/// Extract tokens from bytes
+pub mod bytes;
+
+/// Quoting module
+/// 
+/// Builds token streams for code generation
+pub mod quote;
+
+/// Numbers module
+/// 
+/// Lexes numeric literals and converts them to values
+pub mod numbers;
+
+/// Escapes module
+/// 
+/// Decodes the escape sequences of string literals
+pub mod escapes;
+
+/// Identifier checks module
+/// 
+/// Detects mixed-script and confusable identifiers
+pub mod identifiers;
+
+/// Source files module
+/// 
+/// Keeps source files and maps offsets to locations
+pub mod source;
+
+/// Suggestions module
+/// 
+/// Finds known names close to a misspelled one
+pub mod suggestion;
+
+/// Limits module
+/// 
+/// Bounds the work done on adversarial inputs
+pub mod limits;
+
+/// Compact tokens module
+/// 
+/// Stores tokens as places in their sources to save memory
+pub mod compact;
+
+/// Caching module
+/// 
+/// Stores tokens and ASTs in a compact binary form to skip lexing and parsing unchanged files
+pub mod cache;
+
+/// Configuration module
+/// 
+/// Gathers the settings of every stage of a language
+pub mod config;
+
+/// Pipeline module
+/// 
+/// Runs the stages of a compilation over directories and reports its progress to the tools wrapping it
+pub mod pipeline;
+
+/// Fuzzing module
+/// 
+/// Generates random programs to compare parsers
+pub mod fuzz;
+
+/// Warnings module
+/// 
+/// Sorts warnings into categories whose levels users choose
+pub mod warnings;
+
+/// Statistics module
+/// 
+/// Counts how token kinds follow each other in lexed code and which rules consume them
+pub mod stats;
+
+/// Fixes module
+/// 
+/// Rewrites sources with the fix-its suggested by diagnostics
+pub mod fixes;
+
+/// Test suite module
+/// 
+/// Checks the diagnostics of a language against the expectations written in its test files
+pub mod suite;
+
 /// Semantical analysis module
 /// 
 /// Extracts Abstract Syntax Trees from tokens
 pub mod parser;
 
+/// Abstract interpretation module
+/// 
+/// Computes abstract values over control flow graphs
+pub mod analysis;
+
+/// An error that can be rendered by [build_report]
+pub trait Reportable{
+    /// A human readable description of the error
+    fn message(&self) -> String;
+
+    /// Where the error happened, if it is known
+    fn location(&self) -> Option<Location>;
+
+    /// Renders the error with the line where it happened
+    fn report(&self) -> String{
+        self.report_with(&ReportStyle::new())
+    }
+
+    /// Renders the error like [report](Reportable::report), with the paths shown as *style* says
+    fn report_with(&self, style:&ReportStyle) -> String{
+        match self.location(){
+            Some(loc) => build_report_with(&self.message(), loc, style),
+            None => self.message()
+        }
+    }
+
+    /// Renders the error like [report_with](Reportable::report_with),
+    /// taking the line from *sources* when its file is known there, see [build_report_in]
+    fn report_in(&self, sources:&SourceManager, style:&ReportStyle) -> String{
+        match self.location(){
+            Some(loc) => build_report_in(&self.message(), loc, style, sources),
+            None => self.message()
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+/// How the file paths of the reports are shown
+pub struct ReportStyle{
+    /// Paths inside this directory are shown relative to it
+    root: Option<PathBuf>,
+
+    /// Whether paths are hyperlinks to the files, using the OSC 8 terminal sequence
+    hyperlinks: bool
+}
+
+impl ReportStyle{
+    /// Paths are shown as they are, without hyperlinks
+    pub fn new() -> Self{ ReportStyle { root: None, hyperlinks: false } }
+
+    /// Shows the paths inside *root* relative to it, builder style
+    pub fn with_root(mut self, root:impl Into<PathBuf>) -> Self{
+        self.root = Some(root.into());
+        self
+    }
+
+    /// Makes the paths hyperlinks to the files in the terminals supporting them, builder style
+    pub fn with_hyperlinks(mut self) -> Self{
+        self.hyperlinks = true;
+        self
+    }
+
+    /// Renders a file path following this style
+    pub fn path(&self, file:&str) -> String{
+        let path = Path::new(file);
+        let absolute = std::fs::canonicalize(path)
+            .or_else(|_| std::env::current_dir().map(|dir| dir.join(path)))
+            .unwrap_or_else(|_| path.to_path_buf());
+
+        let shown = self.root.as_ref()
+            .and_then(|root| {
+                let root = std::fs::canonicalize(root).unwrap_or_else(|_| root.clone());
+                absolute.strip_prefix(root).ok().or_else(|| path.strip_prefix(self.root.as_ref()?).ok())
+            })
+            .map_or_else(|| file.to_string(), |relative| relative.display().to_string());
+
+        if self.hyperlinks { format!("\x1b]8;;file://{}\x1b\\{shown}\x1b]8;;\x1b\\", absolute.display()) }
+        else { shown }
+    }
+}
+
 /// Build an error message
 pub fn build_report(message:&str, loc:Location) -> String{
-    use std::fs::File;
-    use std::io::BufReader;
-    use std::io::prelude::*;
+    build_report_with(message, loc, &ReportStyle::new())
+}
+
+/// Build an error message, with the file path shown as *style* says
+/// 
+/// The file is read up to the line of the error only
+pub fn build_report_with(message:&str, loc:Location, style:&ReportStyle) -> String{
+    let line = read_line(&loc.file, loc.line);
+    render_report(message, &loc, style, line.as_deref())
+}
 
-    if let Ok(file) = File::open(loc.file.clone()){
-        let mut reader = BufReader::new(file);
-        let mut contents = String::new();
+/// Build an error message like [build_report_with], taking the line from the [line index](source::Source::line)
+/// of the source in *sources* instead of reading the file
+/// 
+/// The files unknown to *sources* are read as by [build_report_with], so rendering many errors
+/// against large files only reads each of them once, when loading them in *sources*
+pub fn build_report_in(message:&str, loc:Location, style:&ReportStyle, sources:&SourceManager) -> String{
+    match sources.get(&loc.file) {
+        Some(source) => render_report(message, &loc, style, source.line(loc.line)),
+        None => build_report_with(message, loc, style)
+    }
+}
 
-        if reader.read_to_string(&mut contents).is_ok(){
-            let line = contents.lines().nth(loc.line).unwrap();
+// Renders an error message with the line it happened on, if known
+fn render_report(message:&str, loc:&Location, style:&ReportStyle, line:Option<&str>) -> String{
+    let file = style.path(&loc.file);
 
-            let end = line.len() - loc.column;
-            let highlighted = highlight(line, loc.column, end);
+    match line {
+        Some(line) => format!("{message} at {file} {}:{}\n{}", loc.line, loc.column, highlight(line, loc.column, line.chars().count())),
+        None => format!("{message} at {file} {}:{}", loc.line, loc.column)
+    }
+}
 
-            format!("{message} at {} {}:{}\n{highlighted}", loc.file, loc.line, loc.column)
-        }else{
-            format!("{message} at {} {}:{}", loc.file, loc.line, loc.column)
-        }
+// Reads the line *line* of a file without its line break, stopping there
+fn read_line(path:&str, line:usize) -> Option<String>{
+    use std::io::{BufRead, BufReader};
+
+    let mut reader = BufReader::new(std::fs::File::open(path).ok()?);
+    let mut current = 0;
 
-    }else{
-        format!("{message} at {} {}:{}", loc.file, loc.line, loc.column)
+    loop{
+        let mut chunk = vec![];
+        if reader.read_until(b'\n', &mut chunk).ok()? == 0 { return None; }
+
+        // A chunk ends with the only `\n` in it, but lone `\r` may split it into more lines
+        let text = String::from_utf8(chunk).ok()?;
+        for content in source::lines(&text){
+            if current == line { return Some(content.to_string()); }
+            current += 1;
+        }
     }
 }
 
@@ -50,7 +248,7 @@ pub fn report(message:&str, loc:Location){
 
 /// Highlights an area under a text
 fn highlight(text:&str, start:usize, end:usize) -> String{
-    let size = end - start;
+    let size = end.saturating_sub(start);
     format!("{text}\n{}{}", " ".repeat(start), "^".repeat(size))
 }
 
@@ -0,0 +1,198 @@
+use std::{fmt::Display, error::Error, time::{Duration, Instant}};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+/// Bounds on the work done on a single input
+/// 
+/// Every limit is disabled by default, except the [stack](Limits::max_stack) that is always bounded
+/// 
+/// # Exemples
+/// ```rust
+/// use crate::neoglot_lib::limits::Limits;
+/// use std::time::Duration;
+/// 
+/// let limits = Limits::new()
+///     .with_max_depth(64)
+///     .with_max_tokens(10_000)
+///     .with_time_budget(Duration::from_millis(50));
+/// 
+/// assert_eq!(limits.max_depth, Some(64));
+/// ```
+pub struct Limits{
+    /// How deeply rules may nest
+    pub max_depth: Option<usize>,
+
+    /// How many tokens (or symbols) may be consumed
+    pub max_tokens: Option<usize>,
+
+    /// How long the work may take
+    pub time_budget: Option<Duration>,
+
+    /// How many characters a single token may hold
+    pub max_token_length: Option<usize>,
+
+    /// How many bytes an input may hold
+    pub max_input_size: Option<usize>,
+
+    /// How many bytes of stack the recursive algorithms may use on a single input,
+    /// [DEFAULT_MAX_STACK] if unset
+    /// 
+    /// Deeply nested inputs then fail with [Limit::Stack] instead of overflowing the stack.
+    /// Work done on a thread with a small stack should lower it
+    pub max_stack: Option<usize>,
+
+    /// How many times a repeated group of a regex may match in a row
+    pub max_repetitions: Option<usize>
+}
+
+/// The stack the recursive algorithms may use when [Limits::max_stack] is unset, in bytes
+/// 
+/// This fits the 2 MiB given to the threads spawned by the standard library
+pub const DEFAULT_MAX_STACK: usize = 1 << 20;
+
+impl Limits{
+    pub fn new() -> Self{ Limits { max_depth: None, max_tokens: None, time_budget: None, max_token_length: None, max_input_size: None, max_stack: None, max_repetitions: None } }
+
+    pub fn with_max_depth(mut self, depth:usize) -> Self{
+        self.max_depth = Some(depth);
+        self
+    }
+
+    pub fn with_max_tokens(mut self, tokens:usize) -> Self{
+        self.max_tokens = Some(tokens);
+        self
+    }
+
+    pub fn with_time_budget(mut self, budget:Duration) -> Self{
+        self.time_budget = Some(budget);
+        self
+    }
+
+    pub fn with_max_token_length(mut self, length:usize) -> Self{
+        self.max_token_length = Some(length);
+        self
+    }
+
+    pub fn with_max_input_size(mut self, size:usize) -> Self{
+        self.max_input_size = Some(size);
+        self
+    }
+
+    pub fn with_max_stack(mut self, bytes:usize) -> Self{
+        self.max_stack = Some(bytes);
+        self
+    }
+
+    pub fn with_max_repetitions(mut self, repetitions:usize) -> Self{
+        self.max_repetitions = Some(repetitions);
+        self
+    }
+
+    /// Returns the exceeded limit if *depth* is too deep
+    pub fn check_depth(&self, depth:usize) -> Result<(), Limit>{
+        match self.max_depth {
+            Some(max) if depth > max => Err(Limit::Depth(max)),
+            _ => Ok(())
+        }
+    }
+
+    /// Returns the exceeded limit if *tokens* are too many
+    pub fn check_tokens(&self, tokens:usize) -> Result<(), Limit>{
+        match self.max_tokens {
+            Some(max) if tokens > max => Err(Limit::Tokens(max)),
+            _ => Ok(())
+        }
+    }
+
+    /// Returns the exceeded limit if a token of *length* characters is too long
+    pub fn check_token_length(&self, length:usize) -> Result<(), Limit>{
+        match self.max_token_length {
+            Some(max) if length > max => Err(Limit::TokenLength(max)),
+            _ => Ok(())
+        }
+    }
+
+    /// Returns the exceeded limit if a group repeated *repetitions* times in a row is repeated too much
+    pub fn check_repetitions(&self, repetitions:usize) -> Result<(), Limit>{
+        match self.max_repetitions {
+            Some(max) if repetitions > max => Err(Limit::Repetitions(max)),
+            _ => Ok(())
+        }
+    }
+
+    /// Returns the exceeded limit if an input of *size* bytes is too large
+    pub fn check_input_size(&self, size:usize) -> Result<(), Limit>{
+        match self.max_input_size {
+            Some(max) if size > max => Err(Limit::InputSize(max)),
+            _ => Ok(())
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// A [limit](Limits) that has been exceeded, along with its configured value
+pub enum Limit{
+    Depth(usize),
+    Tokens(usize),
+    Time(Duration),
+    TokenLength(usize),
+    InputSize(usize),
+    Stack(usize),
+    Repetitions(usize)
+}
+
+impl Display for Limit{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Limit::Depth(max) => write!(f, "Maximum depth of {max} exceeded"),
+            Limit::Tokens(max) => write!(f, "Maximum of {max} tokens exceeded"),
+            Limit::Time(budget) => write!(f, "Time budget of {budget:?} exceeded"),
+            Limit::TokenLength(max) => write!(f, "Maximum token length of {max} characters exceeded"),
+            Limit::InputSize(max) => write!(f, "Maximum input size of {max} bytes exceeded"),
+            Limit::Stack(max) => write!(f, "Input nested too deeply, maximum stack of {max} bytes exceeded"),
+            Limit::Repetitions(max) => write!(f, "Maximum of {max} repetitions exceeded")
+        }
+    }
+}
+
+impl Error for Limit{}
+
+#[derive(Debug, Clone, Copy)]
+/// The point in time a [time budget](Limits::time_budget) runs out
+/// and the point of the stack the [stack limit](Limits::max_stack) is measured from
+pub(crate) struct Deadline{
+    at: Option<(Instant, Duration)>,
+
+    /// The stack position at the start and the stack allowed from there
+    stack: (usize, usize)
+}
+
+// The address of a local variable, telling how deep the stack is
+#[inline(never)]
+fn stack_position() -> usize{
+    let marker = 0u8;
+    std::hint::black_box(&marker) as *const u8 as usize
+}
+
+impl Deadline{
+    /// Starts the clock of the time budget of *limits*
+    pub(crate) fn start(limits:&Limits) -> Self{
+        Deadline {
+            at: limits.time_budget.map(|budget| (Instant::now() + budget, budget)),
+            stack: (stack_position(), limits.max_stack.unwrap_or(DEFAULT_MAX_STACK))
+        }
+    }
+
+    /// Returns the exceeded limit if the deadline has passed or if the stack grew too much since the start
+    /// 
+    /// Recursive algorithms call it at each level, so that deep inputs fail before overflowing the stack
+    pub(crate) fn check(&self) -> Result<(), Limit>{
+        let (base, max_stack) = self.stack;
+        if stack_position().abs_diff(base) > max_stack { return Err(Limit::Stack(max_stack)); }
+
+        match self.at {
+            Some((at, budget)) if Instant::now() > at => Err(Limit::Time(budget)),
+            _ => Ok(())
+        }
+    }
+}
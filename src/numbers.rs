@@ -0,0 +1,318 @@
+use std::{fmt::Display, error::Error};
+
+use crate::{lexer::{LexerNode, Location, Token, TokenKind}, regex::{Quantifier, Regex, RegexElement}, Reportable};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+/// The value of a numeric literal
+pub enum NumberValue{
+    Integer(u128),
+    Float(f64)
+}
+
+#[derive(Debug, Clone, PartialEq)]
+/// A numeric literal converted by a [NumberFormat]
+pub struct Number{
+    pub value: NumberValue,
+
+    /// 16, 8 or 2 if the literal had a radix prefix, 10 otherwise
+    pub radix: u32,
+
+    /// The suffix ending the literal, as in `1u8`
+    pub suffix: Option<String>
+}
+
+#[derive(Debug, Clone, PartialEq)]
+/// Error type of the numeric literals conversion
+/// 
+/// Each error holds the location of the faulty character
+pub enum NumberError{
+    /// A character that isn't part of the number nor of a known suffix
+    UnexpectedCharacter(Location),
+
+    /// A radix prefix, a fraction dot or an exponent marker isn't followed by any digit
+    MissingDigits(Location),
+
+    /// The integer doesn't fit in 128 bits
+    Overflow(Location)
+}
+
+impl Reportable for NumberError{
+    fn message(&self) -> String{
+        match self {
+            NumberError::UnexpectedCharacter(_) => String::from("Unexpected character in number"),
+            NumberError::MissingDigits(_) => String::from("Missing digits in number"),
+            NumberError::Overflow(_) => String::from("Number too large")
+        }
+    }
+
+    fn location(&self) -> Option<Location>{
+        match self {
+            NumberError::UnexpectedCharacter(loc)
+            | NumberError::MissingDigits(loc)
+            | NumberError::Overflow(loc) => Some(loc.clone())
+        }
+    }
+}
+
+impl Display for NumberError{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.report())
+    }
+}
+
+impl Error for NumberError{}
+
+#[derive(Debug, Clone, PartialEq)]
+/// Describes the numeric literals of a language, to lex them and convert them to values
+/// 
+/// By default only decimal integers are accepted, signs are left to the operators
+/// 
+/// # Exemples
+/// ```rust
+/// use crate::neoglot_lib::{lexer::*, numbers::*, regex::Symbol};
+/// 
+/// #[derive(PartialEq, PartialOrd, Eq, Copy, Clone, Debug, Hash)]
+/// enum TokenType{ Number }
+/// 
+/// impl Symbol for TokenType{}
+/// impl TokenKind for TokenType{}
+/// 
+/// let format = NumberFormat::new()
+///     .with_radix_prefixes()
+///     .with_underscores()
+///     .with_fractions()
+///     .with_exponents()
+///     .with_suffixes(&["u8", "f64"]);
+/// 
+/// let lexer = Lexer::new().with(format.node(TokenType::Number));
+/// 
+/// let LexingResult::Ok(tokens) = lexer.tokenize_content("0xff_u8 1_000.5e-1f64".to_string(), "") else { panic!() };
+/// 
+/// assert_eq!(format.parse_token(&tokens[0]), Ok(Number{ value: NumberValue::Integer(255), radix: 16, suffix: Some("u8".to_string()) }));
+/// assert_eq!(format.parse_token(&tokens[1]).map(|n| n.value), Ok(NumberValue::Float(100.05)));
+/// ```
+pub struct NumberFormat{
+    /// Whether `0x`, `0o` and `0b` prefixes are accepted
+    radix_prefixes: bool,
+
+    /// Whether underscores may separate the digits, after the first one
+    underscores: bool,
+
+    /// Whether decimal numbers may have a fraction, as in `3.14`
+    fractions: bool,
+
+    /// Whether decimal numbers may have an exponent, as in `1e-3`
+    exponents: bool,
+
+    /// The suffixes that may end a literal
+    suffixes: Vec<String>
+}
+
+// The radix given by a prefix
+fn prefix_radix(prefix:char) -> Option<u32>{
+    match prefix {
+        'x' | 'X' => Some(16),
+        'o' | 'O' => Some(8),
+        'b' | 'B' => Some(2),
+        _ => None
+    }
+}
+
+// One digit of a radix
+fn digit(radix:u32) -> RegexElement<char>{
+    let once = Quantifier::Exactly(1);
+
+    match radix {
+        16 => RegexElement::AnyOf(vec![RegexElement::Set('0', '9', once), RegexElement::Set('a', 'f', once), RegexElement::Set('A', 'F', once)], once),
+        _ => RegexElement::Set('0', char::from_digit(radix - 1, radix).unwrap_or('9'), once)
+    }
+}
+
+// An element that may be absent, without consuming anything if it is only partially there
+fn optional(elements:Vec<RegexElement<char>>) -> RegexElement<char>{
+    RegexElement::Group(vec![RegexElement::Group(elements, Quantifier::Exactly(1))], Quantifier::ZeroOrOne)
+}
+
+impl Default for NumberFormat{
+    fn default() -> Self { Self::new() }
+}
+
+impl NumberFormat{
+    pub fn new() -> Self{
+        NumberFormat { radix_prefixes: false, underscores: false, fractions: false, exponents: false, suffixes: vec![] }
+    }
+
+    /// Accepts hexadecimal `0x`, octal `0o` and binary `0b` integers, builder style
+    pub fn with_radix_prefixes(mut self) -> Self{
+        self.radix_prefixes = true;
+        self
+    }
+
+    /// Accepts underscores between the digits, as in `1_000`, builder style
+    pub fn with_underscores(mut self) -> Self{
+        self.underscores = true;
+        self
+    }
+
+    /// Accepts fractions in decimal numbers, as in `3.14`, builder style
+    /// 
+    /// The dot must be followed by a digit, so `3.max` is not a number followed by `max`
+    pub fn with_fractions(mut self) -> Self{
+        self.fractions = true;
+        self
+    }
+
+    /// Accepts exponents in decimal numbers, as in `1e-3` or `2E8`, builder style
+    pub fn with_exponents(mut self) -> Self{
+        self.exponents = true;
+        self
+    }
+
+    /// Accepts *suffixes* at the end of the literals, as in `1u8`, builder style
+    /// 
+    /// The digits are read first, so a suffix starting with a digit of the literal isn't recognized, as `f32` in `0x1f32`
+    pub fn with_suffixes(mut self, suffixes:&[&str]) -> Self{
+        self.suffixes.extend(suffixes.iter().map(|s| s.to_string()));
+
+        // The longest suffixes are tried first
+        self.suffixes.sort_by_key(|s| std::cmp::Reverse(s.chars().count()));
+        self
+    }
+
+    // The digits of a radix, separated by underscores if allowed
+    fn digits(&self, radix:u32) -> Vec<RegexElement<char>>{
+        let mut rest = vec![digit(radix)];
+        if self.underscores { rest.push(RegexElement::Item('_', Quantifier::Exactly(1))); }
+
+        vec![digit(radix), RegexElement::AnyOf(rest, Quantifier::ZeroOrMany)]
+    }
+
+    /// The regex matching the literals of this format
+    pub fn regex(&self) -> Regex<char>{
+        let mut decimal = self.digits(10);
+
+        if self.fractions{
+            decimal.push(optional([vec![RegexElement::Item('.', Quantifier::Exactly(1))], self.digits(10)].concat()));
+        }
+
+        if self.exponents{
+            decimal.push(optional([
+                vec![
+                    RegexElement::AnyOf(vec![RegexElement::Item('e', Quantifier::Exactly(1)), RegexElement::Item('E', Quantifier::Exactly(1))], Quantifier::Exactly(1)),
+                    RegexElement::AnyOf(vec![RegexElement::Item('+', Quantifier::Exactly(1)), RegexElement::Item('-', Quantifier::Exactly(1))], Quantifier::ZeroOrOne)
+                ],
+                self.digits(10)
+            ].concat()));
+        }
+
+        let mut forms = vec![];
+
+        if self.radix_prefixes{
+            for (prefix, radix) in [('x', 16), ('o', 8), ('b', 2)]{
+                let marker = RegexElement::AnyOf(vec![
+                    RegexElement::Item(prefix, Quantifier::Exactly(1)),
+                    RegexElement::Item(prefix.to_ascii_uppercase(), Quantifier::Exactly(1))
+                ], Quantifier::Exactly(1));
+
+                forms.push(RegexElement::Group([vec![RegexElement::Item('0', Quantifier::Exactly(1)), marker], self.digits(radix)].concat(), Quantifier::Exactly(1)));
+            }
+        }
+
+        forms.push(RegexElement::Group(decimal, Quantifier::Exactly(1)));
+
+        let mut regex = Regex::new().then(RegexElement::AnyOf(forms, Quantifier::Exactly(1)));
+
+        if !self.suffixes.is_empty(){
+            let suffixes = self.suffixes.iter()
+                .map(|s| RegexElement::Group(s.chars().map(|c| RegexElement::Item(c, Quantifier::Exactly(1))).collect(), Quantifier::Exactly(1)))
+                .collect();
+
+            regex = regex.then(optional(vec![RegexElement::AnyOf(suffixes, Quantifier::Exactly(1))]));
+        }
+
+        regex
+    }
+
+    /// A [LexerNode] producing tokens of kind *kind* for the literals of this format
+    pub fn node<Kind:TokenKind>(&self, kind:Kind) -> LexerNode<Kind>{
+        LexerNode::new(self.regex(), kind)
+    }
+
+    /// Converts the literal of a token to a [Number]
+    pub fn parse_token<Kind:TokenKind>(&self, token:&Token<Kind>) -> Result<Number, NumberError>{
+        self.parse(&token.literal, &token.location)
+    }
+
+    /// Converts a literal starting at *location* to a [Number]
+    pub fn parse(&self, literal:&str, location:&Location) -> Result<Number, NumberError>{
+        let chars = literal.chars().collect::<Vec<char>>();
+        let at = |i:usize| Location { column: location.column + i, ..location.clone() };
+
+        let mut i = 0;
+        let mut radix = 10;
+
+        if self.radix_prefixes && chars.first() == Some(&'0'){
+            if let Some(prefixed) = chars.get(1).copied().and_then(prefix_radix){
+                radix = prefixed;
+                i = 2;
+            }
+        }
+
+        // Reads the digits of radix from i, returns them without underscores
+        let digits = |i:&mut usize, radix:u32| -> Result<String, NumberError>{
+            let start = *i;
+            let mut digits = String::new();
+
+            while let Some(c) = chars.get(*i){
+                if c.is_digit(radix) { digits.push(*c); }
+                else if *c == '_' && self.underscores && *i > start {}
+                else { break; }
+
+                *i += 1;
+            }
+
+            if digits.is_empty() { Err(NumberError::MissingDigits(at(start))) }
+            else { Ok(digits) }
+        };
+
+        let integer = digits(&mut i, radix)?;
+        let mut float = None;
+
+        if radix == 10{
+            let mut text = integer.clone();
+
+            if self.fractions && chars.get(i) == Some(&'.'){
+                i += 1;
+                text = format!("{text}.{}", digits(&mut i, 10)?);
+                float = Some(text.clone());
+            }
+
+            if self.exponents && matches!(chars.get(i), Some('e' | 'E')){
+                i += 1;
+
+                let sign = match chars.get(i) {
+                    Some(sign @ ('+' | '-')) => { i += 1; *sign },
+                    _ => '+'
+                };
+
+                text = format!("{text}e{sign}{}", digits(&mut i, 10)?);
+                float = Some(text);
+            }
+        }
+
+        let rest = chars[i..].iter().collect::<String>();
+        let suffix = if rest.is_empty() { None } else {
+            match self.suffixes.iter().find(|s| **s == rest) {
+                Some(suffix) => Some(suffix.clone()),
+                None => return Err(NumberError::UnexpectedCharacter(at(i)))
+            }
+        };
+
+        let value = match float {
+            Some(text) => NumberValue::Float(text.parse().map_err(|_| NumberError::UnexpectedCharacter(at(0)))?),
+            None => NumberValue::Integer(u128::from_str_radix(&integer, radix).map_err(|_| NumberError::Overflow(at(0)))?)
+        };
+
+        Ok(Number { value, radix, suffix })
+    }
+}
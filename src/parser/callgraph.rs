@@ -0,0 +1,171 @@
+use std::{collections::HashMap, hash::Hash};
+
+use super::AST;
+
+#[derive(Debug, Clone, PartialEq)]
+/// Which functions call which, built by [call_graph]
+pub struct CallGraph<N>{
+    /// Names of the defined functions, in order of definition
+    pub functions: Vec<N>,
+
+    /// Calls between defined functions, as (caller, callee) indices, without duplicates
+    pub calls: Vec<(usize, usize)>,
+
+    /// Functions called outside of any definition
+    pub top_level_calls: Vec<usize>,
+
+    /// Calls to names that aren't defined, with the index of the caller if there is one
+    pub unresolved: Vec<(Option<usize>, N)>
+}
+
+// Collects the definitions of a forest, nested ones included
+fn collect_definitions<T:PartialEq+Clone, N:Clone>(forest:&[AST<T>], definition:&impl Fn(&T) -> Option<N>, names:&mut Vec<N>){
+    for node in forest{
+        if let Some(name) = definition(&node.kind){ names.push(name); }
+        collect_definitions(&node.children, definition, names);
+    }
+}
+
+impl<N:Eq+Hash+Clone> CallGraph<N>{
+    /// Returns the index of the function named *name*
+    pub fn index_of(&self, name:&N) -> Option<usize>{
+        self.functions.iter().position(|f| f == name)
+    }
+
+    /// Iterates over the functions called by *function*
+    pub fn callees(&self, function:usize) -> impl Iterator<Item = usize> + '_{
+        self.calls.iter().filter(move |(caller, _)| *caller == function).map(|(_, callee)| *callee)
+    }
+
+    /// Iterates over the functions calling *function*
+    pub fn callers(&self, function:usize) -> impl Iterator<Item = usize> + '_{
+        self.calls.iter().filter(move |(_, callee)| *callee == function).map(|(caller, _)| *caller)
+    }
+
+    /// Splits the functions into strongly connected components:
+    /// groups of functions that can all reach each other through calls
+    /// 
+    /// Components are listed callees first, so each one only calls itself and the previous ones
+    pub fn components(&self) -> Vec<Vec<usize>>{
+        // Iterative Tarjan's algorithm, so that deep call chains can't overflow the stack
+        let count = self.functions.len();
+        let mut index = vec![None; count];
+        let mut low = vec![0; count];
+        let mut on_stack = vec![false; count];
+        let mut stack = vec![];
+        let mut components = vec![];
+        let mut next = 0;
+
+        for root in 0..count{
+            if index[root].is_some() { continue; }
+
+            let mut frames = vec![(root, self.callees(root).collect::<Vec<usize>>(), 0)];
+            index[root] = Some(next);
+            low[root] = next;
+            next += 1;
+            stack.push(root);
+            on_stack[root] = true;
+
+            while let Some((node, callees, i)) = frames.last_mut(){
+                let node = *node;
+
+                if let Some(&callee) = callees.get(*i){
+                    *i += 1;
+
+                    match index[callee] {
+                        None => {
+                            index[callee] = Some(next);
+                            low[callee] = next;
+                            next += 1;
+                            stack.push(callee);
+                            on_stack[callee] = true;
+                            frames.push((callee, self.callees(callee).collect(), 0));
+                        },
+                        Some(callee_index) if on_stack[callee] => low[node] = low[node].min(callee_index),
+                        Some(_) => {}
+                    }
+
+                    continue;
+                }
+
+                frames.pop();
+                if let Some((parent, _, _)) = frames.last(){ low[*parent] = low[*parent].min(low[node]); }
+
+                if Some(low[node]) == index[node]{
+                    let mut component = vec![];
+
+                    while let Some(member) = stack.pop(){
+                        on_stack[member] = false;
+                        component.push(member);
+                        if member == node { break; }
+                    }
+
+                    component.sort();
+                    components.push(component);
+                }
+            }
+        }
+
+        components
+    }
+
+    /// Returns the groups of functions that are recursive, directly or through each other
+    pub fn recursive(&self) -> Vec<Vec<usize>>{
+        self.components().into_iter()
+            .filter(|c| c.len() > 1 || self.calls.contains(&(c[0], c[0])))
+            .collect()
+    }
+
+    /// Returns the functions that can't be reached from the top level calls nor from *roots*
+    pub fn unreachable(&self, roots:&[usize]) -> Vec<usize>{
+        let mut reached = vec![false; self.functions.len()];
+        let mut pending = self.top_level_calls.iter().chain(roots).copied().collect::<Vec<usize>>();
+
+        while let Some(function) = pending.pop(){
+            if std::mem::replace(&mut reached[function], true) { continue; }
+            pending.extend(self.callees(function));
+        }
+
+        (0..self.functions.len()).filter(|f| !reached[*f]).collect()
+    }
+}
+
+/// Builds the [CallGraph] of a forest of [AST]
+/// 
+/// A call belongs to the innermost definition containing it
+/// 
+/// definition: Gives the name of the function a node kind defines, or None
+/// 
+/// call: Gives the name of the function a node kind calls, or None
+pub fn call_graph<T:PartialEq+Clone, N:Eq+Hash+Clone>(forest:&[AST<T>], definition:impl Fn(&T) -> Option<N>, call:impl Fn(&T) -> Option<N>) -> CallGraph<N>{
+    let mut functions = vec![];
+    collect_definitions(forest, &definition, &mut functions);
+
+    let mut indices = HashMap::new();
+    for (i, name) in functions.iter().enumerate(){ indices.entry(name.clone()).or_insert(i); }
+
+    let mut graph = CallGraph { functions, calls: vec![], top_level_calls: vec![], unresolved: vec![] };
+
+    // Walks the nodes along with the innermost definition they are in
+    let mut defined = 0;
+    let mut pending = forest.iter().rev().map(|node| (node, None)).collect::<Vec<(&AST<T>, Option<usize>)>>();
+
+    while let Some((node, mut caller)) = pending.pop(){
+        if definition(&node.kind).is_some(){
+            caller = Some(defined);
+            defined += 1;
+        }
+
+        if let Some(name) = call(&node.kind){
+            match (caller, indices.get(&name)) {
+                (Some(caller), Some(&callee)) => if !graph.calls.contains(&(caller, callee)) { graph.calls.push((caller, callee)); },
+                (None, Some(&callee)) => if !graph.top_level_calls.contains(&callee) { graph.top_level_calls.push(callee); },
+                (_, None) => graph.unresolved.push((caller, name))
+            }
+        }
+
+        pending.extend(node.children.iter().rev().map(|child| (child, caller)));
+    }
+
+    graph
+}
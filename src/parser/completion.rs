@@ -0,0 +1,73 @@
+use crate::{lexer::{Location, Span, Token, TokenKind}, source::Source};
+
+use super::AST;
+
+#[derive(Debug, Clone, PartialEq)]
+/// The syntactic context around a cursor, as computed by [completion_context]
+pub struct CompletionContext<'a, K:TokenKind, T:PartialEq+Clone>{
+    /// Where the cursor is
+    pub location: Location,
+
+    /// The token being typed, which contains the cursor or ends right on it
+    pub current: Option<&'a Token<K>>,
+
+    /// The tokens entirely before the cursor, not counting [current](CompletionContext::current)
+    pub preceding: &'a [Token<K>],
+
+    /// The nodes containing the cursor, outermost first
+    pub ancestors: Vec<&'a AST<T>>
+}
+
+impl<K:TokenKind, T:PartialEq+Clone> CompletionContext<'_, K, T>{
+    /// The kinds of the last *n* tokens before the cursor, closest last
+    pub fn previous_kinds(&self, n:usize) -> Vec<K>{
+        let start = self.preceding.len().saturating_sub(n);
+        self.preceding[start..].iter().map(|t| t.kind).collect()
+    }
+
+    /// The innermost node containing the cursor
+    pub fn innermost(&self) -> Option<&AST<T>>{
+        self.ancestors.last().copied()
+    }
+}
+
+/// Analyzes the context of a cursor for code completion
+/// 
+/// Returns None if *offset* is outside of *source*
+/// 
+/// source: The source the tokens were extracted from
+/// 
+/// tokens: The tokens of the source, in order
+/// 
+/// forest: The trees parsed from the tokens, they may be incomplete
+/// 
+/// offset: The byte offset of the cursor
+/// 
+/// span: Gives the span of a node kind, or None if it is unknown
+pub fn completion_context<'a, K:TokenKind, T:PartialEq+Clone>(
+    source:&Source,
+    tokens:&'a [Token<K>],
+    forest:&'a [AST<T>],
+    offset:usize,
+    span:impl Fn(&T) -> Option<Span>
+) -> Option<CompletionContext<'a, K, T>>{
+    let location = source.offset_to_location(offset)?;
+
+    // Tokens strictly before the cursor
+    let before = tokens.partition_point(|t| t.location < location);
+
+    let (current, preceding) = match before.checked_sub(1).map(|i| (i, &tokens[i])){
+        Some((i, token)) if token.span().end >= location => (Some(token), &tokens[..i]),
+        _ => (None, &tokens[..before])
+    };
+
+    let mut ancestors = vec![];
+    let mut level = forest;
+
+    while let Some(node) = level.iter().find(|node| span(&node.kind).is_some_and(|s| s.contains(&location))){
+        ancestors.push(node);
+        level = &node.children;
+    }
+
+    Some(CompletionContext { location, current, preceding, ancestors })
+}
@@ -0,0 +1,40 @@
+use crate::lexer::{Lexer, TokenKind};
+
+use super::Parser;
+
+#[derive(Debug, Clone, PartialEq)]
+/// Token kinds on which a [Lexer] and a [Parser] disagree, found by [coverage]
+pub struct Coverage<T:TokenKind>{
+    /// Kinds the lexer produces but no parser node accepts
+    pub unconsumed: Vec<T>,
+
+    /// Kinds the parser nodes name but the lexer never produces
+    pub unproduced: Vec<T>
+}
+
+impl<T:TokenKind> Coverage<T>{
+    /// Returns true if the lexer and the parser agree on every kind
+    pub fn is_complete(&self) -> bool{ self.unconsumed.is_empty() && self.unproduced.is_empty() }
+}
+
+/// Cross-references the kinds a [Lexer] produces with the kinds the [nodes](super::ParserNode) of a [Parser] use
+/// 
+/// consumed: Kinds consumed outside of the nodes' regexes, by an [ExpressionParser](super::expression::ExpressionParser) for instance
+pub fn coverage<T:TokenKind>(lexer:&Lexer<T>, parser:&Parser<T>, consumed:&[T]) -> Coverage<T>{
+    let mut produced:Vec<T> = vec![];
+    for kind in lexer.kinds(){
+        if !produced.contains(&kind) { produced.push(kind); }
+    }
+
+    let unconsumed = produced.iter()
+        .filter(|kind| !consumed.contains(kind) && !parser.nodes.iter().any(|node| node.regex.may_accept(kind)))
+        .copied()
+        .collect();
+
+    let mut unproduced = vec![];
+    for kind in parser.nodes.iter().flat_map(|node| node.regex.symbols()).chain(consumed){
+        if !produced.contains(kind) && !unproduced.contains(kind) { unproduced.push(*kind); }
+    }
+
+    Coverage { unconsumed, unproduced }
+}
@@ -0,0 +1,120 @@
+use crate::{lexer::{Token, TokenKind}, source::Source};
+
+use super::{AST, Parser, ParsingError};
+
+#[derive(Debug, Clone, PartialEq)]
+/// A [token](Token) along with the whitespace and comments preceding it in the source
+pub struct TriviaToken<T:TokenKind>{
+    /// The source text between the previous token and this one
+    pub leading: String,
+
+    pub token: Token<T>
+}
+
+#[derive(Debug, Clone, PartialEq)]
+/// The nodes of a concrete syntax tree, which keeps every character of the source
+pub enum Syntax<T:TokenKind>{
+    /// A node produced by a [ParserNode](super::ParserNode)
+    Node(T),
+
+    /// A token, with its leading trivia
+    Token(TriviaToken<T>),
+
+    /// The source text after the last token
+    Trailing(String)
+}
+
+/// A concrete syntax tree along with the errors met while parsing it
+pub type CstResult<T> = (Vec<AST<Syntax<T>>>, Vec<ParsingError<T>>);
+
+/// Attaches to each token the source text preceding it
+/// 
+/// Returns the tokens and the text after the last one,
+/// or None if the tokens are not in order or don't come from *source*
+pub fn attach_trivia<T:TokenKind>(source:&Source, tokens:&[Token<T>]) -> Option<(Vec<TriviaToken<T>>, String)>{
+    let content = source.content();
+    let mut offset = 0;
+    let mut attached = Vec::with_capacity(tokens.len());
+
+    for token in tokens{
+        let start = source.location_to_offset(&token.location)?;
+        let end = start + token.literal.len();
+
+        if start < offset || content.get(start..end)? != token.literal { return None; }
+
+        attached.push(TriviaToken { leading: content[offset..start].to_string(), token: token.clone() });
+        offset = end;
+    }
+
+    Some((attached, content[offset..].to_string()))
+}
+
+/// Writes back the source text of a concrete syntax tree
+/// 
+/// The output is byte for byte the source the tree was built from, as long as the tree is left unmodified
+pub fn render<T:TokenKind>(forest:&[AST<Syntax<T>>]) -> String{
+    let mut text = String::new();
+
+    for node in forest.iter().flat_map(|ast| ast.iter()){
+        match &node.kind{
+            Syntax::Token(t) => {
+                text.push_str(&t.leading);
+                text.push_str(&t.token.literal);
+            },
+            Syntax::Trailing(trivia) => text.push_str(trivia),
+            Syntax::Node(_) => {}
+        }
+    }
+
+    text
+}
+
+// Converts a parsed node, attaching the tokens it consumed to it
+fn convert<T:TokenKind>(ast:AST<T>, tokens:Vec<TriviaToken<T>>) -> AST<Syntax<T>>{
+    let mut children = tokens.into_iter()
+        .map(|t| AST { kind: Syntax::Token(t), children: vec![] })
+        .collect::<Vec<AST<Syntax<T>>>>();
+
+    children.extend(ast.children.into_iter().map(|child| convert(child, vec![])));
+
+    AST { kind: Syntax::Node(ast.kind), children }
+}
+
+impl<T:TokenKind> Parser<'_, T>{
+    /// Parses every token into a concrete syntax tree, to be [rendered](render) back into *source*
+    /// 
+    /// Each top-level node holds the tokens it consumed, followed by its children.
+    /// Tokens that could not be parsed are kept at the top level and their errors are returned
+    /// 
+    /// Returns None if the tokens don't come from *source*
+    pub fn parse_cst(&mut self, source:&Source) -> Option<CstResult<T>>{
+        let (attached, trailing) = attach_trivia(source, self.tokens)?;
+        let mut attached = attached.into_iter();
+
+        let mut forest = vec![];
+        let mut errors = vec![];
+
+        while !self.finished(){
+            let before = self.tokens.len();
+
+            match self.parse_with_node(){
+                Ok(ast) => {
+                    let consumed = attached.by_ref().take(before - self.tokens.len()).collect();
+                    forest.push(convert(ast, consumed));
+                },
+
+                Err(e) => {
+                    errors.push(e);
+                    if self.tokens.len() == before { self.skip(1); }
+
+                    let skipped = attached.by_ref().take(before - self.tokens.len());
+                    forest.extend(skipped.map(|t| AST { kind: Syntax::Token(t), children: vec![] }));
+                }
+            }
+        }
+
+        forest.push(AST { kind: Syntax::Trailing(trailing), children: vec![] });
+
+        Some((forest, errors))
+    }
+}
@@ -0,0 +1,70 @@
+use crate::lexer::{Span, Token, TokenKind};
+
+use super::{AST, Parser, ParsingError};
+
+#[derive(Debug, Clone, PartialEq)]
+/// An event emitted by [Parser::parse_events]
+pub enum ParseEvent<'a, T:TokenKind>{
+    /// A node starts
+    /// 
+    /// Top-level nodes have the span of the tokens they were parsed from,
+    /// nested nodes have no span
+    EnterNode(T, Option<Span>),
+
+    /// A token consumed by the current top-level node
+    Token(&'a Token<T>),
+
+    /// The last entered node ends
+    ExitNode,
+
+    /// The tokens could not be parsed, one token has been skipped
+    Error(ParsingError<T>)
+}
+
+// Emits the events of a node and its descendants, without recursion
+fn emit<'a, T:TokenKind>(ast:AST<T>, span:Option<Span>, tokens:&'a [Token<T>], handler:&mut impl FnMut(ParseEvent<'a, T>)){
+    handler(ParseEvent::EnterNode(ast.kind, span));
+    for token in tokens{ handler(ParseEvent::Token(token)); }
+
+    // None stands for the exit of a node, pushed below its children
+    let mut stack = vec![None];
+    stack.extend(ast.children.into_iter().rev().map(Some));
+
+    while let Some(node) = stack.pop(){
+        let Some(node) = node else {
+            handler(ParseEvent::ExitNode);
+            continue;
+        };
+
+        handler(ParseEvent::EnterNode(node.kind, None));
+
+        stack.push(None);
+        stack.extend(node.children.into_iter().rev().map(Some));
+    }
+}
+
+impl<'a, T:TokenKind> Parser<'a, T>{
+
+    /// Parses every token with the [nodes](super::ParserNode) and reports the result as events
+    /// instead of building a forest
+    /// 
+    /// Each top-level node is reported as soon as it is parsed then dropped,
+    /// as `EnterNode`, the `Token`s it consumed, the events of its children and `ExitNode`
+    pub fn parse_events(&mut self, mut handler:impl FnMut(ParseEvent<'a, T>)){
+        while !self.finished(){
+            let before = self.tokens;
+
+            match self.parse_with_node(){
+                Ok(ast) => {
+                    let consumed = &before[..before.len() - self.tokens.len()];
+                    emit(ast, Span::covering(consumed), consumed, &mut handler);
+                },
+
+                Err(e) => {
+                    handler(ParseEvent::Error(e));
+                    self.skip(1);
+                }
+            }
+        }
+    }
+}
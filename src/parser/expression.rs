@@ -1,8 +1,8 @@
-use std::collections::{HashSet, HashMap};
+use std::{collections::{HashMap, HashSet}, fmt::Display, error::Error};
 
-use crate::lexer::{TokenKind, Token};
+use crate::{lexer::{TokenKind, Token, KindNames, KindSet}, limits::{Deadline, Limits}};
 
-use super::{AST, ParsingError};
+use super::{AST, ErrorKind, ParsingError, OperandSide, Parser, find_matching};
 
 #[derive(Debug, PartialEq, Clone)]
 /// The nodes in an expression
@@ -13,10 +13,83 @@ pub enum Expr<'a, T:TokenKind>{
     /// An operand
     Operand(T),
 
+    /// A pair of [brackets](ExpressionParser::add_bracket), holding the kind they were registered with
+    /// 
+    /// Its only child is the enclosed expression, it has no children if the brackets are empty
+    Bracket(T),
+
     /// An unknown sequence that could not be parsed
     /// Can be fed to a [Parser](super::Parser) for further processing
-    Unknown(&'a[Token<T>])
+    Unknown(&'a[Token<T>]),
+
+    /// Stands for an operand missing from an incomplete expression,
+    /// only produced by [parse_recovering](ExpressionParser::parse_recovering)
+    Missing
+}
+
+/// [Expr::Missing] is the error node of the expressions
+impl<T:TokenKind> ErrorKind for Expr<'_, T>{
+    fn error() -> Self { Expr::Missing }
+
+    fn is_error(&self) -> bool { matches!(self, Expr::Missing) }
+}
+
+#[derive(Debug, PartialEq, Clone)]
+/// Error type of the [ExpressionParser] configuration
+pub enum RegistrationError<T:TokenKind>{
+    /// The operator is already registered with another priority
+    ConflictingPriority{
+        operator: T,
+        registered: usize,
+        requested: usize
+    },
+
+    /// The token is used both as an operator and as a group token
+    GroupOperator(T),
+
+    /// The operator must be registered first
+    UnknownOperator(T),
+
+    /// The token already delimits another group or brackets
+    ConflictingGroup(T)
+}
+impl<T:TokenKind> Display for RegistrationError<T>{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_fmt(format_args!("{self:?}"))
+    }
+}
+impl<T:TokenKind> Error for RegistrationError<T>{}
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+/// What an [ExpressionParser] does with two operands following each other
+/// without an operator in between, as in `a b`
+pub enum Adjacency<T:TokenKind>{
+    /// Keep the whole sequence as an [Expr::Unknown]
+    Unknown,
+
+    /// Act as if the given operator stood between the operands,
+    /// useful for implicit multiplication or function application
+    Implicit(T),
+
+    /// Fail with [ParsingError::AdjacentOperands]
+    Error
+}
+
+// Where an expression is split around its root operator
+enum Split<T>{
+    // An operator token at this index
+    Operator(usize),
+
+    // Two adjacent operands joined by an implicit operator, the right one starting at this index
+    Adjacency(usize, T)
 }
+
+/// Result type of the expression parsing process
+pub type ExpressionResult<'a, T> = Result<AST<Expr<'a, T>>, Vec<ParsingError<T>>>;
+
+/// Result type of [parse_recovering](ExpressionParser::parse_recovering), a tree along with the recovered errors
+pub type RecoveredExpression<'a, T> = (AST<Expr<'a, T>>, Vec<ParsingError<T>>);
+
 /// A parser of expressions
 /// 
 /// # Exemples
@@ -32,135 +105,135 @@ pub enum Expr<'a, T:TokenKind>{
 /// 
 /// let mut parser = ExpressionParser::<TokenType>::new();
 /// 
-/// parser.add_operator(TokenType::ADD, 1);
-/// parser.add_operator(TokenType::SUB, 1);
-/// parser.add_operator(TokenType::MUL, 2);
+/// parser.add_operator(TokenType::ADD, 1).unwrap();
+/// parser.add_operator(TokenType::SUB, 1).unwrap();
+/// parser.add_operator(TokenType::MUL, 2).unwrap();
 /// 
-/// parser.set_high_priority_group(TokenType::OPEN_PAREN, TokenType::CLOSED_PAREN);
+/// parser.set_high_priority_group(TokenType::OPEN_PAREN, TokenType::CLOSED_PAREN).unwrap();
 /// 
 /// // A + B
 /// let expr1 = &[
-///     Token{ 
-///         location: Location{ file: String::from(""), line: 0, column: 0 },
-///         kind: TokenType::A, literal: String::from("A")
-///     },
-///     Token{ 
-///         location: Location{ file: String::from(""), line: 0, column: 1 },
-///         kind: TokenType::ADD, literal: String::from("+")
-///     },
-/// 
-///     Token{ 
-///         location: Location{ file: String::from(""), line: 0, column: 2 },
-///         kind: TokenType::B, literal: String::from("B")
-///     }
+///     Token::new(
+///         Location{ file: String::from(""), line: 0, column: 0 },
+///         TokenType::A, String::from("A")
+///     ),
+///     Token::new(
+///         Location{ file: String::from(""), line: 0, column: 1 },
+///         TokenType::ADD, String::from("+")
+///     ),
+/// 
+///     Token::new(
+///         Location{ file: String::from(""), line: 0, column: 2 },
+///         TokenType::B, String::from("B")
+///     )
 /// ];
 /// // A - B
 /// let expr2 = &[
-///     Token{ 
-///         location: Location{ file: String::from(""), line: 0, column: 0 },
-///         kind: TokenType::A, literal: String::from("A")
-///     },
-///     Token{ 
-///         location: Location{ file: String::from(""), line: 0, column: 1 },
-///         kind: TokenType::SUB, literal: String::from("+")
-///     },
-/// 
-///     Token{ 
-///         location: Location{ file: String::from(""), line: 0, column: 2 },
-///         kind: TokenType::B, literal: String::from("B")
-///     }
+///     Token::new(
+///         Location{ file: String::from(""), line: 0, column: 0 },
+///         TokenType::A, String::from("A")
+///     ),
+///     Token::new(
+///         Location{ file: String::from(""), line: 0, column: 1 },
+///         TokenType::SUB, String::from("+")
+///     ),
+/// 
+///     Token::new(
+///         Location{ file: String::from(""), line: 0, column: 2 },
+///         TokenType::B, String::from("B")
+///     )
 /// ];
 /// 
 /// // A +(A * B)
 /// let expr3 = &[
-///     Token{ 
-///         location: Location{ file: String::from(""), line: 0, column: 0 },
-///         kind: TokenType::A, literal: String::from("A")
-///     },
-///     Token{ 
-///         location: Location{ file: String::from(""), line: 0, column: 1 },
-///         kind: TokenType::ADD, literal: String::from("+")
-///     },
-/// 
-///     Token{ 
-///         location: Location{ file: String::from(""), line: 0, column: 2 },
-///         kind: TokenType::OPEN_PAREN, literal: String::from("(")
-///     },
-/// 
-///     Token{ 
-///         location: Location{ file: String::from(""), line: 0, column: 3 },
-///         kind: TokenType::A, literal: String::from("A")
-///     },
-/// 
-///     Token{ 
-///         location: Location{ file: String::from(""), line: 0, column: 4 },
-///         kind: TokenType::MUL, literal: String::from("*")
-///     },
-/// 
-///     Token{ 
-///         location: Location{ file: String::from(""), line: 0, column: 5 },
-///         kind: TokenType::B, literal: String::from("B")
-///     },
-/// 
-///     Token{ 
-///         location: Location{ file: String::from(""), line: 0, column: 6 },
-///         kind: TokenType::CLOSED_PAREN, literal: String::from(")")
-///     }
+///     Token::new(
+///         Location{ file: String::from(""), line: 0, column: 0 },
+///         TokenType::A, String::from("A")
+///     ),
+///     Token::new(
+///         Location{ file: String::from(""), line: 0, column: 1 },
+///         TokenType::ADD, String::from("+")
+///     ),
+/// 
+///     Token::new(
+///         Location{ file: String::from(""), line: 0, column: 2 },
+///         TokenType::OPEN_PAREN, String::from("(")
+///     ),
+/// 
+///     Token::new(
+///         Location{ file: String::from(""), line: 0, column: 3 },
+///         TokenType::A, String::from("A")
+///     ),
+/// 
+///     Token::new(
+///         Location{ file: String::from(""), line: 0, column: 4 },
+///         TokenType::MUL, String::from("*")
+///     ),
+/// 
+///     Token::new(
+///         Location{ file: String::from(""), line: 0, column: 5 },
+///         TokenType::B, String::from("B")
+///     ),
+/// 
+///     Token::new(
+///         Location{ file: String::from(""), line: 0, column: 6 },
+///         TokenType::CLOSED_PAREN, String::from(")")
+///     )
 /// ];
 /// 
 /// // A - A*B
 /// let expr4 = &[
-///     Token{ 
-///         location: Location{ file: String::from(""), line: 0, column: 0 },
-///         kind: TokenType::A, literal: String::from("A")
-///     },
-///     Token{ 
-///         location: Location{ file: String::from(""), line: 0, column: 1 },
-///         kind: TokenType::SUB, literal: String::from("-")
-///     },
-/// 
-///     Token{ 
-///         location: Location{ file: String::from(""), line: 0, column: 2 },
-///         kind: TokenType::A, literal: String::from("A")
-///     },
-/// 
-///     Token{ 
-///         location: Location{ file: String::from(""), line: 0, column: 3 },
-///         kind: TokenType::MUL, literal: String::from("*")
-///     },
-/// 
-///     Token{ 
-///         location: Location{ file: String::from(""), line: 0, column: 4 },
-///         kind: TokenType::B, literal: String::from("B")
-///     },
+///     Token::new(
+///         Location{ file: String::from(""), line: 0, column: 0 },
+///         TokenType::A, String::from("A")
+///     ),
+///     Token::new(
+///         Location{ file: String::from(""), line: 0, column: 1 },
+///         TokenType::SUB, String::from("-")
+///     ),
+/// 
+///     Token::new(
+///         Location{ file: String::from(""), line: 0, column: 2 },
+///         TokenType::A, String::from("A")
+///     ),
+/// 
+///     Token::new(
+///         Location{ file: String::from(""), line: 0, column: 3 },
+///         TokenType::MUL, String::from("*")
+///     ),
+/// 
+///     Token::new(
+///         Location{ file: String::from(""), line: 0, column: 4 },
+///         TokenType::B, String::from("B")
+///     ),
 /// 
 /// ];
 /// 
 /// // A - B - C
 /// let expr5 = &[
-///     Token{ 
-///         location: Location{ file: String::from(""), line: 0, column: 0 },
-///         kind: TokenType::A, literal: String::from("A")
-///     },
-///     Token{ 
-///         location: Location{ file: String::from(""), line: 0, column: 1 },
-///         kind: TokenType::SUB, literal: String::from("-")
-///     },
-/// 
-///     Token{ 
-///         location: Location{ file: String::from(""), line: 0, column: 2 },
-///         kind: TokenType::B, literal: String::from("B")
-///     },
-/// 
-///     Token{ 
-///         location: Location{ file: String::from(""), line: 0, column: 3 },
-///         kind: TokenType::SUB, literal: String::from("-")
-///     },
-/// 
-///     Token{ 
-///         location: Location{ file: String::from(""), line: 0, column: 4 },
-///         kind: TokenType::C, literal: String::from("C")
-///     }
+///     Token::new(
+///         Location{ file: String::from(""), line: 0, column: 0 },
+///         TokenType::A, String::from("A")
+///     ),
+///     Token::new(
+///         Location{ file: String::from(""), line: 0, column: 1 },
+///         TokenType::SUB, String::from("-")
+///     ),
+/// 
+///     Token::new(
+///         Location{ file: String::from(""), line: 0, column: 2 },
+///         TokenType::B, String::from("B")
+///     ),
+/// 
+///     Token::new(
+///         Location{ file: String::from(""), line: 0, column: 3 },
+///         TokenType::SUB, String::from("-")
+///     ),
+/// 
+///     Token::new(
+///         Location{ file: String::from(""), line: 0, column: 4 },
+///         TokenType::C, String::from("C")
+///     )
 /// ];
 /// 
 /// let result1 = AST{
@@ -249,27 +322,60 @@ pub enum Expr<'a, T:TokenKind>{
 /// 
 /// 
 /// ```
+#[derive(Debug, Clone)]
 pub struct ExpressionParser<T: TokenKind>{
     /// Set of known operators
-    operators:HashSet<T>,
+    operators:KindSet<T>,
 
     /// [HashMap] of operators and their priority
     priority:HashMap<T, usize>,
 
+    /// Known operators in registration order
+    order:Vec<T>,
+
     /// A [token](TokenKind) that acts like an open parenthesis on priority
     high_priority_group_start:Option<T>,
 
     /// A [token](TokenKind) that acts like a closed parenthesis on priority
-    high_priority_group_end:Option<T>
+    high_priority_group_end:Option<T>,
+
+    /// How adjacent operands are handled
+    adjacency:Adjacency<T>,
+
+    /// Bounds on the parsing work
+    limits:Limits,
+
+    /// Friendly names of the operators
+    names:KindNames<T>,
+
+    /// Brackets as (open, close, kind), in registration order
+    brackets:Vec<(T, T, T)>,
+
+    /// Operators that may also stand before a single operand, as in `-a`
+    prefix:KindSet<T>,
+
+    /// Whether the spacing around a prefix operator decides how it is used, as in `a - 1` and `a -1`
+    spacing_sensitive:bool
+}
+
+impl<T:TokenKind> Default for ExpressionParser<T>{
+    fn default() -> Self { Self::new() }
 }
 
 impl<T:TokenKind> ExpressionParser<T>{
     pub fn new() -> Self {
         ExpressionParser {
-            operators: HashSet::new(),
+            operators: KindSet::new(),
             priority: HashMap::new(),
+            order: vec![],
             high_priority_group_start: None,
-            high_priority_group_end: None
+            high_priority_group_end: None,
+            adjacency: Adjacency::Unknown,
+            limits: Limits::new(),
+            names: KindNames::new(),
+            brackets: vec![],
+            prefix: KindSet::new(),
+            spacing_sensitive: false
         }
     }
     
@@ -278,175 +384,840 @@ impl<T:TokenKind> ExpressionParser<T>{
     /// operator: The operator to add
     /// 
     /// priority: Its priority
-    pub fn add_operator(&mut self, operator:T, priority:usize){
-        self.operators.insert(operator);
-        self.priority.insert(operator, priority);
+    /// 
+    /// Registering the same operator twice is allowed only with the same priority
+    pub fn add_operator(&mut self, operator:T, priority:usize) -> Result<(), RegistrationError<T>>{
+        if let Some(registered) = self.priority_of(operator){
+            if registered != priority{
+                return Err(RegistrationError::ConflictingPriority { operator, registered, requested: priority });
+            }
+        }
+
+        if self.is_group_token(operator){
+            return Err(RegistrationError::GroupOperator(operator));
+        }
+
+        if self.priority.insert(operator, priority).is_none(){
+            self.operators.insert(operator);
+            self.order.push(operator);
+        }
+
+        Ok(())
     }
 
 
+    /// Adds an operator to the list of known operators, builder style
+    /// 
+    /// # Panics
+    /// If the registration conflicts with the current configuration,
+    /// see [add_operator](ExpressionParser::add_operator)
+    pub fn with_operator(mut self, operator:T, priority:usize) -> Self{
+        if let Err(e) = self.add_operator(operator, priority){
+            panic!("Invalid operator registration: {e}");
+        }
+        self
+    }
+
     /// Assign the [tokens](TokenKind) used to modify the priority
     /// 
     /// start: The start of the new priority
     /// 
     /// end: The end of the new priority
-    pub fn set_high_priority_group(&mut self, start:T, end:T){
+    /// 
+    /// Neither of them can be a registered operator
+    /// 
+    /// Operators inside a group always bind tighter than the ones outside of it,
+    /// regardless of their priorities
+    pub fn set_high_priority_group(&mut self, start:T, end:T) -> Result<(), RegistrationError<T>>{
+        for token in [start, end]{
+            if self.operators.contains(token){
+                return Err(RegistrationError::GroupOperator(token));
+            }
+
+            if self.brackets.iter().any(|(open, close, _)| token == *open || token == *close){
+                return Err(RegistrationError::ConflictingGroup(token));
+            }
+        }
+
         self.high_priority_group_start = Some(start);
         self.high_priority_group_end = Some(end);
+
+        Ok(())
+    }
+
+    /// Assign the [tokens](TokenKind) used to modify the priority, builder style
+    /// 
+    /// # Panics
+    /// If the group conflicts with the current configuration,
+    /// see [set_high_priority_group](ExpressionParser::set_high_priority_group)
+    pub fn with_high_priority_group(mut self, start:T, end:T) -> Self{
+        if let Err(e) = self.set_high_priority_group(start, end){
+            panic!("Invalid group registration: {e}");
+        }
+        self
+    }
+
+    /// Registers a pair of brackets producing an [Expr::Bracket] of kind *kind*
+    /// 
+    /// Unlike the high priority group, the brackets are kept in the tree, as for `|x|` or `{a, b}`.
+    /// They enclose an operand, the operators inside them never split the expression outside
+    /// 
+    /// *open* and *close* may be the same token, as in `|x|`, such brackets can't be nested.
+    /// Neither of them can be an operator or delimit another group
+    pub fn add_bracket(&mut self, open:T, close:T, kind:T) -> Result<(), RegistrationError<T>>{
+        for token in [open, close]{
+            if self.operators.contains(token){
+                return Err(RegistrationError::GroupOperator(token));
+            }
+
+            if self.is_group_token(token){
+                return Err(RegistrationError::ConflictingGroup(token));
+            }
+        }
+
+        self.brackets.push((open, close, kind));
+        Ok(())
+    }
+
+    /// Registers a pair of brackets, builder style
+    /// 
+    /// # Panics
+    /// If the brackets conflict with the current configuration,
+    /// see [add_bracket](ExpressionParser::add_bracket)
+    pub fn with_bracket(mut self, open:T, close:T, kind:T) -> Self{
+        if let Err(e) = self.add_bracket(open, close, kind){
+            panic!("Invalid bracket registration: {e}");
+        }
+        self
+    }
+
+    /// Iterates over the registered brackets as (open, close, kind), in registration order
+    pub fn brackets(&self) -> impl Iterator<Item = &(T, T, T)>{
+        self.brackets.iter()
+    }
+
+    /// Sets how two operands following each other without an operator are handled
+    /// 
+    /// By default the sequence is kept as an [Expr::Unknown]
+    /// 
+    /// An [implicit](Adjacency::Implicit) operator must be registered first, its priority is used for the adjacency
+    pub fn set_adjacency(&mut self, adjacency:Adjacency<T>) -> Result<(), RegistrationError<T>>{
+        if let Adjacency::Implicit(operator) = adjacency{
+            if !self.operators.contains(operator){
+                return Err(RegistrationError::UnknownOperator(operator));
+            }
+        }
+
+        self.adjacency = adjacency;
+        Ok(())
+    }
+
+    /// Sets how adjacent operands are handled, builder style
+    /// 
+    /// # Panics
+    /// If the implicit operator isn't registered,
+    /// see [set_adjacency](ExpressionParser::set_adjacency)
+    pub fn with_adjacency(mut self, adjacency:Adjacency<T>) -> Self{
+        if let Err(e) = self.set_adjacency(adjacency){
+            panic!("Invalid adjacency: {e}");
+        }
+        self
+    }
+
+    /// Allows a registered operator to also stand before a single operand, as in `-a`
+    /// 
+    /// It is used as a prefix at the start of an expression or right after another operator,
+    /// producing an [Expr::Operator] with a single child. A prefix binds tighter than any infix operator,
+    /// so `-a * b` is parsed as `(-a) * b`
+    /// 
+    /// The operator must be registered first
+    pub fn add_prefix_operator(&mut self, operator:T) -> Result<(), RegistrationError<T>>{
+        if !self.operators.contains(operator){
+            return Err(RegistrationError::UnknownOperator(operator));
+        }
+
+        self.prefix.insert(operator);
+        Ok(())
+    }
+
+    /// Allows a registered operator to also be used as a prefix, builder style
+    /// 
+    /// # Panics
+    /// If the operator isn't registered,
+    /// see [add_prefix_operator](ExpressionParser::add_prefix_operator)
+    pub fn with_prefix_operator(mut self, operator:T) -> Self{
+        if let Err(e) = self.add_prefix_operator(operator){
+            panic!("Invalid prefix operator: {e}");
+        }
+        self
+    }
+
+    /// Lets the spacing decide whether a [prefix operator](ExpressionParser::add_prefix_operator)
+    /// following an operand is used as a prefix, builder style
+    /// 
+    /// A prefix operator with whitespace before it and none after it, as in `a -1`, is then a prefix
+    /// and the operands on each side are [adjacent](Adjacency), while `a - 1` and `a-1` stay infix.
+    /// The spacing is read from the [locations](crate::lexer::Location) of the tokens, see [Token::touches]
+    pub fn with_spacing_sensitivity(mut self) -> Self{
+        self.spacing_sensitive = true;
+        self
+    }
+
+    /// Bounds the parsing work of each expression, builder style
+    /// 
+    /// The depth limit bounds how deeply operators nest, as in `a - b - c - ...`
+    pub fn with_limits(mut self, limits:Limits) -> Self{
+        self.limits = limits;
+        self
+    }
+
+    /// Gives a friendly name and an optional description to a registered operator
+    /// 
+    /// The operator must be registered first
+    pub fn describe_operator(&mut self, operator:T, name:&str, description:Option<&str>) -> Result<(), RegistrationError<T>>{
+        if !self.operators.contains(operator){
+            return Err(RegistrationError::UnknownOperator(operator));
+        }
+
+        self.names.describe(operator, name, description);
+        Ok(())
+    }
+
+    /// Gives a friendly name and an optional description to a registered operator, builder style
+    /// 
+    /// # Panics
+    /// If the operator isn't registered,
+    /// see [describe_operator](ExpressionParser::describe_operator)
+    pub fn with_operator_name(mut self, operator:T, name:&str, description:Option<&str>) -> Self{
+        if let Err(e) = self.describe_operator(operator, name, description){
+            panic!("Invalid operator description: {e}");
+        }
+        self
+    }
+
+    /// The friendly names of the operators
+    pub fn operator_names(&self) -> &KindNames<T>{ &self.names }
+
+    /// Iterates over the known operators, in registration order
+    pub fn operators(&self) -> impl Iterator<Item = &T>{
+        self.order.iter()
+    }
+
+    /// Returns the priority of *operator* or None if it isn't a known operator
+    pub fn priority_of(&self, operator:T) -> Option<usize>{
+        self.priority.get(&operator).copied()
+    }
+
+    /// Returns true if *kind* is a known operator
+    pub fn is_operator(&self, kind:T) -> bool{ self.operators.contains(kind) }
+
+    /// Returns true if *kind* is an operator that may be used as a [prefix](ExpressionParser::add_prefix_operator)
+    pub fn is_prefix_operator(&self, kind:T) -> bool{ self.prefix.contains(kind) }
+
+    /// The precedence level of *operator*, or None if it isn't a known operator
+    /// 
+    /// Levels count the distinct priorities below the one of the operator,
+    /// so the loosest operators are at level 0 and the next ones at level 1 whatever their priorities.
+    /// Used as a prefix, an operator binds tighter than every level
+    /// 
+    /// # Exemples
+    /// ```rust
+    /// use crate::neoglot_lib::{lexer::*, parser::expression::ExpressionParser, regex::Symbol};
+    /// 
+    /// #[derive(Debug, Copy, Clone, Hash, PartialOrd, Eq, PartialEq)]
+    /// enum TokenType{ Add, Sub, Mul, Open, Close, Bar, Abs }
+    /// 
+    /// impl Symbol for TokenType{}
+    /// impl TokenKind for TokenType{}
+    /// 
+    /// let parser = ExpressionParser::new()
+    ///     .with_operator(TokenType::Add, 10)
+    ///     .with_operator(TokenType::Sub, 10)
+    ///     .with_operator(TokenType::Mul, 20)
+    ///     .with_prefix_operator(TokenType::Sub)
+    ///     .with_high_priority_group(TokenType::Open, TokenType::Close)
+    ///     .with_bracket(TokenType::Bar, TokenType::Bar, TokenType::Abs);
+    /// 
+    /// assert_eq!(parser.precedence(TokenType::Sub), Some(0));
+    /// assert_eq!(parser.precedence(TokenType::Mul), Some(1));
+    /// assert!(parser.is_prefix_operator(TokenType::Sub) && !parser.is_operator(TokenType::Open));
+    /// 
+    /// assert_eq!(parser.grouping_pairs().collect::<Vec<_>>(), vec![(TokenType::Open, TokenType::Close), (TokenType::Bar, TokenType::Bar)]);
+    /// assert!(parser.is_grouping(TokenType::Close) && !parser.is_grouping(TokenType::Abs));
+    /// ```
+    pub fn precedence(&self, operator:T) -> Option<usize>{
+        let priority = self.priority_of(operator)?;
+        let below = self.priority.values().filter(|p| **p < priority).collect::<HashSet<&usize>>();
+
+        Some(below.len())
+    }
+
+    /// Iterates over the pairs of tokens that enclose a part of an expression as (open, close):
+    /// the [high priority group](ExpressionParser::set_high_priority_group) first, then the [brackets](ExpressionParser::add_bracket)
+    /// in registration order
+    pub fn grouping_pairs(&self) -> impl Iterator<Item = (T, T)> + '_{
+        self.high_priority_group_start.zip(self.high_priority_group_end).into_iter()
+            .chain(self.brackets.iter().map(|(open, close, _)| (*open, *close)))
+    }
+
+    /// Returns true if *kind* opens or closes a part of an expression, see [grouping_pairs](ExpressionParser::grouping_pairs)
+    pub fn is_grouping(&self, kind:T) -> bool{ self.is_group_token(kind) }
+
+    /// Renders an expression back to source text, with parentheses only where the priorities require them
+    /// 
+    /// Operators of the same priority group to the left as when parsing, so `a - (b - c)` keeps its parentheses
+    /// and `(a - b) - c` loses them. Binary operators are surrounded by spaces, the ones rendered as an empty text
+    /// as an [implicit](Adjacency::Implicit) operator are not. [Unknown](Expr::Unknown) sequences are rendered as
+    /// their literals separated by spaces and [missing](Expr::Missing) operands as `?`
+    /// 
+    /// Returns None if parentheses are needed but no [high priority group](ExpressionParser::set_high_priority_group) is registered,
+    /// or if the kind of a [bracket](Expr::Bracket) isn't registered
+    /// 
+    /// text: Gives the source text of a token kind
+    /// 
+    /// # Exemples
+    /// ```rust
+    /// use crate::neoglot_lib::{lexer::*, parser::{expression::*, AST}, regex::Symbol};
+    /// 
+    /// #[derive(Debug, Copy, Clone, Hash, PartialOrd, Eq, PartialEq)]
+    /// enum TokenType{ A, B, C, Sub, Mul, Open, Close }
+    /// 
+    /// impl Symbol for TokenType{}
+    /// impl TokenKind for TokenType{}
+    /// 
+    /// let parser = ExpressionParser::new()
+    ///     .with_operator(TokenType::Sub, 1)
+    ///     .with_operator(TokenType::Mul, 2)
+    ///     .with_high_priority_group(TokenType::Open, TokenType::Close);
+    /// 
+    /// let operand = |kind| AST{ kind: Expr::Operand(kind), children: vec![] };
+    /// let operator = |kind, left, right| AST{ kind: Expr::Operator(kind), children: vec![left, right] };
+    /// let text = |kind| match kind {
+    ///     TokenType::Sub => "-".to_string(),
+    ///     TokenType::Mul => "*".to_string(),
+    ///     TokenType::Open => "(".to_string(),
+    ///     TokenType::Close => ")".to_string(),
+    ///     other => format!("{other:?}")
+    /// };
+    /// 
+    /// // (A - B) * (A - (B - C))
+    /// let right = operator(TokenType::Sub, operand(TokenType::A), operator(TokenType::Sub, operand(TokenType::B), operand(TokenType::C)));
+    /// let ast = operator(TokenType::Mul, operator(TokenType::Sub, operand(TokenType::A), operand(TokenType::B)), right);
+    /// 
+    /// assert_eq!(parser.render(&ast, text).as_deref(), Some("(A - B) * (A - (B - C))"));
+    /// ```
+    pub fn render(&self, ast:&AST<Expr<T>>, text:impl Fn(T) -> String) -> Option<String>{
+        self.render_node(ast, &text)
+    }
+
+    fn render_node(&self, ast:&AST<Expr<T>>, text:&impl Fn(T) -> String) -> Option<String>{
+        let parenthesized = |ast:&AST<Expr<T>>| -> Option<String> {
+            let (open, close) = self.high_priority_group_start.zip(self.high_priority_group_end)?;
+            Some(format!("{}{}{}", text(open), self.render_node(ast, text)?, text(close)))
+        };
+
+        // The priority of a binary operator node, None for the nodes that never need parentheses
+        let binary_priority = |ast:&AST<Expr<T>>| match (&ast.kind, ast.children.len()) {
+            (Expr::Operator(operator), 2) => Some(self.priority_of(*operator).unwrap_or(usize::MAX)),
+            _ => None
+        };
+
+        match (&ast.kind, ast.children.as_slice()) {
+            (Expr::Operator(operator), [left, right]) => {
+                let priority = self.priority_of(*operator).unwrap_or(usize::MAX);
+
+                let left = match binary_priority(left) {
+                    Some(p) if p < priority => parenthesized(left)?,
+                    _ => self.render_node(left, text)?
+                };
+                let right = match binary_priority(right) {
+                    Some(p) if p <= priority => parenthesized(right)?,
+                    _ => self.render_node(right, text)?
+                };
+
+                match text(*operator) {
+                    operator if operator.is_empty() => Some(format!("{left} {right}")),
+                    operator => Some(format!("{left} {operator} {right}"))
+                }
+            },
+
+            // Prefix operators bind tighter than any binary one
+            (Expr::Operator(operator), [operand]) => {
+                let operand = match binary_priority(operand) {
+                    Some(_) => parenthesized(operand)?,
+                    None => self.render_node(operand, text)?
+                };
+
+                Some(format!("{}{operand}", text(*operator)))
+            },
+
+            (Expr::Bracket(kind), children) => {
+                let (open, close, _) = self.brackets.iter().find(|(_, _, k)| k == kind)?;
+                let content = match children.first() {
+                    Some(child) => self.render_node(child, text)?,
+                    None => String::new()
+                };
+
+                Some(format!("{}{content}{}", text(*open), text(*close)))
+            },
+
+            (Expr::Operator(operator), _) | (Expr::Operand(operator), _) => Some(text(*operator)),
+            (Expr::Unknown(tokens), _) => Some(tokens.iter().map(|t| t.literal.as_str()).collect::<Vec<&str>>().join(" ")),
+            (Expr::Missing, _) => Some("?".to_string())
+        }
+    }
+
+    /// Returns true if the token at *i* is a prefix operator used as a prefix
+    fn is_prefix(&self, candidates:&[Token<T>], i:usize) -> bool{
+        if !candidates.get(i).is_some_and(|c| self.prefix.contains(c.kind)){ return false; }
+        if i == 0 { return true; }
+
+        let prev = candidates[i-1].kind;
+        if self.operators.contains(prev) || self.high_priority_group_start == Some(prev){ return true; }
+
+        // After an operand only `a -1` makes a prefix
+        self.spacing_sensitive && !candidates[i-1].touches(&candidates[i])
+            && candidates.get(i+1).is_some_and(|next| candidates[i].touches(next))
+    }
+
+    /// Returns true if the tokens at *i-1* and *i* are two adjacent operands
+    fn is_adjacency(&self, candidates:&[Token<T>], i:usize) -> bool{
+        if i == 0 || i >= candidates.len(){ return false; }
+
+        let (prev, next) = (candidates[i-1].kind, candidates[i].kind);
+
+        let ends_operand = !self.operators.contains(prev) && self.high_priority_group_start != Some(prev);
+        let starts_operand = (!self.operators.contains(next) && self.high_priority_group_end != Some(next))
+            || self.is_prefix(candidates, i);
+
+        ends_operand && starts_operand
+    }
+
+    /// Finds the first adjacency outside of any group
+    fn find_adjacency(&self, candidates:&[Token<T>]) -> Option<usize>{
+        let mut depth:usize = 0;
+        let mut skip_to = 0;
+
+        for (i, c) in candidates.iter().enumerate(){
+            // The content of the brackets is a single operand
+            if i < skip_to { continue; }
+            if let Some(end) = self.bracket_end(candidates, i){ skip_to = end.unwrap_or(candidates.len()) + 1; }
+
+            if depth == 0 && self.is_adjacency(candidates, i){ return Some(i); }
+
+            if self.high_priority_group_start == Some(c.kind){ depth += 1; }
+            else if self.high_priority_group_end == Some(c.kind){ depth = depth.saturating_sub(1); }
+        }
+
+        None
+    }
+
+    /// Returns true if *kind* is one of the high priority group tokens or brackets
+    fn is_group_token(&self, kind:T) -> bool{
+        self.high_priority_group_start == Some(kind) || self.high_priority_group_end == Some(kind)
+            || self.brackets.iter().any(|(open, close, _)| kind == *open || kind == *close)
+    }
+
+    /// Returns the brackets opened by *kind*, as (close, kind)
+    fn bracket_opened_by(&self, kind:T) -> Option<(T, T)>{
+        self.brackets.iter().find(|(open, _, _)| *open == kind).map(|(_, close, kind)| (*close, *kind))
+    }
+
+    /// Returns the index of the token closing the brackets opened at *i*,
+    /// Err(()) if they are never closed and None if no brackets are opened at *i*
+    fn bracket_end(&self, candidates:&[Token<T>], i:usize) -> Option<Result<usize, ()>>{
+        let (close, _) = self.bracket_opened_by(candidates.get(i)?.kind)?;
+
+        Some(find_matching(candidates, candidates[i].kind, close, i).map_err(|_| ()))
+    }
+
+    /// Finds the first bracket token that has no counterpart
+    /// 
+    /// Returns the index of that token
+    fn find_unbalanced_bracket(&self, candidates:&[Token<T>]) -> Option<usize>{
+        let mut i = 0;
+
+        while i < candidates.len(){
+            match self.bracket_end(candidates, i){
+                Some(Ok(end)) => i = end,
+                Some(Err(())) => return Some(i),
+                None if self.brackets.iter().any(|(_, close, _)| *close == candidates[i].kind) => return Some(i),
+                None => {}
+            }
+
+            i += 1;
+        }
+
+        None
+    }
+
+    /// Returns the kind and content of the brackets spanning the whole expression, if any
+    fn whole_bracket<'a>(&self, candidates:&'a[Token<T>]) -> Option<(T, &'a[Token<T>])>{
+        let (_, kind) = self.bracket_opened_by(candidates.first()?.kind)?;
+
+        match self.bracket_end(candidates, 0){
+            Some(Ok(end)) if end == candidates.len() - 1 => Some((kind, &candidates[1..end])),
+            _ => None
+        }
     }
 
     /// Finds the operator with the least priority
     /// 
+    /// Operators are compared by group nesting depth first, then by priority,
+    /// so an operator inside a high priority group always binds tighter than one outside,
+    /// whatever their priorities are.
+    /// 
+    /// On equal depth and priority the rightmost operator is chosen, making operators left associative.
+    /// Operators used as a prefix never split the expression
+    /// 
     /// candidates: An expression
-    fn find_min_priority(&self, candidates:&[Token<T>]) -> Option<usize>{
-        let mut min_priority = None;
+    fn find_min_priority(&self, candidates:&[Token<T>]) -> Option<Split<T>>{
+        let mut min_priority:Option<(usize, usize)> = None;
         let mut min_priority_indx = None;
-        let mut priority_multiplier = 1;
+        let mut depth:usize = 0;
+        let mut skip_to = 0;
 
-        for i in 0..candidates.len(){
-            let c = &candidates[i];
-            if let Some(priority) = self.priority.get(&c.kind){
+        let implicit = match self.adjacency{
+            Adjacency::Implicit(operator) => self.priority_of(operator).map(|p| (operator, p)),
+            _ => None
+        };
 
-                // If we are inside a parenthesis-like bloc,
-                // the priority must be multiplied
-                // we also skip the bloc start/end
-                if self.high_priority_group_start.is_some_and(|e| e == c.kind){
-                    priority_multiplier = priority_multiplier * 100;
-                    continue;
-                }else if self.high_priority_group_end.is_some_and(|e| e == c.kind){
-                    priority_multiplier = priority_multiplier / 100;
-                    continue;
-                }else{ priority_multiplier = 1 };
-
-                match min_priority {
-                    Some(min_p) =>{
-                        if priority*priority_multiplier <= min_p {
-                            min_priority = Some(*priority * priority_multiplier);
-                            min_priority_indx = Some(i);
-                        }
-                    },
+        for (i, c) in candidates.iter().enumerate(){
 
-                    None => {
-                        min_priority = Some(priority * priority_multiplier);
-                        min_priority_indx = Some(i);
-                    }
+            // The operators inside brackets never split the expression
+            if i < skip_to { continue; }
+            if let Some(end) = self.bracket_end(candidates, i){ skip_to = end.unwrap_or(candidates.len()) + 1; }
+
+            // An adjacency acts like an operator standing right before this token
+            if let Some((operator, priority)) = implicit.filter(|_| self.is_adjacency(candidates, i)){
+                let key = (depth, priority);
+
+                if min_priority.is_none_or(|min_p| key <= min_p){
+                    min_priority = Some(key);
+                    min_priority_indx = Some(Split::Adjacency(i, operator));
+                }
+            }
+
+            // Group start/end only change the depth
+            if self.high_priority_group_start.is_some_and(|e| e == c.kind){
+                depth += 1;
+                continue;
+            }else if self.high_priority_group_end.is_some_and(|e| e == c.kind){
+                depth = depth.saturating_sub(1);
+                continue;
+            }
+
+            if self.is_prefix(candidates, i){ continue; }
+
+            if let Some(priority) = self.priority.get(&c.kind){
+                let key = (depth, *priority);
+
+                if min_priority.is_none_or(|min_p| key <= min_p){
+                    min_priority = Some(key);
+                    min_priority_indx = Some(Split::Operator(i));
                 }
-                
             }
         }
 
         min_priority_indx
     }
 
-    /// Checks if the number of start_groups is equals to the number of end_groups
-    fn check_groups_validity(&self, candidates:&[Token<T>]) -> bool{
-        if self.high_priority_group_start.is_none() || self.high_priority_group_end.is_none(){
-            return true;
-        }
-        let mut open_groups = 0;
-        for c in candidates{
-            if c.kind == self.high_priority_group_start.unwrap(){ open_groups += 1; }
-            else if c.kind == self.high_priority_group_end.unwrap(){ open_groups -= 1; }
+    /// Finds the first group token that has no counterpart
+    /// 
+    /// This is either a closing token without an opening one,
+    /// or the last opening token that is never closed
+    /// 
+    /// Returns the index of that token
+    fn find_unbalanced_group(&self, candidates:&[Token<T>]) -> Option<usize>{
+        let (Some(start), Some(end)) = (self.high_priority_group_start, self.high_priority_group_end) else {
+            return None;
+        };
 
-            if open_groups < 0{ break; }
+        let mut open_groups = vec![];
+        for (i, c) in candidates.iter().enumerate(){
+            if c.kind == start{ open_groups.push(i); }
+            else if c.kind == end && open_groups.pop().is_none(){ return Some(i); }
         }
-        
-        open_groups == 0
+
+        open_groups.pop()
     }
 
-    /// Strips the leading and trailing groups token
-    fn strip_group<'a>(&self, candidates:&'a[Token<T>]) -> Result<Option<&'a[Token<T>]>, ParsingError<T>>{
-        
-        if self.high_priority_group_start.is_none()
-        || self.high_priority_group_end.is_none()
-        || candidates.is_empty(){
-            return Ok(Some(candidates));
+    /// Strips the groups tokens surrounding the whole expression
+    /// 
+    /// Fails on unbalanced groups and on empty groups
+    fn strip_group<'a>(&self, candidates:&'a[Token<T>]) -> Result<&'a[Token<T>], ParsingError<T>>{
+        let (Some(start), Some(end)) = (self.high_priority_group_start, self.high_priority_group_end) else {
+            return Ok(candidates);
+        };
+
+        if let Some(stray) = self.find_unbalanced_group(candidates){
+            return Err(ParsingError::InvalidGroups(candidates[stray].location.clone()));
         }
 
-        if !self.check_groups_validity(candidates){
-            let loc = candidates[0].location.clone();
-            return Err(ParsingError::InvalidGroups(loc))
+        let mut stripped = candidates;
+
+        while stripped.first().is_some_and(|c| c.kind == start){
+            // Only strip when the first group spans the whole expression, as in (a)
+            // and not in (a) + (b)
+            if find_matching(stripped, start, end, 0) != Ok(stripped.len()-1){ break; }
+
+            if stripped.len() == 2{
+                return Err(ParsingError::EmptyGroup(stripped[0].location.clone()));
+            }
+
+            stripped = &stripped[1..stripped.len()-1];
         }
 
-        let mut left = 0;
-        let mut right = 0;
-        let mut search = true;
+        Ok(stripped)
+    }
+
+
+    /// Returns the number of leading tokens making up an expression
+    /// 
+    /// The expression alternates operands and operators, it ends on the first token breaking the alternation
+    /// or on an operator whose priority is below *min_priority*
+    fn expression_length(&self, candidates:&[Token<T>], operands:&KindSet<T>, min_priority:usize) -> usize{
+        let implicit = match self.adjacency{
+            Adjacency::Implicit(operator) => self.priority_of(operator).filter(|p| *p >= min_priority).is_some(),
+            _ => false
+        };
+
         let mut i = 0;
 
-        while search && i < candidates.len() {
-            let c = &candidates[i];
+        loop{
+            // An operand, either a single token or a whole group
+            match candidates.get(i){
+                Some(c) if self.high_priority_group_start == Some(c.kind) => {
+                    let end = self.high_priority_group_end.unwrap_or(c.kind);
 
-            if Some(c.kind) == self.high_priority_group_start{ left += 1; }
-            else{ search = false; }
+                    // An unclosed group ends the expression, the parsing reports it
+                    match find_matching(candidates, c.kind, end, i){
+                        Ok(close) => i = close + 1,
+                        Err(_) => return i + 1
+                    }
+                },
+                Some(c) if self.bracket_opened_by(c.kind).is_some() => match self.bracket_end(candidates, i) {
+                    Some(Ok(close)) => i = close + 1,
+                    _ => return i + 1
+                },
+                Some(c) if operands.contains(c.kind) => i += 1,
+                // Prefixes come before the operand
+                Some(c) if self.prefix.contains(c.kind) => {
+                    i += 1;
+                    continue;
+                },
+                _ => return i
+            }
 
-            i += 1;
+            // An operator binding tight enough, or an implicit one before the next operand
+            match candidates.get(i){
+                // `a -1` is two adjacent operands
+                Some(_) if self.is_prefix(candidates, i) => if !implicit { return i; },
+                Some(c) if self.priority_of(c.kind).is_some_and(|p| p >= min_priority) => i += 1,
+                Some(c) if implicit && (operands.contains(c.kind) || self.high_priority_group_start == Some(c.kind) || self.bracket_opened_by(c.kind).is_some()) => {},
+                _ => return i
+            }
         }
+    }
 
-        search = true;
-        i = candidates.len()-1;
+    /// Parse the expression at the current position of a [Parser], consuming its tokens
+    /// 
+    /// Unlike [parse](ExpressionParser::parse) the end of the expression doesn't need to be known beforehand,
+    /// it stops right before the first token that can't continue it
+    /// 
+    /// operands: The [tokens](TokenKind) accepted as operands
+    /// 
+    /// min_priority: Operators with a lower priority end the expression, as in a precedence climbing parser
+    /// 
+    /// Returns None without consuming anything if no expression starts at the current position
+    pub fn parse_from<'a>(&self, parser:&mut Parser<'a, T>, operands:&KindSet<T>, min_priority:usize) -> Option<ExpressionResult<'a, T>>{
+        let length = self.expression_length(parser.tokens, operands, min_priority);
+        let expression = &parser.tokens[..length];
+
+        parser.skip(length);
+        self.parse(expression)
+    }
 
-        while search{
-            let c = &candidates[i];
+    /// Parse an expression, recovering from the errors instead of failing
+    /// 
+    /// Missing operands and the content of empty groups are replaced by [Expr::Missing],
+    /// unclosed and stray group tokens are ignored. Each recovery is reported as a [ParsingError]
+    /// 
+    /// This keeps a tree available for incomplete expressions, as the ones being typed in an editor
+    pub fn parse_recovering<'a>(&self, candidates:&'a[Token<T>]) -> Option<RecoveredExpression<'a, T>>
+    {
+        if candidates.is_empty(){ return None; }
 
-            if Some(c.kind) == self.high_priority_group_end { right +=1; }
-            else { search = false; }
+        let mut errors = vec![];
 
-            if i == 0{ search = false;}
-            else { i -= 1;}
+        if let Err(limit) = self.limits.check_tokens(candidates.len()){
+            errors.push(ParsingError::LimitExceeded { limit, location: Some(candidates[0].location.clone()) });
+            return Some((AST { kind: Expr::Unknown(candidates), children: vec![] }, errors));
         }
 
-        Ok(candidates.get(left..candidates.len()-right))
+        let ast = self.recover_nested(candidates, 0, &Deadline::start(&self.limits), &mut errors);
+        Some((ast, errors))
     }
 
+    // Strips the group tokens surrounding the whole expression and the unbalanced ones at its edges
+    fn strip_recovering<'a>(&self, candidates:&'a[Token<T>], errors:&mut Vec<ParsingError<T>>) -> &'a[Token<T>]{
+        let (Some(start), Some(end)) = (self.high_priority_group_start, self.high_priority_group_end) else {
+            return candidates;
+        };
+
+        let mut stripped = candidates;
+
+        loop{
+            let stray = self.find_unbalanced_group(stripped);
+
+            if stray == Some(0){
+                errors.push(ParsingError::InvalidGroups(stripped[0].location.clone()));
+                stripped = &stripped[1..];
+            }
+            else if stray.is_some() && stray == stripped.len().checked_sub(1){
+                errors.push(ParsingError::InvalidGroups(stripped[stripped.len()-1].location.clone()));
+                stripped = &stripped[..stripped.len()-1];
+            }
+            else if stripped.first().is_some_and(|c| c.kind == start) && find_matching(stripped, start, end, 0) == Ok(stripped.len()-1){
+                if stripped.len() == 2 { errors.push(ParsingError::EmptyGroup(stripped[0].location.clone())); }
+                stripped = &stripped[1..stripped.len()-1];
+            }
+            else { return stripped; }
+        }
+    }
+
+    // Parse a sub-expression nested at depth, recovering from errors
+    fn recover_nested<'a>(&self, candidates:&'a[Token<T>], depth:usize, deadline:&Deadline, errors:&mut Vec<ParsingError<T>>) -> AST<Expr<'a, T>>
+    {
+        let missing = AST { kind: Expr::Missing, children: vec![] };
+
+        if let Err(limit) = self.limits.check_depth(depth).and_then(|_| deadline.check()){
+            errors.push(ParsingError::LimitExceeded { limit, location: candidates.first().map(|c| c.location.clone()) });
+            return missing;
+        }
+
+        let candidates = self.strip_recovering(candidates, errors);
+
+        match candidates.len() {
+            0 => return missing,
+            1 => return AST{ kind: Expr::Operand(candidates[0].kind), children: vec![] },
+            _ => {}
+        }
+
+        if let Some((kind, content)) = self.whole_bracket(candidates){
+            let children = if content.is_empty() { vec![] } else { vec![self.recover_nested(content, depth + 1, deadline, errors)] };
+            return AST{ kind: Expr::Bracket(kind), children };
+        }
+
+        if let Some(stray) = self.find_unbalanced_bracket(candidates){
+            errors.push(ParsingError::InvalidGroups(candidates[stray].location.clone()));
+            return AST { kind: Expr::Unknown(candidates), children: vec![] };
+        }
+
+        if self.adjacency == Adjacency::Error{
+            if let Some(i) = self.find_adjacency(candidates){
+                errors.push(ParsingError::AdjacentOperands(candidates[i].location.clone()));
+            }
+        }
+
+        let Some(split) = self.find_min_priority(candidates) else {
+            if self.is_prefix(candidates, 0){
+                return AST{ kind: Expr::Operator(candidates[0].kind), children: vec![self.recover_nested(&candidates[1..], depth + 1, deadline, errors)] };
+            }
+
+            if let Some(stray) = self.find_unbalanced_group(candidates){
+                errors.push(ParsingError::InvalidGroups(candidates[stray].location.clone()));
+            }
+
+            return AST { kind: Expr::Unknown(candidates), children: vec![] };
+        };
+
+        let (operator, left_sub_expr, right_sub_expr, min_indx) = match split{
+            Split::Operator(i) => (candidates[i].kind, &candidates[0..i], &candidates[i+1..], i),
+            Split::Adjacency(i, operator) => (operator, &candidates[0..i], &candidates[i..], i)
+        };
+
+        let children = [(left_sub_expr, OperandSide::Left), (right_sub_expr, OperandSide::Right)].map(|(sub_expr, side)| {
+            if sub_expr.is_empty(){
+                errors.push(ParsingError::MissingOperand { operator, side, location: candidates[min_indx].location.clone() });
+            }
+
+            self.recover_nested(sub_expr, depth + 1, deadline, errors)
+        });
+
+        AST{ kind: Expr::Operator(operator), children: children.into() }
+    }
 
     /// Parse an expression
-    pub fn parse<'a>(&self, candidates:&'a[Token<T>]) -> Option<Result<AST<Expr<'a, T>>, Vec<ParsingError<T>>>>
+    pub fn parse<'a>(&self, candidates:&'a[Token<T>]) -> Option<ExpressionResult<'a, T>>
+    {
+        if let Err(limit) = self.limits.check_tokens(candidates.len()){
+            return Some(Err(vec![ParsingError::LimitExceeded { limit, location: candidates.first().map(|c| c.location.clone()) }]));
+        }
+
+        self.parse_nested(candidates, 0, &Deadline::start(&self.limits))
+    }
+
+    // Parse a sub-expression nested at depth
+    fn parse_nested<'a>(&self, candidates:&'a[Token<T>], depth:usize, deadline:&Deadline) -> Option<ExpressionResult<'a, T>>
     {
         if candidates.is_empty(){ return None; }
 
+        if let Err(limit) = self.limits.check_depth(depth).and_then(|_| deadline.check()){
+            return Some(Err(vec![ParsingError::LimitExceeded { limit, location: Some(candidates[0].location.clone()) }]));
+        }
+
+        let candidates = match self.strip_group(candidates){
+            Ok(stripped) => stripped,
+            Err(e) => return Some(Err(vec![e]))
+        };
+
         if candidates.len() == 1{
             return Some(Ok(AST{ kind: Expr::Operand(candidates[0].kind), children: vec![] }));
         }
 
+        if let Some(stray) = self.find_unbalanced_bracket(candidates){
+            return Some(Err(vec![ParsingError::InvalidGroups(candidates[stray].location.clone())]));
+        }
+
+        if let Some((kind, content)) = self.whole_bracket(candidates){
+            return match self.parse_nested(content, depth + 1, deadline) {
+                Some(Ok(ast)) => Some(Ok(AST{ kind: Expr::Bracket(kind), children: vec![ast] })),
+                Some(Err(e)) => Some(Err(e)),
+                None => Some(Ok(AST{ kind: Expr::Bracket(kind), children: vec![] }))
+            };
+        }
+
 
-        let min_indx = self.find_min_priority(candidates);
+        if self.adjacency == Adjacency::Error{
+            if let Some(i) = self.find_adjacency(candidates){
+                return Some(Err(vec![ParsingError::AdjacentOperands(candidates[i].location.clone())]));
+            }
+        }
+
+        let split = self.find_min_priority(candidates);
         
-        let result = if let Some(min_indx) = min_indx{
-            let operator = candidates[min_indx].kind;
+        let result = if let Some(split) = split{
+            let (operator, left_sub_expr, right_sub_expr, min_indx) = match split{
+                Split::Operator(i) => (candidates[i].kind, &candidates[0..i], &candidates[i+1..], i),
+
+                Split::Adjacency(i, operator) => (operator, &candidates[0..i], &candidates[i..], i)
+            };
 
             let mut errors:Vec<ParsingError<T>> = vec![];
             let mut children = vec![];
 
 
-            let left_sub_expr = self.strip_group(candidates.get(0..min_indx).unwrap_or_default());
-            let right_sub_expr = self.strip_group(candidates.get(min_indx+1..candidates.len()).unwrap_or_default());
-
-            match left_sub_expr{
-                Ok(opt) => {
-                    if let Some(left) = self.parse(opt.unwrap_or_default()){
-                        match left {
-                            Ok(ast) => children.push(ast),
-                            Err(e) => {
-                                for err in e { errors.push(err); }
-                            }
-                        }
-                    }
-                },
-                Err(e) => errors.push(e)
-            }
-
-            match right_sub_expr{
-                Ok(opt) => {
-                    if let Some(right) = self.parse(opt.unwrap_or_default()){
-                        match right {
-                            Ok(ast) => children.push(ast),
-                            Err(e) => {
-                                for err in e { errors.push(err); }
-                            }
-                        }
-                        
-                    }
-                },
-                Err(e) => errors.push(e)
+            for (sub_expr, side) in [(left_sub_expr, OperandSide::Left), (right_sub_expr, OperandSide::Right)]{
+                match self.parse_nested(sub_expr, depth + 1, deadline){
+                    Some(Ok(ast)) => children.push(ast),
+                    Some(Err(e)) => {
+                        for err in e { errors.push(err); }
+                    },
+                    None => errors.push(ParsingError::MissingOperand {
+                        operator, side, location: candidates[min_indx].location.clone()
+                    })
+                }
             }
 
             if !errors.is_empty(){
@@ -455,6 +1226,14 @@ impl<T:TokenKind> ExpressionParser<T>{
                 Some(Ok(AST{ kind: Expr::Operator(operator), children }))
             }
             
+        }else if self.is_prefix(candidates, 0){
+            let operator = candidates[0].kind;
+
+            match self.parse_nested(&candidates[1..], depth + 1, deadline) {
+                Some(Ok(ast)) => Some(Ok(AST{ kind: Expr::Operator(operator), children: vec![ast] })),
+                Some(Err(e)) => Some(Err(e)),
+                None => Some(Err(vec![ParsingError::MissingOperand { operator, side: OperandSide::Right, location: candidates[0].location.clone() }]))
+            }
         }else{
             Some(Ok(AST { kind: Expr::Unknown(candidates), children: vec![] }))
         };
@@ -1,30 +1,284 @@
 /// Special module for expression parsing
 pub mod expression;
 
-use std::{fmt::{Debug, Display}, error::Error};
+/// Folding ranges and outline extraction for editors
+pub mod outline;
 
-use crate::{lexer::{TokenKind, Token, Location}, regex::Regex};
+/// Syntactic context analysis for code completion
+pub mod completion;
 
+/// Event-driven parsing
+pub mod events;
 
-#[derive(Debug, PartialEq, Clone)]
+/// Recording and export of parse traces
+pub mod trace;
+
+/// Concrete syntax trees and round-trip rendering
+pub mod cst;
+
+/// Call graphs and recursion detection
+pub mod callgraph;
+
+/// Token kinds coverage between lexers and parsers
+pub mod coverage;
+
+/// Definitions and references indexing for editors
+pub mod xref;
+
+use std::{fmt::{Debug, Display}, error::Error, hash::{Hash, Hasher}};
+
+use crate::{cache::StableHasher, lexer::{TokenCategory, TokenKind, TokenLike, Token, Location, KindNames, KindSet}, limits::{Deadline, Limit, Limits}, regex::Regex, Reportable};
+
+
+#[derive(Debug)]
 /// An Abstract Syntax Tree is a semantical unit
+/// 
+/// Cloning, comparing, iterating and [dismantling](AST::dismantle) don't recurse, however deep the tree is.
+/// Dropping and the [Debug] formatting do, so trees deeper than the stack allows should be dismantled instead of dropped
 pub struct AST<T:PartialEq+Clone>{
     /// The type of this AST
     pub kind: T,
     pub children:Vec<AST<T>>
 }
 
+impl<T:PartialEq+Clone> Clone for AST<T>{
+    fn clone(&self) -> Self{
+        // A node is copied once its children are, from the last copies made
+        let mut pending = vec![(self, false)];
+        let mut copies:Vec<AST<T>> = vec![];
+
+        while let Some((node, copied_children)) = pending.pop(){
+            if copied_children {
+                let children = copies.split_off(copies.len() - node.children.len());
+                copies.push(AST { kind: node.kind.clone(), children });
+            }else{
+                pending.push((node, true));
+                pending.extend(node.children.iter().rev().map(|child| (child, false)));
+            }
+        }
+
+        copies.pop().expect("the root is copied last")
+    }
+}
+
+impl<T:PartialEq+Clone> PartialEq for AST<T>{
+    fn eq(&self, other:&Self) -> bool{
+        let mut pending = vec![(self, other)];
+
+        while let Some((a, b)) = pending.pop(){
+            if a.kind != b.kind || a.children.len() != b.children.len() { return false; }
+            pending.extend(a.children.iter().zip(&b.children));
+        }
+
+        true
+    }
+}
+
+impl<T:PartialEq+Clone> AST<T>{
+    /// Creates an AST of *kind* over *children*
+    pub fn new(kind:T, children:Vec<AST<T>>) -> Self{ AST { kind, children } }
+
+    /// Creates an AST of *kind* without children
+    pub fn leaf(kind:T) -> Self{ AST { kind, children: vec![] } }
+
+    /// Drops this AST and its descendants without recursion
+    /// 
+    /// Dropping a tree goes down its children recursively, which overflows the stack on deep enough trees
+    pub fn dismantle(mut self){
+        let mut pending = std::mem::take(&mut self.children);
+
+        while let Some(mut node) = pending.pop(){
+            pending.append(&mut node.children);
+        }
+    }
+
+    /// Iterates over this AST and all of its descendants in preorder
+    pub fn iter(&self) -> Preorder<'_, T>{
+        Preorder { stack: vec![self] }
+    }
+
+    /// Hashes this AST, its kind and all of its descendants
+    /// 
+    /// Equal trees have equal fingerprints, across runs too
+    pub fn fingerprint(&self) -> u64 where T:Hash{
+        self.fingerprint_by(|kind| kind.clone())
+    }
+
+    /// Hashes this AST like [fingerprint](AST::fingerprint), only looking at the part of the kinds given by *key*
+    /// 
+    /// This is how spans or other positions held by the kinds can be ignored,
+    /// so that a moved but unchanged subtree keeps its fingerprint
+    pub fn fingerprint_by<K:Hash>(&self, key:impl Fn(&T) -> K) -> u64{
+        self.fingerprints_by(key)[0]
+    }
+
+    /// Returns the fingerprints of this AST and of all of its descendants, in preorder
+    /// 
+    /// Each fingerprint is built from the ones of the children, so the whole tree is only hashed once.
+    /// The tree is walked without recursion, however deep it is
+    pub fn fingerprints_by<K:Hash>(&self, key:impl Fn(&T) -> K) -> Vec<u64>{
+        let nodes = self.iter().collect::<Vec<&AST<T>>>();
+        let mut sizes = vec![1; nodes.len()];
+        let mut prints = vec![0; nodes.len()];
+
+        // The children are hashed before their parent
+        for (i, ast) in nodes.iter().enumerate().rev(){
+            let mut hasher = StableHasher::new();
+            key(&ast.kind).hash(&mut hasher);
+            ast.children.len().hash(&mut hasher);
+
+            // In preorder the first child follows its parent, the next ones follow the subtree of the previous one
+            let mut child = i + 1;
+            for _ in &ast.children{
+                hasher.write_u64(prints[child]);
+                sizes[i] += sizes[child];
+                child += sizes[child];
+            }
+
+            prints[i] = hasher.finish();
+        }
+
+        prints
+    }
+}
+
+/// A kind of [AST] with a variant standing for input that could not be parsed
+/// 
+/// Recovering parsers put error nodes where the input was broken,
+/// later passes can recognize them and skip them all the same way
+pub trait ErrorKind{
+    /// The kind of an error node
+    fn error() -> Self;
+
+    /// Returns true if this is the kind of an error node
+    fn is_error(&self) -> bool;
+}
+
+impl<T:PartialEq+Clone+ErrorKind> AST<T>{
+    /// An error node, without children
+    pub fn error() -> Self{ AST { kind: T::error(), children: vec![] } }
+
+    /// Returns true if this is an error node
+    pub fn is_error(&self) -> bool{ self.kind.is_error() }
+
+    /// Returns true if this AST or one of its descendants is an error node
+    pub fn has_errors(&self) -> bool{ self.iter().any(AST::is_error) }
+
+    /// Iterates over the error nodes of this AST, in preorder
+    pub fn errors(&self) -> impl Iterator<Item = &AST<T>>{
+        self.iter().filter(|ast| ast.is_error())
+    }
+
+    /// Iterates over this AST and its descendants in preorder, skipping the error nodes and everything under them
+    pub fn iter_valid(&self) -> SkipErrors<'_, T>{
+        SkipErrors { stack: vec![self] }
+    }
+}
+
+/// Preorder iterator over an [AST] and its descendants without the error nodes,
+/// see [AST::iter_valid]
+pub struct SkipErrors<'a, T:PartialEq+Clone+ErrorKind>{
+    stack: Vec<&'a AST<T>>
+}
+
+impl<'a, T:PartialEq+Clone+ErrorKind> Iterator for SkipErrors<'a, T>{
+    type Item = &'a AST<T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop{
+            let node = self.stack.pop()?;
+            if node.is_error() { continue; }
+
+            self.stack.extend(node.children.iter().rev());
+            return Some(node);
+        }
+    }
+}
+
+// What is left to render of an AST
+enum Rendering<'a, T:PartialEq+Clone>{
+    Node(&'a AST<T>),
+    Text(&'static str)
+}
+
+/// Renders the tree on one line as `kind(child child ...)`, without recursion however deep it is
+impl<T:PartialEq+Clone+Debug> Display for AST<T>{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut stack = vec![Rendering::Node(self)];
+
+        while let Some(step) = stack.pop(){
+            let ast = match step {
+                Rendering::Text(text) => { f.write_str(text)?; continue; },
+                Rendering::Node(ast) => ast
+            };
+
+            write!(f, "{:?}", ast.kind)?;
+
+            if !ast.children.is_empty(){
+                f.write_str("(")?;
+                stack.push(Rendering::Text(")"));
+
+                for (i, child) in ast.children.iter().enumerate().rev(){
+                    stack.push(Rendering::Node(child));
+                    if i > 0 { stack.push(Rendering::Text(" ")); }
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Preorder iterator over an [AST] and its descendants
+pub struct Preorder<'a, T:PartialEq+Clone>{
+    stack: Vec<&'a AST<T>>
+}
+
+impl<'a, T:PartialEq+Clone> Iterator for Preorder<'a, T>{
+    type Item = &'a AST<T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let node = self.stack.pop()?;
+        self.stack.extend(node.children.iter().rev());
+
+        Some(node)
+    }
+}
+
+impl<'a, T:PartialEq+Clone> IntoIterator for &'a AST<T>{
+    type Item = &'a AST<T>;
+    type IntoIter = Preorder<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter { self.iter() }
+}
+
 #[derive(Debug, Clone, PartialEq)]
 /// Error type of the parsing process
 pub enum ParsingError<T:TokenKind>{
     /// Groups are not closed properly
+    /// 
+    /// Holds the location of the group token without counterpart
     InvalidGroups(Location),
 
+    /// A group holds nothing, as in `()`
+    EmptyGroup(Location),
+
     /// Could not parse a sequence of tokens
     UnparsedSequence(Location),
 
-    /// A block wasn't closed properly
-    UnclosedBlock(Location),
+    /// A block wasn't closed properly, see [Parser::slice_block]
+    /// 
+    /// It is located at *opener*, where the closing token is most likely missing
+    UnclosedBlock{
+        /// The opening token of the block
+        start: Location,
+
+        /// The innermost opening token left unclosed, the block's own one if every inner block is closed
+        opener: Location,
+
+        /// The last token scanned, where a closing token was expected
+        stopped: Location
+    },
 
     /// Self explanatory
     UnexpectedToken{
@@ -33,25 +287,122 @@ pub enum ParsingError<T:TokenKind>{
         location: Location
     },
 
+    /// An operator lacks one of its operands, as in `a +`
+    MissingOperand{
+        operator: T,
+        side: OperandSide,
+        location: Location
+    },
+
+    /// Two operands follow each other without an operator in between
+    /// 
+    /// Holds the location of the second operand
+    AdjacentOperands(Location),
+
+    /// A [limit](Limits) was exceeded, at the given location if known
+    LimitExceeded{
+        limit: Limit,
+        location: Option<Location>
+    },
+
     /// No tokens provided
     NoTokens
 }
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// The side of an operator an operand stands on
+pub enum OperandSide{
+    Left,
+    Right
+}
+impl<T:TokenKind> ParsingError<T>{
+    /// The human readable description of the error, with the token kinds shown by their friendly *names*
+    pub fn message_with(&self, names:&KindNames<T>) -> String{
+        self.message_by(|kind| format!("`{}`", names.name(kind)))
+    }
+
+    // The human readable description of the error, with the token kinds shown by *show*
+    fn message_by(&self, show:impl Fn(T) -> String) -> String{
+        match self {
+            ParsingError::InvalidGroups(_) => String::from("Unbalanced group"),
+            ParsingError::EmptyGroup(_) => String::from("Empty group"),
+            ParsingError::UnparsedSequence(_) => String::from("Could not parse sequence"),
+            ParsingError::UnclosedBlock { .. } => String::from("Unclosed block"),
+
+            ParsingError::UnexpectedToken { expected, got, .. } => match (expected, got) {
+                (Some(expected), Some(got)) => format!("Expected {} but found {}", show(*expected), show(*got)),
+                (Some(expected), None) => format!("Expected {} but found nothing", show(*expected)),
+                (None, Some(got)) => format!("Unexpected {}", show(*got)),
+                (None, None) => String::from("Unexpected token")
+            },
+
+            ParsingError::MissingOperand { operator, side, .. } => {
+                let side = match side { OperandSide::Left => "left", OperandSide::Right => "right" };
+                format!("Missing {side} operand of {}", show(*operator))
+            },
+
+            ParsingError::AdjacentOperands(_) => String::from("Missing operator between operands"),
+            ParsingError::LimitExceeded { limit, .. } => limit.to_string(),
+            ParsingError::NoTokens => String::from("No tokens to parse")
+        }
+    }
+}
+
+impl<T:TokenCategory> ParsingError<T>{
+    /// The human readable description of the error like [message_with](ParsingError::message_with),
+    /// each token kind being preceded by its [category](TokenCategory::category), as "Expected keyword `then`"
+    pub fn message_with_categories(&self, names:&KindNames<T>) -> String{
+        self.message_by(|kind| match kind.category().name() {
+            Some(category) => format!("{category} `{}`", names.name(kind)),
+            None => format!("`{}`", names.name(kind))
+        })
+    }
+}
+
+impl<T:TokenKind> Reportable for ParsingError<T>{
+    fn message(&self) -> String{ self.message_with(&KindNames::new()) }
+
+    fn location(&self) -> Option<Location>{
+        match self {
+            ParsingError::InvalidGroups(loc)
+            | ParsingError::EmptyGroup(loc)
+            | ParsingError::UnparsedSequence(loc)
+            | ParsingError::AdjacentOperands(loc) => Some(loc.clone()),
+
+            ParsingError::UnclosedBlock { opener, .. } => Some(opener.clone()),
+
+            ParsingError::UnexpectedToken { location, .. }
+            | ParsingError::MissingOperand { location, .. } => Some(location.clone()),
+
+            ParsingError::LimitExceeded { location, .. } => location.clone(),
+
+            ParsingError::NoTokens => None
+        }
+    }
+}
+
 impl<T:TokenKind> Display for ParsingError<T>{
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        f.write_fmt(format_args!("{self:?}"))
+        f.write_str(&self.report())
     }
 }
 impl<T:TokenKind> Error for ParsingError<T>{}
 
+/// Result type of the parsing process
 pub type ParsingResult<T> = Result<AST<T>, ParsingError<T>>;
 
-/// Result type of the parsing process
+/// Result type of [parsing a prefix](Parser::parse_prefix), with the remaining tokens
+pub type PrefixResult<'t, T> = Result<(AST<T>, &'t [Token<T>]), ParsingError<T>>;
+
 /*#[derive(Debug)]
 pub enum ParsingResult<T: TokenKind>{
     Ok(Vec<AST<T>>),
     Err(Vec<ParsingError<T>>)
 }*/
 
+/// The closure type used by a [ParserNode] to build an [AST]
+pub type NodeParser<T> = Box<dyn Fn(&[Token<T>]) -> ParsingResult<T>>;
+
 /// A ParserNode match a set of [tokens](Token) into one type of [AST]
 /// 
 /// # Exemples
@@ -67,29 +418,25 @@ pub enum ParsingResult<T: TokenKind>{
 /// 
 /// 
 /// let tokens = &[
-///     Token{
-///         location: Location{ file: String::from("file"), line: 0, column: 0 },
-///         kind: TokenType::A,
-///         literal: String::from("a")
-///     },
-/// 
-///     Token{
-///         location: Location{ file: String::from("file"), line: 0, column: 2 },
-///         kind: TokenType::A,
-///         literal: String::from("a")
-///     },
-/// 
-///     Token{
-///         location: Location{ file: String::from("file"), line: 1, column: 0 },
-///         kind: TokenType::B,
-///         literal: String::from("b")
-///     },
-/// 
-///     Token{
-///         location: Location{ file: String::from("file"), line: 2, column: 0 },
-///         kind: TokenType::B,
-///         literal: String::from("b")
-///     }
+///     Token::new(
+///         Location{ file: String::from("file"), line: 0, column: 0 },
+///         TokenType::A, String::from("a")
+///     ),
+/// 
+///     Token::new(
+///         Location{ file: String::from("file"), line: 0, column: 2 },
+///         TokenType::A, String::from("a")
+///     ),
+/// 
+///     Token::new(
+///         Location{ file: String::from("file"), line: 1, column: 0 },
+///         TokenType::B, String::from("b")
+///     ),
+/// 
+///     Token::new(
+///         Location{ file: String::from("file"), line: 2, column: 0 },
+///         TokenType::B, String::from("b")
+///     )
 /// ];
 /// 
 /// let nodes = vec![
@@ -136,35 +483,45 @@ pub enum ParsingResult<T: TokenKind>{
 /// ]);
 /// 
 /// ```
-
 pub struct ParserNode<T: TokenKind>{
     /// The matching sequence
     pub regex: Regex<T>,
 
     /// The closure that transforms the [tokens](Token) into an [AST] ([Fn])
-    pub parser: Box<dyn Fn(&[Token<T>]) -> ParsingResult<T>>
+    pub parser: NodeParser<T>
 }
 
 
 
 impl<T: TokenKind> ParserNode<T>{
 
+    /// Parses the start of *tokens* and moves them past the tokens matched, see [parse_prefix](ParserNode::parse_prefix)
     pub fn parse(&self, tokens: &mut &[Token<T>]) -> Option<ParsingResult<T>>{
-        let token_types = tokens.iter().map(|e| e.kind).collect::<Vec<T>>();
-        let (matched, _) = self.regex.split_first(&token_types);
-
+        let (result, remainder) = self.parse_prefix(tokens)?;
+        *tokens = remainder;
 
-        let result = if matched.is_empty(){
-            None
-        }else{
-            Some((self.parser)(&tokens[0..matched.len()]))
-        };
+        Some(result)
+    }
 
-        *tokens = &tokens[matched.len()..];
+    /// Splits *tokens* into the longest prefix matched by the [regex](ParserNode::regex) and the remainder
+    /// 
+    /// Both are slices of *tokens*, so that the remainder can be handed to other nodes with its positions intact
+    pub fn split<'t>(&self, tokens:&'t [Token<T>]) -> (&'t [Token<T>], &'t [Token<T>]){
+        let kinds = tokens.iter().map(|t| t.kind).collect::<Vec<T>>();
+        let (matched, _) = self.regex.split_first(&kinds);
 
-        result
+        tokens.split_at(matched.len())
+    }
 
+    /// Builds an [AST] from the tokens matched at the start of *tokens*, see [split](ParserNode::split),
+    /// and returns it with the unmatched remainder
+    /// 
+    /// Returns None if the regex doesn't match any token
+    pub fn parse_prefix<'t>(&self, tokens:&'t [Token<T>]) -> Option<(ParsingResult<T>, &'t [Token<T>])>{
+        let (matched, remainder) = self.split(tokens);
+        if matched.is_empty() { return None; }
 
+        Some(((self.parser)(matched), remainder))
     }
 }
 
@@ -192,18 +549,248 @@ pub fn expect<T:TokenKind>(kind:Option<T>, expected:T, location:Location) -> Res
 }
 
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// A delimiter without counterpart, as reported by [find_matching]
+pub enum Unmatched{
+    /// The opener at this index is never closed
+    Opener(usize),
+
+    /// The closer at this index closes nothing
+    Closer(usize),
+
+    /// The token at this index is neither an opener nor a closer
+    NotADelimiter(usize)
+}
+
+/// Finds the delimiter balancing the one at index *start*
+/// 
+/// If *start* is an opener, the matching closer is searched forward,
+/// if it is a closer, the matching opener is searched backward
+/// 
+/// Identical delimiters, as quotes, can't be nested: *start* is then an opener
+/// closed by the next occurrence of the delimiter
+/// 
+/// tokens: The tokens to scan
+/// 
+/// open: The opening delimiter
+/// 
+/// close: The closing delimiter
+/// 
+/// start: The index of the delimiter to balance
+pub fn find_matching<T:TokenKind, Tok:TokenLike<T>>(tokens:&[Tok], open:T, close:T, start:usize) -> Result<usize, Unmatched>{
+    let Some(delimiter) = tokens.get(start) else { return Err(Unmatched::NotADelimiter(start)); };
+
+    let mut depth = 0;
+
+    if delimiter.kind() == open && open == close{
+        tokens[start+1..].iter().position(|token| token.kind() == close)
+            .map(|p| start + 1 + p)
+            .ok_or(Unmatched::Opener(start))
+
+    }else if delimiter.kind() == open{
+        for (i, token) in tokens.iter().enumerate().skip(start){
+            if token.kind() == open { depth += 1; }
+            else if token.kind() == close { depth -= 1; }
+
+            if depth == 0 { return Ok(i); }
+        }
+
+        Err(Unmatched::Opener(start))
+
+    }else if delimiter.kind() == close{
+        for i in (0..=start).rev(){
+            let token = &tokens[i];
+
+            if token.kind() == close { depth += 1; }
+            else if token.kind() == open { depth -= 1; }
+
+            if depth == 0 { return Ok(i); }
+        }
+
+        Err(Unmatched::Closer(start))
+
+    }else{
+        Err(Unmatched::NotADelimiter(start))
+    }
+}
+
+/// Helpers to pre-process a sequence of [tokens](Token), or of any [TokenLike]
+pub trait TokenStream<T:TokenKind>{
+    /// The type of the tokens
+    type Token:TokenLike<T>;
+
+    /// Splits the tokens into top-level statements ended by *terminator*
+    /// 
+    /// Terminators inside the blocks delimited by one of the *blocks* pairs are ignored,
+    /// the terminators themselves and empty statements are dropped,
+    /// and a last statement without terminator is kept
+    fn split_statements<'a>(&'a self, terminator:T, blocks:&'a [(T, T)]) -> Statements<'a, T, Self::Token>;
+}
+
+impl<T:TokenKind, Tok:TokenLike<T>> TokenStream<T> for [Tok]{
+    type Token = Tok;
+
+    fn split_statements<'a>(&'a self, terminator:T, blocks:&'a [(T, T)]) -> Statements<'a, T, Tok>{
+        Statements { tokens: self, terminator, blocks }
+    }
+}
+
+/// Iterator over the statements of a sequence of [tokens](Token),
+/// see [TokenStream::split_statements]
+pub struct Statements<'a, T:TokenKind, Tok:TokenLike<T> = Token<T>>{
+    tokens: &'a [Tok],
+    terminator: T,
+    blocks: &'a [(T, T)]
+}
+
+impl<'a, T:TokenKind, Tok:TokenLike<T>> Iterator for Statements<'a, T, Tok>{
+    type Item = &'a [Tok];
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while !self.tokens.is_empty(){
+            let mut depth:usize = 0;
+            let mut end = self.tokens.len();
+
+            for (i, token) in self.tokens.iter().enumerate(){
+                if self.blocks.iter().any(|(open, _)| *open == token.kind()){ depth += 1; }
+                else if self.blocks.iter().any(|(_, close)| *close == token.kind()){ depth = depth.saturating_sub(1); }
+                else if depth == 0 && token.kind() == self.terminator{
+                    end = i;
+                    break;
+                }
+            }
+
+            let statement = &self.tokens[..end];
+            self.tokens = self.tokens.get(end+1..).unwrap_or_default();
+
+            if !statement.is_empty(){ return Some(statement); }
+        }
+
+        None
+    }
+}
+
 /// Parse a set of [tokens](Token) into a list of [AST]
+/// 
+/// # Resolution order
+/// Each parse tries the [nodes](ParserNode) in the order of [nodes](Parser::nodes),
+/// the first one that applies to the next tokens gives the result, be it a tree or an error,
+/// and the next ones aren't tried. The nodes can be reordered with [move_node](Parser::move_node)
 pub struct Parser<'a, T: TokenKind>{
     /// Tokens to parse
     tokens: &'a [Token<T>],
 
+    /// Every token given to the parser
+    origin: &'a [Token<T>],
+
     /// The parsing modules
-    pub nodes: Vec<Box<ParserNode<T>>>
+    pub nodes: Vec<Box<ParserNode<T>>>,
+
+    /// The node attempts, when tracing
+    trace: Option<trace::ParseTrace>,
+
+    /// Bounds on the parsing work
+    limits: Limits,
+
+    /// When the time budget runs out, set on the first parse
+    deadline: Option<Deadline>,
+
+    /// How deeply [nested](Parser::nested) rules currently are
+    depth: usize
+}
+
+#[derive(Debug, Clone)]
+/// The state of a [Parser] at some point, see [Parser::snapshot]
+/// 
+/// The [Lexer](crate::lexer::Lexer) keeps no state between inputs so it doesn't need any
+pub struct ParserSnapshot<'a, T:TokenKind>{
+    /// The tokens left to parse
+    tokens: &'a [Token<T>],
+
+    /// How deeply nested rules were
+    depth: usize,
+
+    /// How many trace entries were recorded, if tracing
+    traced: Option<usize>
+}
+
+impl<T: TokenKind> Default for Parser<'_, T>{
+    fn default() -> Self { Self::new(&[]) }
 }
 
 impl<'a, T: TokenKind> Parser<'a, T>{
 
-    pub fn new(tokens: &'a[Token<T>]) -> Self{ Parser { tokens, nodes: vec![] } }
+    pub fn new(tokens: &'a[Token<T>]) -> Self{
+        Parser { tokens, origin: tokens, nodes: vec![], trace: None, limits: Limits::new(), deadline: None, depth: 0 }
+    }
+
+    /// Bounds the parsing work, builder style
+    /// 
+    /// The tokens limit bounds how many tokens are consumed in total,
+    /// the depth limit bounds how deeply [nested](Parser::nested) rules go
+    /// and the time budget starts with the first parse
+    pub fn with_limits(mut self, limits:Limits) -> Self{
+        self.limits = limits;
+        self
+    }
+
+    // Fails with ParsingError::LimitExceeded if the tokens or time limit is exceeded
+    fn check_limits(&mut self) -> Result<(), ParsingError<T>>{
+        let deadline = *self.deadline.get_or_insert_with(|| Deadline::start(&self.limits));
+
+        let consumed = self.position(self.tokens);
+        let exceeded = match self.limits.max_tokens {
+            Some(max) if consumed >= max => Err(Limit::Tokens(max)),
+            _ => deadline.check()
+        };
+
+        exceeded.map_err(|limit| ParsingError::LimitExceeded { limit, location: self.peek().map(|t| t.location.clone()) })
+    }
+
+    /// Runs *rule* one level deeper, failing instead if the depth or [stack](Limits::max_stack) limit is reached
+    /// 
+    /// Hand-written recursive rules should recurse through this to be protected from stack overflows
+    pub fn nested<R>(&mut self, rule:impl FnOnce(&mut Self) -> Result<R, ParsingError<T>>) -> Result<R, ParsingError<T>>{
+        if let Err(limit) = self.limits.check_depth(self.depth + 1){
+            return Err(ParsingError::LimitExceeded { limit, location: self.peek().map(|t| t.location.clone()) });
+        }
+
+        self.check_limits()?;
+
+        self.depth += 1;
+        let result = rule(self);
+        self.depth -= 1;
+
+        result
+    }
+
+    /// Adds a [ParserNode] to this Parser, builder style
+    pub fn with(mut self, node: ParserNode<T>) -> Self{
+        self.nodes.push(Box::new(node));
+        self
+    }
+
+    /// Inserts a [ParserNode] at *index*, before the nodes from *index* on,
+    /// or at the end if *index* is past it
+    pub fn insert_node(&mut self, index:usize, node: ParserNode<T>){
+        self.nodes.insert(index.min(self.nodes.len()), Box::new(node));
+    }
+
+    /// Removes the node at *index* and returns it, None if there is none
+    pub fn remove_node(&mut self, index:usize) -> Option<ParserNode<T>>{
+        (index < self.nodes.len()).then(|| *self.nodes.remove(index))
+    }
+
+    /// Moves the node at *from* to *to*, shifting the nodes in between
+    /// 
+    /// Returns false and does nothing if either position is out of the nodes
+    pub fn move_node(&mut self, from:usize, to:usize) -> bool{
+        if from >= self.nodes.len() || to >= self.nodes.len() { return false; }
+
+        let node = self.nodes.remove(from);
+        self.nodes.insert(to, node);
+        true
+    }
 
     /// Parse with the first [ParserNode] that match the current sequence of tokens
     pub fn parse_with_node(&mut self) -> ParsingResult<T>{
@@ -212,9 +799,27 @@ impl<'a, T: TokenKind> Parser<'a, T>{
             return Err(ParsingError::NoTokens);
         }
 
-        for node in &self.nodes{
-                
-            if let Some(result) = node.parse(&mut self.tokens){
+        self.check_limits()?;
+
+        for (i, node) in self.nodes.iter().enumerate(){
+            let start = self.position(self.tokens);
+            let result = node.parse(&mut self.tokens);
+            let end = self.position(self.tokens);
+
+            if let Some(trace) = &mut self.trace{
+                trace.entries.push(trace::TraceEntry {
+                    node: i,
+                    start,
+                    end,
+                    outcome: match &result {
+                        Some(Ok(_)) => trace::TraceOutcome::Parsed,
+                        Some(Err(_)) => trace::TraceOutcome::Failed,
+                        None => trace::TraceOutcome::Skipped
+                    }
+                });
+            }
+
+            if let Some(result) = result{
                 return result;
                 /*match result{
                     ParsingResult::Ok(frst) => {
@@ -234,18 +839,168 @@ impl<'a, T: TokenKind> Parser<'a, T>{
 
         }
 
-        return Err(ParsingError::UnparsedSequence(self.tokens[0].location.clone()));
+        Err(ParsingError::UnparsedSequence(self.tokens[0].location.clone()))
+    }
 
-        
+    /// Parses the start of *tokens* with the first [node](ParserNode) that applies, as [parse_with_node](Parser::parse_with_node),
+    /// and returns the tree with the remainder the node didn't match
+    /// 
+    /// The tokens are usually a part of the ones of the parser, as a [statement](TokenStream::split_statements)
+    /// or the remainder of an earlier prefix, so that several nodes can share a statement.
+    /// The tokens left to the parser don't change, the [trace](Parser::start_trace) and the limits count positions in all the tokens
+    pub fn parse_prefix(&mut self, tokens:&'a [Token<T>]) -> PrefixResult<'a, T>{
+        let left = std::mem::replace(&mut self.tokens, tokens);
+        let result = self.parse_with_node();
+        let remainder = std::mem::replace(&mut self.tokens, left);
+
+        result.map(|ast| (ast, remainder))
+    }
+
+    // The index of the first of *tokens* in all the tokens of the parser,
+    // other tokens being counted as the end of them
+    fn position(&self, tokens:&[Token<T>]) -> usize{
+        let range = self.origin.as_ptr_range();
+
+        if range.contains(&tokens.as_ptr()) || tokens.as_ptr() == range.end{
+            (tokens.as_ptr() as usize - range.start as usize) / std::mem::size_of::<Token<T>>()
+        }
+        else { self.origin.len().saturating_sub(tokens.len()) }
+    }
+
+    /// Parses every remaining token with the [nodes](ParserNode), recovering from the errors
+    /// 
+    /// Each run of tokens no node could parse is skipped and replaced by one [error node](ErrorKind),
+    /// only the first error of the run is reported
+    pub fn parse_recovering(&mut self) -> (Vec<AST<T>>, Vec<ParsingError<T>>) where T:ErrorKind{
+        let mut forest = vec![];
+        let mut errors = vec![];
+        let mut recovering = false;
+
+        while !self.finished(){
+            match self.parse_with_node(){
+                Ok(ast) => {
+                    forest.push(ast);
+                    recovering = false;
+                },
+
+                // Nothing more can be parsed once a limit is exceeded
+                Err(e @ ParsingError::LimitExceeded { .. }) => {
+                    errors.push(e);
+                    forest.push(AST::error());
+                    break;
+                },
+
+                Err(e) => {
+                    if !recovering{
+                        errors.push(e);
+                        forest.push(AST::error());
+                        recovering = true;
+                    }
+                    self.skip(1);
+                }
+            }
+        }
+
+        (forest, errors)
     }
 
-    /// Skips *num* numbers of tokens if possible
-    pub fn skip(&mut self, num: usize){
-        if let Some(t) = self.tokens.get(num..){
-            self.tokens = t;
+    /// Skips *num* numbers of tokens, or all the remaining ones if there are fewer
+    /// 
+    /// Returns the number of tokens actually skipped
+    pub fn skip(&mut self, num: usize) -> usize{
+        let skipped = num.min(self.tokens.len());
+        self.tokens = &self.tokens[skipped..];
+
+        skipped
+    }
+
+    /// Skips tokens as long as they are of type *kind*
+    /// 
+    /// Returns the number of tokens skipped
+    pub fn skip_while(&mut self, kind: T) -> usize{
+        let skipped = self.tokens.iter().take_while(|t| t.kind == kind).count();
+
+        self.skip(skipped)
+    }
+
+    /// Runs *rule* once, rolling back the consumed tokens if it fails
+    /// 
+    /// Returns the result of the rule or None if it failed
+    pub fn opt<R>(&mut self, rule:impl FnOnce(&mut Self) -> Result<R, ParsingError<T>>) -> Option<R>{
+        let checkpoint = self.tokens;
+
+        match rule(self){
+            Ok(result) => Some(result),
+            Err(_) => {
+                self.tokens = checkpoint;
+                None
+            }
         }
     }
 
+    /// Runs *rule* until it fails, rolling back the tokens consumed by the failed attempt
+    /// 
+    /// Also stops when the rule succeeds without consuming any token, since it would repeat forever
+    pub fn many<R>(&mut self, mut rule:impl FnMut(&mut Self) -> Result<R, ParsingError<T>>) -> Vec<R>{
+        let mut results = vec![];
+
+        loop{
+            let remaining = self.tokens.len();
+            let Some(result) = self.opt(&mut rule) else { break; };
+            results.push(result);
+
+            if self.tokens.len() == remaining { break; }
+        }
+
+        results
+    }
+
+    /// Captures the current state of the parser, to [restore](Parser::restore) it later
+    pub fn snapshot(&self) -> ParserSnapshot<'a, T>{
+        ParserSnapshot {
+            tokens: self.tokens,
+            depth: self.depth,
+            traced: self.trace.as_ref().map(|trace| trace.entries.len())
+        }
+    }
+
+    /// Brings the parser back to a [snapshot](Parser::snapshot)
+    /// 
+    /// The tokens consumed and the trace entries recorded since are forgotten,
+    /// the time budget keeps running
+    pub fn restore(&mut self, snapshot:ParserSnapshot<'a, T>){
+        self.tokens = snapshot.tokens;
+        self.depth = snapshot.depth;
+
+        if let (Some(trace), Some(traced)) = (&mut self.trace, snapshot.traced){
+            trace.entries.truncate(traced);
+        }
+    }
+
+    /// Runs *rule* and restores the parser afterwards, whatever the rule did
+    pub fn speculate<R>(&mut self, rule:impl FnOnce(&mut Self) -> R) -> R{
+        let snapshot = self.snapshot();
+        let result = rule(self);
+        self.restore(snapshot);
+
+        result
+    }
+
+    /// Runs *rule* on a parser over other *tokens*, with the same [nodes](ParserNode) and limits
+    /// 
+    /// Lets tools parse "what if" edits of the source, as the tokens of a
+    /// [Lexer](crate::lexer::Lexer) run on the edited text, without touching this parser.
+    /// The other parser starts untraced, with a fresh time budget
+    pub fn speculate_on<R>(&mut self, tokens:&[Token<T>], rule:impl FnOnce(&mut Parser<'_, T>) -> R) -> R{
+        let mut other = Parser::new(tokens).with_limits(self.limits);
+        other.nodes = std::mem::take(&mut self.nodes);
+
+        let result = rule(&mut other);
+        self.nodes = other.nodes;
+
+        result
+    }
+
     /// Pops the current token out of the parser and return it or None
     pub fn pop(&mut self) -> Option<&Token<T>>{
         if self.finished() { return None; }
@@ -258,7 +1013,7 @@ impl<'a, T: TokenKind> Parser<'a, T>{
 
     /// Returns the current token or None
     pub fn peek(&self) -> Option<&Token<T>>{
-        self.tokens.get(0)
+        self.tokens.first()
     }
 
     /// returns the token at index *i* or None
@@ -266,6 +1021,13 @@ impl<'a, T: TokenKind> Parser<'a, T>{
         self.tokens.get(i)
     }
 
+    /// Returns true if the token at index *i* is directly followed by the next one, see [Token::touches]
+    /// 
+    /// Returns false if either of them is missing
+    pub fn touches_next(&self, i:usize) -> bool{
+        self.tokens.get(i).zip(self.tokens.get(i + 1)).is_some_and(|(token, next)| token.touches(next))
+    }
+
     /// Returns true if all tokens have been consumed
     pub fn finished(&self) -> bool{ self.tokens.is_empty() }
 
@@ -276,39 +1038,43 @@ impl<'a, T: TokenKind> Parser<'a, T>{
         self.peek().unwrap().kind == kind
     }
 
+    /// Returns true if the current token is of one of the types in *kinds*
+    pub fn on_any_of(&self, kinds:&KindSet<T>) -> bool{
+        self.peek().is_some_and(|t| kinds.contains(t.kind))
+    }
+
 
     /// Slices a block out of the tokens for further parsing
     /// 
     /// The opening and last closing tokens are omitted
+    /// 
+    /// If the block isn't closed, the [error](ParsingError::UnclosedBlock) points at the innermost opening token left unclosed,
+    /// as the second `{` of `{ a { b { c } d`
     pub fn slice_block(&self, begin:T, end:T) -> Result<&'a[Token<T>], ParsingError<T>>{
 
-        let mut open_blocks = 1;
-        let mut i = 1;
-        let mut last_block_end = 0;
-
         if self.finished(){ return Ok(&[]); }
 
-        if let Err(e) = expect(Some(self.tokens[0].kind), begin, self.tokens[0].location.clone()){
-            return Err(e);
-        }
+        expect(Some(self.tokens[0].kind), begin, self.tokens[0].location.clone())?;
 
-        while i < self.tokens.len() && open_blocks != 0{
-            let token = self.peek_at(i).unwrap();
+        match find_matching(self.tokens, begin, end, 0){
+            Ok(last_block_end) => Ok(&self.tokens[1..last_block_end]),
+            Err(_) => {
+                // The openers still unclosed, the innermost last
+                let mut openers = vec![];
 
-            if token.kind == begin { open_blocks += 1; }
-            else if token.kind == end{
-                open_blocks -=1;
+                for (i, token) in self.tokens.iter().enumerate(){
+                    if token.kind == begin { openers.push(i); }
+                    else if token.kind == end { openers.pop(); }
+                }
 
-                if open_blocks == 0 { last_block_end = i; }
-            }
-
-            i += 1;
-        }
+                let opener = openers.last().map_or(0, |i| *i);
 
-        if open_blocks == 0{
-            Ok(&self.tokens[1..last_block_end])
-        }else{
-            Err(ParsingError::UnclosedBlock(self.tokens[0].location.clone()))
+                Err(ParsingError::UnclosedBlock {
+                    start: self.tokens[0].location.clone(),
+                    opener: self.tokens[opener].location.clone(),
+                    stopped: self.tokens[self.tokens.len() - 1].location.clone()
+                })
+            }
         }
 
     }
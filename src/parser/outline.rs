@@ -0,0 +1,65 @@
+use crate::lexer::Span;
+
+use super::AST;
+
+#[derive(Debug, Clone, PartialEq)]
+/// A range of lines an editor can fold
+pub struct FoldingRange{
+    /// The first line of the range
+    pub start_line: usize,
+
+    /// The last line of the range
+    pub end_line: usize
+}
+
+#[derive(Debug, Clone, PartialEq)]
+/// An entry of the outline of a file, usually a definition
+pub struct OutlineItem<'a, T:PartialEq+Clone>{
+    /// The [AST] of the definition
+    pub node: &'a AST<T>,
+
+    /// Where the definition is
+    pub span: Span,
+
+    /// The definitions nested inside this one
+    pub children: Vec<OutlineItem<'a, T>>
+}
+
+/// Computes the folding ranges of a forest of [AST]
+/// 
+/// Every node for which *span* returns a [Span] over several lines makes a range
+/// 
+/// span: Gives the span of a node kind, or None if the node should not be folded
+pub fn folding_ranges<T:PartialEq+Clone>(forest:&[AST<T>], span:impl Fn(&T) -> Option<Span>) -> Vec<FoldingRange>{
+    let mut ranges = forest.iter()
+        .flat_map(|ast| ast.iter())
+        .filter_map(|node| span(&node.kind))
+        .filter(|s| s.end.line > s.start.line)
+        .map(|s| FoldingRange{ start_line: s.start.line, end_line: s.end.line })
+        .collect::<Vec<FoldingRange>>();
+
+    ranges.sort_by_key(|r| (r.start_line, r.end_line));
+    ranges.dedup();
+
+    ranges
+}
+
+/// Extracts the outline of a forest of [AST]
+/// 
+/// Definitions nested in other definitions are kept as their children
+/// 
+/// definition: Gives the span of a node kind if it is a definition, or None
+pub fn outline<'a, T:PartialEq+Clone>(forest:&'a [AST<T>], definition:&impl Fn(&T) -> Option<Span>) -> Vec<OutlineItem<'a, T>>{
+    let mut items = vec![];
+
+    for node in forest{
+        let children = outline(&node.children, definition);
+
+        match definition(&node.kind){
+            Some(span) => items.push(OutlineItem { node, span, children }),
+            None => items.extend(children)
+        }
+    }
+
+    items
+}
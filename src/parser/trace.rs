@@ -0,0 +1,86 @@
+use crate::lexer::TokenKind;
+
+use super::Parser;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// The outcome of a [ParserNode](super::ParserNode) attempt
+pub enum TraceOutcome{
+    /// The node matched and built an [AST](super::AST)
+    Parsed,
+
+    /// The node matched but failed to build an [AST](super::AST)
+    Failed,
+
+    /// The node did not match
+    Skipped
+}
+
+#[derive(Debug, Clone, PartialEq)]
+/// One attempt of a [ParserNode](super::ParserNode) recorded in a [ParseTrace]
+pub struct TraceEntry{
+    /// The index of the node in [Parser::nodes]
+    pub node: usize,
+
+    /// The index of the first token the node was tried on
+    pub start: usize,
+
+    /// The index after the last token the node consumed
+    pub end: usize,
+
+    pub outcome: TraceOutcome
+}
+
+#[derive(Debug, Clone, PartialEq, Default)]
+/// Every node attempt made by a [Parser] while tracing, in order
+pub struct ParseTrace{
+    pub entries: Vec<TraceEntry>
+}
+
+impl ParseTrace{
+    /// Exports the trace in the Chrome tracing JSON format
+    /// 
+    /// Token indices are used as timestamps so the trace can be loaded in `chrome://tracing`
+    /// or Perfetto to see which nodes consumed which tokens
+    /// 
+    /// name: Gives the name of a node from its index
+    pub fn to_chrome_trace(&self, name:impl Fn(usize) -> String) -> String{
+        let events = self.entries.iter().map(|e| {
+            let outcome = match e.outcome {
+                TraceOutcome::Parsed => "parsed",
+                TraceOutcome::Failed => "failed",
+                TraceOutcome::Skipped => "skipped"
+            };
+
+            format!(
+                "{{\"name\":\"{}\",\"cat\":\"{outcome}\",\"ph\":\"X\",\"ts\":{},\"dur\":{},\"pid\":0,\"tid\":0,\"args\":{{\"node\":{}}}}}",
+                escape_json(&name(e.node)), e.start, e.end - e.start, e.node
+            )
+        }).collect::<Vec<String>>();
+
+        format!("[{}]", events.join(","))
+    }
+}
+
+// Escapes a string to be put between quotes in JSON
+fn escape_json(text:&str) -> String{
+    let mut escaped = String::new();
+
+    for c in text.chars(){
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            c if (c as u32) < 0x20 => escaped.push_str(&format!("\\u{:04x}", c as u32)),
+            c => escaped.push(c)
+        }
+    }
+
+    escaped
+}
+
+impl<T:TokenKind> Parser<'_, T>{
+    /// Starts recording the node attempts, discarding any previous trace
+    pub fn start_trace(&mut self){ self.trace = Some(ParseTrace::default()); }
+
+    /// Stops recording and returns the trace, if tracing was started
+    pub fn take_trace(&mut self) -> Option<ParseTrace>{ self.trace.take() }
+}
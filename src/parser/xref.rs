@@ -0,0 +1,135 @@
+use std::{collections::HashMap, hash::Hash};
+
+use crate::lexer::{Location, Span};
+
+use super::AST;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// What a node of an [AST] names, as told to [xref_index]
+pub enum Occurrence<N>{
+    /// The node defines the name, with the span to jump to
+    Definition(N, Span),
+
+    /// The node refers to the name
+    Reference(N, Span)
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// A definition found by [xref_index]
+pub struct Definition<N>{
+    pub name: N,
+    pub span: Span,
+
+    /// The index of the innermost definition containing this one
+    pub parent: Option<usize>
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// A reference found by [xref_index]
+pub struct Reference<N>{
+    pub name: N,
+    pub span: Span,
+
+    /// The index of the definition it resolves to, or None if the name isn't defined where it is used
+    pub definition: Option<usize>
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// The definitions and references of forests of [AST], built by [xref_index]
+/// 
+/// Answers the queries behind go-to-definition, find-references and document symbols in editors
+pub struct XrefIndex<N>{
+    /// The definitions, in preorder of the forests
+    pub definitions: Vec<Definition<N>>,
+
+    /// The references, in preorder of the forests
+    pub references: Vec<Reference<N>>
+}
+
+impl<N> XrefIndex<N>{
+    /// Returns the index of the definition named at *location*
+    /// 
+    /// A reference gives the definition it resolves to, otherwise the innermost definition whose span contains *location* is given
+    pub fn definition_at(&self, location:&Location) -> Option<usize>{
+        if let Some(reference) = self.references.iter().find(|r| r.span.contains(location)){
+            return reference.definition;
+        }
+
+        self.definitions.iter().enumerate()
+            .filter(|(_, d)| d.span.contains(location))
+            .max_by(|(_, a), (_, b)| a.span.start.cmp(&b.span.start))
+            .map(|(i, _)| i)
+    }
+
+    /// Iterates over the spans of the references resolving to *definition*
+    pub fn references_of(&self, definition:usize) -> impl Iterator<Item = &Span> + '_{
+        self.references.iter().filter(move |r| r.definition == Some(definition)).map(|r| &r.span)
+    }
+
+    /// Iterates over the indices of the definitions in *file*, in order
+    pub fn document_symbols<'a>(&'a self, file:&'a str) -> impl Iterator<Item = usize> + 'a{
+        self.definitions.iter().enumerate().filter(move |(_, d)| d.span.start.file == file).map(|(i, _)| i)
+    }
+
+    /// Iterates over the references that don't resolve to any definition
+    pub fn unresolved(&self) -> impl Iterator<Item = &Reference<N>>{
+        self.references.iter().filter(|r| r.definition.is_none())
+    }
+}
+
+/// Builds the [XrefIndex] of several forests of [AST], usually one per file
+/// 
+/// The children of a node form a scope: a definition is visible in the whole scope it's in, nested scopes included,
+/// and shadows the definitions of the same name in the enclosing scopes. The roots of all forests share the outermost scope,
+/// so that the top level definitions of a file can be referred to from the others.
+/// In a scope, the first definition of a name wins
+/// 
+/// occurrence: Tells whether a node kind defines or refers to a name, or None
+pub fn xref_index<'a, T:PartialEq+Clone+'a, N:Eq+Hash+Clone>(forests:impl IntoIterator<Item = &'a [AST<T>]>, occurrence:impl Fn(&T) -> Option<Occurrence<N>>) -> XrefIndex<N>{
+    let mut index = XrefIndex { definitions: vec![], references: vec![] };
+
+    // The enclosing scope of every scope, the outermost one being 0
+    let mut scopes:Vec<Option<usize>> = vec![None];
+    let mut defined = HashMap::new();
+    let mut pending_references = vec![];
+
+    for forest in forests{
+        let mut pending = forest.iter().rev().map(|node| (node, 0, None)).collect::<Vec<(&AST<T>, usize, Option<usize>)>>();
+
+        while let Some((node, scope, mut parent)) = pending.pop(){
+            match occurrence(&node.kind) {
+                Some(Occurrence::Definition(name, span)) => {
+                    defined.entry((scope, name.clone())).or_insert(index.definitions.len());
+                    index.definitions.push(Definition { name, span, parent });
+                    parent = Some(index.definitions.len() - 1);
+                },
+                Some(Occurrence::Reference(name, span)) => {
+                    pending_references.push(scope);
+                    index.references.push(Reference { name, span, definition: None });
+                },
+                None => ()
+            }
+
+            if !node.children.is_empty(){
+                scopes.push(Some(scope));
+                let inner = scopes.len() - 1;
+                pending.extend(node.children.iter().rev().map(|child| (child, inner, parent)));
+            }
+        }
+    }
+
+    // References are resolved once every forest is known, so that files can refer to each other in any order
+    for (reference, scope) in index.references.iter_mut().zip(pending_references){
+        let mut scope = Some(scope);
+
+        while let Some(current) = scope{
+            if let Some(&definition) = defined.get(&(current, reference.name.clone())){
+                reference.definition = Some(definition);
+                break;
+            }
+            scope = scopes[current];
+        }
+    }
+
+    index
+}
@@ -0,0 +1,329 @@
+use std::{error::Error, fmt::Display, fs, io, path::{Path, PathBuf}, time::{Duration, Instant}};
+
+use crate::{lexer::{Lexer, LexingResult, Location, TokenKind}, source::Source, suite::Diagnostic, warnings::WarningCategories, Reportable};
+
+#[derive(Debug, Clone, PartialEq)]
+/// What happened in a compilation, as reported by an [EventLog]
+pub enum PipelineEvent{
+    /// A stage of the compilation starts, as lexing or type checking
+    StageStarted(String),
+
+    /// The last started stage with that name ends, after the given time
+    StageFinished(String, Duration),
+
+    /// A file has been processed, with the number of diagnostics emitted for it
+    FileProcessed{
+        path: String,
+        diagnostics: usize
+    },
+
+    /// An error or warning has been found
+    Diagnostic{
+        message: String,
+        location: Option<Location>
+    }
+}
+
+/// A callback receiving the [events](PipelineEvent) of an [EventLog]
+pub type Listener<'l> = Box<dyn FnMut(&PipelineEvent) + 'l>;
+
+/// Sends the [events](PipelineEvent) of a compilation to listeners
+/// 
+/// Build tools wrapping a compiler can show progress and per-file status from the events
+/// instead of parsing the reports, a log without listeners does nothing
+/// 
+/// # Exemples
+/// ```rust
+/// use crate::neoglot_lib::pipeline::*;
+/// 
+/// let mut processed = vec![];
+/// let mut log = EventLog::new().with_listener(|event| {
+///     if let PipelineEvent::FileProcessed{ path, .. } = event { processed.push(path.clone()); }
+/// });
+/// 
+/// let total = log.stage("count", |log| {
+///     log.file_processed("main.ng", 0);
+///     log.file_processed("lib.ng", 0);
+///     2
+/// });
+/// 
+/// drop(log);
+/// assert_eq!(total, 2);
+/// assert_eq!(processed, vec!["main.ng", "lib.ng"]);
+/// ```
+pub struct EventLog<'l>{
+    listeners: Vec<Listener<'l>>
+}
+
+impl Default for EventLog<'_>{
+    fn default() -> Self { Self::new() }
+}
+
+impl<'l> EventLog<'l>{
+    pub fn new() -> Self{ EventLog { listeners: vec![] } }
+
+    /// Calls *listener* on every event, builder style
+    pub fn with_listener(mut self, listener:impl FnMut(&PipelineEvent) + 'l) -> Self{
+        self.listeners.push(Box::new(listener));
+        self
+    }
+
+    /// Sends an event to every listener, in the order they were added
+    pub fn emit(&mut self, event:PipelineEvent){
+        for listener in &mut self.listeners{ listener(&event); }
+    }
+
+    /// Runs *stage* between a `StageStarted` and a `StageFinished` event
+    pub fn stage<R>(&mut self, name:&str, stage:impl FnOnce(&mut Self) -> R) -> R{
+        self.emit(PipelineEvent::StageStarted(name.to_string()));
+
+        let start = Instant::now();
+        let result = stage(self);
+
+        self.emit(PipelineEvent::StageFinished(name.to_string(), start.elapsed()));
+        result
+    }
+
+    /// Emits a `FileProcessed` event
+    pub fn file_processed(&mut self, path:&str, diagnostics:usize){
+        self.emit(PipelineEvent::FileProcessed { path: path.to_string(), diagnostics });
+    }
+
+    /// Emits a `Diagnostic` event for an error or warning
+    pub fn diagnostic(&mut self, diagnostic:&impl Reportable){
+        self.emit(PipelineEvent::Diagnostic { message: diagnostic.message(), location: diagnostic.location() });
+    }
+}
+
+impl<Kind:TokenKind> Lexer<Kind>{
+    /// Tokenizes a file like [tokenize_file](Lexer::tokenize_file),
+    /// emitting a `Diagnostic` event per error and then a `FileProcessed` event
+    pub fn tokenize_file_logged(&self, path:&str, log:&mut EventLog) -> LexingResult<Kind>{
+        let result = self.tokenize_file(path);
+
+        let diagnostics = match &result {
+            LexingResult::Ok(_) => 0,
+            LexingResult::Err(errors) => {
+                for e in errors{ log.diagnostic(e); }
+                errors.len()
+            }
+        };
+
+        log.file_processed(path, diagnostics);
+        result
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+/// A file a [Pipeline] couldn't read, as one that isn't valid UTF-8
+pub struct UnreadableFile{
+    pub path: String,
+
+    /// What went wrong, as told by the system
+    pub reason: String
+}
+
+impl Reportable for UnreadableFile{
+    fn message(&self) -> String{ format!("Could not read the file: {}", self.reason) }
+
+    fn location(&self) -> Option<Location>{ Some(Location { file: self.path.clone(), line: 0, column: 0 }) }
+}
+
+impl Display for UnreadableFile{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.report())
+    }
+}
+
+impl Error for UnreadableFile{}
+
+/// A step run on each file by a [Pipeline], filling the artifact of the file and returning its diagnostics
+pub type Stage<'s, A> = Box<dyn Fn(&Source, &mut A) -> Vec<Diagnostic> + Send + Sync + 's>;
+
+#[derive(Debug, Clone, PartialEq)]
+/// What a [Pipeline] produced for a file
+pub struct FileOutcome<A>{
+    pub path: String,
+
+    /// The artifact filled by the stages, as the tokens or the trees of the file
+    pub artifact: A,
+
+    /// The diagnostics of every stage run on the file, in stage order
+    pub diagnostics: Vec<Diagnostic>
+}
+
+impl<A> FileOutcome<A>{
+    /// Returns true if an error has been emitted for the file
+    pub fn failed(&self) -> bool{ self.diagnostics.iter().any(Diagnostic::is_error) }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+/// The outcome of a [Pipeline] run on a directory, per file in path order
+pub struct BatchReport<A>{
+    pub files: Vec<FileOutcome<A>>
+}
+
+impl<A> Default for BatchReport<A>{
+    fn default() -> Self { BatchReport { files: vec![] } }
+}
+
+impl<A> BatchReport<A>{
+    /// The diagnostics of every file, in path order
+    pub fn diagnostics(&self) -> impl Iterator<Item = &Diagnostic>{
+        self.files.iter().flat_map(|file| &file.diagnostics)
+    }
+
+    /// The number of errors emitted over all files
+    pub fn error_count(&self) -> usize{ self.diagnostics().filter(|d| d.is_error()).count() }
+
+    /// The paths of the files an error has been emitted for
+    pub fn failed(&self) -> impl Iterator<Item = &str>{
+        self.files.iter().filter(|file| file.failed()).map(|file| file.path.as_str())
+    }
+
+    /// Emits a `Diagnostic` event per diagnostic and a `FileProcessed` event per file, in path order
+    pub fn log(&self, log:&mut EventLog){
+        for file in &self.files{
+            for diagnostic in &file.diagnostics{
+                log.emit(PipelineEvent::Diagnostic { message: diagnostic.message.clone(), location: diagnostic.location.clone() });
+            }
+
+            log.file_processed(&file.path, file.diagnostics.len());
+        }
+    }
+}
+
+/// Runs [stages](Stage) on every file of a directory
+/// 
+/// The stages of a file run in the order they were added, and the stages after one emitting an error are skipped.
+/// The files are independent, so they can be processed on several threads with [with_parallelism](Pipeline::with_parallelism)
+/// 
+/// # Exemples
+/// ```rust
+/// use crate::neoglot_lib::{pipeline::*, suite::Diagnostic};
+/// 
+/// let dir = std::env::temp_dir().join("neoglot_pipeline_doc");
+/// std::fs::create_dir_all(dir.join("nested")).unwrap();
+/// std::fs::write(dir.join("main.ng"), "a b c").unwrap();
+/// std::fs::write(dir.join("nested").join("lib.ng"), "d e").unwrap();
+/// std::fs::write(dir.join("notes.txt"), "not a source").unwrap();
+/// 
+/// let pipeline = Pipeline::new()
+///     .with_stage(|source, words:&mut usize| {
+///         *words = source.content().split_whitespace().count();
+///         Vec::<Diagnostic>::new()
+///     })
+///     .with_parallelism();
+/// 
+/// let report = pipeline.run_dir(&dir, "ng").unwrap();
+/// let words = report.files.iter().map(|file| file.artifact).collect::<Vec<usize>>();
+/// 
+/// assert_eq!(words, vec![3, 2]);
+/// assert_eq!(report.error_count(), 0);
+/// ```
+pub struct Pipeline<'s, A>{
+    stages: Vec<Stage<'s, A>>,
+    parallel: bool,
+
+    /// The levels applied to the diagnostics of every stage
+    warnings: WarningCategories
+}
+
+impl<A> Default for Pipeline<'_, A>{
+    fn default() -> Self { Self::new() }
+}
+
+impl<'s, A> Pipeline<'s, A>{
+    pub fn new() -> Self{ Pipeline { stages: vec![], parallel: false, warnings: WarningCategories::new() } }
+
+    /// Runs *stage* on every file after the stages already added, builder style
+    pub fn with_stage(mut self, stage:impl Fn(&Source, &mut A) -> Vec<Diagnostic> + Send + Sync + 's) -> Self{
+        self.stages.push(Box::new(stage));
+        self
+    }
+
+    /// Applies the levels of *warnings* to the diagnostics of every stage, builder style
+    /// 
+    /// The allowed warnings are dropped and the denied ones are errors, which skip the next stages
+    pub fn with_warnings(mut self, warnings:WarningCategories) -> Self{
+        self.warnings = warnings;
+        self
+    }
+
+    /// Processes the files on as many threads as available, builder style
+    /// 
+    /// The files are spread over all cores with the `parallel` feature, otherwise they are processed one after the other.
+    /// The report is the same as with a single thread
+    pub fn with_parallelism(mut self) -> Self{
+        self.parallel = true;
+        self
+    }
+}
+
+impl<A:Default + Send> Pipeline<'_, A>{
+    /// Runs the stages on *source*, stopping after the first stage emitting an error
+    pub fn run_file(&self, source:&Source) -> FileOutcome<A>{
+        let mut artifact = A::default();
+        let mut diagnostics = vec![];
+
+        for stage in &self.stages{
+            let emitted = self.warnings.apply_all(stage(source, &mut artifact));
+            let failed = emitted.iter().any(Diagnostic::is_error);
+            diagnostics.extend(emitted);
+
+            if failed{ break; }
+        }
+
+        FileOutcome { path: source.path().to_string(), artifact, diagnostics }
+    }
+
+    /// Runs the stages on every file under *root* with the extension *extension*, sub-directories included
+    /// 
+    /// The symbolic links to directories aren't followed. A file that can't be read, as one that isn't valid UTF-8,
+    /// gets an [UnreadableFile] error instead of running the stages, the other files being processed anyway.
+    /// Fails if a directory can't be listed.
+    /// The files of the report are in path order, whether they've been processed in parallel or not
+    pub fn run_dir(&self, root:impl AsRef<Path>, extension:&str) -> io::Result<BatchReport<A>>{
+        let mut paths = vec![];
+        let mut dirs = vec![root.as_ref().to_path_buf()];
+
+        while let Some(dir) = dirs.pop(){
+            for entry in fs::read_dir(dir)?{
+                let entry = entry?;
+                let path = entry.path();
+
+                if entry.file_type()?.is_dir(){ dirs.push(path); }
+                else if path.extension().is_some_and(|e| e == extension){ paths.push(path); }
+            }
+        }
+
+        paths.sort();
+
+        Ok(BatchReport { files: self.run_paths(&paths) })
+    }
+
+    #[cfg(feature = "parallel")]
+    fn run_paths(&self, paths:&[PathBuf]) -> Vec<FileOutcome<A>>{
+        use rayon::prelude::*;
+
+        if self.parallel { paths.par_iter().map(|path| self.run_path(path)).collect() }
+        else { paths.iter().map(|path| self.run_path(path)).collect() }
+    }
+
+    #[cfg(not(feature = "parallel"))]
+    fn run_paths(&self, paths:&[PathBuf]) -> Vec<FileOutcome<A>>{
+        paths.iter().map(|path| self.run_path(path)).collect()
+    }
+
+    fn run_path(&self, path:&Path) -> FileOutcome<A>{
+        let path = path.to_string_lossy();
+
+        match Source::from_file(&path) {
+            Ok(source) => self.run_file(&source),
+            Err(error) => {
+                let unreadable = UnreadableFile { path: path.to_string(), reason: error.to_string() };
+                FileOutcome { path: path.to_string(), artifact: A::default(), diagnostics: vec![Diagnostic::error(&unreadable)] }
+            }
+        }
+    }
+}
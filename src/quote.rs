@@ -0,0 +1,98 @@
+use crate::lexer::{Lexer, LexingError, LexingResult, Location, Origin, Token, TokenKind};
+
+/// Builds streams of [tokens](Token) for code generators and macro systems, ready to be fed to a [Parser](crate::parser::Parser)
+/// 
+/// Tokens written by the Quote are [expanded](Origin::Expanded) and get [generated locations](Location::generated),
+/// their column being their offset in the generated code. Spliced user tokens keep their own origins and locations
+/// 
+/// # Exemples
+/// ```rust
+/// use crate::neoglot_lib::{lexer::*, quote::*, regex::*};
+/// 
+/// #[derive(PartialEq, PartialOrd, Eq, Copy, Clone, Debug, Hash)]
+/// enum TokenType{ UInt, Plus }
+/// 
+/// impl Symbol for TokenType{}
+/// impl TokenKind for TokenType{}
+/// 
+/// let lexer = Lexer::new()
+///     .with(LexerNode::new(Regex::new().then(RegexElement::Set('0', '9', Quantifier::OneOrMany)), TokenType::UInt))
+///     .with(LexerNode::new(Regex::new().then(RegexElement::Item('+', Quantifier::Exactly(1))), TokenType::Plus));
+/// 
+/// let user = Token::new(Location{ file: "main".to_string(), line: 3, column: 8 }, TokenType::UInt, "42".to_string());
+/// 
+/// let tokens = Quote::template(&lexer, "increment", "1 + {}", &[&[user.clone()]]).unwrap().build();
+/// 
+/// assert_eq!(tokens.iter().map(|t| t.literal.as_str()).collect::<Vec<&str>>(), vec!["1", "+", "42"]);
+/// assert!(tokens[1].location.is_generated() && tokens[1].origin == Origin::Expanded);
+/// assert_eq!(tokens[2], user);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Quote<T:TokenKind>{
+    /// What generates the tokens, used in their locations
+    origin: String,
+
+    tokens: Vec<Token<T>>,
+
+    /// Offset of the next generated token
+    column: usize
+}
+
+impl<T:TokenKind> Quote<T>{
+    pub fn new(origin:&str) -> Self{ Quote { origin: origin.to_string(), tokens: vec![], column: 0 } }
+
+    /// Appends a generated token
+    pub fn token(mut self, kind:T, literal:&str) -> Self{
+        self.tokens.push(Token::new(Location::generated(&self.origin, self.column), kind, literal.to_string()).with_origin(Origin::Expanded));
+        self.column += literal.chars().count() + 1;
+        self
+    }
+
+    /// Appends user tokens, keeping their locations
+    pub fn splice(mut self, tokens:&[Token<T>]) -> Self{
+        self.tokens.extend_from_slice(tokens);
+        self
+    }
+
+    /// Appends the generated tokens lexed from *code*
+    pub fn text(mut self, lexer:&Lexer<T>, code:&str) -> Result<Self, Vec<LexingError>>{
+        let origin = Location::generated(&self.origin, 0).file;
+        let shift = |mut location:Location, column| { location.column(location.column + column); location };
+
+        match lexer.tokenize_content(code.to_string(), &origin) {
+            LexingResult::Ok(tokens) => {
+                let column = self.column;
+                // The end of input token of the lexer, if any, has an empty literal
+                self.tokens.extend(tokens.into_iter()
+                    .filter(|t| !t.literal.is_empty())
+                    .map(|t| Token { location: shift(t.location, column), origin: Origin::Expanded, ..t }));
+                self.column += code.chars().count();
+                Ok(self)
+            },
+
+            LexingResult::Err(errors) => Err(errors.into_iter().map(|e| LexingError { location: shift(e.location, self.column), ..e }).collect())
+        }
+    }
+
+    /// Lexes a template, replacing each `{}` with the next group of user tokens
+    /// 
+    /// # Panics
+    /// If the number of `{}` and of groups differ
+    pub fn template(lexer:&Lexer<T>, origin:&str, template:&str, args:&[&[Token<T>]]) -> Result<Self, Vec<LexingError>>{
+        let pieces = template.split("{}").collect::<Vec<&str>>();
+        assert_eq!(pieces.len() - 1, args.len(), "The template has {} placeholders but {} groups of tokens were given", pieces.len() - 1, args.len());
+
+        let mut quote = Quote::new(origin).text(lexer, pieces[0])?;
+
+        for (arg, piece) in args.iter().zip(&pieces[1..]){
+            quote = quote.splice(arg);
+            quote.column += 2;
+            quote = quote.text(lexer, piece)?;
+        }
+
+        Ok(quote)
+    }
+
+    /// Returns the built tokens
+    pub fn build(self) -> Vec<Token<T>>{ self.tokens }
+}
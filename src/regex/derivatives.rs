@@ -0,0 +1,223 @@
+use std::{collections::HashMap, rc::Rc};
+
+use crate::limits::Limits;
+
+use super::{match_element, quantifier_limit, quantifier_min, quantified_once, Context, Quantifier, Regex, RegexElement, Symbol};
+
+// A pattern as derivatives are taken of it, its single symbol elements being numbered leaves
+//
+// Terms are only built through the functions below, which keep them simplified
+// so that the derivatives of a pattern are finitely many
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+enum Term{
+    // Matches no sequence
+    Nothing,
+
+    // Matches the empty sequence only
+    Empty,
+
+    Leaf(usize),
+
+    // The first term then the second one, the first one never being a concatenation itself
+    Concat(Rc<Term>, Rc<Term>),
+
+    // Any of at least two terms, sorted, without duplicates nor alternations
+    Or(Vec<Rc<Term>>),
+
+    // From min to max occurences of a term matching something else than the empty sequence, unbounded without max
+    Repeat(Rc<Term>, usize, Option<usize>)
+}
+
+fn concat(first:Rc<Term>, second:Rc<Term>) -> Rc<Term>{
+    match (first.as_ref(), second.as_ref()) {
+        (Term::Nothing, _) | (_, Term::Nothing) => Rc::new(Term::Nothing),
+        (Term::Empty, _) => second,
+        (_, Term::Empty) => first,
+        (Term::Concat(a, b), _) => concat(a.clone(), concat(b.clone(), second)),
+        _ => Rc::new(Term::Concat(first, second))
+    }
+}
+
+fn or(terms:impl IntoIterator<Item = Rc<Term>>) -> Rc<Term>{
+    let mut flat = vec![];
+    for term in terms{
+        match term.as_ref() {
+            Term::Nothing => {},
+            Term::Or(inner) => flat.extend(inner.iter().cloned()),
+            _ => flat.push(term)
+        }
+    }
+
+    flat.sort();
+    flat.dedup();
+
+    match flat.len() {
+        0 => Rc::new(Term::Nothing),
+        1 => flat.remove(0),
+        _ => Rc::new(Term::Or(flat))
+    }
+}
+
+fn repeat(term:Rc<Term>, min:usize, max:Option<usize>) -> Rc<Term>{
+    if max.is_some_and(|max| min > max) { return Rc::new(Term::Nothing); }
+    if max == Some(0) { return Rc::new(Term::Empty); }
+
+    match term.as_ref() {
+        Term::Nothing if min == 0 => Rc::new(Term::Empty),
+        Term::Nothing => term,
+        Term::Empty => term,
+
+        // The occurences matching the empty sequence are free, so none are needed
+        _ if nullable(&term) && min > 0 => repeat(term, 0, max),
+
+        _ if (min, max) == (1, Some(1)) => term,
+        _ => Rc::new(Term::Repeat(term, min, max))
+    }
+}
+
+// Returns true if the term matches the empty sequence
+fn nullable(term:&Term) -> bool{
+    match term {
+        Term::Nothing | Term::Leaf(_) => false,
+        Term::Empty => true,
+        Term::Concat(a, b) => nullable(a) && nullable(b),
+        Term::Or(terms) => terms.iter().any(|term| nullable(term)),
+        Term::Repeat(term, min, _) => *min == 0 || nullable(term)
+    }
+}
+
+// The term matching what follows a symbol in the sequences *term* matches,
+// *accepted* telling which leaves accept the symbol
+fn derive(term:&Term, accepted:&[bool]) -> Rc<Term>{
+    match term {
+        Term::Nothing | Term::Empty => Rc::new(Term::Nothing),
+        Term::Leaf(i) if accepted[*i] => Rc::new(Term::Empty),
+        Term::Leaf(_) => Rc::new(Term::Nothing),
+
+        Term::Concat(a, b) => {
+            let first = concat(derive(a, accepted), b.clone());
+            if nullable(a) { or([first, derive(b, accepted)]) } else { first }
+        },
+
+        Term::Or(terms) => or(terms.iter().map(|term| derive(term, accepted))),
+
+        Term::Repeat(term, min, max) => concat(derive(term, accepted), repeat(term.clone(), min.saturating_sub(1), max.map(|max| max - 1)))
+    }
+}
+
+// The occurences allowed by a quantifier, without maximum if they are unbounded
+fn bounds(qt:&Quantifier) -> (usize, Option<usize>){
+    let max = quantifier_limit(qt);
+    (quantifier_min(qt), (max != usize::MAX).then_some(max))
+}
+
+// Matches by derivatives: each derivative of the pattern is a state of an automaton built as the symbols are read
+pub(super) struct Derivatives<T:Symbol>{
+    // The single symbol elements of the pattern, expecting exactly one occurence
+    leaves: Vec<RegexElement<T>>,
+
+    limits: Limits,
+
+    // The derivatives met so far, the pattern itself first
+    states: Vec<Rc<Term>>,
+    ids: HashMap<Rc<Term>, usize>,
+
+    // The state reached from each state after each symbol read so far
+    transitions: HashMap<(usize, T), usize>
+}
+
+impl<T:Symbol> Derivatives<T>{
+    // The matcher of *regex*, None if its pattern has assertions
+    pub(super) fn new(regex:&Regex<T>) -> Option<Self>{
+        let mut derivatives = Derivatives { leaves: vec![], limits: regex.limits, states: vec![], ids: HashMap::new(), transitions: HashMap::new() };
+
+        // An empty branch matches nothing
+        let branches = regex.branches()
+            .filter(|branch| !branch.is_empty())
+            .map(|branch| derivatives.sequence(branch))
+            .collect::<Option<Vec<Rc<Term>>>>()?;
+
+        derivatives.state(or(branches));
+        Some(derivatives)
+    }
+
+    fn sequence(&mut self, elements:&[RegexElement<T>]) -> Option<Rc<Term>>{
+        let mut terms = elements.iter().map(|e| self.element(e)).collect::<Option<Vec<Rc<Term>>>>()?;
+        let last = terms.pop().unwrap_or_else(|| Rc::new(Term::Empty));
+
+        Some(terms.into_iter().rev().fold(last, |rest, term| concat(term, rest)))
+    }
+
+    fn element(&mut self, e:&RegexElement<T>) -> Option<Rc<Term>>{
+        let term = match e {
+            RegexElement::Item(_, qt) | RegexElement::Set(_, _, qt) | RegexElement::Class(_, qt) | RegexElement::NoneOf(_, qt) | RegexElement::Predicate(_, qt) => {
+                self.leaves.push(quantified_once(e));
+                (Rc::new(Term::Leaf(self.leaves.len() - 1)), qt)
+            },
+
+            RegexElement::Group(elements, qt) => (self.sequence(elements)?, qt),
+            RegexElement::AnyOf(elements, qt) => (or(elements.iter().map(|e| self.element(e)).collect::<Option<Vec<Rc<Term>>>>()?), qt),
+
+            // Derivatives don't record captures
+            RegexElement::Capture(_, element) | RegexElement::Named(_, element) => return self.element(element),
+
+            RegexElement::SubPattern(regex, qt) => {
+                let branches = regex.branches()
+                    .filter(|branch| !branch.is_empty())
+                    .map(|branch| self.sequence(branch))
+                    .collect::<Option<Vec<Rc<Term>>>>()?;
+
+                (or(branches), qt)
+            },
+
+            RegexElement::StartOfInput | RegexElement::EndOfInput | RegexElement::Boundary(_) | RegexElement::Lookahead(..) | RegexElement::Lookbehind(..) => return None
+        };
+
+        let (term, qt) = term;
+        let (min, max) = bounds(qt);
+        Some(repeat(term, min, max))
+    }
+
+    // The index of the state of *term*, added if it's new
+    fn state(&mut self, term:Rc<Term>) -> usize{
+        if let Some(id) = self.ids.get(&term) { return *id; }
+
+        self.states.push(term.clone());
+        self.ids.insert(term, self.states.len() - 1);
+        self.states.len() - 1
+    }
+
+    // The state reached from *state* after reading *symbol*
+    fn step(&mut self, state:usize, symbol:&T) -> usize{
+        if let Some(next) = self.transitions.get(&(state, symbol.clone())) { return *next; }
+
+        let cx = Context::new(&self.limits, std::slice::from_ref(symbol));
+        let accepted = self.leaves.iter()
+            .map(|leaf| match_element(Some(std::slice::from_ref(symbol)), 0, leaf, &cx).is_ok_and(|(valid, passed)| valid && passed == 1))
+            .collect::<Vec<bool>>();
+
+        let derivative = derive(&self.states[state], &accepted);
+        let next = self.state(derivative);
+
+        self.transitions.insert((state, symbol.clone()), next);
+        next
+    }
+
+    // Returns true if the sequences leading to *state* match
+    fn accepting(&self, state:usize) -> bool{ nullable(&self.states[state]) }
+
+    // Returns true if nothing can be matched from *state* anymore
+    fn dead(&self, state:usize) -> bool{ *self.states[state] == Term::Nothing }
+
+    // Returns true if the symbols are matched, reading them until nothing can be matched anymore
+    pub(super) fn accepts(&mut self, candidate:impl IntoIterator<Item = T>) -> bool{
+        let mut state = 0;
+
+        for symbol in candidate{
+            state = self.step(state, &symbol);
+            if self.dead(state) { return false; }
+        }
+
+        self.accepting(state)
+    }
+}
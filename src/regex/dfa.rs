@@ -0,0 +1,863 @@
+use std::{cmp::Ordering, collections::{BTreeSet, HashMap, HashSet}, error::Error, fmt::Display, iter::Peekable};
+
+use crate::limits::Limits;
+
+use super::{match_element, match_quantifier, quantified_once, Context, ClassItem, Quantifier, Regex, RegexElement, Symbol, SymbolPredicate};
+
+// Stands for a whole class of symbols while compiling:
+// either a symbol named by the pattern, or the symbols strictly between it and the next named one
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+enum ClassSymbol<T:Symbol>{
+    Point(T),
+
+    // Right after the given point, or before every point if None
+    After(Option<T>)
+}
+
+impl<T:Symbol> PartialOrd for ClassSymbol<T>{
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        match (self, other) {
+            (ClassSymbol::Point(a), ClassSymbol::Point(b)) => a.partial_cmp(b),
+            (ClassSymbol::After(None), ClassSymbol::After(None)) => Some(Ordering::Equal),
+            (ClassSymbol::After(None), _) => Some(Ordering::Less),
+            (_, ClassSymbol::After(None)) => Some(Ordering::Greater),
+            (ClassSymbol::After(Some(a)), ClassSymbol::After(Some(b))) => a.partial_cmp(b),
+
+            (ClassSymbol::After(Some(a)), ClassSymbol::Point(b)) => Some(if a < b { Ordering::Less } else { Ordering::Greater }),
+            (ClassSymbol::Point(_), ClassSymbol::After(_)) => other.partial_cmp(self).map(Ordering::reverse)
+        }
+    }
+}
+
+// A class symbol along with the predicates of the pattern its symbols pass,
+// the i-th bit being set if they pass the i-th predicate
+// 
+// Only the class symbol is compared, so that items and sets match it whatever the predicates
+#[derive(Debug, Clone)]
+struct Classified<T:Symbol>{
+    symbol: ClassSymbol<T>,
+    predicates: u64
+}
+
+impl<T:Symbol> PartialEq for Classified<T>{
+    fn eq(&self, other: &Self) -> bool { self.symbol == other.symbol }
+}
+
+impl<T:Symbol> Eq for Classified<T>{}
+
+impl<T:Symbol> PartialOrd for Classified<T>{
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> { self.symbol.partial_cmp(&other.symbol) }
+}
+
+impl<T:Symbol> std::hash::Hash for Classified<T>{
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) { self.symbol.hash(state); }
+}
+
+impl<T:Symbol> Symbol for ClassSymbol<T>{}
+impl<T:Symbol> Symbol for Classified<T>{}
+
+// The same element over classified symbols, *predicates* being the predicates of the pattern
+fn to_classes<T:Symbol>(e:&RegexElement<T>, predicates:&[SymbolPredicate<T>]) -> RegexElement<Classified<T>>{
+    let all = |elements:&[RegexElement<T>]| elements.iter().map(|e| to_classes(e, predicates)).collect();
+    let point = |value:&T| Classified { symbol: ClassSymbol::Point(value.clone()), predicates: 0 };
+
+    match e {
+        RegexElement::Item(value, qt) => RegexElement::Item(point(value), *qt),
+        RegexElement::Set(low, high, qt) => RegexElement::Set(point(low), point(high), *qt),
+        RegexElement::Class(items, qt) => RegexElement::Class(items.iter().map(|item| match item {
+            ClassItem::Single(value) => ClassItem::Single(point(value)),
+            ClassItem::Range(low, high) => ClassItem::Range(point(low), point(high))
+        }).collect(), *qt),
+        RegexElement::Group(elements, qt) => RegexElement::Group(all(elements), *qt),
+        RegexElement::AnyOf(elements, qt) => RegexElement::AnyOf(all(elements), *qt),
+        RegexElement::NoneOf(elements, qt) => RegexElement::NoneOf(all(elements), *qt),
+        RegexElement::Capture(name, element) => RegexElement::Capture(name.clone(), Box::new(to_classes(element, predicates))),
+        RegexElement::Named(name, element) => RegexElement::Named(name.clone(), Box::new(to_classes(element, predicates))),
+        RegexElement::SubPattern(regex, qt) => RegexElement::SubPattern(Regex {
+            pattern: all(&regex.pattern),
+            alternatives: regex.alternatives.iter().map(|alternative| all(alternative)).collect(),
+            limits: regex.limits,
+            name: regex.name.clone(),
+            engine: regex.engine
+        }, *qt),
+        RegexElement::Predicate(predicate, qt) => {
+            let bit = 1 << predicates.iter().position(|p| p == predicate).unwrap_or(0);
+            RegexElement::Predicate(SymbolPredicate::new(move |class:&Classified<T>| class.predicates & bit != 0), *qt)
+        },
+        RegexElement::StartOfInput | RegexElement::EndOfInput | RegexElement::Boundary(_) | RegexElement::Lookahead(..) | RegexElement::Lookbehind(..) => unreachable!("compiled patterns have no assertions")
+    }
+}
+
+// A nondeterministic automaton, each state holding its epsilon transitions
+// and its transitions on the symbols accepted by an element
+struct Nfa<T:Symbol>{
+    epsilons: Vec<Vec<usize>>,
+    transitions: Vec<Vec<(usize, usize)>>,
+
+    // Single symbol elements, expecting exactly one occurence
+    predicates: Vec<RegexElement<Classified<T>>>,
+
+    // The predicate elements of the pattern, see Classified
+    tests: Vec<SymbolPredicate<T>>
+}
+
+impl<T:Symbol> Nfa<T>{
+    fn new(tests:Vec<SymbolPredicate<T>>) -> Self{ Nfa { epsilons: vec![], transitions: vec![], predicates: vec![], tests } }
+
+    fn state(&mut self) -> usize{
+        self.epsilons.push(vec![]);
+        self.transitions.push(vec![]);
+        self.epsilons.len() - 1
+    }
+
+    // Builds the states of the elements in sequence after *from*, returns the last one
+    fn sequence(&mut self, elements:&[RegexElement<T>], from:usize) -> usize{
+        elements.iter().fold(from, |from, e| self.element(e, from))
+    }
+
+    fn element(&mut self, e:&RegexElement<T>, from:usize) -> usize{
+        match e {
+            RegexElement::Item(_, qt) | RegexElement::Set(_, _, qt) | RegexElement::Class(_, qt) | RegexElement::NoneOf(_, qt) | RegexElement::Predicate(_, qt) => {
+                self.predicates.push(to_classes(&quantified_once(e), &self.tests));
+                let predicate = self.predicates.len() - 1;
+
+                self.quantified(qt, from, &mut |nfa, from| {
+                    let to = nfa.state();
+                    nfa.transitions[from].push((predicate, to));
+                    to
+                })
+            },
+
+            // As when matching, an empty group has no occurences
+            RegexElement::Group(elements, qt) if elements.is_empty() => if match_quantifier(0, qt) { from } else { self.state() },
+
+            RegexElement::Group(elements, qt) => self.quantified(qt, from, &mut |nfa, from| nfa.sequence(elements, from)),
+
+            // The automaton doesn't record captures
+            RegexElement::Capture(_, element) | RegexElement::Named(_, element) => self.element(element, from),
+
+            RegexElement::SubPattern(regex, qt) => self.quantified(qt, from, &mut |nfa, from| {
+                let to = nfa.state();
+
+                // An empty branch matches nothing
+                for branch in regex.branches().filter(|branch| !branch.is_empty()){
+                    let start = nfa.state();
+                    nfa.epsilons[from].push(start);
+
+                    let end = nfa.sequence(branch, start);
+                    nfa.epsilons[end].push(to);
+                }
+
+                to
+            }),
+
+            RegexElement::StartOfInput | RegexElement::EndOfInput | RegexElement::Boundary(_) | RegexElement::Lookahead(..) | RegexElement::Lookbehind(..) => unreachable!("compiled patterns have no assertions"),
+
+            RegexElement::AnyOf(elements, qt) => self.quantified(qt, from, &mut |nfa, from| {
+                let to = nfa.state();
+
+                for element in elements{
+                    let start = nfa.state();
+                    nfa.epsilons[from].push(start);
+
+                    let end = nfa.element(element, start);
+                    nfa.epsilons[end].push(to);
+                }
+
+                to
+            })
+        }
+    }
+
+    // Repeats the states built by *once* as many times as *qt* allows
+    fn quantified(&mut self, qt:&Quantifier, from:usize, once:&mut dyn FnMut(&mut Self, usize) -> usize) -> usize{
+        match qt {
+            Quantifier::Exactly(n) => (0..*n).fold(from, |from, _| once(self, from)),
+
+            Quantifier::ZeroOrOne => {
+                let start = self.state();
+                self.epsilons[from].push(start);
+
+                let end = once(self, start);
+
+                let to = self.state();
+                self.epsilons[end].push(to);
+                self.epsilons[start].push(to);
+                to
+            },
+
+            Quantifier::ZeroOrMany => {
+                let start = self.state();
+                self.epsilons[from].push(start);
+
+                let end = once(self, start);
+                self.epsilons[end].push(start);
+
+                let to = self.state();
+                self.epsilons[start].push(to);
+                to
+            },
+
+            Quantifier::OneOrMany => self.quantified(&Quantifier::AtLeast(1), from, once),
+
+            Quantifier::AtLeast(n) => {
+                let required = self.quantified(&Quantifier::Exactly(*n), from, once);
+                self.quantified(&Quantifier::ZeroOrMany, required, once)
+            },
+
+            // The automaton accepts the same sequences whichever occurences are preferred
+            Quantifier::ZeroOrManyLazy | Quantifier::OneOrManyLazy | Quantifier::ZeroOrOneLazy => self.quantified(&qt.greedy(), from, once),
+
+            // Nothing can follow an impossible quantifier
+            Quantifier::Between(min, max) if min > max => self.state(),
+
+            Quantifier::Between(min, max) => {
+                let required = self.quantified(&Quantifier::Exactly(*min), from, once);
+                (*min..*max).fold(required, |from, _| self.quantified(&Quantifier::ZeroOrOne, from, once))
+            }
+        }
+    }
+
+    // The states reachable from *states* without reading any symbol
+    fn closure(&self, states:impl IntoIterator<Item = usize>) -> BTreeSet<usize>{
+        let mut closure = BTreeSet::new();
+        let mut stack = states.into_iter().collect::<Vec<usize>>();
+
+        while let Some(state) = stack.pop(){
+            if closure.insert(state){ stack.extend(&self.epsilons[state]); }
+        }
+
+        closure
+    }
+}
+
+// The number of states the nondeterministic automaton of elements in sequence has, see Nfa::sequence
+fn sequence_size<T:Symbol>(elements:&[RegexElement<T>]) -> usize{
+    elements.iter().map(element_size).fold(0, usize::saturating_add)
+}
+
+// The number of states the nondeterministic automaton of an element has, see Nfa::element
+fn element_size<T:Symbol>(e:&RegexElement<T>) -> usize{
+    let alternatives = |sizes:&mut dyn Iterator<Item = usize>| sizes.fold(1usize, |size, alternative| size.saturating_add(alternative).saturating_add(1));
+
+    match e {
+        RegexElement::Item(_, qt) | RegexElement::Set(_, _, qt) | RegexElement::Class(_, qt) | RegexElement::NoneOf(_, qt) | RegexElement::Predicate(_, qt) => quantified_size(qt, 1),
+        RegexElement::Group(elements, qt) if elements.is_empty() => if match_quantifier(0, qt) { 0 } else { 1 },
+        RegexElement::Group(elements, qt) => quantified_size(qt, sequence_size(elements)),
+        RegexElement::Capture(_, element) | RegexElement::Named(_, element) => element_size(element),
+        RegexElement::SubPattern(regex, qt) => quantified_size(qt, alternatives(&mut regex.branches().filter(|branch| !branch.is_empty()).map(sequence_size))),
+        RegexElement::AnyOf(elements, qt) => quantified_size(qt, alternatives(&mut elements.iter().map(element_size))),
+        RegexElement::StartOfInput | RegexElement::EndOfInput | RegexElement::Boundary(_) | RegexElement::Lookahead(..) | RegexElement::Lookbehind(..) => 0
+    }
+}
+
+// The number of states of *size* states repeated as many times as *qt* allows, see Nfa::quantified
+fn quantified_size(qt:&Quantifier, size:usize) -> usize{
+    match qt.greedy() {
+        Quantifier::Exactly(n) => size.saturating_mul(n),
+        Quantifier::ZeroOrOne | Quantifier::ZeroOrMany => size.saturating_add(2),
+        Quantifier::OneOrMany => quantified_size(&Quantifier::AtLeast(1), size),
+        Quantifier::AtLeast(n) => size.saturating_mul(n).saturating_add(size).saturating_add(2),
+        Quantifier::Between(min, max) if min > max => 1,
+        Quantifier::Between(min, max) => size.saturating_mul(min).saturating_add(size.saturating_add(2).saturating_mul(max - min)),
+        Quantifier::ZeroOrManyLazy | Quantifier::OneOrManyLazy | Quantifier::ZeroOrOneLazy => unreachable!("greedy quantifiers")
+    }
+}
+
+#[derive(Debug, Clone)]
+/// A [Regex] compiled to a deterministic automaton, see [Regex::compile]
+/// 
+/// Matching reads each [symbol](Symbol) once, so it takes a time linear in the size of the candidate
+/// 
+/// The automaton accepts the same sequences as the [Regex] it comes from, but its splits keep the longest match:
+/// the longest alternative of an [AnyOf](RegexElement::AnyOf) wins, not the first one that lets the rest match.
+/// So `a|ab` splits `ab` after `ab` once compiled, while the [Regex] splits it after `a`.
+/// [Lazy](Quantifier::is_lazy) quantifiers are treated like greedy ones
+/// 
+/// Each distinct [predicate](RegexElement::Predicate) of the pattern doubles the number of classes of symbols,
+/// so a pattern with many of them makes a large automaton, with at most [MAX_PREDICATES] of them.
+/// Large bounded quantifiers make many states: automata with more than [MAX_STATES] states
+/// or [MAX_TRANSITIONS] transitions aren't built
+/// 
+/// # Exemples
+/// ```rust
+/// use crate::neoglot_lib::regex::{Quantifier, Regex, RegexElement};
+/// 
+/// let dfa = Regex::<char>::new()
+///     .then(RegexElement::Item('-', Quantifier::ZeroOrOne))
+///     .then(RegexElement::Set('0', '9', Quantifier::OneOrMany))
+///     .compile();
+/// 
+/// let candidate = "-57 + 3".chars().collect::<Vec<char>>();
+/// 
+/// assert!(dfa.r#match(&candidate[..3]));
+/// assert!(!dfa.r#match(&candidate));
+/// assert_eq!(dfa.split_first(&candidate), candidate.split_at(3));
+/// ```
+pub struct Dfa<T:Symbol>{
+    /// The symbols named by the pattern, sorted
+    /// 
+    /// A symbol equal to the i-th point is in class 2i+1,
+    /// a symbol between the points i-1 and i is in class 2i
+    points: Vec<T>,
+
+    /// The distinct predicates of the pattern
+    /// 
+    /// A symbol in the class c passing the predicates of the mask m is in the class c * 2^n + m,
+    /// n being the number of predicates
+    predicates: Vec<SymbolPredicate<T>>,
+
+    /// The next state of each state, for each class of symbols
+    transitions: Vec<Vec<usize>>,
+
+    /// Whether each state ends a match
+    accepting: Vec<bool>,
+
+    /// The state nothing can be matched from anymore, if any
+    dead: Option<usize>
+}
+
+impl<T:Symbol> Dfa<T>{
+    /// The number of states of the automaton
+    pub fn states(&self) -> usize{ self.transitions.len() }
+
+    // The class of symbols *symbol* belongs to
+    fn class_of(&self, symbol:&T) -> usize{
+        let i = self.points.partition_point(|p| p < symbol);
+        let class = if self.points.get(i) == Some(symbol) { 2*i + 1 } else { 2*i };
+
+        (class << self.predicates.len()) | self.mask_of(symbol)
+    }
+
+    // The predicates *symbol* passes, the i-th bit being set if it passes the i-th one
+    fn mask_of(&self, symbol:&T) -> usize{
+        self.predicates.iter().enumerate()
+            .filter(|(_, predicate)| predicate.test(symbol))
+            .fold(0, |mask, (i, _)| mask | 1 << i)
+    }
+
+    // Returns false if no symbol can be in the class *class*:
+    // a named symbol with other predicates than its own, or nothing between two adjacent named symbols
+    fn inhabited(&self, class:usize) -> bool{
+        let (class, mask) = (class >> self.predicates.len(), class & ((1 << self.predicates.len()) - 1));
+
+        match (class % 2, class / 2) {
+            (1, i) => self.mask_of(&self.points[i]) == mask,
+            (_, i) if i > 0 && i < self.points.len() => !self.points[i - 1].is_adjacent(&self.points[i]),
+            _ => true
+        }
+    }
+
+    // Returns true if reading the same symbols in both automata leads to states passing *test*,
+    // given whether each of them ends a match
+    // 
+    // Both automata must be compiled over the same classes of symbols
+    fn reaches(&self, other:&Dfa<T>, test:impl Fn(bool, bool) -> bool) -> bool{
+        let classes = (0..self.transitions[0].len()).filter(|class| self.inhabited(*class)).collect::<Vec<usize>>();
+
+        let mut seen = HashSet::from([(0, 0)]);
+        let mut stack = vec![(0, 0)];
+
+        while let Some((a, b)) = stack.pop(){
+            if test(self.accepting[a], other.accepting[b]) { return true; }
+
+            for class in &classes{
+                let next = (self.transitions[a][*class], other.transitions[b][*class]);
+                if seen.insert(next) { stack.push(next); }
+            }
+        }
+
+        false
+    }
+
+    /// Verifies if a set of [Symbols](Symbol) match the automaton
+    pub fn r#match(&self, candidate:&[T]) -> bool{
+        let mut state = 0;
+
+        for symbol in candidate{
+            state = self.transitions[state][self.class_of(symbol)];
+            if Some(state) == self.dead { return false; }
+        }
+
+        self.accepting[state]
+    }
+
+    /// Splits a set of [symbols](Symbol) into the longest matched prefix and the rest
+    pub fn split_first<'a>(&self, candidate:&'a [T]) -> (&'a [T], &'a [T]){
+        let mut state = 0;
+        let mut longest = 0;
+
+        for (i, symbol) in candidate.iter().enumerate(){
+            state = self.transitions[state][self.class_of(symbol)];
+
+            if Some(state) == self.dead { break; }
+            if self.accepting[state] { longest = i + 1; }
+        }
+
+        candidate.split_at(longest)
+    }
+
+    /// Verifies if the [symbols](Symbol) of an iterator match the automaton, reading them one at a time
+    /// 
+    /// The reading stops as soon as nothing can be matched anymore
+    pub fn match_iter(&self, candidate:impl IntoIterator<Item = T>) -> bool{
+        let mut state = 0;
+
+        for symbol in candidate{
+            state = self.transitions[state][self.class_of(&symbol)];
+            if Some(state) == self.dead { return false; }
+        }
+
+        self.accepting[state]
+    }
+
+    /// Reads the longest matched prefix of an iterator, one [symbol](Symbol) at a time, like [split_first](Dfa::split_first)
+    /// 
+    /// A symbol is only consumed if a match can still be extended with it,
+    /// so the iterator is left on the first symbol that couldn't be matched.
+    /// The symbols read past the longest match are returned too, see [StreamMatch]
+    /// 
+    /// # Exemples
+    /// ```rust
+    /// use crate::neoglot_lib::regex::{Quantifier, Regex, RegexElement};
+    /// 
+    /// let dfa = Regex::<char>::new()
+    ///     .then(RegexElement::Set('0', '9', Quantifier::OneOrMany))
+    ///     .then(RegexElement::Group(vec![
+    ///         RegexElement::Item('.', Quantifier::Exactly(1)),
+    ///         RegexElement::Set('0', '9', Quantifier::OneOrMany)
+    ///     ], Quantifier::ZeroOrOne))
+    ///     .compile();
+    /// 
+    /// let mut symbols = "12.x".chars().peekable();
+    /// let first = dfa.read_first(&mut symbols);
+    /// 
+    /// assert_eq!(first.symbols(), ['1', '2']);
+    /// assert_eq!(first.excess(), ['.']);
+    /// assert_eq!(symbols.next(), Some('x'));
+    /// ```
+    pub fn read_first<I:Iterator<Item = T>>(&self, candidate:&mut Peekable<I>) -> StreamMatch<T>{
+        let mut state = 0;
+        let mut read = vec![];
+        let mut length = 0;
+
+        while let Some(symbol) = candidate.next_if(|symbol| match self.transitions[state][self.class_of(symbol)] {
+            next if Some(next) == self.dead => false,
+            next => {
+                state = next;
+                true
+            }
+        }){
+            read.push(symbol);
+            if self.accepting[state] { length = read.len(); }
+        }
+
+        StreamMatch { read, length }
+    }
+
+    // Describes the classes of symbols from *first* to *last* in interval notation,
+    // *first* and *last* being classes of the points, without the predicates
+    fn describe_classes(&self, first:usize, last:usize) -> String{
+        if first == last && first % 2 == 1 { return format!("{:?}", self.points[first / 2]); }
+
+        let low = match first {
+            0 => "(..".to_string(),
+            c if c % 2 == 1 => format!("[{:?}", self.points[c / 2]),
+            c => format!("({:?}", self.points[c / 2 - 1])
+        };
+        let high = match last {
+            c if c % 2 == 1 => format!("{:?}]", self.points[c / 2]),
+            c if c / 2 == self.points.len() => "..)".to_string(),
+            c => format!("{:?})", self.points[c / 2])
+        };
+
+        format!("{low}, {high}")
+    }
+
+    /// Renders the automaton as a [Graphviz](https://graphviz.org) DOT graph
+    /// 
+    /// The state nothing can be matched from and the transitions to it are left out, accepting states are double circles.
+    /// Transitions are labeled with the symbols leading through them in interval notation, as `['0', '9']` or `(.., 'a')`,
+    /// followed by the predicates they pass or not, numbered in the order they appear in the pattern, as `p0 !p1`
+    /// 
+    /// # Exemples
+    /// ```rust
+    /// use crate::neoglot_lib::regex::{Quantifier, Regex, RegexElement};
+    /// 
+    /// let dot = Regex::<char>::new()
+    ///     .then(RegexElement::Set('0', '9', Quantifier::OneOrMany))
+    ///     .compile()
+    ///     .to_dot();
+    /// 
+    /// assert!(dot.starts_with("digraph {"));
+    /// assert!(dot.contains("start -> 0;"));
+    /// assert!(dot.contains("[label=\"['0', '9']\"];"));
+    /// assert!(dot.contains("[shape=doublecircle];"));
+    /// ```
+    pub fn to_dot(&self) -> String{
+        let escape = |label:&str| label.replace('\\', "\\\\").replace('"', "\\\"");
+        let predicates = self.predicates.len();
+        let points = 2 * self.points.len() + 1;
+
+        let mut dot = String::from("digraph {\n    rankdir=LR;\n    start [shape=point];\n");
+
+        for state in (0..self.states()).filter(|s| Some(*s) != self.dead){
+            let shape = if self.accepting[state] { "doublecircle" } else { "circle" };
+            dot.push_str(&format!("    {state} [shape={shape}];\n"));
+        }
+        dot.push_str("    start -> 0;\n");
+
+        for state in (0..self.states()).filter(|s| Some(*s) != self.dead){
+            // The labels of the transitions to each next state, in order of first appearance
+            let mut edges:Vec<(usize, Vec<String>)> = vec![];
+
+            for mask in 0..1usize << predicates{
+                // Runs of consecutive classes going to the same state, the empty classes joining any run
+                let mut run:Option<(usize, usize, usize)> = None;
+                let mut runs = vec![];
+
+                for point in 0..points{
+                    let class = (point << predicates) | mask;
+                    if !self.inhabited(class) { continue; }
+
+                    let next = self.transitions[state][class];
+                    match &mut run {
+                        Some((_, last, target)) if *target == next => *last = point,
+                        _ => {
+                            runs.extend(run.take());
+                            run = Some((point, point, next));
+                        }
+                    }
+                }
+                runs.extend(run);
+
+                let condition = (0..predicates)
+                    .map(|i| if mask & 1 << i != 0 { format!(" p{i}") } else { format!(" !p{i}") })
+                    .collect::<String>();
+
+                for (first, last, next) in runs.into_iter().filter(|(_, _, next)| Some(*next) != self.dead){
+                    let label = format!("{}{condition}", self.describe_classes(first, last));
+
+                    match edges.iter_mut().find(|(target, _)| *target == next) {
+                        Some((_, labels)) => labels.push(label),
+                        None => edges.push((next, vec![label]))
+                    }
+                }
+            }
+
+            for (next, labels) in edges{
+                dot.push_str(&format!("    {state} -> {next} [label=\"{}\"];\n", escape(&labels.join(" | "))));
+            }
+        }
+
+        dot.push('}');
+        dot
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// The [symbols](Symbol) read from an iterator by [Dfa::read_first]
+pub struct StreamMatch<T:Symbol>{
+    /// Every symbol consumed from the iterator
+    /// 
+    /// The ones past the match were read to find out it couldn't be extended
+    pub read: Vec<T>,
+
+    /// The length of the longest matched prefix of the read symbols
+    pub length: usize
+}
+
+impl<T:Symbol> StreamMatch<T>{
+    /// The matched symbols
+    pub fn symbols(&self) -> &[T]{ &self.read[..self.length] }
+
+    /// The symbols read past the match, to give back to the next reading
+    pub fn excess(&self) -> &[T]{ &self.read[self.length..] }
+}
+
+/// The most distinct [predicates](RegexElement::Predicate) a [Dfa] handles
+pub const MAX_PREDICATES:usize = 16;
+
+/// The most states a [Dfa], or the nondeterministic automaton it is built from, may have
+pub const MAX_STATES:usize = 10_000;
+
+/// The most transitions a [Dfa] may have, one per state and class of symbols
+pub const MAX_TRANSITIONS:usize = 1 << 22;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// Why a [Regex] can't be [compiled](Regex::try_compile) to a [Dfa]
+pub enum CompileError{
+    /// The pattern has an assertion, as [StartOfInput](RegexElement::StartOfInput), that the automaton can't check
+    Assertion,
+
+    /// The patterns have more than [MAX_PREDICATES] distinct [predicates](RegexElement::Predicate), along with their number
+    Predicates(usize),
+
+    /// The automaton, or the nondeterministic one it is built from, needs more than [MAX_STATES] states, along with the number found
+    States(usize),
+
+    /// The automaton needs more than [MAX_TRANSITIONS] transitions, along with the number found
+    Transitions(usize)
+}
+
+impl Display for CompileError{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CompileError::Assertion => write!(f, "Assertions can't be compiled"),
+            CompileError::Predicates(count) => write!(f, "{count} distinct predicates found, at most {MAX_PREDICATES} can be compiled"),
+            CompileError::States(count) => write!(f, "{count} states needed, at most {MAX_STATES} can be built"),
+            CompileError::Transitions(count) => write!(f, "{count} transitions needed, at most {MAX_TRANSITIONS} can be built")
+        }
+    }
+}
+
+impl Error for CompileError{}
+
+// Collects the symbols named by elements and their distinct predicates
+fn collect_points<T:Symbol>(elements:&[RegexElement<T>], points:&mut Vec<T>, predicates:&mut Vec<SymbolPredicate<T>>) -> Result<(), CompileError>{
+    for e in elements{
+        match e {
+            RegexElement::Item(value, _) => points.push(value.clone()),
+            RegexElement::Set(low, high, _) => points.extend([low.clone(), high.clone()]),
+            RegexElement::Class(items, _) => for item in items{
+                match item {
+                    ClassItem::Single(value) => points.push(value.clone()),
+                    ClassItem::Range(low, high) => points.extend([low.clone(), high.clone()])
+                }
+            },
+            RegexElement::Group(elements, _) | RegexElement::AnyOf(elements, _) | RegexElement::NoneOf(elements, _) => collect_points(elements, points, predicates)?,
+            RegexElement::Capture(_, element) | RegexElement::Named(_, element) => collect_points(std::slice::from_ref(element.as_ref()), points, predicates)?,
+            RegexElement::SubPattern(regex, _) => for branch in regex.branches(){ collect_points(branch, points, predicates)?; },
+            RegexElement::Predicate(predicate, _) => if !predicates.contains(predicate) { predicates.push(predicate.clone()); },
+            RegexElement::StartOfInput | RegexElement::EndOfInput | RegexElement::Boundary(_) | RegexElement::Lookahead(..) | RegexElement::Lookbehind(..) => return Err(CompileError::Assertion)
+        }
+    }
+
+    Ok(())
+}
+
+// The symbols named by regexes and their distinct predicates, the symbols sorted,
+// failing if the regexes can't be compiled
+fn alphabet<'r, T:Symbol + 'r>(regexes:impl IntoIterator<Item = &'r Regex<T>>) -> Result<(Vec<T>, Vec<SymbolPredicate<T>>), CompileError>{
+    let mut points = vec![];
+    let mut predicates = vec![];
+    for regex in regexes{
+        for branch in regex.branches(){ collect_points(branch, &mut points, &mut predicates)?; }
+    }
+
+    if predicates.len() > MAX_PREDICATES { return Err(CompileError::Predicates(predicates.len())); }
+    points.sort_by(|a, b| a.partial_cmp(b).unwrap_or(Ordering::Equal));
+    points.dedup();
+
+    Ok((points, predicates))
+}
+
+impl<T:Symbol> Regex<T>{
+    /// Compiles this regex to a [deterministic automaton](Dfa) matching in linear time
+    /// 
+    /// The automaton accepts the same sequences as the regex, its [splits](Dfa::split_first) keeping the longest match.
+    /// The regex itself doesn't go through it: [match](Regex::match) and [split_first](Regex::split_first) keep their own [engine](Regex::with_engine),
+    /// so the automaton is to be kept and matched with directly.
+    /// The [limits](Regex::with_limits) of the regex don't apply to the automaton
+    /// 
+    /// # Panics
+    /// If the pattern can't be compiled, see [try_compile](Regex::try_compile)
+    pub fn compile(&self) -> Dfa<T>{
+        self.try_compile().unwrap_or_else(|error| panic!("{error}"))
+    }
+
+    /// Compiles this regex to a [deterministic automaton](Dfa), see [compile](Regex::compile)
+    /// 
+    /// Fails if the pattern has more than [MAX_PREDICATES] distinct [predicates](RegexElement::Predicate),
+    /// assertions as [StartOfInput](RegexElement::StartOfInput) that the automaton can't check,
+    /// or if the automaton would have more than [MAX_STATES] states or [MAX_TRANSITIONS] transitions
+    pub fn try_compile(&self) -> Result<Dfa<T>, CompileError>{
+        let (points, predicates) = alphabet([self])?;
+        self.compile_over(points, predicates)
+    }
+
+    /// Renders the [compiled](Regex::try_compile) automaton of this regex as a DOT graph, see [Dfa::to_dot]
+    /// 
+    /// Fails if the pattern can't be compiled
+    pub fn to_dot(&self) -> Result<String, CompileError>{ Ok(self.try_compile()?.to_dot()) }
+
+    // Compiles this regex over the classes of symbols of *points* and *predicates*,
+    // which name at least the symbols and predicates of the pattern, the pattern having no assertions,
+    // failing if the automaton is too large
+    fn compile_over(&self, points:Vec<T>, predicates:Vec<SymbolPredicate<T>>) -> Result<Dfa<T>, CompileError>{
+        // An empty branch matches nothing
+        let branches = self.branches().filter(|branch| !branch.is_empty()).collect::<Vec<&[RegexElement<T>]>>();
+
+        let states = branches.iter().map(|branch| sequence_size(branch)).fold(2usize, |states, size| states.saturating_add(size).saturating_add(1));
+        if states > MAX_STATES { return Err(CompileError::States(states)); }
+
+        let count = (2 * points.len() + 1).saturating_mul(1 << predicates.len());
+        if count > MAX_TRANSITIONS { return Err(CompileError::Transitions(count)); }
+
+        // One representative per class, in class order
+        let classes = std::iter::once(ClassSymbol::After(None))
+            .chain(points.iter().flat_map(|p| [ClassSymbol::Point(p.clone()), ClassSymbol::After(Some(p.clone()))]))
+            .flat_map(|symbol| (0..1u64 << predicates.len()).map(move |mask| Classified { symbol: symbol.clone(), predicates: mask }))
+            .collect::<Vec<Classified<T>>>();
+
+        let mut nfa = Nfa::new(predicates.clone());
+        let start = nfa.state();
+        let end = nfa.state();
+
+        for branch in branches{
+            let branch_start = nfa.state();
+            nfa.epsilons[start].push(branch_start);
+
+            let branch_end = nfa.sequence(branch, branch_start);
+            nfa.epsilons[branch_end].push(end);
+        }
+
+        // Which predicates accept each class
+        let accepts = nfa.predicates.iter()
+            .map(|predicate| classes.iter()
+                .map(|class| match_element(Some(std::slice::from_ref(class)), 0, predicate, &Context::new(&Limits::new(), std::slice::from_ref(class))).is_ok_and(|(valid, passed)| valid && passed == 1))
+                .collect::<Vec<bool>>())
+            .collect::<Vec<Vec<bool>>>();
+
+        // Subset construction
+        let mut sets = vec![nfa.closure([start])];
+        let mut indices = HashMap::from([(sets[0].clone(), 0)]);
+        let mut transitions:Vec<Vec<usize>> = vec![];
+
+        while transitions.len() < sets.len(){
+            if sets.len() > MAX_STATES { return Err(CompileError::States(sets.len())); }
+
+            let count = sets.len().saturating_mul(classes.len());
+            if count > MAX_TRANSITIONS { return Err(CompileError::Transitions(count)); }
+
+            let set = sets[transitions.len()].clone();
+
+            let row = (0..classes.len()).map(|class| {
+                let next = nfa.closure(set.iter()
+                    .flat_map(|state| &nfa.transitions[*state])
+                    .filter(|(predicate, _)| accepts[*predicate][class])
+                    .map(|(_, to)| *to));
+
+                *indices.entry(next.clone()).or_insert_with(|| {
+                    sets.push(next);
+                    sets.len() - 1
+                })
+            }).collect();
+
+            transitions.push(row);
+        }
+
+        Ok(Dfa {
+            points,
+            predicates,
+            transitions,
+            accepting: sets.iter().map(|set| set.contains(&end)).collect(),
+            dead: indices.get(&BTreeSet::new()).copied()
+        })
+    }
+
+    // The automata of this regex and *other*, compiled over the same classes of symbols
+    fn compile_pair(&self, other:&Regex<T>) -> Result<(Dfa<T>, Dfa<T>), CompileError>{
+        let (points, predicates) = alphabet([self, other])?;
+        Ok((self.compile_over(points.clone(), predicates.clone())?, other.compile_over(points, predicates)?))
+    }
+
+    /// Returns true if a sequence of [symbols](Symbol) matches both this regex and *other*,
+    /// as two token patterns that conflict in a lexer
+    /// 
+    /// The regexes are compared on the sequences their [automata](Regex::compile) accept.
+    /// The symbols before the smallest and after the greatest one named by the patterns are assumed to exist,
+    /// as well as those between two named symbols that aren't [adjacent](Symbol::is_adjacent)
+    /// 
+    /// Fails if the patterns can't be [compiled](Regex::try_compile) together: they have assertions,
+    /// more than [MAX_PREDICATES] distinct [predicates](RegexElement::Predicate) between them, or too large automata
+    /// 
+    /// # Exemples
+    /// ```rust
+    /// use crate::neoglot_lib::regex::{dfa::CompileError, Quantifier, Regex, RegexElement};
+    /// 
+    /// let identifier = Regex::<char>::new().then(RegexElement::Set('a', 'z', Quantifier::OneOrMany));
+    /// let keyword = Regex::new().then(RegexElement::Item('i', Quantifier::Exactly(1))).then(RegexElement::Item('f', Quantifier::Exactly(1)));
+    /// let number = Regex::new().then(RegexElement::Set('0', '9', Quantifier::OneOrMany));
+    /// 
+    /// assert_eq!(identifier.intersects(&keyword), Ok(true));
+    /// assert_eq!(identifier.intersects(&number), Ok(false));
+    /// 
+    /// assert_eq!(keyword.is_subset_of(&identifier), Ok(true));
+    /// assert_eq!(identifier.is_subset_of(&keyword), Ok(false));
+    /// assert_eq!(identifier.is_equivalent(&identifier.clone().or(keyword)), Ok(true));
+    /// 
+    /// let anchored = Regex::new().then(RegexElement::StartOfInput).then(RegexElement::Item('i', Quantifier::Exactly(1)));
+    /// assert_eq!(anchored.intersects(&identifier), Err(CompileError::Assertion));
+    /// ```
+    pub fn intersects(&self, other:&Regex<T>) -> Result<bool, CompileError>{
+        let (a, b) = self.compile_pair(other)?;
+        Ok(a.reaches(&b, |a, b| a && b))
+    }
+
+    /// Returns true if every sequence of [symbols](Symbol) matching this regex matches *other*, see [intersects](Regex::intersects)
+    /// 
+    /// Fails in the same cases as [intersects](Regex::intersects)
+    pub fn is_subset_of(&self, other:&Regex<T>) -> Result<bool, CompileError>{
+        let (a, b) = self.compile_pair(other)?;
+        Ok(!a.reaches(&b, |a, b| a && !b))
+    }
+
+    /// Returns true if this regex and *other* match the same sequences of [symbols](Symbol), see [intersects](Regex::intersects)
+    /// 
+    /// Fails in the same cases as [intersects](Regex::intersects)
+    pub fn is_equivalent(&self, other:&Regex<T>) -> Result<bool, CompileError>{
+        let (a, b) = self.compile_pair(other)?;
+        Ok(!a.reaches(&b, |a, b| a != b))
+    }
+
+    /// Returns true if every sequence of [symbols](Symbol) matching this regex, but the empty one,
+    /// starts with a non-empty sequence matching one of *others*, see [intersects](Regex::intersects)
+    /// 
+    /// So a [lexer node](crate::lexer::LexerNode) of this regex never makes a token when nodes of the *others* are tried before it,
+    /// see [Lexer::check](crate::lexer::Lexer::check).
+    /// Fails if the patterns can't be compiled together
+    /// 
+    /// # Exemples
+    /// ```rust
+    /// use crate::neoglot_lib::regex::{Quantifier, Regex, RegexElement};
+    /// 
+    /// let letter = Regex::<char>::new().then(RegexElement::Set('a', 'z', Quantifier::Exactly(1)));
+    /// let keyword = Regex::new().then(RegexElement::Item('i', Quantifier::Exactly(1))).then(RegexElement::Item('f', Quantifier::Exactly(1)));
+    /// let identifier = Regex::new().then(RegexElement::Set('a', 'z', Quantifier::OneOrMany)).then(RegexElement::Set('0', '9', Quantifier::ZeroOrMany));
+    /// 
+    /// assert_eq!(keyword.is_shadowed_by([&letter]), Ok(true));
+    /// assert_eq!(identifier.is_shadowed_by([&keyword]), Ok(false));
+    /// ```
+    pub fn is_shadowed_by<'r>(&self, others:impl IntoIterator<Item = &'r Regex<T>>) -> Result<bool, CompileError> where T:'r{
+        let others = others.into_iter().collect::<Vec<&Regex<T>>>();
+        let (points, predicates) = alphabet(std::iter::once(self).chain(others.iter().copied()))?;
+
+        let dfa = self.compile_over(points.clone(), predicates.clone())?;
+        let others = others.iter().map(|other| other.compile_over(points.clone(), predicates.clone())).collect::<Result<Vec<Dfa<T>>, CompileError>>()?;
+        let classes = (0..dfa.transitions[0].len()).filter(|class| dfa.inhabited(*class)).collect::<Vec<usize>>();
+
+        // The states of the automata after a sequence none of the others matched a non-empty prefix of,
+        // and whether the sequence is empty
+        let start = (0, vec![0; others.len()], true);
+        let mut seen = HashSet::from([start.clone()]);
+        let mut stack = vec![start];
+
+        while let Some((state, states, empty)) = stack.pop(){
+            if !empty && dfa.accepting[state] { return Ok(false); }
+            if Some(state) == dfa.dead { continue; }
+
+            for class in &classes{
+                let next = states.iter().zip(&others).map(|(state, other)| other.transitions[*state][*class]).collect::<Vec<usize>>();
+
+                // The rest of the sequence is lexed by an other
+                if next.iter().zip(&others).any(|(state, other)| other.accepting[*state]) { continue; }
+
+                let next = (dfa.transitions[state][*class], next, false);
+                if seen.insert(next.clone()) { stack.push(next); }
+            }
+        }
+
+        Ok(true)
+    }
+}
@@ -0,0 +1,2157 @@
+/// Compilation of regexes to deterministic automata
+pub mod dfa;
+
+/// Step by step logs of matches, to debug patterns
+pub mod trace;
+
+// Matching by derivatives of the pattern, see Engine::Derivatives
+mod derivatives;
+
+use std::{collections::{BTreeSet, HashSet}, hash::Hash, fmt::{Debug, Display}, ops::RangeInclusive, sync::Arc};
+
+use crate::{fuzz::Rng, limits::{Deadline, Limit, Limits}};
+
+use derivatives::Derivatives;
+
+/// How many draws [generate](Regex::generate) makes before giving up
+const SAMPLE_ATTEMPTS:usize = 16;
+
+/// A symbol is the smallest bit of information a [regex](Regex) can work with
+/// 
+/// Implement this trait for any type you want to be supported with [regex](Regex)
+/// 
+/// # Examples
+/// ```rust
+/// use crate::neoglot_lib::regex::Symbol;
+/// 
+/// // Implement the needed traits
+/// #[derive(PartialEq, Eq, PartialOrd, Hash, Clone, Debug)]
+/// struct Foo{
+/// }
+/// 
+/// // Now your type can be used to do regular expressions
+/// impl Symbol for Foo {}
+/// ```
+pub trait Symbol : PartialEq+Eq+PartialOrd+Hash+Clone+Debug{
+    /// Returns true if no symbol lies strictly between this one and *next*, as `'a'` and `'b'`
+    /// 
+    /// Only used to [compare regexes](Regex::intersects), it returns false by default
+    fn is_adjacent(&self, _next:&Self) -> bool{ false }
+}
+
+impl Symbol for char{
+    fn is_adjacent(&self, next:&Self) -> bool{
+        // Only the surrogates aren't chars
+        let (low, high) = (*self as u32 + 1, *next as u32);
+        low >= high || ((0xD800..0xE000).contains(&low) && high <= 0xE000)
+    }
+}
+
+impl Symbol for u8{
+    fn is_adjacent(&self, next:&Self) -> bool{ self.checked_add(1) == Some(*next) }
+}
+
+#[derive(Debug, Clone, PartialEq, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+/// A Quantifier is the number of occurences of a [RegexElement]
+pub enum Quantifier{
+    /// The [RegexElement] must have the exact amount of occurences given
+    /// 
+    /// This is equivalent to '{n}'
+    Exactly(usize),
+
+    /// The [RegexElement] must have at least one occurence
+    /// 
+    /// This is equivalent to '+'
+    OneOrMany,
+
+    /// The [RegexElement] may have several occurences or none
+    /// 
+    /// This is equivalent to '*'
+    ZeroOrMany,
+
+    /// The [RegexElement] may have one occurence or none
+    /// 
+    /// This is equivalent to '?'
+    ZeroOrOne,
+
+    /// The [RegexElement] must have between the given minimum and maximum of occurences, both included
+    /// 
+    /// This is equivalent to '{min,max}'
+    Between(usize, usize),
+
+    /// The [RegexElement] must have at least the given amount of occurences
+    /// 
+    /// This is equivalent to '{n,}'
+    AtLeast(usize),
+
+    /// Like [ZeroOrMany](Quantifier::ZeroOrMany), but with as few occurences as the rest of the sequence allows
+    /// 
+    /// This is equivalent to '*?'
+    ZeroOrManyLazy,
+
+    /// Like [OneOrMany](Quantifier::OneOrMany), but with as few occurences as the rest of the sequence allows
+    /// 
+    /// This is equivalent to '+?'
+    OneOrManyLazy,
+
+    /// Like [ZeroOrOne](Quantifier::ZeroOrOne), but without the occurence if the rest of the sequence allows
+    /// 
+    /// This is equivalent to '??'
+    ZeroOrOneLazy
+}
+
+impl Quantifier{
+    /// Returns true for the lazy quantifiers, which consume as few occurences as possible
+    /// 
+    /// A lazy element only looks at the elements following it in the same sequence,
+    /// that is the rest of the [Regex] or of the enclosing [Group](RegexElement::Group)
+    pub fn is_lazy(&self) -> bool{
+        matches!(self, Quantifier::ZeroOrManyLazy | Quantifier::OneOrManyLazy | Quantifier::ZeroOrOneLazy)
+    }
+
+    /// The greedy quantifier accepting the same numbers of occurences
+    pub fn greedy(&self) -> Quantifier{
+        match self {
+            Quantifier::ZeroOrManyLazy => Quantifier::ZeroOrMany,
+            Quantifier::OneOrManyLazy => Quantifier::OneOrMany,
+            Quantifier::ZeroOrOneLazy => Quantifier::ZeroOrOne,
+            qt => *qt
+        }
+    }
+}
+
+/// Formats in regex notation, as `+` or `{2,5}`, nothing for exactly one occurence
+impl Display for Quantifier{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Quantifier::Exactly(1) => Ok(()),
+            Quantifier::Exactly(n) => write!(f, "{{{n}}}"),
+            Quantifier::OneOrMany => f.write_str("+"),
+            Quantifier::ZeroOrMany => f.write_str("*"),
+            Quantifier::ZeroOrOne => f.write_str("?"),
+            Quantifier::Between(min, max) => write!(f, "{{{min},{max}}}"),
+            Quantifier::AtLeast(n) => write!(f, "{{{n},}}"),
+            Quantifier::ZeroOrManyLazy => f.write_str("*?"),
+            Quantifier::OneOrManyLazy => f.write_str("+?"),
+            Quantifier::ZeroOrOneLazy => f.write_str("??")
+        }
+    }
+}
+#[derive(Clone)]
+/// A test on a single [Symbol], see [Predicate](RegexElement::Predicate)
+/// 
+/// Two predicates are equal only if one is a clone of the other
+/// 
+/// # Exemples
+/// ```rust
+/// use crate::neoglot_lib::regex::{Quantifier, Regex, RegexElement, SymbolPredicate};
+/// 
+/// let word = Regex::<char>::new()
+///     .then(RegexElement::Predicate(SymbolPredicate::new(|c:&char| c.is_alphabetic()), Quantifier::OneOrMany));
+/// 
+/// assert!(word.r#match(&['d', 'é', 'j', 'à']));
+/// assert!(!word.r#match(&['d', '1']));
+/// ```
+pub struct SymbolPredicate<T>(Arc<dyn Fn(&T) -> bool + Send + Sync>);
+
+impl<T> SymbolPredicate<T>{
+    pub fn new(test:impl Fn(&T) -> bool + Send + Sync + 'static) -> Self{ SymbolPredicate(Arc::new(test)) }
+
+    /// Returns true if *symbol* passes the test
+    pub fn test(&self, symbol:&T) -> bool{ (self.0)(symbol) }
+}
+
+impl<T> PartialEq for SymbolPredicate<T>{
+    fn eq(&self, other: &Self) -> bool { Arc::ptr_eq(&self.0, &other.0) }
+}
+
+impl<T> Debug for SymbolPredicate<T>{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result { write!(f, "SymbolPredicate({:p})", Arc::as_ptr(&self.0)) }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+/// A part of a [Class](RegexElement::Class)
+pub enum ClassItem<T:Symbol>{
+    /// A single [Symbol]
+    Single(T),
+
+    /// The [Symbols](Symbol) between the lower and the upper end, both included
+    Range(T, T)
+}
+
+impl<T:Symbol> ClassItem<T>{
+    /// Returns true if *symbol* is in this item
+    pub fn contains(&self, symbol:&T) -> bool{
+        match self {
+            ClassItem::Single(value) => value == symbol,
+            ClassItem::Range(low, high) => low <= symbol && symbol <= high
+        }
+    }
+}
+
+/// Formats the symbols in their debug form, as `'a'` or `'a'-'z'`
+impl<T:Symbol> Display for ClassItem<T>{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ClassItem::Single(value) => write!(f, "{value:?}"),
+            ClassItem::Range(low, high) => write!(f, "{low:?}-{high:?}")
+        }
+    }
+}
+
+impl<T:Symbol> From<T> for ClassItem<T>{
+    fn from(value:T) -> Self { ClassItem::Single(value) }
+}
+
+impl<T:Symbol> From<RangeInclusive<T>> for ClassItem<T>{
+    fn from(range:RangeInclusive<T>) -> Self {
+        let (low, high) = range.into_inner();
+        ClassItem::Range(low, high)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+/// RegexElements are what make up a [Regex]
+/// 
+/// They indicate what set of [Symbols](Symbol) are expected
+/// 
+/// With the `serde` feature they can be serialized, except the [predicates](RegexElement::Predicate)
+/// that hold code: serializing one fails
+pub enum  RegexElement<T:Symbol>{
+    /// A single [Symbol]
+    Item(T, Quantifier),
+
+    /// A group of other RegexElements
+    /// 
+    /// A Group is valid only if all elements inside are valid
+    /// 
+    /// An occurence matching no symbols ends the repetition, so that groups of optional elements always terminate:
+    /// the occurences still needed by the quantifier then match no symbols too.
+    /// The [repetitions](Limits::max_repetitions) in a row can be bounded
+    /// 
+    /// This is equivalent to '(...)'
+    Group(Vec<RegexElement<T>>, Quantifier),
+
+    /// Convenience way of doing alternation
+    /// 
+    /// As suggested it is valid if any of its elements are valid, the first valid one being taken for each occurence.
+    /// When the rest of the pattern fails after it, as at the end of a [full match](Regex::match), the other alternatives are tried
+    /// 
+    /// This is equivalent to '(a|b|c|...|z)'
+    AnyOf(Vec<RegexElement<T>>, Quantifier),
+
+    /// Convenience way of doing negation
+    /// 
+    /// As suggested it is valid only if none of its elements are valid
+    /// 
+    /// So it accepts anything except the elements inside
+    /// 
+    /// This is equivalent to '[\^abc...]'
+    NoneOf(Vec<RegexElement<T>>, Quantifier),
+
+    /// The first parameter is the lower end of the set
+    /// 
+    /// The second parameter is the upper end of the set
+    /// 
+    /// It accepts any [Symbol] that is inside the set
+    /// 
+    /// This is equivalent to '[..-..]'
+    Set(T, T, Quantifier),
+
+    /// Any [Symbol] in one of the [items](ClassItem), so that several ranges are matched in one pass
+    /// 
+    /// This is equivalent to '[a-zA-Z0-9_]'
+    Class(Vec<ClassItem<T>>, Quantifier),
+
+    /// Records the [Symbols](Symbol) matched by the element under the given name,
+    /// see [Regex::captures]
+    /// 
+    /// This is equivalent to '(?<name>...)'
+    Capture(String, Box<RegexElement<T>>),
+
+    /// A single element given a human readable name, as "float literal", matching like the element
+    /// 
+    /// The name stands for the element in [traces](Regex::trace) and lexing errors, see [name](RegexElement::name)
+    Named(String, Box<RegexElement<T>>),
+
+    /// Another [Regex] embedded in this one, so that small regexes can be reused in bigger ones
+    /// 
+    /// Each occurence matches like the embedded regex alone, taking the longest of its [alternatives](Regex::or),
+    /// its [limits](Regex::with_limits) are ignored in favor of the ones of the enclosing regex
+    /// 
+    /// This is equivalent to '(...)' around the embedded regex
+    SubPattern(Regex<T>, Quantifier),
+
+    /// Any [Symbol] passing the [test](SymbolPredicate), as `char::is_alphabetic`
+    /// 
+    /// This is equivalent to '\p{...}'
+    #[cfg_attr(feature = "serde", serde(skip))]
+    Predicate(SymbolPredicate<T>, Quantifier),
+
+    /// Matches no [Symbol], only at the start of the candidate
+    /// 
+    /// When splitting [from a position](Regex::split_at_match), the candidate starts before that position
+    /// 
+    /// This is equivalent to '^'
+    StartOfInput,
+
+    /// Matches no [Symbol], only at the end of the candidate
+    /// 
+    /// This is equivalent to '$'
+    EndOfInput,
+
+    /// Matches no [Symbol], only between a symbol passing the [test](SymbolPredicate) and one that doesn't, in any order.
+    /// The outside of the candidate doesn't pass it
+    /// 
+    /// This is equivalent to '\b' with `char::is_alphanumeric`
+    #[cfg_attr(feature = "serde", serde(skip))]
+    Boundary(SymbolPredicate<T>),
+
+    /// Matches no [Symbol], only where the element matches the symbols that follow, or where it doesn't if the flag is false
+    /// 
+    /// This is equivalent to '(?=...)' and '(?!...)'
+    Lookahead(Box<RegexElement<T>>, bool),
+
+    /// Matches no [Symbol], only where the element matches symbols ending right there, or where it doesn't if the flag is false
+    /// 
+    /// The element may start anywhere before, in the whole candidate.
+    /// This is equivalent to '(?<=...)' and '(?<!...)'
+    Lookbehind(Box<RegexElement<T>>, bool)
+
+}
+
+impl<T:Symbol> RegexElement<T>{
+    /// The human readable name of the element: the one of a [Named](RegexElement::Named) element
+    /// or of the [regex](Regex::with_name) embedded in a [SubPattern](RegexElement::SubPattern), looking through [captures](RegexElement::Capture)
+    pub fn name(&self) -> Option<&str>{
+        match self {
+            RegexElement::Named(name, _) => Some(name),
+            RegexElement::Capture(_, element) => element.name(),
+            RegexElement::SubPattern(regex, _) => regex.name(),
+            _ => None
+        }
+    }
+
+    /// Describes what the element expects for diagnostics: its [name](RegexElement::name), or the element tree if it has none
+    pub fn describe(&self) -> String{
+        self.name().map_or_else(|| format!("{self:?}"), String::from)
+    }
+}
+
+// Formats elements one after the other, separated by *separator*
+fn write_sequence<T:Symbol>(f:&mut std::fmt::Formatter<'_>, elements:&[RegexElement<T>], separator:&str) -> std::fmt::Result{
+    for (i, e) in elements.iter().enumerate(){
+        if i > 0 { f.write_str(separator)?; }
+        write!(f, "{e}")?;
+    }
+
+    Ok(())
+}
+
+/// Formats in regex notation, the symbols in their debug form and the elements of a sequence separated by spaces,
+/// as `['0'-'9']+ ('.' ['0'-'9']+)?`
+/// 
+/// [Named](RegexElement::Named) elements are preceded by their name in a comment, as `(?#digit)['0'-'9']`,
+/// [predicates](RegexElement::Predicate) are written `\p` and [boundaries](RegexElement::Boundary) `\b` as their tests can't be shown
+impl<T:Symbol> Display for RegexElement<T>{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RegexElement::Item(value, qt) => write!(f, "{value:?}{qt}"),
+            RegexElement::Set(low, high, qt) => write!(f, "[{low:?}-{high:?}]{qt}"),
+            RegexElement::Class(items, qt) => {
+                f.write_str("[")?;
+                for (i, item) in items.iter().enumerate(){
+                    if i > 0 { f.write_str(" ")?; }
+                    write!(f, "{item}")?;
+                }
+                write!(f, "]{qt}")
+            },
+            RegexElement::Group(elements, qt) => {
+                f.write_str("(")?;
+                write_sequence(f, elements, " ")?;
+                write!(f, "){qt}")
+            },
+            RegexElement::AnyOf(elements, qt) => {
+                f.write_str("(")?;
+                write_sequence(f, elements, " | ")?;
+                write!(f, "){qt}")
+            },
+            RegexElement::NoneOf(elements, qt) => {
+                f.write_str("[^")?;
+                write_sequence(f, elements, " ")?;
+                write!(f, "]{qt}")
+            },
+            RegexElement::Capture(name, element) => write!(f, "(?<{name}>{element})"),
+            RegexElement::Named(name, element) => write!(f, "(?#{name}){element}"),
+            RegexElement::SubPattern(regex, qt) => write!(f, "({regex}){qt}"),
+            RegexElement::Predicate(_, qt) => write!(f, "\\p{qt}"),
+            RegexElement::StartOfInput => f.write_str("^"),
+            RegexElement::EndOfInput => f.write_str("$"),
+            RegexElement::Boundary(_) => f.write_str("\\b"),
+            RegexElement::Lookahead(element, true) => write!(f, "(?={element})"),
+            RegexElement::Lookahead(element, false) => write!(f, "(?!{element})"),
+            RegexElement::Lookbehind(element, true) => write!(f, "(?<={element})"),
+            RegexElement::Lookbehind(element, false) => write!(f, "(?<!{element})")
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+/// Describes a pattern of [Symbols](Symbol)
+/// 
+/// With the `serde` feature it can be serialized along with its limits, to cache the regexes built at runtime
+/// 
+/// Quantified single symbol elements take as many symbols as they can,
+/// then give some back if the rest of their sequence needs them, so `[a-z]+ e` matches `same`
+/// 
+/// # Examples
+/// ```rust
+/// use crate::neoglot_lib::regex::{Quantifier, Regex, RegexElement};
+/// 
+/// let regex = Regex::<char>::new()
+///         .then(RegexElement::Item('-', Quantifier::ZeroOrOne))
+///         .then(RegexElement::Set('0', '9', Quantifier::OneOrMany));
+/// 
+/// // We can use [' ', ' '] directly but i can't be bothered
+/// let candidate1 = &"  ".chars().collect::<Vec<char>>();
+/// let candidate2 = &"125".chars().collect::<Vec<char>>();
+/// let candidate3 = &"-57".chars().collect::<Vec<char>>();
+/// let candidate4 = &"-".chars().collect::<Vec<char>>();
+/// let candidate5 = &"0.78".chars().collect::<Vec<char>>();
+/// 
+/// // Simple matching of a pattern
+/// assert_eq!(regex.r#match(candidate1), false);
+/// assert_eq!(regex.r#match(candidate2), true);
+/// assert_eq!(regex.r#match(candidate3), true);
+/// assert_eq!(regex.r#match(candidate4), false);
+/// assert_eq!(regex.r#match(candidate5), false);
+/// 
+/// let result1:(&[char], &[char]) = (&[], &[' ', ' ']);
+/// let result2:(&[char], &[char]) = (&['1', '2', '5'], &[]);
+/// let result3:(&[char], &[char]) = (&['-', '5', '7'], &[]);
+/// let result4:(&[char], &[char]) = (&[], &['-']);
+/// let result5:(&[char], &[char]) = (&['0'], &['.', '7', '8']);
+/// 
+/// // Taking the first matching symbols and the rest
+/// // This is usefull for dismantling a large set of symbols into token for instance
+/// assert_eq!(regex.split_first(candidate1), result1);
+/// assert_eq!(regex.split_first(candidate2), result2);
+/// assert_eq!(regex.split_first(candidate3), result3);
+/// assert_eq!(regex.split_first(candidate4), result4);
+/// assert_eq!(regex.split_first(candidate5), result5);
+/// 
+/// ```
+pub struct Regex<T:Symbol>{
+    pattern:Vec<RegexElement<T>>,
+
+    /// The other whole patterns this regex may match, see [or](Regex::or)
+    alternatives:Vec<Vec<RegexElement<T>>>,
+
+    /// Bounds on the matching work
+    limits:Limits,
+
+    /// What the regex matches in human readable words, see [with_name](Regex::with_name)
+    #[cfg_attr(feature = "serde", serde(default))]
+    name:Option<String>,
+
+    /// How the pattern is matched, see [with_engine](Regex::with_engine)
+    #[cfg_attr(feature = "serde", serde(default))]
+    engine:Engine
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+/// How a [Regex] matches its pattern, see [with_engine](Regex::with_engine)
+pub enum Engine{
+    /// Tries the elements one after the other, giving symbols back when the rest of the pattern needs them
+    /// 
+    /// The first valid alternative of an [AnyOf](RegexElement::AnyOf) is taken for each occurence,
+    /// and some nested quantifiers may take exponential time to fail
+    #[default]
+    Backtracking,
+
+    /// Takes the [Brzozowski derivative](https://en.wikipedia.org/wiki/Brzozowski_derivative) of the pattern by each symbol read,
+    /// each derivative met being kept as the state of an automaton built along the way
+    /// 
+    /// Each symbol is read once, so a [full match](Regex::match) takes a time linear in the size of the candidate, as the [compiled automaton](Regex::compile) does,
+    /// and it accepts the same sequences as backtracking does.
+    /// Patterns with assertions, as [StartOfInput](RegexElement::StartOfInput), are still matched by backtracking
+    Derivatives
+}
+
+/// Formats the [branches](Regex::or) in regex notation, separated by ` | ` as the alternatives of an [AnyOf](RegexElement::AnyOf), see [RegexElement]
+/// 
+/// # Exemples
+/// ```rust
+/// use crate::neoglot_lib::regex::{Quantifier, Regex, RegexElement};
+/// 
+/// let number = Regex::<char>::new()
+///     .then(RegexElement::Item('-', Quantifier::ZeroOrOne))
+///     .then(RegexElement::Set('0', '9', Quantifier::OneOrMany))
+///     .then(RegexElement::Group(vec![
+///         RegexElement::Item('.', Quantifier::Exactly(1)),
+///         RegexElement::Set('0', '9', Quantifier::Between(1, 3))
+///     ], Quantifier::ZeroOrOne));
+/// 
+/// assert_eq!(number.to_string(), "'-'? ['0'-'9']+ ('.' ['0'-'9']{1,3})?");
+/// assert_eq!(number.clone().or(Regex::new().then(RegexElement::Item('x', Quantifier::Exactly(1)))).to_string(), "'-'? ['0'-'9']+ ('.' ['0'-'9']{1,3})? | 'x'");
+/// ```
+impl<T:Symbol> Display for Regex<T>{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (i, branch) in self.branches().enumerate(){
+            if i > 0 { f.write_str(" | ")?; }
+            write_sequence(f, branch, " ")?;
+        }
+
+        Ok(())
+    }
+}
+
+// Returns if a given number match a quantifier
+fn match_quantifier(num:usize, quantifier:&Quantifier) -> bool{
+    match quantifier {
+        Quantifier::Exactly(n) => *n == num,
+        Quantifier::OneOrMany | Quantifier::OneOrManyLazy => num >= 1,
+        Quantifier::ZeroOrMany | Quantifier::ZeroOrManyLazy => true,
+        Quantifier::ZeroOrOne | Quantifier::ZeroOrOneLazy => num == 0 || num == 1,
+        Quantifier::Between(min, max) => (*min..=*max).contains(&num),
+        Quantifier::AtLeast(n) => num >= *n
+    }
+}
+
+// Returns the maximum number of occurences a quantifier will consume
+fn quantifier_limit(quantifier:&Quantifier) -> usize{
+    match quantifier {
+        Quantifier::Exactly(n) => *n,
+        Quantifier::ZeroOrOne | Quantifier::ZeroOrOneLazy => 1,
+        Quantifier::Between(_, max) => *max,
+        Quantifier::OneOrMany | Quantifier::ZeroOrMany | Quantifier::AtLeast(_)
+        | Quantifier::OneOrManyLazy | Quantifier::ZeroOrManyLazy => usize::MAX
+    }
+}
+
+// Returns the minimum number of occurences a quantifier accepts
+fn quantifier_min(quantifier:&Quantifier) -> usize{
+    match quantifier {
+        Quantifier::Exactly(n) | Quantifier::Between(n, _) | Quantifier::AtLeast(n) => *n,
+        Quantifier::OneOrMany | Quantifier::OneOrManyLazy => 1,
+        Quantifier::ZeroOrMany | Quantifier::ZeroOrOne | Quantifier::ZeroOrManyLazy | Quantifier::ZeroOrOneLazy => 0
+    }
+}
+
+// The quantifier of an element, seen through captures
+fn element_quantifier<T:Symbol>(e:&RegexElement<T>) -> Option<Quantifier>{
+    match e {
+        RegexElement::Item(_, qt) | RegexElement::Set(_, _, qt) | RegexElement::Class(_, qt) | RegexElement::NoneOf(_, qt) | RegexElement::Predicate(_, qt)
+        | RegexElement::Group(_, qt) | RegexElement::AnyOf(_, qt) | RegexElement::SubPattern(_, qt) => Some(*qt),
+        RegexElement::Capture(_, element) | RegexElement::Named(_, element) => element_quantifier(element),
+        RegexElement::StartOfInput | RegexElement::EndOfInput | RegexElement::Boundary(_) | RegexElement::Lookahead(..) | RegexElement::Lookbehind(..) => None
+    }
+}
+
+// The fewest and most Symbols an element may read, None if there is no most
+fn element_width<T:Symbol>(e:&RegexElement<T>) -> (usize, Option<usize>){
+    let repeated = |(min, max):(usize, Option<usize>), qt:&Quantifier| {
+        let limit = Some(quantifier_limit(qt)).filter(|limit| *limit != usize::MAX);
+        (min.saturating_mul(quantifier_min(qt)), max.zip(limit).and_then(|(max, limit)| max.checked_mul(limit)))
+    };
+
+    // The widths of elements in sequence add up, the ones of alternatives span them all
+    let sequence = |elements:&[RegexElement<T>]| elements.iter().map(element_width).fold((0usize, Some(0usize)), |(min, max), (e_min, e_max)| {
+        (min.saturating_add(e_min), max.zip(e_max).and_then(|(max, e_max)| max.checked_add(e_max)))
+    });
+    let alternatives = |widths:Vec<(usize, Option<usize>)>| widths.into_iter().reduce(|(min, max), (other_min, other_max)| {
+        (min.min(other_min), max.zip(other_max).map(|(max, other_max)| max.max(other_max)))
+    }).unwrap_or((0, Some(0)));
+
+    match e {
+        RegexElement::Item(_, qt) | RegexElement::Set(_, _, qt) | RegexElement::Class(_, qt) | RegexElement::NoneOf(_, qt) | RegexElement::Predicate(_, qt) => repeated((1, Some(1)), qt),
+        RegexElement::Group(elements, qt) => repeated(sequence(elements), qt),
+        RegexElement::AnyOf(elements, qt) => repeated(alternatives(elements.iter().map(element_width).collect()), qt),
+        RegexElement::SubPattern(regex, qt) => repeated(alternatives(regex.branches().filter(|branch| !branch.is_empty()).map(sequence).collect()), qt),
+        RegexElement::Capture(_, element) | RegexElement::Named(_, element) => element_width(element),
+        RegexElement::StartOfInput | RegexElement::EndOfInput | RegexElement::Boundary(_) | RegexElement::Lookahead(..) | RegexElement::Lookbehind(..) => (0, Some(0))
+    }
+}
+
+// Counts the leading Symbols accepted by a predicate, given their index in the candidate, up to what the quantifier and cap allow
+fn count_occurences<T:Symbol>(candidate: Option<&[T]>, qt:&Quantifier, cap:usize, accept: impl Fn(usize, &T) -> bool) -> usize{
+    let Some(candidate) = candidate else { return 0; };
+
+    // Fixed-width fast path: compare the whole window at once
+    if let Quantifier::Exactly(n) = qt {
+        if let Some(window) = candidate.get(..*n) {
+            if window.iter().enumerate().all(|(i, c)| accept(i, c)) { return *n; }
+        }
+    }
+
+    candidate.iter()
+        .take(quantifier_limit(qt).min(cap))
+        .enumerate()
+        .take_while(|(i, c)| accept(*i, c))
+        .count()
+}
+
+// The Symbols matched by each capture element, the last match of an element being the one kept
+type Recorded<'p, 'a, T> = Vec<(&'p RegexElement<T>, &'a [T])>;
+
+// What the matching functions share: the bounds on the work,
+// and the whole input so that the assertions know what is around them
+// 
+// The matching functions take their candidate with the index where it starts in the input
+struct Context<'i, T:Symbol>{
+    deadline: Deadline,
+    limits: Limits,
+    input: &'i [T]
+}
+
+impl<'i, T:Symbol> Context<'i, T>{
+    fn new(limits:&Limits, input:&'i [T]) -> Self{ Context { deadline: Deadline::start(limits), limits: *limits, input } }
+
+    fn check(&self) -> Result<(), Limit>{ self.deadline.check() }
+
+    fn check_repetitions(&self, repetitions:usize) -> Result<(), Limit>{ self.limits.check_repetitions(repetitions) }
+
+    // Returns true if the assertion *e* holds at *position* in the input
+    fn holds(&self, e:&RegexElement<T>, position:usize) -> Result<bool, Limit>{
+        if position > self.input.len() { return Ok(false); }
+
+        let holds = match e {
+            RegexElement::StartOfInput => position == 0,
+            RegexElement::EndOfInput => position == self.input.len(),
+            RegexElement::Boundary(predicate) => {
+                let before = position.checked_sub(1).and_then(|i| self.input.get(i)).is_some_and(|s| predicate.test(s));
+                before != self.input.get(position).is_some_and(|s| predicate.test(s))
+            },
+
+            RegexElement::Lookahead(element, positive) => element_ends(self.input, position, element, self)?.is_empty() != *positive,
+
+            RegexElement::Lookbehind(element, positive) => {
+                let mut found = false;
+
+                // Only the starts from which the element may reach the position, the closest first as they are the most likely
+                let (min, max) = element_width(element);
+                let furthest = max.map_or(0, |max| position.saturating_sub(max));
+                let closest = position.checked_sub(min);
+
+                for start in closest.into_iter().flat_map(|closest| (furthest..=closest).rev()){
+                    if element_ends(&self.input[..position], start, element, self)?.contains(&position) {
+                        found = true;
+                        break;
+                    }
+                }
+
+                found == *positive
+            },
+
+            _ => false
+        };
+
+        Ok(holds)
+    }
+}
+
+// Returns if a set of Symbols match a single RegexElement
+// and the number of Symbols that has been read
+fn match_element<T:Symbol>(candidate: Option<&[T]>, at:usize, e:&RegexElement<T>, cx:&Context<T>) -> Result<(bool, usize), Limit>{
+    match_capturing(candidate, at, e, cx, &mut vec![])
+}
+
+// Matches like match_element, recording what the capture elements match
+fn match_capturing<'p, 'a, T:Symbol>(candidate: Option<&'a [T]>, at:usize, e:&'p RegexElement<T>, cx:&Context<T>, captures:&mut Recorded<'p, 'a, T>) -> Result<(bool, usize), Limit>{
+    match_occurences(candidate, at, e, usize::MAX, cx, captures)
+}
+
+// Returns true for the elements each occurence of which is a single Symbol, seen through captures
+fn is_symbol_element<T:Symbol>(e:&RegexElement<T>) -> bool{
+    match e {
+        RegexElement::Item(..) | RegexElement::Set(..) | RegexElement::Class(..) | RegexElement::NoneOf(..) | RegexElement::Predicate(..) => true,
+        RegexElement::Capture(_, element) | RegexElement::Named(_, element) => is_symbol_element(element),
+        RegexElement::Group(..) | RegexElement::AnyOf(..) | RegexElement::SubPattern(..)
+        | RegexElement::StartOfInput | RegexElement::EndOfInput | RegexElement::Boundary(_) | RegexElement::Lookahead(..) | RegexElement::Lookbehind(..) => false
+    }
+}
+
+// Matches the elements one after the other
+// 
+// A lazy element takes the fewest occurences after which the rest of the sequence is valid.
+// A greedy single symbol element gives back occurences until the rest of the sequence is valid.
+// Other elements, when the rest of the sequence is invalid after them, try every other length
+// they could match following the standard regex semantics, see element_ends.
+// If the sequence is invalid, the Symbols read are the ones of its valid elements without giving anything back.
+// An anchored sequence is only valid if it ends at the end of the input, as if followed by EndOfInput
+fn match_sequence<'p, 'a, T:Symbol>(candidate: Option<&'a [T]>, at:usize, elements:&'p [RegexElement<T>], anchored:bool, cx:&Context<T>, captures:&mut Recorded<'p, 'a, T>) -> Result<(bool, usize), Limit>{
+    let Some((e, rest)) = elements.split_first() else { return Ok((!anchored || candidate.is_some_and(|c| c.is_empty()), 0)); };
+    let qt = element_quantifier(e);
+    let lazy = qt.is_some_and(|qt| qt.is_lazy());
+    let mark = captures.len();
+
+    // Matches the rest after *passed* symbols, returns the outcome of the whole sequence
+    let attempt = |passed:usize, captures:&mut Recorded<'p, 'a, T>| -> Result<(bool, usize), Limit> {
+        let (valid, rest_passed) = match_sequence(candidate.and_then(|c| c.get(passed..)), at + passed, rest, anchored, cx, captures)?;
+        Ok((valid, passed + rest_passed))
+    };
+
+    let mut fallback = None;
+
+    // The length matched first, not to be tried again
+    let mut first = None;
+
+    if lazy{
+        let mut previous = None;
+
+        for cap in qt.map_or(0, |qt| quantifier_min(&qt)).. {
+            let (valid, passed) = match_occurences(candidate, at, e, cap, cx, captures)?;
+
+            // No more occurences can be taken
+            if !valid || previous == Some(passed) { break; }
+
+            let outcome = attempt(passed, captures)?;
+            if outcome.0 { return Ok(outcome); }
+
+            captures.truncate(mark);
+            fallback.get_or_insert(outcome);
+            previous = Some(passed);
+        }
+    }
+    else{
+        let (valid, passed) = match_capturing(candidate, at, e, cx, captures)?;
+
+        if valid{
+            let greedy = attempt(passed, captures)?;
+            if greedy.0 { return Ok(greedy); }
+
+            fallback = Some(greedy);
+            first = Some(passed);
+        }
+
+        if is_symbol_element(e){
+            // Each occurence is one symbol, so at most *cap* occurences pass *cap* symbols
+            for cap in (qt.map_or(0, |qt| quantifier_min(&qt))..first.unwrap_or(0)).rev(){
+                captures.truncate(mark);
+                let (valid, passed) = match_occurences(candidate, at, e, cap, cx, captures)?;
+
+                if valid{
+                    let outcome = attempt(passed, captures)?;
+                    if outcome.0 { return Ok(outcome); }
+                }
+            }
+
+            captures.truncate(mark);
+            return Ok(fallback.unwrap_or((false, 0)));
+        }
+    }
+
+    if let Some(candidate) = candidate.filter(|_| !is_symbol_element(e)){
+        // The ends are found in the input up to the end of the candidate
+        let mut ends = element_ends(&cx.input[..at + candidate.len()], at, e, cx)?.into_iter().map(|end| end - at).collect::<Vec<usize>>();
+        if !lazy { ends.reverse(); }
+
+        for end in ends.into_iter().filter(|end| Some(*end) != first){
+            // The captures are recorded if the element alone matches exactly up to the end
+            captures.truncate(mark);
+            if !match_capturing(Some(&candidate[..end]), at, e, cx, captures)?.eq(&(true, end)) { captures.truncate(mark); }
+
+            let outcome = attempt(end, captures)?;
+            if outcome.0 { return Ok(outcome); }
+        }
+    }
+
+    captures.truncate(mark);
+    Ok(fallback.unwrap_or((false, 0)))
+}
+
+// Every position where an element starting at *start* may end, following the standard regex semantics:
+// unlike match_capturing, groups may take fewer occurences and elements of groups may give back symbols
+// to the ones after them, and any alternative of an AnyOf may be taken
+// 
+// *candidate* is the input up to where the element may end, the positions being indexes in the input
+fn element_ends<T:Symbol>(candidate:&[T], start:usize, e:&RegexElement<T>, cx:&Context<T>) -> Result<BTreeSet<usize>, Limit>{
+    cx.check()?;
+
+    let ends = match e {
+        RegexElement::Item(_, qt) | RegexElement::Set(_, _, qt) | RegexElement::Class(_, qt) | RegexElement::NoneOf(_, qt) | RegexElement::Predicate(_, qt) => {
+            let (_, available) = match_element(candidate.get(start..), start, e, cx)?;
+            (0..=available).filter(|n| match_quantifier(*n, qt)).map(|n| start + n).collect()
+        },
+
+        RegexElement::Capture(_, element) | RegexElement::Named(_, element) => element_ends(candidate, start, element, cx)?,
+
+        RegexElement::StartOfInput | RegexElement::EndOfInput | RegexElement::Boundary(_) | RegexElement::Lookahead(..) | RegexElement::Lookbehind(..) => {
+            if start <= candidate.len() && cx.holds(e, start)? { BTreeSet::from([start]) } else { BTreeSet::new() }
+        },
+
+        RegexElement::AnyOf(elements, qt) => repeat_ends(start, qt, cx, |from| {
+            let mut ends = BTreeSet::new();
+            for element in elements{ ends.extend(element_ends(candidate, from, element, cx)?); }
+            Ok(ends)
+        })?,
+
+        // As when matching, an empty group has no occurences
+        RegexElement::Group(elements, qt) if elements.is_empty() => {
+            if match_quantifier(0, qt) { BTreeSet::from([start]) } else { BTreeSet::new() }
+        },
+
+        RegexElement::Group(elements, qt) => repeat_ends(start, qt, cx, |from| sequence_ends(candidate, from, elements, cx))?,
+
+        RegexElement::SubPattern(regex, qt) => repeat_ends(start, qt, cx, |from| {
+            // As when matching, an empty branch matches nothing
+            let mut ends = BTreeSet::new();
+            for branch in regex.branches().filter(|branch| !branch.is_empty()){ ends.extend(sequence_ends(candidate, from, branch, cx)?); }
+            Ok(ends)
+        })?
+    };
+
+    Ok(ends)
+}
+
+// Every position where elements in sequence starting at *start* may end, see element_ends
+fn sequence_ends<T:Symbol>(candidate:&[T], start:usize, elements:&[RegexElement<T>], cx:&Context<T>) -> Result<BTreeSet<usize>, Limit>{
+    let mut positions = BTreeSet::from([start]);
+
+    for element in elements{
+        let mut after = BTreeSet::new();
+        for position in positions{ after.extend(element_ends(candidate, position, element, cx)?); }
+        positions = after;
+    }
+
+    Ok(positions)
+}
+
+// Every position where occurences starting at *start* may end, as many as *qt* allows,
+// *once* giving the positions where a single occurence starting at a position may end
+fn repeat_ends<T:Symbol>(start:usize, qt:&Quantifier, cx:&Context<T>, once:impl Fn(usize) -> Result<BTreeSet<usize>, Limit>) -> Result<BTreeSet<usize>, Limit>{
+    let mut ends = BTreeSet::new();
+    let mut reached = BTreeSet::<usize>::new();
+    let mut current = BTreeSet::from([start]);
+    let mut occurences = 0;
+
+    loop{
+        if match_quantifier(occurences, qt) { ends.extend(&current); }
+        if current.is_empty() || occurences == quantifier_limit(qt) { break; }
+
+        let mut next = BTreeSet::new();
+        for from in &current{ next.extend(once(*from)?); }
+
+        // Past the minimum, occurences must consume symbols and reach new positions, so that this ends
+        occurences += 1;
+        if occurences > quantifier_min(qt){
+            reached.extend(&current);
+            next.retain(|position| !reached.contains(position));
+        }
+
+        if !next.is_empty() { cx.check_repetitions(occurences)?; }
+        current = next;
+    }
+
+    Ok(ends)
+}
+
+// Matches one occurence of an embedded regex: the longest valid match of its branches,
+// the first branch winning a tie, as Regex::match_start does
+fn match_branches<'p, 'a, T:Symbol>(candidate: Option<&'a [T]>, at:usize, regex:&'p Regex<T>, cx:&Context<T>, captures:&mut Recorded<'p, 'a, T>) -> Result<(bool, usize), Limit>{
+    let mut best:Option<(bool, usize, Recorded<'p, 'a, T>)> = None;
+
+    for branch in regex.branches(){
+        let mut recorded = vec![];
+        let (valid, passed) = match_sequence(candidate, at, branch, false, cx, &mut recorded)?;
+
+        // An empty branch matches nothing
+        let valid = valid && !branch.is_empty();
+
+        if best.as_ref().is_none_or(|(best_valid, best_passed, _)| valid && (!best_valid || passed > *best_passed)) {
+            best = Some((valid, passed, recorded));
+        }
+    }
+
+    let Some((valid, passed, recorded)) = best else { return Ok((false, 0)); };
+    if valid { captures.extend(recorded); }
+
+    Ok((valid, passed))
+}
+
+// Matches occurences one after the other, as many as the quantifier and cap allow,
+// *once* matching a single occurence
+// 
+// After the first occurence, the occurences are only repeated while *first* alone keeps consuming symbols
+// and while they consume symbols themselves
+#[allow(clippy::too_many_arguments)]
+fn match_repeated<'p, 'a, T:Symbol>(candidate: Option<&'a [T]>, at:usize, qt:&Quantifier, cap:usize, first:Option<&RegexElement<T>>, cx:&Context<T>, captures:&mut Recorded<'p, 'a, T>,
+    once:impl Fn(Option<&'a [T]>, usize, &mut Recorded<'p, 'a, T>) -> Result<(bool, usize), Limit>) -> Result<(bool, usize), Limit>{
+    let mut ind = 0;
+    let mut occurences = 0;
+    let mut repeating = false;
+
+    // The symbols read by the invalid occurence ending the repetition, only counted if the repetition is invalid
+    let mut partial = 0;
+
+    if let Some(candidate) = candidate{
+
+        loop{
+            // An occurence can't match if its first element doesn't, even matching no symbols
+            if let Some(first) = first.filter(|_| repeating){
+                if !match_element(candidate.get(ind..), at + ind, first, cx)?.0 { break; }
+            }
+
+            // The captures of an invalid occurence are dropped
+            let occurence = captures.len();
+            let (valid, passed) = once(candidate.get(ind..), at + ind, captures)?;
+
+            // An occurence matching no symbols could be repeated forever without consuming anything:
+            // the repetition ends there, the occurences still needed matching no symbols too
+            if repeating && passed == 0 {
+                captures.truncate(occurence);
+                if valid { occurences = occurences.max(quantifier_min(qt).min(cap)); }
+                break;
+            }
+
+            // An invalid occurence ends the repetition without consuming anything
+            if !valid {
+                captures.truncate(occurence);
+                partial = passed;
+                break;
+            }
+
+            cx.check_repetitions(occurences + 1)?;
+            ind += passed;
+            occurences += 1;
+
+            // Past the quantifier's limit the occurences can't be valid anymore
+            if occurences > quantifier_limit(qt) || occurences >= cap { break; }
+
+            repeating = true;
+        }
+    }
+
+    let valid = match_quantifier(occurences, qt);
+    Ok((valid, if valid { ind } else { ind + partial }))
+}
+
+// The ends of the elements of a branch matching the start of a candidate, see Regex::split_first_with_boundaries:
+// the longest prefix of the branch that matches is kept
+fn branch_boundaries<T:Symbol>(branch:&[RegexElement<T>], candidate:&[T], cx:&Context<T>) -> Result<Boundaries, Limit>{
+    // Each element is wrapped in a capture recording what it matched, captures don't change the matching
+    let wrapped = branch.iter().map(|e| RegexElement::Capture(String::new(), Box::new(e.clone()))).collect::<Vec<RegexElement<T>>>();
+
+    for matched in (0..=wrapped.len()).rev(){
+        let mut recorded = vec![];
+        if !match_sequence(Some(candidate), 0, &wrapped[..matched], false, cx, &mut recorded)?.0 { continue; }
+
+        let mut end = 0;
+        let ends = wrapped[..matched].iter().map(|e| {
+            end += recorded.iter().rev().find(|(capture, _)| std::ptr::eq(*capture, e)).map_or(0, |(_, symbols)| symbols.len());
+            end
+        }).collect();
+
+        // An empty pattern matches nothing
+        let failed = (matched < wrapped.len() || wrapped.is_empty()).then_some(matched);
+
+        return Ok(Boundaries { ends, failed });
+    }
+
+    Ok(Boundaries { ends: vec![], failed: Some(0) })
+}
+
+// Matches like match_capturing, with at most *cap* occurences of the element
+fn match_occurences<'p, 'a, T:Symbol>(candidate: Option<&'a [T]>, at:usize, e:&'p RegexElement<T>, cap:usize, cx:&Context<T>, captures:&mut Recorded<'p, 'a, T>) -> Result<(bool, usize), Limit>{
+    cx.check()?;
+
+    // The captures of an invalid match are dropped
+    let mark = captures.len();
+
+    let result = match e {
+        RegexElement::Item(value, qt) => {
+            let occurences = count_occurences(candidate, qt, cap, |_, c| value == c);
+
+            (match_quantifier(occurences, qt), occurences)
+        },
+
+        RegexElement::Set(low, high, qt) => {
+            let occurences = count_occurences(candidate, qt, cap, |_, c| low <= c && c <= high);
+
+            (match_quantifier(occurences, qt), occurences)
+        },
+
+        RegexElement::Class(items, qt) => {
+            let occurences = count_occurences(candidate, qt, cap, |_, c| items.iter().any(|item| item.contains(c)));
+
+            (match_quantifier(occurences, qt), occurences)
+        },
+
+        RegexElement::Predicate(predicate, qt) => {
+            let occurences = count_occurences(candidate, qt, cap, |_, c| predicate.test(c));
+
+            (match_quantifier(occurences, qt), occurences)
+        },
+
+        RegexElement::AnyOf(elements, qt) => match_repeated(candidate, at, qt, cap, None, cx, captures, |candidate, at, captures| {
+            let mut outcome = (false, 0);
+
+            for element in elements{
+                outcome = match_capturing(candidate, at, element, cx, captures)?;
+
+                if outcome.0 { break; }
+            }
+
+            Ok(outcome)
+        })?,
+
+        RegexElement::NoneOf(elements, qt) => {
+            let occurences = count_occurences(candidate, qt, cap, |i, c| {
+                !elements.is_empty()
+                && elements.iter().all(|element| !match_element(Some(std::slice::from_ref(c)), at + i, element, cx).is_ok_and(|(valid, _)| valid))
+            });
+
+            (match_quantifier(occurences, qt), occurences)
+        },
+
+        // An empty group has no occurences
+        RegexElement::Group(elements, qt) if elements.is_empty() => (match_quantifier(0, qt), 0),
+
+        RegexElement::Group(elements, qt) => match_repeated(candidate, at, qt, cap, elements.first(), cx, captures, |candidate, at, captures| {
+            match_sequence(candidate, at, elements, false, cx, captures)
+        })?,
+
+        RegexElement::SubPattern(regex, qt) => match_repeated(candidate, at, qt, cap, None, cx, captures, |candidate, at, captures| {
+            match_branches(candidate, at, regex, cx, captures)
+        })?,
+
+        RegexElement::StartOfInput | RegexElement::EndOfInput | RegexElement::Boundary(_) | RegexElement::Lookahead(..) | RegexElement::Lookbehind(..) => (candidate.is_some() && cx.holds(e, at)?, 0),
+
+        RegexElement::Named(_, element) => match_occurences(candidate, at, element, cap, cx, captures)?,
+
+        RegexElement::Capture(_, element) => {
+            let (valid, passed) = match_occurences(candidate, at, element, cap, cx, captures)?;
+
+            if let Some(matched) = candidate.and_then(|c| c.get(..passed)).filter(|_| valid){
+                captures.push((e, matched));
+            }
+
+            (valid, passed)
+        }
+    };
+
+    if !result.0 { captures.truncate(mark); }
+
+    Ok(result)
+}
+
+// Returns if the elements may match a sequence starting with *symbol*,
+// *cx* being on the symbol alone and *empty* on no symbols
+fn sequence_starts<T:Symbol>(elements:&[RegexElement<T>], symbol:&T, cx:&Context<T>, empty:&Context<T>) -> bool{
+    for e in elements{
+        if element_starts(e, symbol, cx, empty) { return true; }
+
+        // The next element may only come first if this one can match no symbols
+        if !element_ends(&[], 0, e, empty).is_ok_and(|ends| ends.contains(&0)) { return false; }
+    }
+
+    false
+}
+
+fn element_starts<T:Symbol>(e:&RegexElement<T>, symbol:&T, cx:&Context<T>, empty:&Context<T>) -> bool{
+    match e {
+        RegexElement::Group(elements, _) => sequence_starts(elements, symbol, cx, empty),
+        RegexElement::AnyOf(elements, _) => elements.iter().any(|e| element_starts(e, symbol, cx, empty)),
+        RegexElement::Capture(_, element) | RegexElement::Named(_, element) => element_starts(element, symbol, cx, empty),
+        RegexElement::SubPattern(regex, _) => regex.branches().any(|branch| sequence_starts(branch, symbol, cx, empty)),
+        _ => match_element(Some(std::slice::from_ref(symbol)), 0, &quantified_once(e), cx).is_ok_and(|(valid, passed)| valid && passed == 1)
+    }
+}
+
+// The same element expecting exactly one occurence
+fn quantified_once<T:Symbol>(e:&RegexElement<T>) -> RegexElement<T>{
+    let once = Quantifier::Exactly(1);
+
+    match e.clone() {
+        RegexElement::Item(value, _) => RegexElement::Item(value, once),
+        RegexElement::Set(low, high, _) => RegexElement::Set(low, high, once),
+        RegexElement::Class(items, _) => RegexElement::Class(items, once),
+        RegexElement::NoneOf(elements, _) => RegexElement::NoneOf(elements, once),
+        RegexElement::Group(elements, _) => RegexElement::Group(elements, once),
+        RegexElement::AnyOf(elements, _) => RegexElement::AnyOf(elements, once),
+        RegexElement::Capture(name, element) => RegexElement::Capture(name, Box::new(quantified_once(&element))),
+        RegexElement::Named(name, element) => RegexElement::Named(name, Box::new(quantified_once(&element))),
+        RegexElement::SubPattern(regex, _) => RegexElement::SubPattern(regex, once),
+        RegexElement::Predicate(predicate, _) => RegexElement::Predicate(predicate, once),
+        assertion @ (RegexElement::StartOfInput | RegexElement::EndOfInput | RegexElement::Boundary(_) | RegexElement::Lookahead(..) | RegexElement::Lookbehind(..)) => assertion
+    }
+}
+
+// Returns how deeply elements are nested in a pattern, without recursion as the depth isn't checked yet
+fn pattern_depth<T:Symbol>(pattern:&[RegexElement<T>]) -> usize{
+    let mut pending = pattern.iter().map(|e| (e, 1)).collect::<Vec<(&RegexElement<T>, usize)>>();
+    let mut depth = 0;
+
+    while let Some((e, level)) = pending.pop(){
+        depth = depth.max(level);
+
+        match e {
+            RegexElement::Group(elements, _) | RegexElement::AnyOf(elements, _) | RegexElement::NoneOf(elements, _) => pending.extend(elements.iter().map(|e| (e, level + 1))),
+            RegexElement::Capture(_, element) | RegexElement::Named(_, element) | RegexElement::Lookahead(element, _) | RegexElement::Lookbehind(element, _) => pending.push((element.as_ref(), level + 1)),
+            RegexElement::SubPattern(regex, _) => pending.extend(regex.branches().flatten().map(|e| (e, level + 1))),
+            _ => {}
+        }
+    }
+
+    depth
+}
+
+
+
+// A random number of occurences allowed by the quantifier, at most *max_repeat* more than its minimum
+fn sample_count(quantifier:&Quantifier, rng:&mut Rng, max_repeat:usize) -> usize{
+    let min = quantifier_min(quantifier);
+    let max = quantifier_limit(quantifier).min(min.saturating_add(max_repeat));
+
+    min + rng.below(max - min + 1)
+}
+
+// Appends random symbols matching the elements in sequence, the ones of a single symbol element being drawn from *universe*
+// 
+// The assertions add no symbols, so the result may not match: it is checked by the caller
+fn sample_sequence<T:Symbol>(elements:&[RegexElement<T>], rng:&mut Rng, universe:&[T], max_repeat:usize, out:&mut Vec<T>) -> Option<()>{
+    for e in elements{ sample_element(e, rng, universe, max_repeat, out)?; }
+    Some(())
+}
+
+fn sample_element<T:Symbol>(e:&RegexElement<T>, rng:&mut Rng, universe:&[T], max_repeat:usize, out:&mut Vec<T>) -> Option<()>{
+    match e {
+        RegexElement::Item(value, qt) => {
+            let count = sample_count(qt, rng, max_repeat);
+            out.extend(std::iter::repeat_n(value.clone(), count));
+        },
+
+        RegexElement::Set(_, _, qt) | RegexElement::Class(_, qt) | RegexElement::NoneOf(_, qt) | RegexElement::Predicate(_, qt) => {
+            let once = quantified_once(e);
+            let accepted = universe.iter()
+                .filter(|symbol| {
+                    let alone = std::slice::from_ref(*symbol);
+                    match_element(Some(alone), 0, &once, &Context::new(&Limits::new(), alone)).is_ok_and(|(valid, passed)| valid && passed == 1)
+                })
+                .collect::<Vec<&T>>();
+
+            for _ in 0..sample_count(qt, rng, max_repeat){ out.push((*rng.pick(&accepted)?).clone()); }
+        },
+
+        RegexElement::Group(elements, qt) => for _ in 0..sample_count(qt, rng, max_repeat){
+            sample_sequence(elements, rng, universe, max_repeat, out)?;
+        },
+
+        RegexElement::AnyOf(elements, qt) => for _ in 0..sample_count(qt, rng, max_repeat){
+            sample_element(rng.pick(elements)?, rng, universe, max_repeat, out)?;
+        },
+
+        RegexElement::SubPattern(regex, qt) => for _ in 0..sample_count(qt, rng, max_repeat){
+            let branches = regex.branches().collect::<Vec<&[RegexElement<T>]>>();
+            sample_sequence(rng.pick(&branches)?, rng, universe, max_repeat, out)?;
+        },
+
+        RegexElement::Capture(_, element) | RegexElement::Named(_, element) => sample_element(element, rng, universe, max_repeat, out)?,
+
+        RegexElement::StartOfInput | RegexElement::EndOfInput | RegexElement::Boundary(_) | RegexElement::Lookahead(..) | RegexElement::Lookbehind(..) => ()
+    }
+
+    Some(())
+}
+
+// The numbers of occurences worth trying for a quantifier
+fn example_counts(quantifier:&Quantifier) -> Vec<usize>{
+    match quantifier {
+        Quantifier::Exactly(n) => vec![*n],
+        Quantifier::OneOrMany => vec![1, 2],
+        Quantifier::ZeroOrMany => vec![0, 1, 2],
+        Quantifier::ZeroOrOne => vec![0, 1],
+        Quantifier::Between(min, max) if min < max => vec![*min, *max],
+        Quantifier::Between(min, max) if min == max => vec![*min],
+        Quantifier::Between(_, _) => vec![],
+        Quantifier::AtLeast(n) => vec![*n, n + 1],
+        Quantifier::ZeroOrManyLazy | Quantifier::OneOrManyLazy | Quantifier::ZeroOrOneLazy => example_counts(&quantifier.greedy())
+    }
+}
+
+// Repeats each example by every count allowed by the quantifier
+fn repeat_examples(examples:Vec<String>, quantifier:&Quantifier, limit:usize) -> Vec<String>{
+    example_counts(quantifier).into_iter()
+        .flat_map(|n| examples.iter().map(move |e| e.repeat(n)))
+        .take(limit)
+        .collect()
+}
+
+// Concatenates the examples of consecutive elements, keeping at most limit combinations
+fn sequence_examples(elements:&[RegexElement<char>], limit:usize) -> Vec<String>{
+    elements.iter().fold(vec![String::new()], |prefixes, element| {
+        let suffixes = element_examples(element, limit);
+        prefixes.iter()
+            .flat_map(|p| suffixes.iter().map(move |s| format!("{p}{s}")))
+            .take(limit)
+            .collect()
+    })
+}
+
+// Builds a few strings accepted by a single element
+fn element_examples(e:&RegexElement<char>, limit:usize) -> Vec<String>{
+    match e {
+        RegexElement::Item(value, qt) => repeat_examples(vec![value.to_string()], qt, limit),
+
+        RegexElement::Set(low, high, qt) => {
+            let mut bounds = vec![low.to_string()];
+            if high != low { bounds.push(high.to_string()); }
+            repeat_examples(bounds, qt, limit)
+        },
+
+        RegexElement::Class(items, qt) => {
+            let bounds = items.iter().flat_map(|item| match item {
+                ClassItem::Single(value) => vec![value.to_string()],
+                ClassItem::Range(low, high) if high != low => vec![low.to_string(), high.to_string()],
+                ClassItem::Range(low, _) => vec![low.to_string()]
+            }).collect();
+
+            repeat_examples(bounds, qt, limit)
+        },
+
+        RegexElement::AnyOf(elements, qt) => repeat_examples(elements.iter().flat_map(|e| element_examples(e, limit)).take(limit).collect(), qt, limit),
+
+        RegexElement::NoneOf(elements, qt) => {
+            let outsider = sample_chars()
+                .find(|c| {
+                    let alone = [*c];
+                    elements.iter().all(|e| !match_element(Some(&alone), 0, e, &Context::new(&Limits::new(), &alone)).is_ok_and(|(valid, _)| valid))
+                });
+
+            match outsider {
+                Some(c) => repeat_examples(vec![c.to_string()], qt, limit),
+                None => repeat_examples(vec![], qt, limit)
+            }
+        },
+
+        RegexElement::Group(elements, qt) => repeat_examples(sequence_examples(elements, limit), qt, limit),
+
+        RegexElement::Capture(_, element) | RegexElement::Named(_, element) => element_examples(element, limit),
+
+        RegexElement::SubPattern(regex, qt) => repeat_examples(regex.branches().flat_map(|branch| sequence_examples(branch, limit)).take(limit).collect(), qt, limit),
+
+        RegexElement::Predicate(predicate, qt) => repeat_examples(sample_chars().filter(|c| predicate.test(c)).take(1).map(String::from).collect(), qt, limit),
+
+        // The examples not matching where the assertions are end up filtered out
+        RegexElement::StartOfInput | RegexElement::EndOfInput | RegexElement::Boundary(_) | RegexElement::Lookahead(..) | RegexElement::Lookbehind(..) => vec![String::new()]
+    }
+}
+
+// A few characters of various kinds, to pick examples from
+fn sample_chars() -> impl Iterator<Item = char>{
+    "a0_ A".chars().chain('!'..='~').chain(['é', '\u{3bb}'])
+}
+
+// The other cases of a character, when they are single characters
+fn case_variants(c:char) -> impl Iterator<Item = char>{
+    fn single(mut mapped:impl Iterator<Item = char>) -> Option<char>{
+        mapped.next().filter(|_| mapped.next().is_none())
+    }
+
+    let lower = single(c.to_lowercase());
+    let upper = single(c.to_uppercase()).filter(|u| Some(*u) != lower);
+
+    lower.into_iter().chain(upper).filter(move |v| *v != c)
+}
+
+// The items plus the other cases of the characters in them, the added characters merged into ranges
+fn fold_items(items:Vec<ClassItem<char>>) -> Vec<ClassItem<char>>{
+    let mut added = items.iter()
+        .flat_map(|item| match item {
+            ClassItem::Single(c) => *c..=*c,
+            ClassItem::Range(low, high) => *low..=*high
+        })
+        .flat_map(case_variants)
+        .filter(|v| !items.iter().any(|item| item.contains(v)))
+        .collect::<Vec<char>>();
+    added.sort();
+    added.dedup();
+
+    let mut folded = items;
+    let mut run:Option<(char, char)> = None;
+
+    for c in added.into_iter().map(Some).chain([None]){
+        match (run, c) {
+            (Some((low, high)), Some(c)) if high.is_adjacent(&c) => run = Some((low, c)),
+            _ => {
+                if let Some((low, high)) = run{
+                    folded.push(if low == high { ClassItem::Single(low) } else { ClassItem::Range(low, high) });
+                }
+                run = c.map(|c| (c, c));
+            }
+        }
+    }
+
+    folded
+}
+
+// The same element matching the characters in any case
+fn fold_case(e:RegexElement<char>) -> RegexElement<char>{
+    let fold_all = |elements:Vec<RegexElement<char>>| elements.into_iter().map(fold_case).collect::<Vec<RegexElement<char>>>();
+
+    match e {
+        RegexElement::Item(c, qt) => match case_variants(c).next() {
+            Some(_) => RegexElement::Class(fold_items(vec![ClassItem::Single(c)]), qt),
+            None => RegexElement::Item(c, qt)
+        },
+        RegexElement::Set(low, high, qt) => match fold_items(vec![ClassItem::Range(low, high)]) {
+            items if items.len() > 1 => RegexElement::Class(items, qt),
+            _ => RegexElement::Set(low, high, qt)
+        },
+        RegexElement::Class(items, qt) => RegexElement::Class(fold_items(items), qt),
+        RegexElement::Group(elements, qt) => RegexElement::Group(fold_all(elements), qt),
+        RegexElement::AnyOf(elements, qt) => RegexElement::AnyOf(fold_all(elements), qt),
+        RegexElement::NoneOf(elements, qt) => RegexElement::NoneOf(fold_all(elements), qt),
+        RegexElement::Capture(name, element) => RegexElement::Capture(name, Box::new(fold_case(*element))),
+        RegexElement::Named(name, element) => RegexElement::Named(name, Box::new(fold_case(*element))),
+        RegexElement::SubPattern(regex, qt) => RegexElement::SubPattern(regex.case_insensitive(true), qt),
+        RegexElement::Predicate(predicate, qt) => RegexElement::Predicate(
+            SymbolPredicate::new(move |c| predicate.test(c) || case_variants(*c).any(|v| predicate.test(&v))), qt
+        ),
+        RegexElement::Lookahead(element, positive) => RegexElement::Lookahead(Box::new(fold_case(*element)), positive),
+        RegexElement::Lookbehind(element, positive) => RegexElement::Lookbehind(Box::new(fold_case(*element)), positive),
+        assertion @ (RegexElement::StartOfInput | RegexElement::EndOfInput | RegexElement::Boundary(_)) => assertion
+    }
+}
+
+impl Regex<char>{
+    /// Makes the elements already in this regex, and its [alternatives](Regex::or), match their characters in any case, builder style
+    /// 
+    /// A character matches if it or its lower or upper case is expected, so `select` matches `SELECT` and `Select`.
+    /// Case mappings to several characters, as `ß` to `SS`, are ignored.
+    /// The elements are rewritten into [classes](RegexElement::Class), so the regex still [compiles](Regex::compile)
+    /// and serializes, but elements added afterwards match as written and *false* leaves the regex unchanged
+    /// 
+    /// # Exemples
+    /// ```rust
+    /// use crate::neoglot_lib::regex::{Quantifier, Regex, RegexElement};
+    /// 
+    /// let keyword = "select".chars()
+    ///     .fold(Regex::new(), |regex, c| regex.then(RegexElement::Item(c, Quantifier::Exactly(1))))
+    ///     .case_insensitive(true);
+    /// 
+    /// assert!(keyword.r#match(&"SELECT".chars().collect::<Vec<char>>()));
+    /// assert!(keyword.r#match(&"sElEcT".chars().collect::<Vec<char>>()));
+    /// assert!(!keyword.r#match(&"selec".chars().collect::<Vec<char>>()));
+    /// ```
+    pub fn case_insensitive(mut self, enabled:bool) -> Self{
+        if !enabled{ return self; }
+
+        self.pattern = self.pattern.into_iter().map(fold_case).collect();
+        self.alternatives = self.alternatives.into_iter()
+            .map(|alternative| alternative.into_iter().map(fold_case).collect())
+            .collect();
+        self
+    }
+
+    /// Builds up to *limit* distinct strings matched by this regex
+    /// 
+    /// Quantifiers are explored with a few repetition counts only, so the examples are representative rather than exhaustive
+    pub fn examples(&self, limit:usize) -> Vec<String>{
+        let mut seen = HashSet::new();
+        // The examples of the alternatives take turns
+        let branches = self.branches().map(|branch| sequence_examples(branch, limit)).collect::<Vec<Vec<String>>>();
+        let mut examples = (0..limit)
+            .flat_map(|i| branches.iter().filter_map(move |examples| examples.get(i).cloned()))
+            .collect::<Vec<String>>();
+        examples.retain(|e| seen.insert(e.clone()) && self.r#match(&e.chars().collect::<Vec<char>>()));
+        examples.truncate(limit);
+        examples
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+/// What the [captures](RegexElement::Capture) of a [Regex] matched, see [Regex::captures]
+/// 
+/// The captures are indexed in the order they appear in the pattern,
+/// or in the [alternative](Regex::or) that matched
+pub struct Captures<'a, T:Symbol>{
+    matched: &'a [T],
+    groups: Vec<(String, Option<&'a [T]>)>
+}
+
+impl<'a, T:Symbol> Captures<'a, T>{
+    /// The [symbols](Symbol) matched by the whole pattern
+    pub fn matched(&self) -> &'a [T]{ self.matched }
+
+    /// The [symbols](Symbol) matched by the capture at *index*,
+    /// None if it doesn't exist or didn't take part in the match
+    pub fn get(&self, index:usize) -> Option<&'a [T]>{
+        self.groups.get(index).and_then(|(_, matched)| *matched)
+    }
+
+    /// The [symbols](Symbol) matched by the first capture named *name*,
+    /// None if it doesn't exist or didn't take part in the match
+    pub fn name(&self, name:&str) -> Option<&'a [T]>{
+        self.groups.iter().find(|(n, _)| n == name).and_then(|(_, matched)| *matched)
+    }
+
+    /// The number of captures in the pattern
+    pub fn len(&self) -> usize{ self.groups.len() }
+
+    /// Returns true if the pattern has no captures
+    pub fn is_empty(&self) -> bool{ self.groups.is_empty() }
+
+    /// Iterates over the names of the captures and what they matched, in pattern order
+    pub fn iter(&self) -> impl Iterator<Item = (&str, Option<&'a [T]>)>{
+        self.groups.iter().map(|(name, matched)| (name.as_str(), *matched))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+/// An occurence of a [Regex] in a set of [symbols](Symbol), see [Regex::find_all]
+pub struct Match<'a, T:Symbol>{
+    /// The index of the first matched symbol
+    pub start: usize,
+
+    /// The number of matched symbols
+    pub length: usize,
+
+    /// The matched symbols
+    pub symbols: &'a [T]
+}
+
+impl<T:Symbol> Match<'_, T>{
+    /// The index right after the last matched symbol
+    pub fn end(&self) -> usize{ self.start + self.length }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+/// A part of a set of [symbols](Symbol) split by a [Regex], see [Regex::split_all]
+pub enum Chunk<'a, T:Symbol>{
+    /// Symbols matched by the pattern
+    Matched(&'a [T]),
+
+    /// Symbols between two matches, or before the first or after the last one
+    Unmatched(&'a [T])
+}
+
+impl<'a, T:Symbol> Chunk<'a, T>{
+    /// The symbols of the chunk
+    pub fn symbols(&self) -> &'a [T]{
+        match self {
+            Chunk::Matched(symbols) | Chunk::Unmatched(symbols) => symbols
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+/// Where the [elements](RegexElement) of a [Regex] end in a match, see [Regex::split_first_with_boundaries]
+pub struct Boundaries{
+    /// The end of each element that matched, in pattern order and relative to the start of the match
+    pub ends: Vec<usize>,
+
+    /// The index of the first element that couldn't match after the ones before it,
+    /// None if the whole pattern matched
+    pub failed: Option<usize>
+}
+
+impl<T:Symbol> Default for Regex<T>{
+    fn default() -> Self { Self::new() }
+}
+
+impl<T:Symbol> Regex<T>{
+
+    /// Creates a new Regex
+    pub fn new() -> Self{ Regex { pattern: vec![], alternatives: vec![], limits: Limits::new(), name: None, engine: Engine::Backtracking } }
+
+    ///Adds an [element](RegexElement) to the regex
+    /// 
+    /// After [or](Regex::or) the element is added at the end of every alternative
+    pub fn then(mut self, e:RegexElement<T>) -> Self{
+        for alternative in &mut self.alternatives{ alternative.push(e.clone()); }
+        self.pattern.push(e);
+        self
+
+    }
+
+    /// Makes this regex match either its own pattern or the one of *other*, with the limits of this regex
+    /// 
+    /// The longest match of the alternatives is kept, the first alternative winning a tie
+    /// 
+    /// # Exemples
+    /// ```rust
+    /// use crate::neoglot_lib::regex::{Quantifier, Regex, RegexElement};
+    /// 
+    /// let hex = Regex::<char>::new()
+    ///     .then(RegexElement::Item('#', Quantifier::Exactly(1)))
+    ///     .then(RegexElement::Set('0', '9', Quantifier::OneOrMany));
+    /// 
+    /// let word = Regex::<char>::new().then(RegexElement::Set('a', 'z', Quantifier::OneOrMany));
+    /// let either = hex.or(word);
+    /// 
+    /// assert!(either.r#match(&['#', '1', '2']));
+    /// assert!(either.r#match(&['a', 'b']));
+    /// assert!(!either.r#match(&['#', 'a']));
+    /// ```
+    pub fn or(mut self, other:Regex<T>) -> Self{
+        self.alternatives.push(other.pattern);
+        self.alternatives.extend(other.alternatives);
+        self
+    }
+
+    // The pattern and its alternatives
+    fn branches(&self) -> impl Iterator<Item = &[RegexElement<T>]>{
+        std::iter::once(self.pattern.as_slice()).chain(self.alternatives.iter().map(Vec::as_slice))
+    }
+
+
+    /// Lists the [symbols](Symbol) named by the pattern, in order and without duplicates
+    /// 
+    /// Both ends of sets are listed, the symbols between them aren't
+    pub fn symbols(&self) -> Vec<&T>{
+        fn collect<'a, T:Symbol>(elements:&'a [RegexElement<T>], symbols:&mut Vec<&'a T>){
+            for e in elements{
+                match e {
+                    RegexElement::Item(value, _) => symbols.push(value),
+                    RegexElement::Set(low, high, _) => symbols.extend([low, high]),
+                    RegexElement::Class(items, _) => for item in items{
+                        match item {
+                            ClassItem::Single(value) => symbols.push(value),
+                            ClassItem::Range(low, high) => symbols.extend([low, high])
+                        }
+                    },
+                    RegexElement::Group(elements, _) | RegexElement::AnyOf(elements, _) | RegexElement::NoneOf(elements, _) => collect(elements, symbols),
+                    RegexElement::Capture(_, element) | RegexElement::Named(_, element) | RegexElement::Lookahead(element, _) | RegexElement::Lookbehind(element, _) => collect(std::slice::from_ref(element.as_ref()), symbols),
+                    RegexElement::SubPattern(regex, _) => for branch in regex.branches(){ collect(branch, symbols); },
+                    RegexElement::Predicate(..) | RegexElement::StartOfInput | RegexElement::EndOfInput | RegexElement::Boundary(_) => {}
+                }
+            }
+        }
+
+        let mut symbols = vec![];
+        for branch in self.branches(){ collect(branch, &mut symbols); }
+
+        let mut unique = vec![];
+        for symbol in symbols{
+            if !unique.contains(&symbol) { unique.push(symbol); }
+        }
+        unique
+    }
+
+    /// Returns true if some element of the pattern accepts *symbol*,
+    /// so that it may be part of a match
+    pub fn may_accept(&self, symbol:&T) -> bool{
+        let cx = Context::new(&Limits::new(), std::slice::from_ref(symbol));
+
+        fn accepts<T:Symbol>(e:&RegexElement<T>, symbol:&T, cx:&Context<T>) -> bool{
+            match e {
+                RegexElement::Group(elements, _) | RegexElement::AnyOf(elements, _) => elements.iter().any(|e| accepts(e, symbol, cx)),
+                RegexElement::Capture(_, element) | RegexElement::Named(_, element) => accepts(element, symbol, cx),
+                RegexElement::SubPattern(regex, _) => regex.branches().flatten().any(|e| accepts(e, symbol, cx)),
+                _ => match_element(Some(std::slice::from_ref(symbol)), 0, &quantified_once(e), cx).is_ok_and(|(valid, passed)| valid && passed == 1)
+            }
+        }
+
+        self.branches().flatten().any(|e| accepts(e, symbol, &cx))
+    }
+
+    /// Returns true if a match may start with *symbol*: an element that may come first in a branch accepts it,
+    /// the elements before it being able to match no symbols
+    pub fn may_start(&self, symbol:&T) -> bool{
+        let cx = Context::new(&Limits::new(), std::slice::from_ref(symbol));
+        let empty = Context::new(&Limits::new(), &[]);
+
+        self.branches().any(|branch| sequence_starts(branch, symbol, &cx, &empty))
+    }
+
+    /// Draws random symbols matched by the pattern, the unbounded quantifiers repeating at most *max_repeat* times more than they need
+    /// 
+    /// The symbols of the elements other than [items](RegexElement::Item) are drawn from *universe*, so that fuzzers
+    /// can check properties as "every generated literal lexes". The same [seed](Rng::new) gives the same symbols.
+    /// Returns None if no draw matched, as when an element accepts no symbol of the universe or an assertion keeps failing
+    /// 
+    /// # Exemples
+    /// ```rust
+    /// use crate::neoglot_lib::{fuzz::Rng, regex::{Quantifier, Regex, RegexElement}};
+    /// 
+    /// let number = Regex::new()
+    ///     .then(RegexElement::Item('-', Quantifier::ZeroOrOne))
+    ///     .then(RegexElement::Set('0', '9', Quantifier::OneOrMany));
+    /// 
+    /// let universe = ('0'..='9').chain(['a', '-']).collect::<Vec<char>>();
+    /// let mut rng = Rng::new(1);
+    /// 
+    /// for _ in 0..10{
+    ///     let literal = number.generate(&mut rng, &universe, 4).unwrap();
+    ///     assert!(number.r#match(&literal) && literal.len() <= 6);
+    /// }
+    /// 
+    /// assert_eq!(number.generate(&mut rng, &['a'], 4), None);
+    /// ```
+    pub fn generate(&self, rng:&mut Rng, universe:&[T], max_repeat:usize) -> Option<Vec<T>>{
+        let branches = self.branches().filter(|branch| !branch.is_empty()).collect::<Vec<&[RegexElement<T>]>>();
+
+        // Assertions may reject a draw
+        for _ in 0..SAMPLE_ATTEMPTS{
+            let mut symbols = vec![];
+            if sample_sequence(rng.pick(&branches)?, rng, universe, max_repeat, &mut symbols).is_some() && self.r#match(&symbols){
+                return Some(symbols);
+            }
+        }
+
+        None
+    }
+
+    /// Names what the regex matches in human readable words, as "float literal", builder style
+    /// 
+    /// The name stands for the regex when it is [embedded](RegexElement::SubPattern) in a [trace](Regex::trace),
+    /// and the [lexer](crate::lexer::Lexer) reports it as expected where its node could have started a token
+    pub fn with_name(mut self, name:&str) -> Self{
+        self.name = Some(name.to_string());
+        self
+    }
+
+    /// The [name](Regex::with_name) of the regex, if it has one
+    pub fn name(&self) -> Option<&str>{ self.name.as_deref() }
+
+    /// Bounds the matching work, builder style
+    /// 
+    /// The depth limits how deeply elements are nested, the tokens limit how many symbols a candidate may have
+    /// 
+    /// Exceeding a limit makes [match](Regex::match) and [split_first](Regex::split_first) fail,
+    /// use [try_match](Regex::try_match) and [try_split_first](Regex::try_split_first) to know which limit was exceeded
+    pub fn with_limits(mut self, limits:Limits) -> Self{
+        self.limits = limits;
+        self
+    }
+
+    /// Chooses how the pattern is matched, by [backtracking](Engine::Backtracking) by default, builder style
+    /// 
+    /// The engine only decides how [full matches](Regex::match) are found, both engines accepting the same sequences.
+    /// The prefix a [split](Regex::split_first) or a [find](Regex::find_all) keeps depends on the order of the alternatives,
+    /// so they are always found by backtracking, as [captures](Regex::captures) and [traces](Regex::trace) are.
+    /// The engines of the regexes embedded in [SubPatterns](RegexElement::SubPattern) are ignored in favor of the one of the enclosing regex
+    /// 
+    /// # Exemples
+    /// ```rust
+    /// use crate::neoglot_lib::regex::{Engine, Quantifier, Regex, RegexElement};
+    /// 
+    /// // (a*)*b fails in exponential time by backtracking
+    /// let regex = Regex::new()
+    ///     .then(RegexElement::Group(vec![RegexElement::Item('a', Quantifier::ZeroOrMany)], Quantifier::ZeroOrMany))
+    ///     .then(RegexElement::Item('b', Quantifier::Exactly(1)))
+    ///     .with_engine(Engine::Derivatives);
+    /// 
+    /// assert!(!regex.r#match(&['a'; 10_000]));
+    /// assert!(regex.match_iter("aaab".chars()));
+    /// ```
+    pub fn with_engine(mut self, engine:Engine) -> Self{
+        self.engine = engine;
+        self
+    }
+
+    /// How the pattern is matched, see [with_engine](Regex::with_engine)
+    pub fn engine(&self) -> Engine{ self.engine }
+
+    // Matches the pattern against the symbols of *input* from *start* up to *end*,
+    // returns if it is valid and the number of Symbols read
+    // 
+    // The assertions see the whole input
+    fn match_start(&self, input:&[T], start:usize, end:usize) -> Result<(bool, usize), Limit>{
+        self.match_start_capturing(input, start, end, &mut vec![]).map(|(valid, ind, _)| (valid, ind))
+    }
+
+    // Matches like match_start, recording what the capture elements match,
+    // also returns the branch whose result is given
+    fn match_start_capturing<'p, 'a>(&'p self, input:&'a [T], start:usize, end:usize, captures:&mut Recorded<'p, 'a, T>) -> Result<(bool, usize, &'p [RegexElement<T>]), Limit>{
+        let candidate = &input[start..end];
+
+        for branch in self.branches(){ self.limits.check_depth(pattern_depth(branch))?; }
+        self.limits.check_tokens(candidate.len())?;
+
+        let cx = Context::new(&self.limits, input);
+
+        let attempt = |branch:&'p [RegexElement<T>]| -> Result<(bool, usize, Recorded<'p, 'a, T>), Limit> {
+            let mut recorded = vec![];
+            let (valid, ind) = match_sequence(Some(candidate), start, branch, false, &cx, &mut recorded)?;
+
+            // An empty pattern matches nothing
+            Ok((valid && !branch.is_empty(), ind, recorded))
+        };
+
+        let mut branch = self.pattern.as_slice();
+        let mut best = attempt(branch)?;
+
+        for alternative in &self.alternatives{
+            let other = attempt(alternative)?;
+
+            if other.0 && (!best.0 || other.1 > best.1) {
+                best = other;
+                branch = alternative;
+            }
+        }
+
+        let (valid, ind, recorded) = best;
+        captures.extend(recorded);
+
+        Ok((valid, ind, branch))
+    }
+
+    /// Verifies if a set of [Symbols](Symbol) match the pattern of this regex
+    pub fn r#match(&self, candidate:&[T]) -> bool{
+        self.try_match(candidate).unwrap_or(false)
+    }
+
+    /// Verifies if a set of [Symbols](Symbol) match the pattern of this regex,
+    /// failing if a [limit](Regex::with_limits) is exceeded
+    pub fn try_match(&self, candidate:&[T]) -> Result<bool, Limit>{
+        for branch in self.branches(){ self.limits.check_depth(pattern_depth(branch))?; }
+        self.limits.check_tokens(candidate.len())?;
+
+        if self.engine == Engine::Derivatives {
+            if let Some(mut derivatives) = Derivatives::new(self) {
+                return Ok(derivatives.accepts(candidate.iter().cloned()));
+            }
+        }
+
+        let cx = Context::new(&self.limits, candidate);
+
+        // Each branch is anchored to the end of the input, so that its elements give back or take more symbols until they reach it,
+        // lazy elements included. An empty branch matches nothing
+        for branch in self.branches().filter(|branch| !branch.is_empty()){
+            if match_sequence(Some(candidate), 0, branch, true, &cx, &mut vec![])?.0 { return Ok(true); }
+        }
+
+        Ok(false)
+    }
+
+    /// [Matches](Regex::match) each candidate, giving the results in the order of the candidates
+    /// 
+    /// With the `parallel` feature the candidates are spread over all cores, so that thousands of literals,
+    /// as the identifiers of a project, are checked without writing the parallel loop. Otherwise they are matched one after the other
+    /// 
+    /// # Exemples
+    /// ```rust
+    /// use crate::neoglot_lib::regex::{Quantifier, Regex, RegexElement};
+    /// 
+    /// let identifier = Regex::<char>::new()
+    ///     .then(RegexElement::Set('a', 'z', Quantifier::Exactly(1)))
+    ///     .then(RegexElement::Set('a', 'z', Quantifier::ZeroOrMany));
+    /// 
+    /// let names = ["count", "x2", "total"].map(|name| name.chars().collect::<Vec<char>>());
+    /// let candidates = names.iter().map(Vec::as_slice).collect::<Vec<&[char]>>();
+    /// 
+    /// assert_eq!(identifier.match_many(&candidates), vec![true, false, true]);
+    /// ```
+    pub fn match_many(&self, candidates:&[&[T]]) -> Vec<bool> where T:Sync{
+        #[cfg(feature = "parallel")]
+        {
+            use rayon::prelude::*;
+            candidates.par_iter().map(|candidate| self.r#match(candidate)).collect()
+        }
+
+        #[cfg(not(feature = "parallel"))]
+        candidates.iter().map(|candidate| self.r#match(candidate)).collect()
+    }
+
+    /// Verifies if the [symbols](Symbol) of an iterator match the pattern, reading them one at a time by [derivatives](Engine::Derivatives)
+    /// whatever the [engine](Regex::with_engine), so that streamed input is matched without being kept
+    /// 
+    /// The reading stops as soon as nothing can be matched anymore.
+    /// The patterns with assertions need the whole input: the symbols are collected and [matched](Regex::match) as usual
+    pub fn match_iter(&self, candidate:impl IntoIterator<Item = T>) -> bool{
+        match Derivatives::new(self) {
+            Some(mut derivatives) => derivatives.accepts(candidate),
+            None => self.r#match(&candidate.into_iter().collect::<Vec<T>>())
+        }
+    }
+
+
+    /// Splits a set of [symbols](Symbol) into two:
+    /// the first matched [symbols](Symbol)
+    /// and the rest
+    pub fn split_first<'a>(&self, candidate: &'a[T]) -> (&'a [T], &'a [T]){
+        self.try_split_first(candidate).unwrap_or((&[], candidate))
+    }
+
+    /// Splits a set of [symbols](Symbol) like [split_first](Regex::split_first),
+    /// failing if a [limit](Regex::with_limits) is exceeded
+    pub fn try_split_first<'a>(&self, candidate: &'a[T]) -> Result<(&'a [T], &'a [T]), Limit>{
+        self.try_split_at_match(candidate, 0)
+    }
+
+    /// Splits the [symbols](Symbol) from *start* like [split_first](Regex::split_first):
+    /// the ones matched from *start* and the rest
+    /// 
+    /// Nothing is matched if *start* is past the end of the candidate
+    pub fn split_at_match<'a>(&self, candidate: &'a[T], start:usize) -> (&'a [T], &'a [T]){
+        let rest = candidate.get(start..).unwrap_or(&[]);
+        self.try_split_at_match(candidate, start).unwrap_or((&[], rest))
+    }
+
+    /// Splits the [symbols](Symbol) from *start* like [split_at_match](Regex::split_at_match),
+    /// failing if a [limit](Regex::with_limits) is exceeded
+    pub fn try_split_at_match<'a>(&self, input: &'a[T], start:usize) -> Result<(&'a [T], &'a [T]), Limit>{
+        let Some(candidate) = input.get(start..) else { return Ok((&[], &[])); };
+        let (valid, ind) = self.match_start(input, start, input.len())?;
+
+        if !valid { return Ok((&[], candidate)); }
+
+        Ok(candidate.split_at(ind))
+    }
+
+    // Splits like split_first, reading at most *length* symbols while the assertions see the whole candidate
+    pub(crate) fn split_first_within<'a>(&self, candidate:&'a [T], length:usize) -> Result<(&'a [T], &'a [T]), Limit>{
+        let (valid, ind) = self.match_start(candidate, 0, candidate.len().min(length))?;
+
+        if !valid { return Ok((&[], candidate)); }
+
+        Ok(candidate.split_at(ind))
+    }
+
+    /// Returns the length of the longest prefix of *candidate* matched by the pattern, and true,
+    /// or if the pattern matches no prefix, the length of the longest prefix matched by the start of a branch, and false
+    /// 
+    /// Every way of matching the elements is tried, as with the standard regex semantics,
+    /// so the longest prefix is found even where [split_first](Regex::split_first) keeps the first valid alternative of an [AnyOf](RegexElement::AnyOf)
+    /// or where quantified groups would need to give symbols back. This is the maximal munch of lexers.
+    /// Exceeding a [limit](Regex::with_limits) gives `(0, false)`
+    /// 
+    /// # Exemples
+    /// ```rust
+    /// use crate::neoglot_lib::regex::{Quantifier, Regex, RegexElement};
+    /// 
+    /// let once = Quantifier::Exactly(1);
+    /// let regex = Regex::new().then(RegexElement::AnyOf(vec![
+    ///     RegexElement::Item('a', once),
+    ///     RegexElement::Group(vec![RegexElement::Item('a', once), RegexElement::Item('b', once)], once)
+    /// ], Quantifier::OneOrMany));
+    /// 
+    /// // The first alternative is taken for each occurence
+    /// assert_eq!(regex.split_first(&['a', 'b', 'a', 'b']).0, &['a']);
+    /// assert_eq!(regex.longest_prefix(&['a', 'b', 'a', 'b']), (4, true));
+    /// 
+    /// let keyword = Regex::new().then(RegexElement::Item('i', once)).then(RegexElement::Item('f', once));
+    /// assert_eq!(keyword.longest_prefix(&['i', 'n']), (1, false));
+    /// ```
+    pub fn longest_prefix(&self, candidate:&[T]) -> (usize, bool){
+        self.try_longest_prefix(candidate).unwrap_or((0, false))
+    }
+
+    /// Returns the longest prefix like [longest_prefix](Regex::longest_prefix),
+    /// failing if a [limit](Regex::with_limits) is exceeded
+    pub fn try_longest_prefix(&self, candidate:&[T]) -> Result<(usize, bool), Limit>{
+        self.longest_prefix_within(candidate, candidate.len())
+    }
+
+    // Returns the longest prefix like try_longest_prefix, reading at most *length* symbols while the assertions see the whole candidate
+    pub(crate) fn longest_prefix_within(&self, candidate:&[T], length:usize) -> Result<(usize, bool), Limit>{
+        for branch in self.branches(){ self.limits.check_depth(pattern_depth(branch))?; }
+        self.limits.check_tokens(candidate.len())?;
+
+        let cx = Context::new(&self.limits, candidate);
+        let bounded = &candidate[..candidate.len().min(length)];
+        let mut full:Option<usize> = None;
+        let mut partial = 0;
+
+        // An empty branch matches nothing
+        for branch in self.branches().filter(|branch| !branch.is_empty()){
+            let mut ends = BTreeSet::from([0]);
+            let mut completed = true;
+
+            for e in branch{
+                let mut next = BTreeSet::new();
+                for start in &ends{ next.extend(element_ends(bounded, *start, e, &cx)?); }
+
+                let Some(&furthest) = next.last() else {
+                    completed = false;
+                    break;
+                };
+
+                partial = partial.max(furthest);
+                ends = next;
+            }
+
+            if let (true, Some(&end)) = (completed, ends.last()){ full = Some(full.map_or(end, |full| full.max(end))); }
+        }
+
+        Ok(full.map_or((partial, false), |end| (end, true)))
+    }
+
+    /// Iterates over the occurences of the pattern in a set of [symbols](Symbol), from left to right
+    /// 
+    /// Each occurence is the match of [split_at_match](Regex::split_at_match) at the first position where something matches,
+    /// the search going on after it, so occurences don't overlap and are never empty.
+    /// The search stops if a [limit](Regex::with_limits) is exceeded
+    /// 
+    /// # Exemples
+    /// ```rust
+    /// use crate::neoglot_lib::regex::{Quantifier, Regex, RegexElement};
+    /// 
+    /// let number = Regex::<char>::new().then(RegexElement::Set('0', '9', Quantifier::OneOrMany));
+    /// let candidate = "x = 12 + 345".chars().collect::<Vec<char>>();
+    /// 
+    /// let found = number.find_all(&candidate).map(|m| (m.start, m.length)).collect::<Vec<(usize, usize)>>();
+    /// assert_eq!(found, vec![(4, 2), (9, 3)]);
+    /// ```
+    pub fn find_all<'r, 'a: 'r>(&'r self, candidate:&'a [T]) -> impl Iterator<Item = Match<'a, T>> + 'r{
+        let mut start = 0;
+
+        std::iter::from_fn(move || {
+            while start < candidate.len(){
+                let (symbols, _) = self.try_split_at_match(candidate, start).ok()?;
+
+                if symbols.is_empty() { start += 1; }
+                else{
+                    let found = Match { start, length: symbols.len(), symbols };
+                    start = found.end();
+                    return Some(found);
+                }
+            }
+
+            None
+        })
+    }
+
+    /// Splits a whole set of [symbols](Symbol) into the [occurences](Regex::find_all) of the pattern
+    /// and the residues between them, in order
+    /// 
+    /// Joining the chunks gives back the symbols, consecutive unmatched symbols make a single residue
+    /// 
+    /// # Exemples
+    /// ```rust
+    /// use crate::neoglot_lib::regex::{Chunk, Quantifier, Regex, RegexElement};
+    /// 
+    /// let word = Regex::<char>::new().then(RegexElement::Set('a', 'z', Quantifier::OneOrMany));
+    /// let candidate = "hi, you".chars().collect::<Vec<char>>();
+    /// 
+    /// assert_eq!(word.split_all(&candidate), vec![
+    ///     Chunk::Matched(&candidate[..2]),
+    ///     Chunk::Unmatched(&candidate[2..4]),
+    ///     Chunk::Matched(&candidate[4..])
+    /// ]);
+    /// ```
+    pub fn split_all<'a>(&self, candidate:&'a [T]) -> Vec<Chunk<'a, T>>{
+        let mut chunks = vec![];
+        let mut end = 0;
+
+        for found in self.find_all(candidate){
+            if found.start > end { chunks.push(Chunk::Unmatched(&candidate[end..found.start])); }
+
+            chunks.push(Chunk::Matched(found.symbols));
+            end = found.end();
+        }
+
+        if end < candidate.len() { chunks.push(Chunk::Unmatched(&candidate[end..])); }
+
+        chunks
+    }
+
+    /// Splits a set of [symbols](Symbol) like [split_first](Regex::split_first),
+    /// also giving where each element of the pattern ends in the match
+    /// 
+    /// When nothing matches, the [Boundaries] tell which element failed after the ones that matched,
+    /// taking the alternative that went the furthest if the regex has [several](Regex::or)
+    /// 
+    /// # Exemples
+    /// ```rust
+    /// use crate::neoglot_lib::regex::{Boundaries, Quantifier, Regex, RegexElement};
+    /// 
+    /// let float = Regex::<char>::new()
+    ///     .then(RegexElement::Set('0', '9', Quantifier::OneOrMany))
+    ///     .then(RegexElement::Item('.', Quantifier::Exactly(1)))
+    ///     .then(RegexElement::Set('0', '9', Quantifier::OneOrMany));
+    /// 
+    /// let (matched, _, boundaries) = float.split_first_with_boundaries(&['1', '2', '.', '5']);
+    /// assert_eq!(matched, &['1', '2', '.', '5']);
+    /// assert_eq!(boundaries, Boundaries{ ends: vec![2, 3, 4], failed: None });
+    /// 
+    /// // The digits after the dot are missing
+    /// let (matched, _, boundaries) = float.split_first_with_boundaries(&['1', '2', '.']);
+    /// assert!(matched.is_empty());
+    /// assert_eq!(boundaries, Boundaries{ ends: vec![2, 3], failed: Some(2) });
+    /// ```
+    pub fn split_first_with_boundaries<'a>(&self, candidate: &'a[T]) -> (&'a [T], &'a [T], Boundaries){
+        self.try_split_first_with_boundaries(candidate)
+            .unwrap_or((&[], candidate, Boundaries { ends: vec![], failed: Some(0) }))
+    }
+
+    // Splits like split_first_with_boundaries, failing if a limit is exceeded
+    fn try_split_first_with_boundaries<'a>(&self, candidate: &'a[T]) -> Result<(&'a [T], &'a [T], Boundaries), Limit>{
+        let (valid, ind, branch) = self.match_start_capturing(candidate, 0, candidate.len(), &mut vec![])?;
+        let cx = Context::new(&self.limits, candidate);
+
+        if valid { return Ok((&candidate[..ind], &candidate[ind..], branch_boundaries(branch, candidate, &cx)?)); }
+
+        let mut furthest = Boundaries { ends: vec![], failed: Some(0) };
+        for branch in self.branches(){
+            let boundaries = branch_boundaries(branch, candidate, &cx)?;
+            if boundaries.failed > furthest.failed { furthest = boundaries; }
+        }
+
+        Ok((&[], candidate, furthest))
+    }
+
+    /// Matches the start of a set of [symbols](Symbol) like [split_first](Regex::split_first),
+    /// returning what each [capture](RegexElement::Capture) matched, or None if the pattern doesn't match
+    /// 
+    /// # Exemples
+    /// ```rust
+    /// use crate::neoglot_lib::regex::{Quantifier, Regex, RegexElement};
+    /// 
+    /// let regex = Regex::<char>::new()
+    ///     .then(RegexElement::Capture("digits".to_string(), Box::new(RegexElement::Set('0', '9', Quantifier::OneOrMany))))
+    ///     .then(RegexElement::Capture("suffix".to_string(), Box::new(RegexElement::Set('a', 'z', Quantifier::ZeroOrMany))));
+    /// 
+    /// let candidate = "42u8".chars().collect::<Vec<char>>();
+    /// let captures = regex.captures(&candidate).unwrap();
+    /// 
+    /// assert_eq!(captures.matched(), &['4', '2', 'u']);
+    /// assert_eq!(captures.get(0), Some(&['4', '2'][..]));
+    /// assert_eq!(captures.name("suffix"), Some(&['u'][..]));
+    /// ```
+    pub fn captures<'a>(&self, candidate:&'a [T]) -> Option<Captures<'a, T>>{
+        let mut recorded = vec![];
+        let (valid, ind, branch) = self.match_start_capturing(candidate, 0, candidate.len(), &mut recorded).ok()?;
+
+        if !valid { return None; }
+
+        // Every capture element in pattern order, with its last match
+        fn collect<'p, 'a, T:Symbol>(elements:&'p [RegexElement<T>], recorded:&Recorded<'p, 'a, T>, groups:&mut Vec<(String, Option<&'a [T]>)>){
+            for e in elements{
+                match e {
+                    RegexElement::Capture(name, element) => {
+                        let matched = recorded.iter().rev().find(|(capture, _)| std::ptr::eq(*capture, e)).map(|(_, matched)| *matched);
+                        groups.push((name.clone(), matched));
+                        collect(std::slice::from_ref(element.as_ref()), recorded, groups);
+                    },
+                    RegexElement::Group(elements, _) | RegexElement::AnyOf(elements, _) => collect(elements, recorded, groups),
+                    RegexElement::Named(_, element) => collect(std::slice::from_ref(element.as_ref()), recorded, groups),
+                    RegexElement::SubPattern(regex, _) => for branch in regex.branches(){ collect(branch, recorded, groups); },
+                    _ => {}
+                }
+            }
+        }
+
+        let mut groups = vec![];
+        collect(branch, &recorded, &mut groups);
+
+        Some(Captures { matched: &candidate[..ind], groups })
+    }
+    
+}
+
+/// Builds a [Regex] from a list of elements, as the chain of [then](Regex::then) calls it expands to
+/// 
+/// The elements are separated by commas, each followed by an optional [quantifier](Quantifier)
+/// among `?`, `+`, `*`, `??`, `+?`, `*?`, `{n}`, `{min,}` and `{min, max}`, without one it occurs exactly once:
+/// - `item(symbol)` is an [Item](RegexElement::Item)
+/// - `set(low..=high)` is a [Set](RegexElement::Set)
+/// - `class[symbol, low..=high, ...]` is a [Class](RegexElement::Class)
+/// - `group[...]`, `none_of[...]` and `any_of[...]` are [Group](RegexElement::Group), [NoneOf](RegexElement::NoneOf)
+///   and [AnyOf](RegexElement::AnyOf) of the elements listed the same way
+/// - `capture(name, element)` is a [Capture](RegexElement::Capture) of a single element
+/// - `named(name, element)` is a single element [Named](RegexElement::Named) for diagnostics
+/// - `predicate(test)` is a [Predicate](RegexElement::Predicate)
+/// - `pattern(regex)` is a [SubPattern](RegexElement::SubPattern)
+/// - `start()`, `end()` and `boundary(test)` are the assertions [StartOfInput](RegexElement::StartOfInput),
+///   [EndOfInput](RegexElement::EndOfInput) and [Boundary](RegexElement::Boundary), without quantifier
+/// - `ahead(element)`, `not_ahead(element)`, `behind(element)` and `not_behind(element)` are positive and negative
+///   [Lookaheads](RegexElement::Lookahead) and [Lookbehinds](RegexElement::Lookbehind) of a single element, without quantifier
+/// - `{element}` is any [RegexElement] built by hand
+/// 
+/// The symbols can be of any [Symbol] type
+/// 
+/// # Exemples
+/// ```rust
+/// use crate::neoglot_lib::{regex, regex::*};
+/// 
+/// let number = regex![item('-')?, set('0'..='9')+, group[item('.'), set('0'..='9')+]?];
+/// 
+/// assert_eq!(number, Regex::new()
+///     .then(RegexElement::Item('-', Quantifier::ZeroOrOne))
+///     .then(RegexElement::Set('0', '9', Quantifier::OneOrMany))
+///     .then(RegexElement::Group(vec![
+///         RegexElement::Item('.', Quantifier::Exactly(1)),
+///         RegexElement::Set('0', '9', Quantifier::OneOrMany)
+///     ], Quantifier::ZeroOrOne)));
+/// 
+/// assert!(number.r#match(&['-', '1', '.', '5']));
+/// ```
+#[macro_export]
+macro_rules! regex {
+    // Reads the elements one by one up to the commas, each with a quantifier of up to two tokens,
+    // then hands them to *out*
+    (@split $out:ident [$($done:expr),*] {$raw:expr} $(, $($rest:tt)*)?) => {
+        $crate::regex!(@split $out [$($done,)* $raw] $($($rest)*)?)
+    };
+    (@split $out:ident [$($done:expr),*] $kind:ident $args:tt $(, $($rest:tt)*)?) => {
+        $crate::regex!(@split $out [$($done,)* $crate::regex!(@element $kind $args)] $($($rest)*)?)
+    };
+    (@split $out:ident [$($done:expr),*] $kind:ident $args:tt $qt:tt $(, $($rest:tt)*)?) => {
+        $crate::regex!(@split $out [$($done,)* $crate::regex!(@element $kind $args $qt)] $($($rest)*)?)
+    };
+    (@split $out:ident [$($done:expr),*] $kind:ident $args:tt $qt:tt $lazy:tt $(, $($rest:tt)*)?) => {
+        $crate::regex!(@split $out [$($done,)* $crate::regex!(@element $kind $args $qt $lazy)] $($($rest)*)?)
+    };
+    (@split chain [$($done:expr),*]) => { $crate::regex::Regex::new()$(.then($done))* };
+    (@split vec [$($done:expr),*]) => { vec![$($done),*] };
+
+    (@element item ($value:expr) $($qt:tt)*) => {
+        $crate::regex::RegexElement::Item($value, $crate::regex!(@quantifier $($qt)*))
+    };
+    (@element set ($range:expr) $($qt:tt)*) => {{
+        let (low, high) = ($range).into_inner();
+        $crate::regex::RegexElement::Set(low, high, $crate::regex!(@quantifier $($qt)*))
+    }};
+    (@element class [$($item:expr),* $(,)?] $($qt:tt)*) => {
+        $crate::regex::RegexElement::Class(vec![$($crate::regex::ClassItem::from($item)),*], $crate::regex!(@quantifier $($qt)*))
+    };
+    (@element group [$($elements:tt)*] $($qt:tt)*) => {
+        $crate::regex::RegexElement::Group($crate::regex!(@split vec [] $($elements)*), $crate::regex!(@quantifier $($qt)*))
+    };
+    (@element none_of [$($elements:tt)*] $($qt:tt)*) => {
+        $crate::regex::RegexElement::NoneOf($crate::regex!(@split vec [] $($elements)*), $crate::regex!(@quantifier $($qt)*))
+    };
+    (@element any_of [$($elements:tt)*] $($qt:tt)*) => {
+        $crate::regex::RegexElement::AnyOf($crate::regex!(@split vec [] $($elements)*), $crate::regex!(@quantifier $($qt)*))
+    };
+    (@element capture ($name:expr, $($element:tt)+)) => {
+        $crate::regex::RegexElement::Capture(String::from($name), Box::new($crate::regex!(@split vec [] $($element)+).remove(0)))
+    };
+    (@element named ($name:expr, $($element:tt)+)) => {
+        $crate::regex::RegexElement::Named(String::from($name), Box::new($crate::regex!(@split vec [] $($element)+).remove(0)))
+    };
+    (@element predicate ($test:expr) $($qt:tt)*) => {
+        $crate::regex::RegexElement::Predicate($crate::regex::SymbolPredicate::new($test), $crate::regex!(@quantifier $($qt)*))
+    };
+    (@element start ()) => { $crate::regex::RegexElement::StartOfInput };
+    (@element end ()) => { $crate::regex::RegexElement::EndOfInput };
+    (@element boundary ($test:expr)) => {
+        $crate::regex::RegexElement::Boundary($crate::regex::SymbolPredicate::new($test))
+    };
+    (@element ahead ($($element:tt)+)) => {
+        $crate::regex::RegexElement::Lookahead(Box::new($crate::regex!(@split vec [] $($element)+).remove(0)), true)
+    };
+    (@element not_ahead ($($element:tt)+)) => {
+        $crate::regex::RegexElement::Lookahead(Box::new($crate::regex!(@split vec [] $($element)+).remove(0)), false)
+    };
+    (@element behind ($($element:tt)+)) => {
+        $crate::regex::RegexElement::Lookbehind(Box::new($crate::regex!(@split vec [] $($element)+).remove(0)), true)
+    };
+    (@element not_behind ($($element:tt)+)) => {
+        $crate::regex::RegexElement::Lookbehind(Box::new($crate::regex!(@split vec [] $($element)+).remove(0)), false)
+    };
+    (@element pattern ($regex:expr) $($qt:tt)*) => {
+        $crate::regex::RegexElement::SubPattern($regex, $crate::regex!(@quantifier $($qt)*))
+    };
+
+    (@quantifier) => { $crate::regex::Quantifier::Exactly(1) };
+    (@quantifier ?) => { $crate::regex::Quantifier::ZeroOrOne };
+    (@quantifier +) => { $crate::regex::Quantifier::OneOrMany };
+    (@quantifier *) => { $crate::regex::Quantifier::ZeroOrMany };
+    (@quantifier ? ?) => { $crate::regex::Quantifier::ZeroOrOneLazy };
+    (@quantifier + ?) => { $crate::regex::Quantifier::OneOrManyLazy };
+    (@quantifier * ?) => { $crate::regex::Quantifier::ZeroOrManyLazy };
+    (@quantifier {$n:expr}) => { $crate::regex::Quantifier::Exactly($n) };
+    (@quantifier {$min:expr,}) => { $crate::regex::Quantifier::AtLeast($min) };
+    (@quantifier {$min:expr, $max:expr}) => { $crate::regex::Quantifier::Between($min, $max) };
+
+    ($($elements:tt)*) => { $crate::regex!(@split chain [] $($elements)*) };
+}
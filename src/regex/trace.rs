@@ -0,0 +1,161 @@
+use std::fmt::Display;
+
+use crate::limits::Limit;
+
+use super::{element_ends, pattern_depth, sequence_ends, Context, Regex, RegexElement, Symbol};
+
+#[derive(Debug, Clone, PartialEq)]
+/// An element attempted while [tracing](Regex::trace) a match
+pub struct TraceStep<T:Symbol>{
+    /// The index of the branch the element is in, 0 for the pattern and then one per [alternative](Regex::or)
+    pub branch: usize,
+
+    /// The element attempted
+    pub element: RegexElement<T>,
+
+    /// The position in the candidate where it was attempted
+    pub start: usize,
+
+    /// The number of symbols it consumed, or None if it couldn't match there
+    pub consumed: Option<usize>
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// How a [traced](Regex::trace) match ended
+pub enum TraceOutcome{
+    /// A branch matched the whole candidate
+    Matched,
+
+    /// No branch matched, the furthest one stopping at the given position of the candidate
+    Failed(usize),
+
+    /// A [limit](crate::limits::Limits) of the regex has been exceeded
+    Stopped(Limit)
+}
+
+#[derive(Debug, Clone, PartialEq)]
+/// The log of a match, built by [Regex::trace]
+/// 
+/// Displays as one line per step followed by the outcome, the [named](RegexElement::name) elements being shown by their name
+pub struct MatchTrace<T:Symbol>{
+    /// The elements attempted, branch after branch
+    pub steps: Vec<TraceStep<T>>,
+
+    pub outcome: TraceOutcome
+}
+
+impl<T:Symbol> MatchTrace<T>{
+    /// Returns the step that failed in the furthest branch, if an element failed
+    pub fn failure(&self) -> Option<&TraceStep<T>>{
+        match self.outcome {
+            TraceOutcome::Failed(position) => self.steps.iter().rev().find(|step| step.consumed.is_none() && step.start == position),
+            _ => None
+        }
+    }
+
+    /// Describes what was expected where the match failed, see [RegexElement::describe]
+    pub fn expected(&self) -> Option<String>{ self.failure().map(|step| step.element.describe()) }
+}
+
+impl<T:Symbol> Display for MatchTrace<T>{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut branch = None;
+
+        for step in &self.steps{
+            if branch != Some(step.branch){
+                writeln!(f, "branch {}", step.branch)?;
+                branch = Some(step.branch);
+            }
+
+            match step.consumed {
+                Some(consumed) => writeln!(f, "  {} at {}: consumed {consumed}", step.element.describe(), step.start)?,
+                None => writeln!(f, "  {} at {}: no match", step.element.describe(), step.start)?
+            }
+        }
+
+        match self.outcome {
+            TraceOutcome::Matched => write!(f, "matched"),
+            TraceOutcome::Failed(position) => write!(f, "failed at {position}"),
+            TraceOutcome::Stopped(limit) => write!(f, "stopped: {limit}")
+        }
+    }
+}
+
+impl<T:Symbol> Regex<T>{
+    /// Matches the whole candidate like [match](Regex::match), logging each element attempted and the symbols it consumed
+    /// 
+    /// Each element takes the most symbols that still let the rest of its branch match the candidate,
+    /// or the most it can take if there's none, so that the trace goes as far as possible before the point of failure.
+    /// The branches are attempted in order until one matches
+    /// 
+    /// The steps follow the standard regex semantics, so on patterns where a quantified group would need
+    /// to give back symbols the trace may match where the engine doesn't
+    /// 
+    /// # Exemples
+    /// ```rust
+    /// use crate::neoglot_lib::regex::{trace::TraceOutcome, Quantifier, Regex, RegexElement};
+    /// 
+    /// let regex = Regex::new()
+    ///     .then(RegexElement::Item('-', Quantifier::ZeroOrOne))
+    ///     .then(RegexElement::Set('0', '9', Quantifier::OneOrMany));
+    /// 
+    /// let trace = regex.trace(&['-', 'x']);
+    /// 
+    /// assert_eq!(trace.outcome, TraceOutcome::Failed(1));
+    /// assert_eq!(trace.failure().map(|step| &step.element), Some(&RegexElement::Set('0', '9', Quantifier::OneOrMany)));
+    /// assert_eq!(trace.to_string(), "branch 0\n  Item('-', ZeroOrOne) at 0: consumed 1\n  Set('0', '9', OneOrMany) at 1: no match\nfailed at 1");
+    /// ```
+    pub fn trace(&self, candidate:&[T]) -> MatchTrace<T>{
+        let mut trace = MatchTrace { steps: vec![], outcome: TraceOutcome::Failed(0) };
+
+        if let Err(limit) = self.trace_branches(candidate, &mut trace){ trace.outcome = TraceOutcome::Stopped(limit); }
+        trace
+    }
+
+    fn trace_branches(&self, candidate:&[T], trace:&mut MatchTrace<T>) -> Result<(), Limit>{
+        for branch in self.branches(){ self.limits.check_depth(pattern_depth(branch))?; }
+        self.limits.check_tokens(candidate.len())?;
+
+        let cx = Context::new(&self.limits, candidate);
+        let mut furthest = 0;
+
+        // An empty branch matches nothing
+        for (index, branch) in self.branches().enumerate().filter(|(_, branch)| !branch.is_empty()){
+            let mut position = 0;
+            let mut failed = false;
+
+            for (i, element) in branch.iter().enumerate(){
+                let ends = element_ends(candidate, position, element, &cx)?;
+
+                let mut viable = None;
+                for end in ends.iter().rev(){
+                    if sequence_ends(candidate, *end, &branch[i + 1..], &cx)?.contains(&candidate.len()){
+                        viable = Some(*end);
+                        break;
+                    }
+                }
+
+                let end = viable.or_else(|| ends.last().copied());
+                trace.steps.push(TraceStep { branch: index, element: element.clone(), start: position, consumed: end.map(|end| end - position) });
+
+                match end {
+                    Some(end) => position = end,
+                    None => {
+                        failed = true;
+                        break;
+                    }
+                }
+            }
+
+            if !failed && position == candidate.len(){
+                trace.outcome = TraceOutcome::Matched;
+                return Ok(());
+            }
+
+            furthest = furthest.max(position);
+        }
+
+        trace.outcome = TraceOutcome::Failed(furthest);
+        Ok(())
+    }
+}
@@ -0,0 +1,236 @@
+use std::{collections::HashMap, error::Error, fmt::Display, fs, io};
+
+use crate::{lexer::Location, Reportable};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+/// A kind of line break
+pub enum LineEnding{
+    /// `\n`, as on Unix
+    Lf,
+
+    /// `\r\n`, as on Windows
+    CrLf,
+
+    /// A lone `\r`, as on classic Mac OS
+    Cr
+}
+
+impl Display for LineEnding{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            LineEnding::Lf => "LF",
+            LineEnding::CrLf => "CRLF",
+            LineEnding::Cr => "CR"
+        })
+    }
+}
+
+/// Iterates over the line breaks of a text, as their byte offset and kind
+/// 
+/// `\r\n` is a single line break
+pub fn line_breaks(content:&str) -> impl Iterator<Item = (usize, LineEnding)> + '_{
+    let bytes = content.as_bytes();
+
+    content.match_indices(['\n', '\r']).filter_map(move |(i, c)| match c {
+        "\n" if i > 0 && bytes[i-1] == b'\r' => None,
+        "\n" => Some((i, LineEnding::Lf)),
+        _ if bytes.get(i+1) == Some(&b'\n') => Some((i, LineEnding::CrLf)),
+        _ => Some((i, LineEnding::Cr))
+    })
+}
+
+/// Splits a text into lines ended by `\n`, `\r\n` or a lone `\r`
+/// 
+/// Like [str::lines] the line breaks are left out and a text ending with a line break has no last empty line
+pub fn lines(content:&str) -> impl Iterator<Item = &str>{
+    let mut start = 0;
+    let mut breaks = line_breaks(content);
+
+    std::iter::from_fn(move || {
+        if start >= content.len() { return None; }
+
+        let line = match breaks.next() {
+            Some((i, ending)) => {
+                let line = &content[start..i];
+                start = i + if ending == LineEnding::CrLf { 2 } else { 1 };
+                line
+            },
+            None => {
+                let line = &content[start..];
+                start = content.len();
+                line
+            }
+        };
+
+        Some(line)
+    })
+}
+
+#[derive(Debug, Clone, PartialEq)]
+/// A line ended differently than the first line of its file, see [Source::check_line_endings]
+pub struct MixedLineEndings{
+    /// The line ending of the first line
+    pub expected: LineEnding,
+
+    pub found: LineEnding,
+
+    /// Where the unexpected line break is
+    pub location: Location
+}
+
+impl Reportable for MixedLineEndings{
+    fn message(&self) -> String{
+        format!("Mixed line endings, {} found where {} was used before", self.found, self.expected)
+    }
+
+    fn location(&self) -> Option<Location>{ Some(self.location.clone()) }
+}
+
+impl Display for MixedLineEndings{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.report())
+    }
+}
+
+impl Error for MixedLineEndings{}
+
+#[derive(Debug, Clone, PartialEq)]
+/// The content of a source file along with the index of its lines
+/// 
+/// Offsets are counted in bytes, [columns](Location::column) in characters
+/// 
+/// Lines may be ended by `\n`, `\r\n` or a lone `\r`, even mixed in the same file
+/// 
+/// # Exemples
+/// ```rust
+/// use crate::neoglot_lib::{lexer::Location, source::Source};
+/// 
+/// let source = Source::new("main.txt", String::from("let a = 1\nlet b = a"));
+/// let location = Location{ file: String::from("main.txt"), line: 1, column: 4 };
+/// 
+/// assert_eq!(source.offset_to_location(14), Some(location.clone()));
+/// assert_eq!(source.location_to_offset(&location), Some(14));
+/// assert_eq!(source.line(1), Some("let b = a"));
+/// ```
+pub struct Source{
+    /// The path of the file
+    path: String,
+
+    /// The whole content of the file
+    content: String,
+
+    /// The offset at which each line starts
+    line_starts: Vec<usize>
+}
+
+impl Source{
+    pub fn new(path:&str, content:String) -> Self{
+        let line_starts = std::iter::once(0)
+            .chain(line_breaks(&content).map(|(i, ending)| if ending == LineEnding::CrLf { i+2 } else { i+1 }))
+            .collect();
+
+        Source { path: path.to_string(), content, line_starts }
+    }
+
+    /// Reads a source from a file
+    pub fn from_file(path:&str) -> io::Result<Self>{
+        Ok(Self::new(path, fs::read_to_string(path)?))
+    }
+
+    /// The path of the file
+    pub fn path(&self) -> &str{ &self.path }
+
+    /// The whole content of the file
+    pub fn content(&self) -> &str{ &self.content }
+
+    /// The number of lines in the file
+    pub fn line_count(&self) -> usize{ self.line_starts.len() }
+
+    /// The line ending of the first line, or None if there is only one line
+    pub fn line_ending(&self) -> Option<LineEnding>{
+        line_breaks(&self.content).next().map(|(_, ending)| ending)
+    }
+
+    /// Finds the first line ended differently than the first line, if any
+    /// 
+    /// Mixed line endings are handled, this is only a warning for the files a tool would rather have consistent
+    pub fn check_line_endings(&self) -> Option<MixedLineEndings>{
+        let mut breaks = line_breaks(&self.content);
+        let (_, expected) = breaks.next()?;
+        let (offset, found) = breaks.find(|(_, ending)| *ending != expected)?;
+
+        Some(MixedLineEndings { expected, found, location: self.offset_to_location(offset)? })
+    }
+
+    /// Returns the content of line *line*, without its line break, or None
+    pub fn line(&self, line:usize) -> Option<&str>{
+        let start = *self.line_starts.get(line)?;
+        let end = self.line_starts.get(line+1).map_or(self.content.len(), |next| next-1);
+
+        let text = &self.content[start..end];
+        Some(text.strip_suffix('\r').unwrap_or(text))
+    }
+
+    /// Converts a byte offset into a [Location], or None if it is out of the file
+    /// or not on a character boundary
+    pub fn offset_to_location(&self, offset:usize) -> Option<Location>{
+        if offset > self.content.len() || !self.content.is_char_boundary(offset){ return None; }
+
+        let line = self.line_starts.partition_point(|start| *start <= offset) - 1;
+        let column = self.content[self.line_starts[line]..offset].chars().count();
+
+        Some(Location { file: self.path.clone(), line, column })
+    }
+
+    /// Converts a [Location] into a byte offset, or None if it is out of the file or past the end of its line
+    /// 
+    /// The column right after the last character of a line is the offset of its line break
+    pub fn location_to_offset(&self, location:&Location) -> Option<usize>{
+        let start = *self.line_starts.get(location.line)?;
+        let line = self.line(location.line)?;
+
+        if location.column == line.chars().count(){ return Some(start + line.len()); }
+
+        line.char_indices().nth(location.column).map(|(i, _)| start + i)
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+/// Keeps track of every [source](Source) of a program
+pub struct SourceManager{
+    sources: HashMap<String, Source>
+}
+
+impl SourceManager{
+    pub fn new() -> Self{ SourceManager { sources: HashMap::new() } }
+
+    /// Adds a source, replacing any source with the same path
+    pub fn add(&mut self, source:Source) -> &Source{
+        let path = source.path.clone();
+        self.sources.insert(path.clone(), source);
+
+        &self.sources[&path]
+    }
+
+    /// Reads a file and adds it, unless it is already known
+    pub fn load(&mut self, path:&str) -> io::Result<&Source>{
+        if !self.sources.contains_key(path){
+            self.sources.insert(path.to_string(), Source::from_file(path)?);
+        }
+
+        Ok(&self.sources[path])
+    }
+
+    /// Returns the source at *path* or None
+    pub fn get(&self, path:&str) -> Option<&Source>{ self.sources.get(path) }
+
+    /// Converts a byte offset in the file at *path* into a [Location]
+    pub fn offset_to_location(&self, path:&str, offset:usize) -> Option<Location>{
+        self.get(path)?.offset_to_location(offset)
+    }
+
+    /// Converts a [Location] into a byte offset in its file
+    pub fn location_to_offset(&self, location:&Location) -> Option<usize>{
+        self.get(&location.file)?.location_to_offset(location)
+    }
+}
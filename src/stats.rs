@@ -0,0 +1,149 @@
+use std::{cmp::Ordering, collections::{BTreeMap, HashMap}, fmt::Display};
+
+use crate::{lexer::{Token, TokenKind}, parser::ParserNode};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// How often each token kind follows each other in a corpus of lexed files, and which [parser nodes](ParserNode) consumed them
+/// 
+/// The most frequent kinds are the ones whose lexer nodes are worth trying first,
+/// and the kinds following a kind make the lookahead sets of the rules starting with it
+/// 
+/// # Exemples
+/// ```rust
+/// use crate::neoglot_lib::{lexer::*, parser::*, regex::*, stats::TransitionStats};
+/// 
+/// #[derive(PartialEq, PartialOrd, Eq, Hash, Debug, Copy, Clone)]
+/// enum Kind{ Let, Name, Equal, Number }
+/// 
+/// impl Symbol for Kind{}
+/// impl TokenKind for Kind{}
+/// 
+/// let nodes = vec![Box::new(ParserNode{
+///     regex: Regex::new()
+///         .then(RegexElement::Item(Kind::Let, Quantifier::Exactly(1)))
+///         .then(RegexElement::Item(Kind::Name, Quantifier::Exactly(1)))
+///         .then(RegexElement::Item(Kind::Equal, Quantifier::Exactly(1)))
+///         .then(RegexElement::AnyOf(vec![RegexElement::Item(Kind::Name, Quantifier::Exactly(1)), RegexElement::Item(Kind::Number, Quantifier::Exactly(1))], Quantifier::Exactly(1))),
+///     parser: Box::new(|_| Ok(AST{ kind: Kind::Let, children: vec![] }))
+/// })];
+/// 
+/// let tokens = [Kind::Let, Kind::Name, Kind::Equal, Kind::Number, Kind::Let, Kind::Name, Kind::Equal, Kind::Name]
+///     .map(|kind| Token::new(Location::generated("doc", 0), kind, String::new()));
+/// 
+/// let mut stats = TransitionStats::new();
+/// stats.add_parsed(&tokens, &nodes);
+/// 
+/// assert_eq!(stats.kinds()[0], (Kind::Name, 3));
+/// assert_eq!(stats.followers(Kind::Equal), vec![(Kind::Name, 1), (Kind::Number, 1)]);
+/// assert_eq!(stats.rules(Kind::Let, Kind::Name), vec![(0, 2)]);
+/// assert_eq!(stats.rules(Kind::Number, Kind::Let), vec![]);
+/// ```
+pub struct TransitionStats<T:TokenKind>{
+    /// The number of files added
+    files: usize,
+
+    /// The number of tokens of each kind
+    kinds: HashMap<T, usize>,
+
+    /// The number of times each kind is followed by each other
+    bigrams: HashMap<(T, T), usize>,
+
+    /// The number of times each pair of kinds was consumed by each parser node, by index of the node
+    rules: HashMap<(T, T), BTreeMap<usize, usize>>
+}
+
+impl<T:TokenKind> Default for TransitionStats<T>{
+    fn default() -> Self { Self::new() }
+}
+
+// Sorts counted items the most frequent first, then in item order
+fn by_frequency<I:PartialOrd>(counts:impl IntoIterator<Item = (I, usize)>) -> Vec<(I, usize)>{
+    let mut counts = counts.into_iter().collect::<Vec<(I, usize)>>();
+    counts.sort_by(|(a, m), (b, n)| n.cmp(m).then(a.partial_cmp(b).unwrap_or(Ordering::Equal)));
+    counts
+}
+
+impl<T:TokenKind> TransitionStats<T>{
+    pub fn new() -> Self{ TransitionStats { files: 0, kinds: HashMap::new(), bigrams: HashMap::new(), rules: HashMap::new() } }
+
+    /// Counts the kinds of the *tokens* of a file and the pairs of kinds following each other
+    pub fn add(&mut self, tokens:&[Token<T>]){
+        self.files += 1;
+
+        for token in tokens{ *self.kinds.entry(token.kind).or_default() += 1; }
+        for pair in tokens.windows(2){ *self.bigrams.entry((pair[0].kind, pair[1].kind)).or_default() += 1; }
+    }
+
+    /// [Counts](TransitionStats::add) the *tokens* of a file, and for each pair of kinds inside the tokens consumed by a node of *nodes*, that node
+    /// 
+    /// The nodes consume the tokens as in [parse_with_node](crate::parser::Parser::parse_with_node), the first node matching some tokens taking them.
+    /// The tokens no node matches are skipped one at a time
+    pub fn add_parsed(&mut self, tokens:&[Token<T>], nodes:&[Box<ParserNode<T>>]){
+        self.add(tokens);
+
+        let mut rest = tokens;
+        while !rest.is_empty(){
+            let consumed = nodes.iter().enumerate().find_map(|(i, node)| {
+                let (matched, remainder) = node.split(rest);
+                (!matched.is_empty()).then_some((i, matched, remainder))
+            });
+
+            let Some((node, matched, remainder)) = consumed else {
+                rest = &rest[1..];
+                continue;
+            };
+
+            for pair in matched.windows(2){
+                *self.rules.entry((pair[0].kind, pair[1].kind)).or_default().entry(node).or_default() += 1;
+            }
+
+            rest = remainder;
+        }
+    }
+
+    /// The number of files added
+    pub fn files(&self) -> usize{ self.files }
+
+    /// The number of tokens of kind *kind*
+    pub fn kind_count(&self, kind:T) -> usize{ self.kinds.get(&kind).copied().unwrap_or(0) }
+
+    /// The number of times a token of kind *first* is followed by one of kind *second*
+    pub fn count(&self, first:T, second:T) -> usize{ self.bigrams.get(&(first, second)).copied().unwrap_or(0) }
+
+    /// The kinds met with their number of tokens, the most frequent first
+    pub fn kinds(&self) -> Vec<(T, usize)>{ by_frequency(self.kinds.iter().map(|(kind, count)| (*kind, *count))) }
+
+    /// The pairs of kinds following each other with their number of occurences, the most frequent first
+    pub fn bigrams(&self) -> Vec<((T, T), usize)>{ by_frequency(self.bigrams.iter().map(|(pair, count)| (*pair, *count))) }
+
+    /// The kinds following *kind* with the number of times they do, the most frequent first
+    pub fn followers(&self, kind:T) -> Vec<(T, usize)>{
+        by_frequency(self.bigrams.iter().filter(|((first, _), _)| *first == kind).map(|((_, second), count)| (*second, *count)))
+    }
+
+    /// The indices of the nodes that consumed *first* followed by *second*, with the number of times they did, the most frequent first
+    /// 
+    /// Only the files [added with nodes](TransitionStats::add_parsed) are counted
+    pub fn rules(&self, first:T, second:T) -> Vec<(usize, usize)>{
+        self.rules.get(&(first, second)).map_or_else(Vec::new, |rules| by_frequency(rules.iter().map(|(node, count)| (*node, *count))))
+    }
+}
+
+/// Formats one pair of kinds per line, the most frequent first, as `Let -> Name: 12 (node 0: 10, node 3: 2)`
+impl<T:TokenKind> Display for TransitionStats<T>{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for ((first, second), count) in self.bigrams(){
+            write!(f, "{first:?} -> {second:?}: {count}")?;
+
+            let rules = self.rules(first, second);
+            if !rules.is_empty(){
+                let rules = rules.iter().map(|(node, count)| format!("node {node}: {count}")).collect::<Vec<String>>();
+                write!(f, " ({})", rules.join(", "))?;
+            }
+
+            writeln!(f)?;
+        }
+
+        Ok(())
+    }
+}
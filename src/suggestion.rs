@@ -0,0 +1,55 @@
+/// Computes the Levenshtein distance between two strings
+/// 
+/// This is the number of character insertions, deletions and substitutions needed to turn *a* into *b*
+pub fn levenshtein(a:&str, b:&str) -> usize{
+    let b = b.chars().collect::<Vec<char>>();
+
+    // Distances between the current prefix of a and every prefix of b
+    let mut row = (0..=b.len()).collect::<Vec<usize>>();
+
+    for (i, ca) in a.chars().enumerate(){
+        let mut diagonal = row[0];
+        row[0] = i + 1;
+
+        for (j, cb) in b.iter().enumerate(){
+            let substitution = diagonal + usize::from(ca != *cb);
+            diagonal = row[j+1];
+            row[j+1] = substitution.min(row[j] + 1).min(diagonal + 1);
+        }
+    }
+
+    row[b.len()]
+}
+
+/// Returns the names of *known* close to *unknown*, closest first
+/// 
+/// A name is close if at most a third of its characters differ, and at least one can
+/// 
+/// # Exemples
+/// ```rust
+/// use crate::neoglot_lib::suggestion::suggest;
+/// 
+/// let known = ["length", "height", "width"];
+/// 
+/// assert_eq!(suggest("lenth", known), vec!["length"]);
+/// assert_eq!(suggest("depth", known), Vec::<&str>::new());
+/// ```
+pub fn suggest<'a>(unknown:&str, known:impl IntoIterator<Item = &'a str>) -> Vec<&'a str>{
+    let threshold = (unknown.chars().count() / 3).max(1);
+
+    let mut candidates = known.into_iter()
+        .filter(|name| *name != unknown)
+        .map(|name| (levenshtein(unknown, name), name))
+        .filter(|(distance, _)| *distance <= threshold)
+        .collect::<Vec<(usize, &str)>>();
+
+    candidates.sort();
+    candidates.dedup();
+
+    candidates.into_iter().map(|(_, name)| name).collect()
+}
+
+/// Builds a "did you mean `...`?" hint with the closest name of *known*, if any
+pub fn did_you_mean<'a>(unknown:&str, known:impl IntoIterator<Item = &'a str>) -> Option<String>{
+    suggest(unknown, known).first().map(|name| format!("did you mean `{name}`?"))
+}
@@ -0,0 +1,239 @@
+use std::{error::Error, fmt::Display, fs, io, path::Path};
+
+use crate::{fixes::Fix, lexer::Location, source::{self, Source}, Reportable};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// A diagnostic a test file expects, written in a comment as `//~ ERROR message`
+/// 
+/// Each `^` after the marker moves the expectation one line up, so `//~^ ERROR message`
+/// expects the diagnostic on the line above the comment
+pub struct Expectation{
+    /// The line the diagnostic is expected on
+    pub line: usize,
+
+    /// The first word after the marker, as `ERROR` or `WARNING`
+    pub kind: String,
+
+    /// A part of the expected message
+    pub message: String
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// A diagnostic emitted by a language on a test file
+pub struct Diagnostic{
+    /// The kind it is matched against, as `ERROR` or `WARNING`
+    pub kind: String,
+
+    pub message: String,
+
+    /// Where it was emitted, diagnostics without a location are never expected
+    pub location: Option<Location>,
+
+    /// The [warning category](crate::warnings::WarningCategories) it belongs to, as `unused`
+    pub category: Option<String>,
+
+    /// The [fix-its](Fix) suggested to solve it, see [apply_diagnostics](crate::fixes::apply_diagnostics)
+    pub fixes: Vec<Fix>
+}
+
+impl Diagnostic{
+    pub fn new(kind:&str, diagnostic:&impl Reportable) -> Self{
+        Diagnostic { kind: kind.to_string(), message: diagnostic.message(), location: diagnostic.location(), category: None, fixes: vec![] }
+    }
+
+    /// Puts the diagnostic in the warning category *category*, builder style
+    pub fn with_category(mut self, category:&str) -> Self{
+        self.category = Some(category.to_string());
+        self
+    }
+
+    /// Suggests *fix* to solve the diagnostic, builder style
+    pub fn with_fix(mut self, fix:Fix) -> Self{
+        self.fixes.push(fix);
+        self
+    }
+
+    /// A diagnostic of kind `ERROR`
+    pub fn error(diagnostic:&impl Reportable) -> Self{ Self::new("ERROR", diagnostic) }
+
+    /// A diagnostic of kind `WARNING`
+    pub fn warning(diagnostic:&impl Reportable) -> Self{ Self::new("WARNING", diagnostic) }
+
+    /// Returns true if this diagnostic is of kind `ERROR`
+    pub fn is_error(&self) -> bool{ self.kind == "ERROR" }
+
+    /// Returns true if this diagnostic fulfills *expectation*: same line, same kind and a message containing the expected one
+    pub fn fulfills(&self, expectation:&Expectation) -> bool{
+        self.location.as_ref().is_some_and(|location| location.line == expectation.line)
+            && self.kind == expectation.kind
+            && self.message.contains(&expectation.message)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// A difference between the diagnostics a test file expects and the ones emitted
+pub enum Mismatch{
+    /// An expected diagnostic hasn't been emitted
+    Missing{
+        expectation: Expectation,
+        file: String
+    },
+
+    /// A diagnostic has been emitted without being expected
+    Unexpected(Diagnostic)
+}
+
+impl Reportable for Mismatch{
+    fn message(&self) -> String{
+        match self {
+            Mismatch::Missing { expectation, .. } => format!("Expected {} `{}` was not emitted", expectation.kind, expectation.message),
+            Mismatch::Unexpected(diagnostic) => format!("Unexpected {} `{}`", diagnostic.kind, diagnostic.message)
+        }
+    }
+
+    fn location(&self) -> Option<Location>{
+        match self {
+            Mismatch::Missing { expectation, file } => Some(Location { file: file.clone(), line: expectation.line, column: 0 }),
+            Mismatch::Unexpected(diagnostic) => diagnostic.location.clone()
+        }
+    }
+}
+
+impl Display for Mismatch{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.report())
+    }
+}
+
+impl Error for Mismatch{}
+
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+/// The outcome of a [TestSuite] run, the mismatches of each test file in path order
+pub struct SuiteReport{
+    pub files: Vec<(String, Vec<Mismatch>)>
+}
+
+impl SuiteReport{
+    /// Returns true if every file got exactly the diagnostics it expects
+    pub fn passed(&self) -> bool{ self.files.iter().all(|(_, mismatches)| mismatches.is_empty()) }
+
+    /// Iterates over the paths of the files with mismatches
+    pub fn failed(&self) -> impl Iterator<Item = &str>{
+        self.files.iter().filter(|(_, mismatches)| !mismatches.is_empty()).map(|(path, _)| path.as_str())
+    }
+
+    /// Iterates over every mismatch
+    pub fn mismatches(&self) -> impl Iterator<Item = &Mismatch>{
+        self.files.iter().flat_map(|(_, mismatches)| mismatches)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// Checks that a language emits exactly the diagnostics its test files expect,
+/// the way compiler test suites are written
+/// 
+/// The expectations are comments starting with a marker, `//~` by default, see [Expectation]
+/// 
+/// # Exemples
+/// ```rust
+/// use crate::neoglot_lib::{lexer::*, regex::*, source::Source, suite::*};
+/// 
+/// #[derive(PartialEq, PartialOrd, Eq, Copy, Clone, Debug, Hash)]
+/// enum TokenType{ Number, Comment }
+/// 
+/// impl Symbol for TokenType{}
+/// impl TokenKind for TokenType{}
+/// 
+/// let lexer = Lexer::new()
+///     .with(LexerNode::new(Regex::new().then(RegexElement::Set('0', '9', Quantifier::OneOrMany)), TokenType::Number))
+///     .with(LexerNode::new(Regex::new()
+///         .then(RegexElement::Item('/', Quantifier::Exactly(2)))
+///         .then(RegexElement::NoneOf(vec![RegexElement::Item('\n', Quantifier::Exactly(1))], Quantifier::ZeroOrMany)), TokenType::Comment));
+/// 
+/// let lex = |source:&Source| match lexer.tokenize_content(source.content().to_string(), source.path()) {
+///     LexingResult::Ok(_) => vec![],
+///     LexingResult::Err(errors) => errors.iter().map(Diagnostic::error).collect()
+/// };
+/// 
+/// let source = Source::new("numbers.ng", "12\n3a //~ ERROR Failed to parse token\n".to_string());
+/// assert!(TestSuite::new().check(&source, lex(&source)).is_empty());
+/// 
+/// let source = Source::new("numbers.ng", "12\n3 //~ ERROR Failed to parse token\n".to_string());
+/// assert_eq!(TestSuite::new().check(&source, lex(&source)).len(), 1);
+/// ```
+pub struct TestSuite{
+    /// What starts an expectation comment
+    marker: String
+}
+
+impl Default for TestSuite{
+    fn default() -> Self { Self::new() }
+}
+
+impl TestSuite{
+    pub fn new() -> Self{ TestSuite { marker: String::from("//~") } }
+
+    /// Sets what starts an expectation comment, builder style
+    pub fn with_marker(mut self, marker:&str) -> Self{
+        self.marker = marker.to_string();
+        self
+    }
+
+    /// Reads the expectations written in *content*
+    /// 
+    /// A marker without a kind, or pointing above the first line, isn't an expectation
+    pub fn expectations(&self, content:&str) -> Vec<Expectation>{
+        source::lines(content).enumerate().filter_map(|(line, text)| {
+            let (_, rest) = text.split_once(&self.marker)?;
+
+            let up = rest.chars().take_while(|c| *c == '^').count();
+            let (kind, message) = rest[up..].trim().split_once(char::is_whitespace).unwrap_or((rest[up..].trim(), ""));
+            if kind.is_empty() { return None; }
+
+            Some(Expectation { line: line.checked_sub(up)?, kind: kind.to_string(), message: message.trim().to_string() })
+        }).collect()
+    }
+
+    /// Compares the diagnostics emitted on *source* with the ones it expects
+    /// 
+    /// Each expectation is fulfilled by a different diagnostic, the first one fulfilling it.
+    /// Returns the missing expectations in line order followed by the unexpected diagnostics in emission order
+    pub fn check(&self, source:&Source, diagnostics:Vec<Diagnostic>) -> Vec<Mismatch>{
+        let mut unexpected = diagnostics.into_iter().map(Some).collect::<Vec<Option<Diagnostic>>>();
+        let mut mismatches = vec![];
+
+        for expectation in self.expectations(source.content()){
+            match unexpected.iter_mut().find(|d| d.as_ref().is_some_and(|d| d.fulfills(&expectation))) {
+                Some(fulfilled) => *fulfilled = None,
+                None => mismatches.push(Mismatch::Missing { expectation, file: source.path().to_string() })
+            }
+        }
+
+        mismatches.extend(unexpected.into_iter().flatten().map(Mismatch::Unexpected));
+        mismatches
+    }
+
+    /// Runs *language* on every file of *dir* with the extension *extension*, in path order,
+    /// and [checks](TestSuite::check) the diagnostics it returns
+    /// 
+    /// Sub-directories are not visited
+    pub fn run(&self, dir:impl AsRef<Path>, extension:&str, mut language:impl FnMut(&Source) -> Vec<Diagnostic>) -> io::Result<SuiteReport>{
+        let mut paths = fs::read_dir(dir)?
+            .map(|entry| entry.map(|e| e.path()))
+            .collect::<io::Result<Vec<_>>>()?;
+
+        paths.retain(|path| path.is_file() && path.extension().is_some_and(|e| e == extension));
+        paths.sort();
+
+        let mut report = SuiteReport::default();
+
+        for path in paths{
+            let source = Source::from_file(&path.to_string_lossy())?;
+            let mismatches = self.check(&source, language(&source));
+
+            report.files.push((source.path().to_string(), mismatches));
+        }
+
+        Ok(report)
+    }
+}
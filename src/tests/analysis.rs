@@ -0,0 +1,54 @@
+use crate::analysis::*;
+
+// A statement of a tiny language
+enum Stmt{
+    Assign(&'static str, i64),
+    Jump
+}
+
+#[test]
+fn sign_and_constancy(){
+    assert_eq!(Sign::of(-3).join(&Sign::bottom()), Sign::Negative);
+    assert_eq!(Sign::of(-3).join(&Sign::of(-1)), Sign::Negative);
+    assert_eq!(Sign::of(0).join(&Sign::of(4)), Sign::Top);
+
+    assert_eq!(Constant::Value(2).join(&Constant::Value(2)), Constant::Value(2));
+    assert_eq!(Constant::Value(2).join(&Constant::Value(3)), Constant::Top);
+}
+
+#[test]
+fn possibly_uninitialized(){
+    // 0: branch to 1 or 2
+    // 1: x = 1             2: y = -1
+    // 3: jump to 4
+    // 4: loop back to 3
+    let cfg = Cfg::new(vec![
+        Stmt::Jump,
+        Stmt::Assign("x", 1),
+        Stmt::Assign("y", -1),
+        Stmt::Jump,
+        Stmt::Jump
+    ], 0)
+        .with_edge(0, 1).with_edge(0, 2)
+        .with_edge(1, 3).with_edge(2, 3)
+        .with_edge(3, 4).with_edge(4, 3);
+
+    let initial = Env::new().with("x", Nullability::Null).with("y", Nullability::Null);
+
+    let states = cfg.analyze(initial, |stmt, env| match stmt {
+        Stmt::Assign(variable, _) => env.clone().with(*variable, Nullability::NonNull),
+        _ => env.clone()
+    });
+
+    assert_eq!(states[3].get(&"x"), Nullability::MaybeNull);
+    assert_eq!(states[4].get(&"y"), Nullability::MaybeNull);
+
+    let signs = cfg.analyze(Env::new(), |stmt, env| match stmt {
+        Stmt::Assign(variable, value) => env.clone().with(*variable, Sign::of(*value)),
+        _ => env.clone()
+    });
+
+    assert_eq!(signs[3].get(&"x"), Sign::Positive);
+    assert_eq!(signs[3].get(&"y"), Sign::Negative);
+    assert_eq!(signs[0].get(&"x"), Sign::Bottom);
+}
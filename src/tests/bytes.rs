@@ -0,0 +1,39 @@
+use crate::{bytes::*, lexer::*, regex::*};
+
+#[derive(PartialEq, PartialOrd, Eq, Hash, Copy, Clone, Debug)]
+enum TokenType{
+    Word,
+    Number
+}
+
+impl Symbol for TokenType{}
+impl TokenKind for TokenType{}
+
+fn lexer() -> ByteLexer<TokenType>{
+    ByteLexer::new()
+        .with(Regex::new().then(RegexElement::Set(b'a', b'z', Quantifier::OneOrMany)), TokenType::Word)
+        .with(Regex::new().then(RegexElement::Set(b'0', b'9', Quantifier::OneOrMany)), TokenType::Number)
+        .with_skipped(b" ")
+}
+
+#[test]
+fn byte_lexing(){
+    let tokens = lexer().tokenize(b"set 42").unwrap();
+
+    assert_eq!(tokens, vec![
+        ByteToken{ offset: 0, kind: TokenType::Word, bytes: b"set".to_vec() },
+        ByteToken{ offset: 4, kind: TokenType::Number, bytes: b"42".to_vec() }
+    ]);
+
+    assert_eq!(tokens[1].to_token("packet"), Token::new(
+        Location{ file: "packet".to_string(), line: 0, column: 4 },
+        TokenType::Number, "42".to_string()
+    ));
+
+    assert_eq!(lexer().tokenize(b"ab\x00c\xff"), Err(vec![
+        ByteLexingError{ offset: 2, byte: 0 },
+        ByteLexingError{ offset: 4, byte: 0xff }
+    ]));
+
+    assert_eq!(lexer().tokenize(b""), Ok(vec![]));
+}
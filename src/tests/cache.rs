@@ -0,0 +1,116 @@
+use crate::{cache::*, lexer::*, regex::*};
+
+#[derive(PartialEq, PartialOrd, Eq, Hash, Copy, Clone, Debug)]
+enum TokenType{
+    UInt,
+    Plus
+}
+
+impl Symbol for TokenType{}
+impl TokenKind for TokenType{}
+
+const KINDS:&[TokenType] = &[TokenType::UInt, TokenType::Plus];
+
+fn lexer() -> Lexer<TokenType>{
+    Lexer::new()
+        .with(LexerNode::new(Regex::new().then(RegexElement::Set('0', '9', Quantifier::OneOrMany)), TokenType::UInt))
+        .with(LexerNode::new(Regex::new().then(RegexElement::Item('+', Quantifier::Exactly(1))), TokenType::Plus))
+}
+
+#[test]
+fn round_trip(){
+    let content = "1 + 22\n+ 1";
+    let LexingResult::Ok(tokens) = lexer().tokenize_content(content.to_string(), "sum.txt") else { panic!() };
+
+    let key = content_hash(content);
+    let bytes = encode_tokens(&tokens, KINDS, key).unwrap();
+
+    assert_eq!(decode_tokens(&bytes, KINDS, key), Some(tokens.clone()));
+    assert_eq!(decode_tokens(&bytes, KINDS, key + 1), None, "wrong key");
+    assert_eq!(decode_tokens(&bytes[..bytes.len()-1], KINDS, key), None, "truncated");
+    assert_eq!(decode_tokens(&bytes, &KINDS[..1], key), None, "unknown kind");
+    assert_eq!(encode_tokens(&tokens, &KINDS[..1], key), None);
+
+    assert_eq!(decode_tokens::<TokenType>(&encode_tokens(&[], KINDS, key).unwrap(), KINDS, key), Some(vec![]));
+}
+
+#[test]
+fn corrupted_count(){
+    let key = content_hash("");
+    let mut bytes = encode_tokens::<TokenType>(&[], KINDS, key).unwrap();
+
+    let len = bytes.len();
+    bytes[len-4..].copy_from_slice(&u32::MAX.to_le_bytes());
+
+    assert_eq!(decode_tokens(&bytes, KINDS, key), None);
+}
+
+#[test]
+fn cached_lexing(){
+    let dir = std::env::temp_dir().join(format!("neoglot-cache-{}", std::process::id()));
+    let cache = TokenCache::new(dir.join("cache"));
+    std::fs::create_dir_all(&dir).unwrap();
+
+    let path = dir.join("sum.txt");
+    let path = path.to_str().unwrap();
+    std::fs::write(path, "1 + 22\n+ 1").unwrap();
+
+    let LexingResult::Ok(lexed) = cache.tokenize_file(&lexer(), path, KINDS) else { panic!() };
+    let content = std::fs::read_to_string(path).unwrap();
+
+    assert_eq!(cache.load(&content, KINDS), Some(lexed.clone()));
+
+    let LexingResult::Ok(cached) = cache.tokenize_file(&lexer(), path, KINDS) else { panic!() };
+    assert_eq!(cached, lexed);
+
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn cached_parsing(){
+    let ast = |kind, children| crate::parser::AST { kind, children };
+    let forest = vec![ast(TokenType::Plus, vec![ast(TokenType::UInt, vec![]), ast(TokenType::Plus, vec![ast(TokenType::UInt, vec![]), ast(TokenType::UInt, vec![])])]), ast(TokenType::UInt, vec![])];
+
+    let key = content_hash("1 + 2 + 3\n4");
+    let bytes = encode_forest(&forest, KINDS, key).unwrap();
+
+    assert_eq!(decode_forest(&bytes, KINDS, key), Some(forest.clone()));
+    assert_eq!(decode_forest(&bytes, KINDS, key + 1), None, "wrong key");
+    assert_eq!(decode_forest(&bytes[..bytes.len()-4], KINDS, key), None, "truncated");
+    assert_eq!(decode_forest(&bytes, &KINDS[..1], key), None, "unknown kind");
+
+    // Deep trees don't recurse
+    let deep = (0..10_000).fold(ast(TokenType::UInt, vec![]), |child, _| ast(TokenType::Plus, vec![child]));
+    let decoded = decode_forest(&encode_forest(std::slice::from_ref(&deep), KINDS, key).unwrap(), KINDS, key).unwrap();
+    assert_eq!(decoded[0].iter().count(), 10_001);
+
+    let dir = std::env::temp_dir().join(format!("neoglot-parse-cache-{}", std::process::id()));
+    let cache = TokenCache::new(dir.join("cache")).with_version(1);
+    std::fs::create_dir_all(&dir).unwrap();
+
+    let path = dir.join("sum.txt");
+    let path = path.to_str().unwrap();
+    std::fs::write(path, "1 + 22").unwrap();
+
+    let mut parses = 0;
+    let mut parse = |tokens:&[Token<TokenType>]| {
+        parses += 1;
+        if tokens.len() == 3 { Ok(vec![ast(TokenType::Plus, vec![ast(TokenType::UInt, vec![]), ast(TokenType::UInt, vec![])])]) } else { Err("not a sum") }
+    };
+
+    let (tokens, parsed) = cache.parse_file(&lexer(), path, KINDS, &mut parse).unwrap();
+    assert_eq!((tokens.len(), parsed.len()), (3, 1));
+    assert_eq!(cache.parse_file(&lexer(), path, KINDS, &mut parse).unwrap().1, parsed);
+
+    // Another version doesn't read the entries, and a changed file is parsed again
+    assert!(TokenCache::new(dir.join("cache")).load_forest::<TokenType>("1 + 22", KINDS).is_none());
+
+    std::fs::write(path, "1 + 2 + 3").unwrap();
+    assert!(matches!(cache.parse_file(&lexer(), path, KINDS, &mut parse), Err(ParseFailure::Parsing("not a sum"))));
+
+    std::fs::write(path, "1 - 2").unwrap();
+    assert!(matches!(cache.parse_file(&lexer(), path, KINDS, &mut parse), Err(ParseFailure::Lexing(_))));
+    assert_eq!(parses, 2);
+
+    std::fs::remove_dir_all(dir).unwrap();
+}
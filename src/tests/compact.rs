@@ -0,0 +1,57 @@
+use crate::{compact::*, lexer::*, parser::*, regex::*, source::Source};
+
+#[derive(PartialEq, PartialOrd, Eq, Hash, Copy, Clone, Debug)]
+enum TokenType{
+    Word,
+    Open,
+    Close,
+    End
+}
+
+impl Symbol for TokenType{}
+impl TokenKind for TokenType{}
+
+fn lexer() -> Lexer<TokenType>{
+    let item = |c, kind| LexerNode::new(Regex::new().then(RegexElement::Item(c, Quantifier::Exactly(1))), kind);
+
+    Lexer::new()
+        .with(LexerNode::new(Regex::new().then(RegexElement::Set('a', 'z', Quantifier::OneOrMany)), TokenType::Word))
+        .with(item('(', TokenType::Open))
+        .with(item(')', TokenType::Close))
+        .with(item(';', TokenType::End))
+}
+
+#[test]
+fn compact_tokens(){
+    let content = "let (é; b);\nprint é;";
+
+    let mut files = Files::new();
+    files.add(Source::new("other", String::new()));
+    let id = files.add(Source::new("main", content.to_string()));
+    assert_eq!(files.add(Source::new("main", content.to_string())), id);
+    assert_eq!(files.id("main"), Some(id));
+
+    let LexingResult::Err(_) = lexer().tokenize_content(content.to_string(), "main") else { panic!() };
+
+    let lexer = lexer().with(LexerNode::new(Regex::new().then(RegexElement::Item('é', Quantifier::Exactly(1))), TokenType::Word));
+    let LexingResult::Ok(tokens) = lexer.tokenize_content(content.to_string(), "main") else { panic!() };
+    let compact = files.tokenize(&lexer, id).unwrap();
+
+    assert_eq!(compact.len(), tokens.len());
+    assert_eq!(compact[4], CompactToken{ file: id, offset: 9, length: 1, kind: TokenType::Word });
+
+    for (compact, token) in compact.iter().zip(&tokens){
+        assert_eq!(files.compact(token).as_ref(), Some(compact));
+        assert_eq!(compact.to_token(&files).as_ref(), Some(token));
+    }
+
+    let moved = Token{ literal: "x".to_string(), ..tokens[0].clone() };
+    assert_eq!(files.compact(&moved), None);
+
+    // Helpers work on both representations
+    let blocks = [(TokenType::Open, TokenType::Close)];
+    assert_eq!(find_matching(&compact, TokenType::Open, TokenType::Close, 1), find_matching(&tokens, TokenType::Open, TokenType::Close, 1));
+    assert_eq!(compact.split_statements(TokenType::End, &blocks).map(<[_]>::len).collect::<Vec<_>>(), vec![6, 2]);
+
+    assert!(std::mem::size_of::<Token<TokenType>>() >= 4 * std::mem::size_of::<CompactToken<TokenType>>());
+}
@@ -0,0 +1,41 @@
+use std::time::Duration;
+
+use crate::{config::*, lexer::*, limits::{Limit, Limits}, regex::*, Reportable};
+
+#[derive(PartialEq, PartialOrd, Eq, Hash, Copy, Clone, Debug)]
+enum TokenType{ Word }
+
+impl Symbol for TokenType{}
+impl TokenKind for TokenType{}
+
+#[test]
+fn language_config(){
+    let text = "# Limits\nlexer.max_token_length = 4\r\nlexer.max_repetitions = 100\nparser.max_depth = 8\nparser.time_budget_ms = 250\n\ndiagnostics.hyperlinks = true\nfeatures = async, generics\n";
+    let config = LanguageConfig::parse(text, "lang.cfg").unwrap();
+
+    assert_eq!(config.lexer.limits, Limits::new().with_max_token_length(4).with_max_repetitions(100));
+    assert_eq!(config.parser, Limits::new().with_max_depth(8).with_time_budget(Duration::from_millis(250)));
+    assert!(config.diagnostics.hyperlinks);
+    assert!(config.has_feature("async") && config.has_feature("generics") && !config.has_feature("macros"));
+    assert_eq!(LanguageConfig::parse(&config.to_string(), "lang.cfg"), Ok(config.clone()));
+    assert_eq!(LanguageConfig::parse("", "lang.cfg"), Ok(LanguageConfig::new()));
+
+    // The stages follow the configuration
+    let lexer = Lexer::new()
+        .with(LexerNode::new(Regex::new().then(RegexElement::Set('a', 'z', Quantifier::OneOrMany)), TokenType::Word))
+        .with_config(&config);
+
+    match lexer.tokenize_content("tiny enormous".to_string(), "main") {
+        LexingResult::Err(errors) => assert_eq!(errors[0].limit, Some(Limit::TokenLength(4))),
+        LexingResult::Ok(_) => panic!("the token length limit was ignored")
+    }
+
+    // Errors are located
+    let unknown = LanguageConfig::parse("lexer.nfc = true\nparser.depth = 3", "lang.cfg").unwrap_err();
+    assert_eq!(unknown.message(), "Unknown configuration key `parser.depth`");
+    assert_eq!(unknown.location().map(|l| l.line), Some(1));
+
+    let invalid = LanguageConfig::parse("lexer.max_input_size = lots", "lang.cfg").unwrap_err();
+    assert_eq!(invalid.message(), "Invalid value `lots` for `lexer.max_input_size`");
+    assert_eq!(LanguageConfig::parse("features", "lang.cfg").unwrap_err().message(), "Expected a `key = value` pair");
+}
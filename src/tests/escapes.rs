@@ -0,0 +1,39 @@
+use crate::{escapes::*, lexer::*, regex::Symbol};
+
+#[derive(PartialEq, PartialOrd, Eq, Hash, Copy, Clone, Debug)]
+enum TokenType{
+    Str
+}
+
+impl Symbol for TokenType{}
+impl TokenKind for TokenType{}
+
+fn span(start:usize, end:usize) -> Span{
+    Span::new(Location{ file: "f".to_string(), line: 1, column: start }, Location{ file: "f".to_string(), line: 1, column: end })
+}
+
+#[test]
+fn escape_decoding(){
+    let escapes = Escapes::new().with_hex().with_unicode().with_escape('e', '\u{1b}');
+    let start = span(0, 0).start;
+
+    assert_eq!(escapes.decode(r#"\"quoted\" \\ \e\0"#, &start), Ok("\"quoted\" \\ \u{1b}\0".to_string()));
+    assert_eq!(escapes.decode(r"\x7e\u{1F600}", &start), Ok("~\u{1F600}".to_string()));
+    assert_eq!(escapes.decode("", &start), Ok(String::new()));
+
+    assert_eq!(escapes.decode(r"\x80 \x4 \u{} \u{D800} \u{41 \u41 \+1", &start), Err(vec![
+        EscapeError::InvalidCodePoint(span(0, 4)),
+        EscapeError::InvalidCodePoint(span(5, 8)),
+        EscapeError::InvalidCodePoint(span(9, 13)),
+        EscapeError::InvalidCodePoint(span(14, 22)),
+        EscapeError::InvalidCodePoint(span(23, 28)),
+        EscapeError::InvalidCodePoint(span(29, 31)),
+        EscapeError::UnknownEscape(span(34, 36))
+    ]));
+
+    assert_eq!(escapes.decode(r"ab\", &start), Err(vec![EscapeError::Unfinished(span(2, 3))]));
+    assert_eq!(Escapes::new().decode(r"\x41", &start), Err(vec![EscapeError::UnknownEscape(span(0, 2))]));
+
+    let token = Token::new(Location{ file: "f".to_string(), line: 1, column: 4 }, TokenType::Str, r#""a\zb""#.to_string());
+    assert_eq!(escapes.decode_token(&token), Err(vec![EscapeError::UnknownEscape(span(6, 8))]));
+}
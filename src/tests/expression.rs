@@ -0,0 +1,572 @@
+use crate::{lexer::*, parser::{expression::*, *}, regex::Symbol};
+
+#[derive(Debug, Hash, Clone, Copy, PartialOrd, PartialEq, Eq)]
+enum TokenType{
+    A, B, C,
+    Add, Sub, Mul,
+    OpenParen, ClosedParen,
+    Pipe, OpenBrace, ClosedBrace,
+    Abs, Set
+}
+
+impl Symbol for TokenType{}
+impl TokenKind for TokenType{}
+
+// Builds a single line of tokens, one column per token
+fn tokens(kinds:&[TokenType]) -> Vec<Token<TokenType>>{
+    kinds.iter().enumerate().map(|(i, kind)| Token::new(
+        Location{ file: "".to_string(), line: 0, column: i },
+        *kind, format!("{kind:?}")
+    )).collect()
+}
+
+fn location(column:usize) -> Location{
+    Location{ file: "".to_string(), line: 0, column }
+}
+
+fn init_parser() -> ExpressionParser<TokenType>{
+    ExpressionParser::new()
+        .with_operator(TokenType::Add, 1)
+        .with_operator(TokenType::Sub, 1)
+        .with_operator(TokenType::Mul, 2)
+        .with_high_priority_group(TokenType::OpenParen, TokenType::ClosedParen)
+}
+
+#[test]
+fn groups(){
+    use TokenType::*;
+
+    let parser = init_parser();
+
+    // (A + B) * C
+    let expr1 = tokens(&[OpenParen, A, Add, B, ClosedParen, Mul, C]);
+    // ((A))
+    let expr2 = tokens(&[OpenParen, OpenParen, A, ClosedParen, ClosedParen]);
+    // (A) + (B)
+    let expr3 = tokens(&[OpenParen, A, ClosedParen, Add, OpenParen, B, ClosedParen]);
+
+    assert_eq!(parser.parse(&expr1), Some(Ok(AST{ kind: Expr::Operator(Mul), children: vec![
+        AST{ kind: Expr::Operator(Add), children: vec![
+            AST{ kind: Expr::Operand(A), children: vec![] },
+            AST{ kind: Expr::Operand(B), children: vec![] }
+        ] },
+        AST{ kind: Expr::Operand(C), children: vec![] }
+    ] })));
+
+    assert_eq!(parser.parse(&expr2), Some(Ok(AST{ kind: Expr::Operand(A), children: vec![] })));
+
+    assert_eq!(parser.parse(&expr3), Some(Ok(AST{ kind: Expr::Operator(Add), children: vec![
+        AST{ kind: Expr::Operand(A), children: vec![] },
+        AST{ kind: Expr::Operand(B), children: vec![] }
+    ] })));
+}
+
+#[test]
+fn empty_and_dangling_groups(){
+    use TokenType::*;
+
+    let parser = init_parser();
+
+    // ()
+    let expr1 = tokens(&[OpenParen, ClosedParen]);
+    // A + ()
+    let expr2 = tokens(&[A, Add, OpenParen, ClosedParen]);
+    // (
+    let expr3 = tokens(&[OpenParen]);
+    // A + B)
+    let expr4 = tokens(&[A, Add, B, ClosedParen]);
+    // (A + (B
+    let expr5 = tokens(&[OpenParen, A, Add, OpenParen, B]);
+
+    assert_eq!(parser.parse(&expr1), Some(Err(vec![ParsingError::EmptyGroup(location(0))])));
+    assert_eq!(parser.parse(&expr2), Some(Err(vec![ParsingError::EmptyGroup(location(2))])));
+    assert_eq!(parser.parse(&expr3), Some(Err(vec![ParsingError::InvalidGroups(location(0))])));
+    assert_eq!(parser.parse(&expr4), Some(Err(vec![ParsingError::InvalidGroups(location(3))])));
+    assert_eq!(parser.parse(&expr5), Some(Err(vec![ParsingError::InvalidGroups(location(3))])));
+}
+
+#[test]
+fn registration(){
+    use TokenType::*;
+
+    let mut parser = init_parser();
+
+    assert_eq!(parser.add_operator(Add, 1), Ok(()));
+    assert_eq!(parser.add_operator(Add, 3), Err(RegistrationError::ConflictingPriority { operator: Add, registered: 1, requested: 3 }));
+    assert_eq!(parser.add_operator(OpenParen, 3), Err(RegistrationError::GroupOperator(OpenParen)));
+    assert_eq!(parser.set_high_priority_group(OpenParen, Mul), Err(RegistrationError::GroupOperator(Mul)));
+
+    assert_eq!(parser.priority_of(Add), Some(1));
+    assert_eq!(parser.priority_of(Mul), Some(2));
+    assert_eq!(parser.priority_of(A), None);
+
+    let operators = parser.operators().copied().collect::<Vec<TokenType>>();
+    assert_eq!(operators, vec![Add, Sub, Mul]);
+}
+
+#[test]
+fn nesting_depth_over_priority(){
+    use TokenType::*;
+
+    // Priorities large enough to collide with any depth multiplier
+    let parser = ExpressionParser::new()
+        .with_operator(Add, 1)
+        .with_operator(Mul, 1000)
+        .with_high_priority_group(OpenParen, ClosedParen);
+
+    // A * (B + C)
+    let expr1 = tokens(&[A, Mul, OpenParen, B, Add, C, ClosedParen]);
+    // A * ((((B + C))))
+    let expr2 = tokens(&[A, Mul, OpenParen, OpenParen, OpenParen, OpenParen, B, Add, C, ClosedParen, ClosedParen, ClosedParen, ClosedParen]);
+
+    let expected = Some(Ok(AST{ kind: Expr::Operator(Mul), children: vec![
+        AST{ kind: Expr::Operand(A), children: vec![] },
+        AST{ kind: Expr::Operator(Add), children: vec![
+            AST{ kind: Expr::Operand(B), children: vec![] },
+            AST{ kind: Expr::Operand(C), children: vec![] }
+        ] }
+    ] }));
+
+    assert_eq!(parser.parse(&expr1), expected);
+    assert_eq!(parser.parse(&expr2), expected);
+}
+
+#[test]
+fn depth_across_operators(){
+    use TokenType::*;
+
+    // The group tokens aren't registered as operators
+    let parser = init_parser();
+    let leaf = |kind| AST::leaf(Expr::Operand(kind));
+
+    // (A * B + C) * A, the operators inside the group all stay at its depth
+    let expr1 = tokens(&[OpenParen, A, Mul, B, Add, C, ClosedParen, Mul, A]);
+    assert_eq!(parser.parse(&expr1), Some(Ok(AST::new(Expr::Operator(Mul), vec![
+        AST::new(Expr::Operator(Add), vec![
+            AST::new(Expr::Operator(Mul), vec![leaf(A), leaf(B)]),
+            leaf(C)
+        ]),
+        leaf(A)
+    ]))));
+
+    // A + (B * C + A) * B, the depth is back to zero once the group closes
+    let expr2 = tokens(&[A, Add, OpenParen, B, Mul, C, Add, A, ClosedParen, Mul, B]);
+    assert_eq!(parser.parse(&expr2), Some(Ok(AST::new(Expr::Operator(Add), vec![
+        leaf(A),
+        AST::new(Expr::Operator(Mul), vec![
+            AST::new(Expr::Operator(Add), vec![
+                AST::new(Expr::Operator(Mul), vec![leaf(B), leaf(C)]),
+                leaf(A)
+            ]),
+            leaf(B)
+        ])
+    ]))));
+}
+
+#[test]
+fn missing_operands(){
+    use TokenType::*;
+
+    let parser = init_parser();
+
+    // A +
+    let expr1 = tokens(&[A, Add]);
+    // * B
+    let expr2 = tokens(&[Mul, B]);
+    // (A -) * B
+    let expr3 = tokens(&[OpenParen, A, Sub, ClosedParen, Mul, B]);
+
+    assert_eq!(parser.parse(&expr1), Some(Err(vec![
+        ParsingError::MissingOperand { operator: Add, side: OperandSide::Right, location: location(1) }
+    ])));
+    assert_eq!(parser.parse(&expr2), Some(Err(vec![
+        ParsingError::MissingOperand { operator: Mul, side: OperandSide::Left, location: location(0) }
+    ])));
+    assert_eq!(parser.parse(&expr3), Some(Err(vec![
+        ParsingError::MissingOperand { operator: Sub, side: OperandSide::Right, location: location(2) }
+    ])));
+}
+
+#[test]
+fn adjacency(){
+    use TokenType::*;
+
+    // A B * C
+    let expr1 = tokens(&[A, B, Mul, C]);
+    // A (B + C)
+    let expr2 = tokens(&[A, OpenParen, B, Add, C, ClosedParen]);
+
+    let unknown = init_parser();
+    assert_eq!(unknown.parse(&expr1[..2]), Some(Ok(AST{ kind: Expr::Unknown(&expr1[..2]), children: vec![] })));
+
+    let implicit = init_parser().with_adjacency(Adjacency::Implicit(Mul));
+    assert_eq!(implicit.parse(&expr1), Some(Ok(AST{ kind: Expr::Operator(Mul), children: vec![
+        AST{ kind: Expr::Operator(Mul), children: vec![
+            AST{ kind: Expr::Operand(A), children: vec![] },
+            AST{ kind: Expr::Operand(B), children: vec![] }
+        ] },
+        AST{ kind: Expr::Operand(C), children: vec![] }
+    ] })));
+    assert_eq!(implicit.parse(&expr2), Some(Ok(AST{ kind: Expr::Operator(Mul), children: vec![
+        AST{ kind: Expr::Operand(A), children: vec![] },
+        AST{ kind: Expr::Operator(Add), children: vec![
+            AST{ kind: Expr::Operand(B), children: vec![] },
+            AST{ kind: Expr::Operand(C), children: vec![] }
+        ] }
+    ] })));
+
+    let error = init_parser().with_adjacency(Adjacency::Error);
+    assert_eq!(error.parse(&expr1), Some(Err(vec![ParsingError::AdjacentOperands(location(1))])));
+    assert_eq!(error.parse(&expr2), Some(Err(vec![ParsingError::AdjacentOperands(location(1))])));
+
+    let mut parser = init_parser();
+    assert_eq!(parser.set_adjacency(Adjacency::Implicit(A)), Err(RegistrationError::UnknownOperator(A)));
+}
+
+#[test]
+fn stable_operator_order(){
+    use TokenType::*;
+
+    let kinds = [Mul, C, Add, B, Sub, A];
+
+    for _ in 0..10{
+        let mut parser = ExpressionParser::new();
+        for (priority, kind) in kinds.iter().enumerate(){
+            parser.add_operator(*kind, priority).unwrap();
+        }
+
+        // Registering again is a no-op
+        parser.add_operator(Add, 2).unwrap();
+
+        assert_eq!(parser.operators().copied().collect::<Vec<TokenType>>(), kinds.to_vec());
+    }
+}
+
+#[test]
+fn parsing_from_cursor(){
+    use TokenType::*;
+
+    let parser = init_parser();
+    let operands = KindSet::of(&[A, B]);
+
+    // A * (B + A) C + B
+    let stream = tokens(&[A, Mul, OpenParen, B, Add, A, ClosedParen, C, Add, B]);
+    let mut cursor = Parser::new(&stream);
+
+    assert_eq!(parser.parse_from(&mut cursor, &operands, 0), Some(Ok(AST{ kind: Expr::Operator(Mul), children: vec![
+        AST{ kind: Expr::Operand(A), children: vec![] },
+        AST{ kind: Expr::Operator(Add), children: vec![
+            AST{ kind: Expr::Operand(B), children: vec![] },
+            AST{ kind: Expr::Operand(A), children: vec![] }
+        ] }
+    ] })));
+    assert_eq!(cursor.peek().map(|t| t.kind), Some(C));
+
+    assert_eq!(parser.parse_from(&mut cursor, &operands, 0), None);
+    assert_eq!(cursor.peek().map(|t| t.kind), Some(C));
+
+    // A * B + A, stopping before the low priority operator
+    let stream = tokens(&[A, Mul, B, Add, A]);
+    let mut cursor = Parser::new(&stream);
+
+    assert_eq!(parser.parse_from(&mut cursor, &operands, 2), Some(Ok(AST{ kind: Expr::Operator(Mul), children: vec![
+        AST{ kind: Expr::Operand(A), children: vec![] },
+        AST{ kind: Expr::Operand(B), children: vec![] }
+    ] })));
+    assert_eq!(cursor.peek().map(|t| t.kind), Some(Add));
+
+    // A + C, the dangling operator is reported
+    let stream = tokens(&[A, Add, C]);
+    let mut cursor = Parser::new(&stream);
+
+    assert_eq!(parser.parse_from(&mut cursor, &operands, 0), Some(Err(vec![
+        ParsingError::MissingOperand{ operator: Add, side: OperandSide::Right, location: location(1) }
+    ])));
+    assert_eq!(cursor.peek().map(|t| t.kind), Some(C));
+
+    // A B with implicit multiplication
+    let parser = init_parser().with_adjacency(Adjacency::Implicit(Mul));
+    let stream = tokens(&[A, B, C]);
+    let mut cursor = Parser::new(&stream);
+
+    assert_eq!(parser.parse_from(&mut cursor, &operands, 0), Some(Ok(AST{ kind: Expr::Operator(Mul), children: vec![
+        AST{ kind: Expr::Operand(A), children: vec![] },
+        AST{ kind: Expr::Operand(B), children: vec![] }
+    ] })));
+    assert_eq!(cursor.peek().map(|t| t.kind), Some(C));
+}
+
+#[test]
+fn limits(){
+    use crate::limits::*;
+    use TokenType::*;
+
+    // A - B - A - B
+    let expr = tokens(&[A, Sub, B, Sub, A, Sub, B]);
+
+    let parser = init_parser().with_limits(Limits::new().with_max_depth(3));
+    assert!(matches!(parser.parse(&expr), Some(Ok(_))));
+
+    let parser = init_parser().with_limits(Limits::new().with_max_depth(2));
+    assert_eq!(parser.parse(&expr), Some(Err(vec![
+        ParsingError::LimitExceeded{ limit: Limit::Depth(2), location: Some(location(0)) },
+        ParsingError::LimitExceeded{ limit: Limit::Depth(2), location: Some(location(2)) }
+    ])));
+
+    let parser = init_parser().with_limits(Limits::new().with_max_tokens(6));
+    assert_eq!(parser.parse(&expr), Some(Err(vec![
+        ParsingError::LimitExceeded{ limit: Limit::Tokens(6), location: Some(location(0)) }
+    ])));
+
+    // A - A - ... nested too deeply for the stack
+    let expr = tokens(&[A, Sub].repeat(20_000)[..39_999]);
+    let errors = init_parser().parse(&expr).unwrap().unwrap_err();
+    assert!(errors.iter().all(|e| matches!(e, ParsingError::LimitExceeded{ limit: Limit::Stack(DEFAULT_MAX_STACK), .. })));
+
+    let errors = init_parser().with_limits(Limits::new().with_max_stack(1 << 16)).parse(&expr).unwrap().unwrap_err();
+    assert!(errors.iter().all(|e| matches!(e, ParsingError::LimitExceeded{ limit: Limit::Stack(65536), .. })));
+}
+
+#[test]
+fn recovering(){
+    use TokenType::*;
+
+    let parser = init_parser();
+    let operand = |kind| AST{ kind: Expr::Operand(kind), children: vec![] };
+    let missing = || AST{ kind: Expr::Missing, children: vec![] };
+
+    // A * (B +
+    let expr = tokens(&[A, Mul, OpenParen, B, Add]);
+    assert_eq!(parser.parse_recovering(&expr), Some((
+        AST{ kind: Expr::Operator(Mul), children: vec![
+            operand(A),
+            AST{ kind: Expr::Operator(Add), children: vec![operand(B), missing()] }
+        ] },
+        vec![
+            ParsingError::InvalidGroups(location(2)),
+            ParsingError::MissingOperand{ operator: Add, side: OperandSide::Right, location: location(4) }
+        ]
+    )));
+
+    // A + B) - ()
+    let expr = tokens(&[A, Add, B, ClosedParen, Sub, OpenParen, ClosedParen]);
+    assert_eq!(parser.parse_recovering(&expr), Some((
+        AST{ kind: Expr::Operator(Sub), children: vec![
+            AST{ kind: Expr::Operator(Add), children: vec![operand(A), operand(B)] },
+            missing()
+        ] },
+        vec![
+            ParsingError::InvalidGroups(location(3)),
+            ParsingError::EmptyGroup(location(5))
+        ]
+    )));
+
+    // The placeholders are the error nodes of the expressions
+    let (ast, _) = parser.parse_recovering(&expr).unwrap();
+    assert_eq!(ast.errors().collect::<Vec<_>>(), vec![&missing()]);
+    assert_eq!(ast.iter_valid().count(), 4);
+
+    // Complete expressions parse the same way
+    let expr = tokens(&[OpenParen, A, Add, B, ClosedParen, Mul, C]);
+    assert_eq!(parser.parse_recovering(&expr).map(|(ast, errors)| (Ok(ast), errors)), parser.parse(&expr).map(|ast| (ast, vec![])));
+
+    assert_eq!(parser.parse_recovering(&[]), None);
+}
+
+#[test]
+fn operator_names(){
+    use TokenType::*;
+
+    let parser = init_parser()
+        .with_operator_name(Add, "addition", Some("Sums two numbers"))
+        .with_operator_name(Mul, "multiplication", None);
+
+    assert_eq!(parser.operator_names().name(Add), "addition");
+    assert_eq!(parser.operator_names().info(Add).and_then(|i| i.description.as_deref()), Some("Sums two numbers"));
+    assert_eq!(parser.operator_names().name(Sub), "Sub");
+
+    let mut unknown = init_parser();
+    assert_eq!(unknown.describe_operator(A, "a", None), Err(RegistrationError::UnknownOperator(A)));
+
+    let error = parser.parse(&tokens(&[A, Add])).unwrap().unwrap_err().remove(0);
+    assert_eq!(error.message_with(parser.operator_names()), "Missing right operand of `addition`");
+    assert_eq!(crate::Reportable::message(&error), "Missing right operand of `Add`");
+
+    let names = KindNames::new().with_description(OpenParen, "opening parenthesis", "Starts a group");
+    let error = ParsingError::UnexpectedToken{ expected: Some(ClosedParen), got: Some(OpenParen), location: location(0) };
+    assert_eq!(error.message_with(&names), "Expected `ClosedParen` but found `opening parenthesis`");
+}
+
+#[test]
+fn brackets(){
+    use TokenType::*;
+
+    let parser = init_parser()
+        .with_bracket(Pipe, Pipe, Abs)
+        .with_bracket(OpenBrace, ClosedBrace, Set);
+
+    let operand = |kind| AST{ kind: Expr::Operand(kind), children: vec![] };
+
+    // |A - B| * C
+    let expr = tokens(&[Pipe, A, Sub, B, Pipe, Mul, C]);
+    assert_eq!(parser.parse(&expr), Some(Ok(AST{ kind: Expr::Operator(Mul), children: vec![
+        AST{ kind: Expr::Bracket(Abs), children: vec![
+            AST{ kind: Expr::Operator(Sub), children: vec![operand(A), operand(B)] }
+        ] },
+        operand(C)
+    ] })));
+
+    // ({A + {}})
+    let expr = tokens(&[OpenParen, OpenBrace, A, Add, OpenBrace, ClosedBrace, ClosedBrace, ClosedParen]);
+    assert_eq!(parser.parse(&expr), Some(Ok(AST{ kind: Expr::Bracket(Set), children: vec![
+        AST{ kind: Expr::Operator(Add), children: vec![operand(A), AST{ kind: Expr::Bracket(Set), children: vec![] }] }
+    ] })));
+
+    // A + |B
+    let expr = tokens(&[A, Add, Pipe, B]);
+    assert_eq!(parser.parse(&expr), Some(Err(vec![ParsingError::InvalidGroups(location(2))])));
+
+    // {A + B
+    let expr = tokens(&[OpenBrace, A, Add, B]);
+    let (ast, errors) = parser.parse_recovering(&expr).unwrap();
+    assert_eq!(ast, AST{ kind: Expr::Unknown(&expr), children: vec![] });
+    assert_eq!(errors, vec![ParsingError::InvalidGroups(location(0))]);
+
+    // A + |B| C, stops before C
+    let expr = tokens(&[A, Add, Pipe, B, Pipe, C]);
+    let mut cursor = Parser::new(&expr);
+    assert!(parser.parse_from(&mut cursor, &KindSet::of(&[A, B, C]), 0).is_some_and(|r| r.is_ok()));
+    assert_eq!(cursor.peek().map(|t| t.kind), Some(C));
+
+    let mut parser = parser;
+    assert_eq!(parser.add_bracket(Pipe, C, Abs), Err(RegistrationError::ConflictingGroup(Pipe)));
+    assert_eq!(parser.add_bracket(C, Add, Abs), Err(RegistrationError::GroupOperator(Add)));
+    assert_eq!(parser.add_bracket(OpenParen, Pipe, Abs), Err(RegistrationError::ConflictingGroup(OpenParen)));
+    assert_eq!(parser.set_high_priority_group(OpenBrace, ClosedParen), Err(RegistrationError::ConflictingGroup(OpenBrace)));
+    assert_eq!(parser.brackets().count(), 2);
+}
+
+// Builds a line of single character tokens, keeping the spaces between them
+fn spaced(text:&str) -> Vec<Token<TokenType>>{
+    use TokenType::*;
+
+    text.chars().enumerate().filter(|(_, c)| *c != ' ').map(|(column, c)| Token::new(
+        location(column),
+        match c { 'a' => A, 'b' => B, '+' => Add, '-' => Sub, '*' => Mul, '(' => OpenParen, _ => ClosedParen },
+        c.to_string()
+    )).collect()
+}
+
+#[test]
+fn operator_spacing(){
+    use TokenType::*;
+
+    let operand = |kind| AST{ kind: Expr::Operand(kind), children: vec![] };
+    let negate = |ast| AST{ kind: Expr::Operator(Sub), children: vec![ast] };
+    let sub = AST{ kind: Expr::Operator(Sub), children: vec![operand(A), operand(B)] };
+
+    let expr = spaced("a -b");
+    assert!(!expr[0].touches(&expr[1]) && expr[1].touches(&expr[2]));
+    assert!(!Parser::new(&expr).touches_next(0) && Parser::new(&expr).touches_next(1));
+
+    let parser = init_parser().with_prefix_operator(Sub);
+    assert_eq!(parser.parse(&spaced("-a")), Some(Ok(negate(operand(A)))));
+    assert_eq!(parser.parse(&spaced("-a * b")), Some(Ok(AST{ kind: Expr::Operator(Mul), children: vec![negate(operand(A)), operand(B)] })));
+    assert_eq!(parser.parse(&spaced("a - -b")), Some(Ok(AST{ kind: Expr::Operator(Sub), children: vec![operand(A), negate(operand(B))] })));
+    assert_eq!(parser.parse(&spaced("a -b")), Some(Ok(sub.clone())));
+
+    // The spacing only matters once enabled
+    let spacing = init_parser().with_prefix_operator(Sub).with_spacing_sensitivity();
+    assert_eq!(spacing.parse(&spaced("a - b")), Some(Ok(sub.clone())));
+    assert_eq!(spacing.parse(&spaced("a-b")), Some(Ok(sub.clone())));
+    assert_eq!(spacing.parse(&spaced("a- b")), Some(Ok(sub)));
+
+    let expr = spaced("a -b");
+    assert_eq!(spacing.parse(&expr), Some(Ok(AST{ kind: Expr::Unknown(&expr), children: vec![] })));
+
+    let implicit = spacing.clone().with_adjacency(Adjacency::Implicit(Mul));
+    assert_eq!(implicit.parse(&spaced("a -(b)")), Some(Ok(AST{ kind: Expr::Operator(Mul), children: vec![operand(A), negate(operand(B))] })));
+
+    let error = spacing.clone().with_adjacency(Adjacency::Error);
+    assert_eq!(error.parse(&spaced("a -b")), Some(Err(vec![ParsingError::AdjacentOperands(location(2))])));
+
+    // a -b + a, stops before -b without an implicit operator
+    let expr = spaced("a -b + a");
+    let mut cursor = Parser::new(&expr);
+    assert_eq!(spacing.parse_from(&mut cursor, &KindSet::of(&[A, B]), 0), Some(Ok(operand(A))));
+    assert_eq!(cursor.peek().map(|t| t.kind), Some(Sub));
+
+    let mut parser = init_parser();
+    assert_eq!(parser.add_prefix_operator(A), Err(RegistrationError::UnknownOperator(A)));
+}
+
+#[test]
+fn introspection(){
+    let parser = init_parser()
+        .with_prefix_operator(TokenType::Sub)
+        .with_bracket(TokenType::OpenBrace, TokenType::ClosedBrace, TokenType::Set);
+
+    let kinds = [TokenType::A, TokenType::Add, TokenType::Sub, TokenType::Mul, TokenType::OpenParen, TokenType::ClosedBrace, TokenType::Set];
+    let operators = kinds.iter().filter(|k| parser.is_operator(**k)).copied().collect::<Vec<TokenType>>();
+    let grouping = kinds.iter().filter(|k| parser.is_grouping(**k)).copied().collect::<Vec<TokenType>>();
+
+    assert_eq!(operators, vec![TokenType::Add, TokenType::Sub, TokenType::Mul]);
+    assert_eq!(grouping, vec![TokenType::OpenParen, TokenType::ClosedBrace]);
+    assert!(parser.is_prefix_operator(TokenType::Sub) && !parser.is_prefix_operator(TokenType::Add));
+
+    // Levels follow the priorities, whatever their gaps
+    assert_eq!(parser.precedence(TokenType::Add), parser.precedence(TokenType::Sub));
+    assert!(parser.precedence(TokenType::Mul) > parser.precedence(TokenType::Add));
+    assert_eq!(parser.precedence(TokenType::A), None);
+
+    let sparse = ExpressionParser::new().with_operator(TokenType::Add, 5).with_operator(TokenType::Mul, 500);
+    assert_eq!((sparse.precedence(TokenType::Add), sparse.precedence(TokenType::Mul)), (Some(0), Some(1)));
+
+    assert_eq!(parser.grouping_pairs().collect::<Vec<_>>(), vec![(TokenType::OpenParen, TokenType::ClosedParen), (TokenType::OpenBrace, TokenType::ClosedBrace)]);
+    assert_eq!(ExpressionParser::<TokenType>::new().grouping_pairs().count(), 0);
+}
+
+#[test]
+fn minimal_parentheses(){
+    use TokenType::*;
+
+    let parser = init_parser()
+        .with_prefix_operator(Sub)
+        .with_bracket(Pipe, Pipe, Abs);
+
+    let text = |kind| match kind {
+        Add => "+".to_string(),
+        Sub => "-".to_string(),
+        Mul => "*".to_string(),
+        OpenParen => "(".to_string(),
+        ClosedParen => ")".to_string(),
+        Pipe => "|".to_string(),
+        other => format!("{other:?}")
+    };
+
+    let cases:[(&[TokenType], &str); 6] = [
+        (&[OpenParen, A, Add, B, ClosedParen, Mul, C], "(A + B) * C"),
+        (&[OpenParen, A, Sub, B, ClosedParen, Sub, C], "A - B - C"),
+        (&[A, Sub, OpenParen, B, Sub, C, ClosedParen], "A - (B - C)"),
+        (&[A, Add, OpenParen, B, Mul, C, ClosedParen], "A + B * C"),
+        (&[Sub, A, Mul, Sub, C], "-A * -C"),
+        (&[Pipe, OpenParen, A, Sub, B, ClosedParen, Pipe, Mul, C], "|A - B| * C")
+    ];
+
+    for (kinds, expected) in cases{
+        let candidates = tokens(kinds);
+        let ast = parser.parse(&candidates).unwrap().unwrap();
+        assert_eq!(parser.render(&ast, text).as_deref(), Some(expected));
+    }
+
+    let sum = AST{ kind: Expr::Operator(Add), children: vec![AST{ kind: Expr::Operand(A), children: vec![] }, AST{ kind: Expr::Operand(B), children: vec![] }] };
+    let negated = AST{ kind: Expr::Operator(Sub), children: vec![sum] };
+    assert_eq!(parser.render(&negated, text).as_deref(), Some("-(A + B)"));
+
+    // Parentheses can't be written without a high priority group
+    let ungrouped = ExpressionParser::new().with_operator(Add, 1).with_operator(Mul, 2);
+    let candidates = tokens(&[OpenParen, A, Add, B, ClosedParen, Mul, C]);
+    let ast = init_parser().parse(&candidates).unwrap().unwrap();
+    assert_eq!(ungrouped.render(&ast, text), None);
+    assert_eq!(ungrouped.render(&ast.children[1], text).as_deref(), Some("C"));
+}
@@ -0,0 +1,69 @@
+use crate::{fixes::*, lexer::*, regex::*, source::Source, suite::Diagnostic, Reportable};
+
+#[derive(PartialEq, PartialOrd, Eq, Hash, Copy, Clone, Debug)]
+enum Kind{ Name, Semicolon }
+
+impl Symbol for Kind{}
+impl TokenKind for Kind{}
+
+struct Lint(&'static str, Location);
+
+impl Reportable for Lint{
+    fn message(&self) -> String{ self.0.to_string() }
+    fn location(&self) -> Option<Location>{ Some(self.1.clone()) }
+}
+
+#[test]
+fn fix_application(){
+    let lexer = Lexer::new()
+        .with(LexerNode::new(Regex::new().then(RegexElement::Set('a', 'z', Quantifier::OneOrMany)), Kind::Name))
+        .with(LexerNode::new(Regex::new().then(RegexElement::Item(';', Quantifier::Exactly(1))), Kind::Semicolon));
+
+    let content = String::from("foo bar;;\nbaz;");
+    let source = Source::new("main.ng", content.clone());
+    let LexingResult::Ok(tokens) = lexer.tokenize_content(content, "main.ng") else { panic!("lexing failed") };
+
+    // Fixes are anchored on the tokens of the nodes they are about
+    let rename = Fix::new("Rename to qux").with_edit(Edit::replace_tokens(&tokens[1..2], "qux").unwrap());
+    let semicolon = Fix::new("Remove the extra semicolon").with_edit(Edit::replace_tokens(&tokens[3..4], "").unwrap());
+    let whole = Fix::new("Remove the statement").with_edit(Edit::replace_tokens(&tokens[0..3], "").unwrap());
+    let both = Fix::new("Swap the statements")
+        .with_edit(Edit::replace_tokens(&tokens[4..6], "foo bar;").unwrap())
+        .with_edit(Edit::replace_tokens(&tokens[0..3], "baz;").unwrap());
+
+    let diagnostics = vec![
+        Diagnostic::warning(&Lint("Bad name", tokens[1].location.clone())).with_fix(rename.clone()),
+        Diagnostic::warning(&Lint("Extra semicolon", tokens[3].location.clone())).with_fix(semicolon.clone()),
+        Diagnostic::warning(&Lint("Useless statement", tokens[0].location.clone())).with_fix(whole.clone()).with_fix(both.clone())
+    ];
+
+    // The first fixes win over the ones overlapping them, all of a fix's edits are skipped together
+    let fixed = apply_diagnostics(&source, &diagnostics);
+    assert_eq!(fixed.content, "foo qux;\nbaz;");
+    assert_eq!(fixed.applied, vec![rename, semicolon]);
+    assert_eq!(fixed.skipped, vec![whole, both.clone()]);
+
+    let fixed = apply(&source, [both]);
+    assert_eq!(fixed.content, "baz;;\nfoo bar;");
+
+    // Insertions at the same place conflict, edits outside of the source are skipped
+    let start = Location { file: String::from("main.ng"), line: 0, column: 0 };
+    let fixed = apply(&source, [
+        Fix::new("a").with_edit(Edit::insert(start.clone(), "a ")),
+        Fix::new("b").with_edit(Edit::insert(start, "b ")),
+        Fix::new("c").with_edit(Edit::insert(Location { file: String::from("other.ng"), line: 0, column: 0 }, "c ")),
+        Fix::new("d").with_edit(Edit::insert(Location { file: String::from("main.ng"), line: 5, column: 0 }, "d "))
+    ]);
+
+    assert_eq!(fixed.content, "a foo bar;;\nbaz;");
+    assert_eq!(fixed.skipped.iter().map(|fix| fix.message.as_str()).collect::<Vec<&str>>(), vec!["b", "c", "d"]);
+    assert!(apply(&source, []).unchanged());
+
+    // Literals over several lines end on their last line
+    let source = Source::new("main.ng", String::from("a /* b\r\n c */ d"));
+    let comment = Token::new(Location { file: String::from("main.ng"), line: 0, column: 2 }, Kind::Name, String::from("/* b\r\n c */"));
+    assert_eq!(comment.span().end, Location { file: String::from("main.ng"), line: 1, column: 5 });
+
+    let fixed = apply(&source, [Fix::new("Remove the comment").with_edit(Edit::replace_tokens(&[comment], "").unwrap())]);
+    assert_eq!(fixed.content, "a  d");
+}
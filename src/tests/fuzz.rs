@@ -0,0 +1,79 @@
+use crate::{fuzz::*, lexer::*, parser::*, regex::*};
+
+#[derive(PartialEq, PartialOrd, Eq, Hash, Debug, Copy, Clone)]
+enum Kind{ Print, Number, Plus, End }
+
+impl Symbol for Kind{}
+impl TokenKind for Kind{}
+
+// print Number (+ Number)* ; and (Number | +) ;
+// Boxed as the nodes of a Parser are
+#[allow(clippy::vec_box)]
+fn nodes() -> Vec<Box<ParserNode<Kind>>>{
+    let once = Quantifier::Exactly(1);
+
+    vec![
+        Box::new(ParserNode{
+            regex: Regex::new()
+                .then(RegexElement::Item(Kind::Print, once))
+                .then(RegexElement::Item(Kind::Number, once))
+                .then(RegexElement::Group(vec![RegexElement::Item(Kind::Plus, once), RegexElement::Item(Kind::Number, once)], Quantifier::ZeroOrMany))
+                .then(RegexElement::Item(Kind::End, once)),
+            parser: Box::new(|_| Ok(AST{ kind: Kind::Print, children: vec![] }))
+        }),
+        Box::new(ParserNode{
+            regex: Regex::new().then(RegexElement::Set(Kind::Number, Kind::Plus, once)).then(RegexElement::Item(Kind::End, once)),
+            parser: Box::new(|_| Ok(AST{ kind: Kind::Number, children: vec![] }))
+        })
+    ]
+}
+
+#[test]
+fn random_programs(){
+    let mut rng = Rng::new(42);
+    let draws = (0..4).map(|_| rng.next_u64()).collect::<Vec<u64>>();
+
+    let mut same = Rng::new(42);
+    assert_eq!((0..4).map(|_| same.next_u64()).collect::<Vec<u64>>(), draws);
+    assert!((0..100).all(|_| rng.below(3) < 3));
+    assert_eq!(rng.pick::<u8>(&[]), None);
+
+    let nodes = nodes();
+    let kinds = [Kind::Print, Kind::Number, Kind::Plus, Kind::End];
+    let generate = |seed| ProgramGenerator::new(seed).with_kinds(&kinds).with_max_repeat(2).programs(&nodes, 4).take(20).collect::<Vec<Vec<Token<Kind>>>>();
+
+    let programs = generate(3);
+    assert_eq!(generate(3), programs);
+    assert_ne!(generate(4), programs);
+
+    // Every program parses, statement after statement
+    for program in &programs{
+        assert_eq!(program.iter().filter(|t| t.kind == Kind::End).count(), 4);
+        assert!(program.iter().all(|t| t.origin == Origin::Expanded && t.literal == format!("{:?}", t.kind)));
+
+        let mut parser = Parser::new(program);
+        parser.nodes = self::nodes();
+        while !parser.finished(){ assert!(parser.parse_with_node().is_ok()); }
+    }
+
+    // The nodes against a hand-written parser, then against one that forgets the sums
+    let by_nodes = |program:&[Token<Kind>]| {
+        let mut parser = Parser::new(program);
+        parser.nodes = self::nodes();
+        std::iter::from_fn(|| parser.parse_with_node().ok().map(|ast| ast.kind)).collect::<Vec<Kind>>()
+    };
+    let by_hand = |program:&[Token<Kind>]| program.split(|t| t.kind == Kind::End)
+        .filter(|statement| !statement.is_empty())
+        .map(|statement| if statement[0].kind == Kind::Print { Kind::Print } else { Kind::Number })
+        .collect::<Vec<Kind>>();
+    let no_sums = |program:&[Token<Kind>]| if program.iter().any(|t| t.kind == Kind::Plus) { vec![] } else { by_hand(program) };
+
+    assert!(differential(programs.clone(), by_nodes, by_hand).is_empty());
+
+    let disagreements = differential(programs.clone(), by_nodes, no_sums);
+    assert!(!disagreements.is_empty());
+    assert!(disagreements.iter().all(|d| d.program == programs[d.index] && d.right.is_empty() && d.left.len() == 4));
+
+    // No node can be matched without the kinds of its sets
+    assert_eq!(ProgramGenerator::new(3).statement(&nodes[1..]), None);
+}
@@ -30,8 +30,8 @@ fn node_lexing(){
     let result1:(&[char], Option<Token<TokenType>>) = (&['h', 'e', 'l', 'l', 'o', ' ', 'w', 'o', 'r', 'l', 'd'], None);
     let result2:(&[char], Option<Token<TokenType>>) = (&[' '], None);
     let result3:(&[char], Option<Token<TokenType>>) = (&['-', '1', '0', '°', 'C'], None);
-    let result4:(&[char], Option<Token<TokenType>>) = (&[' ', '+', ' ', '3', '5', '9'], Some(Token{location: virtual_location.clone(), kind: TokenType::UINT, literal: "1256".to_string()}) );
-    let result5:(&[char], Option<Token<TokenType>>) = (&['_', 'c', 'o', 'b', 'r', 'a', ' ', '(', ')', ' ', 'f', 'u', 'n', 'c', ' ', 'l', 'e', 't', ' ', 'i'], Some(Token{location: virtual_location.clone(), kind: TokenType::UINT, literal: "30".to_string()}) );
+    let result4:(&[char], Option<Token<TokenType>>) = (&[' ', '+', ' ', '3', '5', '9'], Some(Token::new(virtual_location.clone(), TokenType::UINT, "1256".to_string())) );
+    let result5:(&[char], Option<Token<TokenType>>) = (&['_', 'c', 'o', 'b', 'r', 'a', ' ', '(', ')', ' ', 'f', 'u', 'n', 'c', ' ', 'l', 'e', 't', ' ', 'i'], Some(Token::new(virtual_location.clone(), TokenType::UINT, "30".to_string())) );
 
     assert_eq!(node.tokenize(&candidate1, &virtual_location), result1);
     assert_eq!(node.tokenize(&candidate2, &virtual_location), result2);
@@ -90,14 +90,14 @@ fn file_lexing(){
         LexingResult::Err(errors) => {
             assert_eq!(errors.len(), 8);
             assert_eq!(errors, vec![
-                LexingError{ location:Location { file: "invalid.txt".to_string(), line: 2, column: 2 } },
-                LexingError{ location:Location { file: "invalid.txt".to_string(), line: 2, column: 3 } },
-                LexingError{ location:Location { file: "invalid.txt".to_string(), line: 2, column: 4 } },
-                LexingError{ location:Location { file: "invalid.txt".to_string(), line: 2, column: 5 } },
-                LexingError{ location:Location { file: "invalid.txt".to_string(), line: 2, column: 6 } },
-                LexingError{ location:Location { file: "invalid.txt".to_string(), line: 2, column: 7 } },
-                LexingError{ location:Location { file: "invalid.txt".to_string(), line: 2, column: 8 } },
-                LexingError{ location:Location { file: "invalid.txt".to_string(), line: 2, column: 9 } }
+                LexingError::new(Location { file: "invalid.txt".to_string(), line: 2, column: 2 }),
+                LexingError::new(Location { file: "invalid.txt".to_string(), line: 2, column: 3 }),
+                LexingError::new(Location { file: "invalid.txt".to_string(), line: 2, column: 4 }),
+                LexingError::new(Location { file: "invalid.txt".to_string(), line: 2, column: 5 }),
+                LexingError::new(Location { file: "invalid.txt".to_string(), line: 2, column: 6 }),
+                LexingError::new(Location { file: "invalid.txt".to_string(), line: 2, column: 7 }),
+                LexingError::new(Location { file: "invalid.txt".to_string(), line: 2, column: 8 }),
+                LexingError::new(Location { file: "invalid.txt".to_string(), line: 2, column: 9 })
             ]);
         }
     }
@@ -105,33 +105,515 @@ fn file_lexing(){
     match result3{
         LexingResult::Ok(tokens) => {
             assert_eq!(tokens, vec![
-                Token{ location:Location { file: "basic_math_sheet.txt".to_string(), line: 0, column: 0 },
-                    kind: TokenType::UINT, literal: "10".to_string()
-                },
+                Token::new(
+                    Location { file: "basic_math_sheet.txt".to_string(), line: 0, column: 0 },
+                    TokenType::UINT, "10".to_string()
+                ),
 
-                Token{ location:Location { file: "basic_math_sheet.txt".to_string(), line: 0, column: 2 },
-                    kind: TokenType::PLUS, literal: "+".to_string()
-                },
+                Token::new(
+                    Location { file: "basic_math_sheet.txt".to_string(), line: 0, column: 2 },
+                    TokenType::PLUS, "+".to_string()
+                ),
 
-                Token{ location:Location { file: "basic_math_sheet.txt".to_string(), line: 0, column: 3 },
-                    kind: TokenType::UINT, literal: "53".to_string()
-                },
+                Token::new(
+                    Location { file: "basic_math_sheet.txt".to_string(), line: 0, column: 3 },
+                    TokenType::UINT, "53".to_string()
+                ),
 
-                Token{ location:Location { file: "basic_math_sheet.txt".to_string(), line: 1, column: 0 },
-                    kind: TokenType::UINT, literal: "3".to_string()
-                },
+                Token::new(
+                    Location { file: "basic_math_sheet.txt".to_string(), line: 1, column: 0 },
+                    TokenType::UINT, "3".to_string()
+                ),
 
-                Token{ location:Location { file: "basic_math_sheet.txt".to_string(), line: 1, column: 2 },
-                    kind: TokenType::MINUS, literal: "-".to_string()
-                },
+                Token::new(
+                    Location { file: "basic_math_sheet.txt".to_string(), line: 1, column: 2 },
+                    TokenType::MINUS, "-".to_string()
+                ),
 
-                Token{ location:Location { file: "basic_math_sheet.txt".to_string(), line: 1, column: 4 },
-                    kind: TokenType::UINT, literal: "125".to_string()
-                }
+                Token::new(
+                    Location { file: "basic_math_sheet.txt".to_string(), line: 1, column: 4 },
+                    TokenType::UINT, "125".to_string()
+                )
 
             ]);
         },
         LexingResult::Err(_) => assert!(false)
     }
 
-}
\ No newline at end of file
+}
+#[test]
+fn result_iteration(){
+    let location = Location{ file: "".to_string(), line: 0, column: 0 };
+    let tokens = vec![
+        Token::new(location.clone(), TokenType::UINT, "1".to_string()),
+        Token::new(location.clone(), TokenType::PLUS, "+".to_string()),
+        Token::new(location.clone(), TokenType::UINT, "2".to_string())
+    ];
+
+    let result = LexingResult::Ok(tokens.clone());
+    let uints = result.into_iter()
+        .filter(|t| t.as_ref().is_ok_and(|t| t.kind == TokenType::UINT))
+        .collect::<LexingResult<TokenType>>();
+
+    match uints {
+        LexingResult::Ok(t) => assert_eq!(t, vec![tokens[0].clone(), tokens[2].clone()]),
+        LexingResult::Err(_) => assert!(false)
+    }
+
+    let result:LexingResult<TokenType> = LexingResult::Err(vec![LexingError::new(location.clone())]);
+    let collected = result.into_iter().collect::<Result<Vec<Token<TokenType>>, LexingError>>();
+
+    assert_eq!(collected, Err(LexingError::new(location)));
+}
+
+#[test]
+fn display(){
+    let location = Location{ file: "main.txt".to_string(), line: 3, column: 14 };
+    let token = Token::new(location.clone(), TokenType::UINT, "42".to_string());
+
+    assert_eq!(location.to_string(), "main.txt:3:14");
+    assert_eq!(token.to_string(), "UINT \"42\" @ main.txt:3:14");
+}
+
+#[test]
+fn location_ordering(){
+    let loc = |file:&str, line, column| Location{ file: file.to_string(), line, column };
+
+    let mut locations = vec![loc("b.txt", 0, 0), loc("a.txt", 2, 1), loc("a.txt", 0, 5), loc("a.txt", 2, 0)];
+    locations.sort();
+
+    assert_eq!(locations, vec![loc("a.txt", 0, 5), loc("a.txt", 2, 0), loc("a.txt", 2, 1), loc("b.txt", 0, 0)]);
+
+    let token = Token::new(loc("a.txt", 1, 4), TokenType::UINT, "1256".to_string());
+    let span = token.span();
+
+    assert_eq!(span, Span::new(loc("a.txt", 1, 4), loc("a.txt", 1, 8)));
+    assert!(span.contains(&loc("a.txt", 1, 4)));
+    assert!(span.contains(&loc("a.txt", 1, 7)));
+    assert!(!span.contains(&loc("a.txt", 1, 8)));
+    assert!(!span.contains(&loc("b.txt", 1, 5)));
+
+    assert!(span.contains_span(&Span::new(loc("a.txt", 1, 5), loc("a.txt", 1, 8))));
+    assert!(!span.contains_span(&Span::new(loc("a.txt", 1, 5), loc("a.txt", 2, 0))));
+
+    assert!(span.overlaps(&Span::new(loc("a.txt", 1, 7), loc("a.txt", 3, 0))));
+    assert!(!span.overlaps(&Span::new(loc("a.txt", 1, 8), loc("a.txt", 3, 0))));
+}
+
+#[test]
+fn builder_and_clone(){
+    let lexer = Lexer::<TokenType>::default()
+        .with(LexerNode::new(
+            Regex::new().then(RegexElement::Set('0', '9', Quantifier::OneOrMany)),
+            TokenType::UINT
+        ))
+        .with(LexerNode::new(
+            Regex::new().then(RegexElement::Item('+', Quantifier::Exactly(1))),
+            TokenType::PLUS
+        ));
+
+    let lexer = lexer.with_kind_name(TokenType::UINT, "integer", Some("A positive whole number"));
+    assert_eq!(lexer.names().name(TokenType::UINT), "integer");
+    assert_eq!(lexer.names().name(TokenType::PLUS), "PLUS");
+
+    let copy = lexer.clone();
+
+    let kinds = |result:LexingResult<TokenType>| result.into_iter()
+        .map(|t| t.map(|t| t.kind))
+        .collect::<Result<Vec<TokenType>, LexingError>>();
+
+    assert_eq!(kinds(lexer.tokenize_content("1 + 2".to_string(), "")), Ok(vec![TokenType::UINT, TokenType::PLUS, TokenType::UINT]));
+    assert_eq!(kinds(copy.tokenize_content("1 + 2".to_string(), "")), Ok(vec![TokenType::UINT, TokenType::PLUS, TokenType::UINT]));
+}
+
+#[test]
+fn derived_default(){
+    use crate::parser::{Parser, ParsingError, expression::ExpressionParser};
+
+    #[derive(Default)]
+    struct Language<'a>{
+        lexer: Lexer<TokenType>,
+        parser: Parser<'a, TokenType>,
+        expressions: ExpressionParser<TokenType>,
+        identifier: Regex<char>
+    }
+
+    let mut language = Language::default();
+
+    assert_eq!(language.lexer.tokenize_content("".to_string(), "").into_iter().count(), 0);
+    assert_eq!(language.parser.parse_with_node(), Err(ParsingError::NoTokens));
+    assert_eq!(language.expressions.parse(&[]), None);
+    assert_eq!(language.identifier, Regex::new());
+}
+
+#[test]
+fn shadowed_nodes(){
+    let lexer = Lexer::<TokenType>::new()
+        .with(LexerNode::new(
+            Regex::new().then(RegexElement::Item('+', Quantifier::OneOrMany)),
+            TokenType::PLUS
+        ))
+        .with(LexerNode::new(
+            Regex::new().then(RegexElement::Set('0', '9', Quantifier::OneOrMany)),
+            TokenType::UINT
+        ))
+        .with(LexerNode::new(
+            Regex::new().then(RegexElement::Item('+', Quantifier::Exactly(2))),
+            TokenType::TIMES
+        ))
+        .with(LexerNode::new(
+            Regex::new().then(RegexElement::Item('-', Quantifier::ZeroOrOne)).then(RegexElement::Set('0', '9', Quantifier::OneOrMany)),
+            TokenType::MINUS
+        ));
+
+    assert_eq!(lexer.check(), vec![
+        LexerConflict{ node: 2, kind: TokenType::TIMES, examples: vec![("++".to_string(), 0, TokenType::PLUS)] }
+    ]);
+
+    // A node is shadowed only if all of its inputs are lexed by earlier nodes, sampled or not
+    let not_m = LexerNode::new(Regex::new().then(RegexElement::NoneOf(vec![RegexElement::Item('M', Quantifier::Exactly(1))], Quantifier::Exactly(1))), TokenType::MINUS);
+    let lexer = Lexer::<TokenType>::new()
+        .with(not_m.clone())
+        .with(LexerNode::new(Regex::new().then(RegexElement::Set('0', 'z', Quantifier::Exactly(1))), TokenType::UINT));
+
+    assert!(lexer.check().is_empty());
+    assert_eq!(lexer.tokenize_content("M".to_string(), "").into_iter().map(|t| t.map(|t| t.kind)).collect::<Result<Vec<TokenType>, LexingError>>(), Ok(vec![TokenType::UINT]));
+
+    let lexer = Lexer::<TokenType>::new()
+        .with(not_m)
+        .with(LexerNode::new(Regex::new().then(RegexElement::Set('0', '9', Quantifier::OneOrMany)), TokenType::UINT))
+        .with(LexerNode::new(Regex::new().then(RegexElement::StartOfInput).then(RegexElement::Item('M', Quantifier::Exactly(1))), TokenType::PLUS));
+
+    let conflicts = lexer.check();
+    assert_eq!(conflicts.len(), 1);
+    assert_eq!((conflicts[0].node, conflicts[0].kind), (1, TokenType::UINT));
+    assert!(conflicts[0].examples.iter().all(|(_, node, kind)| (*node, *kind) == (0, TokenType::MINUS)));
+
+    let regex = Regex::new()
+        .then(RegexElement::NoneOf(vec![RegexElement::Item('a', Quantifier::Exactly(1))], Quantifier::Exactly(1)))
+        .then(RegexElement::Set('0', '9', Quantifier::ZeroOrOne));
+
+    assert_eq!(regex.examples(8), vec!["0", "00", "09"]);
+}
+
+#[test]
+fn span_arithmetic(){
+    let loc = |line, column| Location{ file: "a.txt".to_string(), line, column };
+
+    let first = Span::new(loc(0, 2), loc(1, 4));
+    let second = Span::new(loc(1, 0), loc(2, 3));
+
+    assert_eq!(first.merge(&second), Span::new(loc(0, 2), loc(2, 3)));
+    assert_eq!(first.intersect(&second), Some(Span::new(loc(1, 0), loc(1, 4))));
+    assert_eq!(first.intersect(&Span::new(loc(1, 4), loc(1, 6))), None);
+
+    let tokens = vec![
+        Token::new(loc(0, 0), TokenType::UINT, "12".to_string()),
+        Token::new(loc(0, 3), TokenType::PLUS, "+".to_string()),
+        Token::new(loc(0, 5), TokenType::UINT, "345".to_string())
+    ];
+
+    assert_eq!(Span::covering(&tokens), Some(Span::new(loc(0, 0), loc(0, 8))));
+    assert_eq!(Span::covering::<TokenType>(&[]), None);
+
+    let sum = tokens[0].spanned().map(|l| l.parse::<u32>().unwrap())
+        .merge(tokens[2].spanned(), |a, b| a + b.parse::<u32>().unwrap());
+
+    assert_eq!(sum, Spanned::new(357, Span::new(loc(0, 0), loc(0, 8))));
+    assert_eq!(sum.to_string(), "357 @ a.txt:0:0-0:8");
+}
+
+#[test]
+fn identifier_checks(){
+    use crate::identifiers::*;
+
+    let token = |literal:&str, column| Token::new(
+        Location{ file: "".to_string(), line: 0, column },
+        if literal == "+" { TokenType::PLUS } else { TokenType::UINT }, literal.to_string()
+    );
+    let loc = |column| Location{ file: "".to_string(), line: 0, column };
+
+    // pay + pаy + рау + pay + pаy + Ωmega + +
+    let tokens = vec![
+        token("pay", 0), token("pаy", 1), token("рау", 2), token("pay", 3), token("pаy", 4), token("Ωmega", 5), token("+", 6)
+    ];
+
+    let lexer = Lexer::<TokenType>::new().with_identifiers(&[TokenType::UINT]);
+
+    assert_eq!(lexer.check_identifiers(&tokens), vec![
+        IdentifierWarning::Confusable{ identifier: "pаy".to_string(), other: "pay".to_string(), location: loc(1) },
+        IdentifierWarning::Confusable{ identifier: "рау".to_string(), other: "pay".to_string(), location: loc(2) },
+        IdentifierWarning::MixedScript{ identifier: "Ωmega".to_string(), scripts: vec![Script::Latin, Script::Greek], location: loc(5) }
+    ]);
+
+    assert!(Lexer::<TokenType>::new().check_identifiers(&tokens).is_empty());
+    assert_eq!(skeleton("Ι0"), "lO");
+}
+
+#[cfg(feature = "unicode")]
+#[test]
+fn identifier_normalization(){
+    let lexer = Lexer::<TokenType>::new()
+        .with(LexerNode::new(Regex::new().then(RegexElement::NoneOf(vec![RegexElement::Item(' ', Quantifier::Exactly(1))], Quantifier::OneOrMany)), TokenType::UINT))
+        .with_identifiers(&[TokenType::UINT])
+        .with_nfc();
+
+    let LexingResult::Ok(tokens) = lexer.tokenize_content("cafe\u{301} caf\u{e9}".to_string(), "") else { panic!() };
+
+    assert_eq!(tokens[0].literal, tokens[1].literal);
+    assert_eq!(tokens[1].literal, "caf\u{e9}");
+}
+
+#[test]
+fn end_of_input(){
+    let lexer = Lexer::<TokenType>::new()
+        .with(LexerNode::new(Regex::new().then(RegexElement::Set('0', '9', Quantifier::OneOrMany)), TokenType::UINT))
+        .with_eof(TokenType::DIVIDE);
+
+    let eof = |content:&str| match lexer.tokenize_content(content.to_string(), "f") {
+        LexingResult::Ok(tokens) => tokens.last().cloned().unwrap(),
+        LexingResult::Err(_) => panic!()
+    };
+    let loc = |line, column| Location{ file: "f".to_string(), line, column };
+
+    assert_eq!(eof("12 3"), Token::new(loc(0, 4), TokenType::DIVIDE, String::new()).with_origin(Origin::Layout));
+    assert_eq!(eof("12\n3\n").location, loc(2, 0));
+    assert_eq!(eof("12\n 3").location, loc(1, 2));
+    assert_eq!(eof("").location, loc(0, 0));
+}
+
+#[test]
+fn input_limits(){
+    use crate::{limits::*, Reportable};
+
+    let lexer = Lexer::<TokenType>::new()
+        .with(LexerNode::new(Regex::new().then(RegexElement::Set('0', '9', Quantifier::OneOrMany)), TokenType::UINT))
+        .with_limits(Limits::new().with_max_token_length(3).with_max_input_size(16));
+
+    let loc = |column| Location{ file: "f".to_string(), line: 0, column };
+
+    let LexingResult::Err(errors) = lexer.tokenize_content("12 12345 1".to_string(), "f") else { panic!() };
+    assert_eq!(errors, vec![LexingError::new(loc(3)).with_limit(Limit::TokenLength(3))]);
+    assert_eq!(errors[0].message(), "Maximum token length of 3 characters exceeded");
+
+    let LexingResult::Err(errors) = lexer.tokenize_content("1 2 3 4 5 6 7 8 9".to_string(), "f") else { panic!() };
+    assert_eq!(errors, vec![LexingError::new(loc(0)).with_limit(Limit::InputSize(16))]);
+
+    let small = lexer.clone().with_limits(Limits::new().with_max_input_size(8));
+    let LexingResult::Err(errors) = small.tokenize_file("src/tests/basic_math_sheet.txt") else { panic!() };
+    assert_eq!(errors[0].limit, Some(Limit::InputSize(8)));
+
+    assert!(matches!(lexer.tokenize_content("123 4".to_string(), "f"), LexingResult::Ok(_)));
+}
+
+#[test]
+fn long_tokens(){
+    use crate::limits::*;
+    use std::sync::{Arc, atomic::{AtomicUsize, Ordering}};
+
+    // Counts the symbols the string body is tested on
+    let tested = Arc::new(AtomicUsize::new(0));
+    let counter = tested.clone();
+    let body = SymbolPredicate::new(move |c:&char| { counter.fetch_add(1, Ordering::Relaxed); *c != '"' });
+
+    let lexer = Lexer::<TokenType>::new()
+        .with(LexerNode::new(
+            Regex::new()
+                .then(RegexElement::Item('"', Quantifier::Exactly(1)))
+                .then(RegexElement::Predicate(body, Quantifier::ZeroOrMany))
+                .then(RegexElement::Item('"', Quantifier::Exactly(1))),
+            TokenType::DIVIDE
+        ))
+        .with(LexerNode::new(Regex::new().then(RegexElement::Set('0', '9', Quantifier::OneOrMany)), TokenType::UINT))
+        .with_limits(Limits::new().with_max_token_length(8));
+
+    let loc = |line, column| Location{ file: "f".to_string(), line, column };
+    let long = "a".repeat(100_000);
+
+    // Closed or not, a long string is reported once and the rest of its line skipped
+    for content in [format!("1 \"{long}\" 2\n3"), format!("1 \"{long} 2\n3")]{
+        tested.store(0, Ordering::Relaxed);
+
+        let LexingResult::Err(errors) = lexer.tokenize_content(content, "f") else { panic!() };
+        assert_eq!(errors, vec![LexingError::new(loc(0, 2)).with_limit(Limit::TokenLength(8))]);
+        assert!(tested.load(Ordering::Relaxed) < 100);
+    }
+
+    let LexingResult::Ok(tokens) = lexer.tokenize_content("\"123456\" 12345678".to_string(), "f") else { panic!() };
+    assert_eq!(tokens.iter().map(|t| t.kind).collect::<Vec<TokenType>>(), vec![TokenType::DIVIDE, TokenType::UINT]);
+
+    // The assertions see past the symbols read, a number being a size only before its unit
+    let sizes = Lexer::<TokenType>::new()
+        .with(LexerNode::new(crate::regex![set('0'..='9')+, ahead(group[item(' ')*, item('k'), item('b')])], TokenType::TIMES))
+        .with(LexerNode::new(crate::regex![set('0'..='9')+], TokenType::UINT))
+        .with(LexerNode::new(crate::regex![set('a'..='z')+], TokenType::MINUS))
+        .with_limits(Limits::new().with_max_token_length(3));
+
+    let LexingResult::Ok(tokens) = sizes.tokenize_content("12    kb 12".to_string(), "f") else { panic!() };
+    assert_eq!(tokens.iter().map(|t| t.kind).collect::<Vec<TokenType>>(), vec![TokenType::TIMES, TokenType::MINUS, TokenType::UINT]);
+}
+
+#[test]
+fn line_endings(){
+    let lexer = Lexer::<TokenType>::new()
+        .with(LexerNode::new(Regex::new().then(RegexElement::Set('0', '9', Quantifier::OneOrMany)), TokenType::UINT))
+        .with_eof(TokenType::DIVIDE);
+
+    let LexingResult::Ok(tokens) = lexer.tokenize_content("1\r\n 2\r3\n\r\n4\r".to_string(), "f") else { panic!() };
+    let locations = tokens.iter().map(|t| (t.location.line, t.location.column)).collect::<Vec<_>>();
+
+    assert_eq!(locations, vec![(0, 0), (1, 1), (2, 0), (4, 0), (5, 0)]);
+}
+
+#[test]
+fn token_categories(){
+    #[derive(PartialEq, PartialOrd, Eq, Hash, Copy, Clone, Debug)]
+    enum Kind{ Let, Equal, Number, Space, Name }
+
+    impl Symbol for Kind{}
+    impl TokenKind for Kind{}
+    impl TokenCategory for Kind{
+        fn is_keyword(&self) -> bool{ *self == Kind::Let }
+        fn is_operator(&self) -> bool{ *self == Kind::Equal }
+        fn is_literal(&self) -> bool{ *self == Kind::Number }
+        fn is_trivia(&self) -> bool{ *self == Kind::Space }
+    }
+
+    let node = |regex:Regex<char>, kind| LexerNode::new(regex, kind);
+    let word = |w:&str| w.chars().fold(Regex::new(), |regex, c| regex.then(RegexElement::Item(c, Quantifier::Exactly(1))));
+    let lexer = Lexer::new()
+        .with(node(word("let"), Kind::Let))
+        .with(node(word("="), Kind::Equal))
+        .with(node(Regex::new().then(RegexElement::Set('0', '9', Quantifier::OneOrMany)), Kind::Number))
+        .with(node(Regex::new().then(RegexElement::Item(' ', Quantifier::OneOrMany)), Kind::Space))
+        .with(node(Regex::new().then(RegexElement::Set('a', 'z', Quantifier::OneOrMany)), Kind::Name));
+
+    let LexingResult::Ok(tokens) = lexer.tokenize_content("let x = 12".to_string(), "main") else { panic!("lexing failed") };
+
+    let kinds = without_trivia(&tokens).iter().map(|t| t.kind).collect::<Vec<Kind>>();
+    assert_eq!(kinds, vec![Kind::Let, Kind::Name, Kind::Equal, Kind::Number]);
+
+    let categories = highlights(&tokens).into_iter().map(|(span, category)| (span.start.column, span.end.column, category)).collect::<Vec<_>>();
+    assert_eq!(categories, vec![
+        (0, 3, Category::Keyword), (3, 4, Category::Trivia), (5, 6, Category::Trivia),
+        (6, 7, Category::Operator), (7, 8, Category::Trivia), (8, 10, Category::Literal)
+    ]);
+
+    let error = crate::parser::ParsingError::UnexpectedToken { expected: Some(Kind::Let), got: Some(Kind::Name), location: tokens[0].location.clone() };
+    assert_eq!(error.message_with_categories(&KindNames::new()), "Expected keyword `Let` but found `Name`");
+}
+
+#[test]
+fn resolution_order(){
+    let digits = || LexerNode::new(Regex::new().then(RegexElement::Set('0', '9', Quantifier::OneOrMany)), TokenType::UINT);
+    let sign = || LexerNode::new(Regex::new().then(RegexElement::Item('-', Quantifier::Exactly(1))), TokenType::MINUS);
+    let signed = || LexerNode::new(Regex::new()
+        .then(RegexElement::Item('-', Quantifier::Exactly(1)))
+        .then(RegexElement::Set('0', '9', Quantifier::OneOrMany)), TokenType::TIMES);
+
+    let kinds = |lexer:&Lexer<TokenType>, content:&str| match lexer.tokenize_content(content.to_string(), "") {
+        LexingResult::Ok(tokens) => tokens.iter().map(|t| t.kind).collect::<Vec<TokenType>>(),
+        LexingResult::Err(_) => vec![]
+    };
+
+    // The first registered node wins at each position, even after another node made a token
+    let mut lexer = Lexer::new().with(sign()).with(digits()).with(signed());
+    assert_eq!(kinds(&lexer, "1-2"), vec![TokenType::UINT, TokenType::MINUS, TokenType::UINT]);
+    assert_eq!(lexer.check().len(), 1);
+
+    assert!(lexer.move_node(2, 0));
+    assert_eq!(lexer.position(TokenType::TIMES), Some(0));
+    assert_eq!(kinds(&lexer, "1-2"), vec![TokenType::UINT, TokenType::TIMES]);
+
+    assert_eq!(lexer.remove_node(0).map(|node| node.kind()), Some(TokenType::TIMES));
+    assert!(lexer.remove_node(5).is_none());
+    lexer.insert_node(0, LexerNode::new(Regex::new().then(RegexElement::Item('-', Quantifier::OneOrMany)), TokenType::DIVIDE));
+    assert_eq!(lexer.nodes().iter().map(|node| node.kind()).collect::<Vec<TokenType>>(), vec![TokenType::DIVIDE, TokenType::MINUS, TokenType::UINT]);
+    assert_eq!(kinds(&lexer, "--2"), vec![TokenType::DIVIDE, TokenType::UINT]);
+}
+
+#[test]
+fn token_origins(){
+    use crate::{cache::{decode_tokens, encode_tokens}, quote::Quote};
+
+    #[derive(PartialEq, PartialOrd, Eq, Hash, Copy, Clone, Debug)]
+    enum Kind{ Number, End }
+
+    impl Symbol for Kind{}
+    impl TokenKind for Kind{}
+    impl TokenCategory for Kind{
+        fn is_literal(&self) -> bool{ *self == Kind::Number }
+        fn is_trivia(&self) -> bool{ *self == Kind::End }
+    }
+
+    let lexer = Lexer::new()
+        .with(LexerNode::new(Regex::new().then(RegexElement::Set('0', '9', Quantifier::OneOrMany)), Kind::Number))
+        .with_eof(Kind::End);
+
+    let LexingResult::Ok(tokens) = lexer.tokenize_content("1 23".to_string(), "main") else { panic!("lexing failed") };
+    assert_eq!(tokens.iter().map(|t| t.origin).collect::<Vec<Origin>>(), vec![Origin::Written, Origin::Written, Origin::Layout]);
+    assert!(!tokens[0].is_synthetic() && tokens[2].is_synthetic());
+
+    // The end of input is never highlighted
+    assert_eq!(highlights(&tokens).len(), 2);
+
+    let bytes = encode_tokens(&tokens, &[Kind::Number, Kind::End], 7).unwrap();
+    assert_eq!(decode_tokens(&bytes, &[Kind::Number, Kind::End], 7), Some(tokens.clone()));
+
+    let quoted = Quote::new("macro").token(Kind::Number, "4").splice(&tokens[..1]).text(&lexer, "5").unwrap().build();
+    assert_eq!(quoted.iter().map(|t| t.origin).collect::<Vec<Origin>>(), vec![Origin::Expanded, Origin::Written, Origin::Expanded]);
+}
+
+#[test]
+fn normalized_keywords(){
+    use crate::cache::{decode_tokens, encode_tokens};
+
+    #[derive(PartialEq, PartialOrd, Eq, Hash, Copy, Clone, Debug)]
+    enum Kind{ If, Name }
+
+    impl Symbol for Kind{}
+    impl TokenKind for Kind{}
+
+    let word = |text:&str| text.chars().fold(Regex::new(), |regex, c| regex.then(RegexElement::Item(c, Quantifier::Exactly(1))));
+    let lexer = Lexer::new()
+        .with(LexerNode::new(word("if").case_insensitive(true), Kind::If))
+        .with(LexerNode::new(Regex::new().then(RegexElement::Class(vec!['a'.into(), 'A'.into()], Quantifier::OneOrMany)), Kind::Name))
+        .with_normalized_keywords(&[Kind::If], str::to_lowercase);
+
+    let LexingResult::Ok(tokens) = lexer.tokenize_content("IF If if Aa".to_string(), "main") else { panic!("lexing failed") };
+
+    assert_eq!(tokens.iter().map(|t| t.literal.as_str()).collect::<Vec<&str>>(), vec!["IF", "If", "if", "Aa"]);
+    assert_eq!(tokens.iter().map(|t| t.normalized()).collect::<Vec<&str>>(), vec!["if", "if", "if", "Aa"]);
+
+    // Only the normalized forms differing from the literals are stored
+    assert_eq!(tokens[0], Token::new(Location { file: "main".to_string(), line: 0, column: 0 }, Kind::If, "IF".to_string()).with_normalized("if".to_string()));
+    assert_eq!(tokens[2].normalized, None);
+    assert_eq!(tokens[3].normalized, None);
+
+    let bytes = encode_tokens(&tokens, &[Kind::If, Kind::Name], 7).unwrap();
+    assert_eq!(decode_tokens(&bytes, &[Kind::If, Kind::Name], 7), Some(tokens));
+}
+
+#[test]
+fn expected_names(){
+    use crate::Reportable;
+
+    let float = crate::regex![set('0'..='9')+, item('.'), set('0'..='9')+].with_name("float literal");
+    let string = crate::regex![item('"'), none_of[item('"')]*, item('"')].with_name("string literal");
+    let lexer = Lexer::new()
+        .with(LexerNode::new(float, TokenType::UINT))
+        .with(LexerNode::new(string, TokenType::PLUS))
+        .with(LexerNode::new(crate::regex![item('-')], TokenType::MINUS));
+
+    let LexingResult::Err(errors) = lexer.tokenize_content("\"ab 1. -".to_string(), "main") else { panic!("lexing succeeded") };
+
+    // Only the named nodes that could start a token on the symbol are expected
+    assert_eq!(errors[0].expected, vec!["string literal"]);
+    assert_eq!(errors[0].message(), "Failed to parse token, expected string literal");
+    assert_eq!(errors[3].expected, vec!["float literal"]);
+    assert!(errors[1].expected.is_empty());
+    assert_eq!(errors[1].message(), "Failed to parse token");
+
+    let both = LexingError::new(Location::generated("main", 0)).with_expected(vec!["a".to_string(), "b".to_string(), "c".to_string()]);
+    assert_eq!(both.message(), "Failed to parse token, expected a, b or c");
+}
@@ -1,3 +1,24 @@
+// The original tests predate the lint gate
+#[allow(clippy::bool_assert_comparison)]
 mod regex;
+#[allow(clippy::upper_case_acronyms, clippy::assertions_on_constants)]
 mod lexer;
-mod parser;
\ No newline at end of file
+#[allow(clippy::vec_box, clippy::assertions_on_constants)]
+mod parser;
+mod expression;
+mod source;
+mod suggestion;
+mod cache;
+mod analysis;
+mod bytes;
+mod quote;
+mod numbers;
+mod escapes;
+mod pipeline;
+mod compact;
+mod config;
+mod suite;
+mod fuzz;
+mod warnings;
+mod fixes;
+mod stats;
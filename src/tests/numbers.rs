@@ -0,0 +1,73 @@
+use crate::{lexer::*, numbers::*, regex::Symbol};
+
+#[derive(PartialEq, PartialOrd, Eq, Hash, Copy, Clone, Debug)]
+enum TokenType{
+    Number,
+    Name,
+    Dot
+}
+
+impl Symbol for TokenType{}
+impl TokenKind for TokenType{}
+
+fn format() -> NumberFormat{
+    NumberFormat::new()
+        .with_radix_prefixes()
+        .with_underscores()
+        .with_fractions()
+        .with_exponents()
+        .with_suffixes(&["u", "u8", "f32"])
+}
+
+fn location(column:usize) -> Location{
+    Location{ file: "".to_string(), line: 0, column }
+}
+
+#[test]
+fn number_lexing(){
+    use crate::regex::*;
+
+    let lexer = Lexer::new()
+        .with(format().node(TokenType::Number))
+        .with(LexerNode::new(Regex::new().then(RegexElement::Set('a', 'z', Quantifier::OneOrMany)), TokenType::Name))
+        .with(LexerNode::new(Regex::new().then(RegexElement::Item('.', Quantifier::Exactly(1))), TokenType::Dot));
+
+    let LexingResult::Ok(tokens) = lexer.tokenize_content("0x1f32 0b102 3.max 1e 2.5E+3f32 1_000u 0o".to_string(), "") else { panic!() };
+
+    let literals = tokens.iter().map(|t| (t.kind, t.literal.as_str())).collect::<Vec<(TokenType, &str)>>();
+    assert_eq!(literals, vec![
+        (TokenType::Number, "0x1f32"),
+        (TokenType::Number, "0b10"), (TokenType::Number, "2"),
+        (TokenType::Number, "3"), (TokenType::Dot, "."), (TokenType::Name, "max"),
+        (TokenType::Number, "1"), (TokenType::Name, "e"),
+        (TokenType::Number, "2.5E+3f32"),
+        (TokenType::Number, "1_000u"),
+        (TokenType::Number, "0"), (TokenType::Name, "o")
+    ]);
+}
+
+#[test]
+fn number_values(){
+    let format = format();
+    let parse = |literal:&str| format.parse(literal, &location(0));
+
+    assert_eq!(parse("0x1f32"), Ok(Number{ value: NumberValue::Integer(0x1f32), radix: 16, suffix: None }));
+    assert_eq!(parse("0B1_01u8"), Ok(Number{ value: NumberValue::Integer(5), radix: 2, suffix: Some("u8".to_string()) }));
+    assert_eq!(parse("0o777").map(|n| n.value), Ok(NumberValue::Integer(0o777)));
+    assert_eq!(parse("2.5E+3f32"), Ok(Number{ value: NumberValue::Float(2500.0), radix: 10, suffix: Some("f32".to_string()) }));
+    assert_eq!(parse("1e-2").map(|n| n.value), Ok(NumberValue::Float(0.01)));
+    assert_eq!(parse("007").map(|n| n.value), Ok(NumberValue::Integer(7)));
+
+    assert_eq!(parse("0x"), Err(NumberError::MissingDigits(location(2))));
+    assert_eq!(parse("3."), Err(NumberError::MissingDigits(location(2))));
+    assert_eq!(parse("1e+"), Err(NumberError::MissingDigits(location(3))));
+    assert_eq!(parse("_1"), Err(NumberError::MissingDigits(location(0))));
+    assert_eq!(parse("12i32"), Err(NumberError::UnexpectedCharacter(location(2))));
+    assert_eq!(parse(&"9".repeat(40)), Err(NumberError::Overflow(location(0))));
+
+    // Without the options, only decimal integers
+    let plain = NumberFormat::new();
+    assert_eq!(plain.parse("1_0", &location(4)), Err(NumberError::UnexpectedCharacter(location(5))));
+    assert_eq!(plain.parse("0x1", &location(0)), Err(NumberError::UnexpectedCharacter(location(1))));
+    assert_eq!(plain.parse("1.5", &location(0)), Err(NumberError::UnexpectedCharacter(location(1))));
+}
@@ -15,23 +15,20 @@ impl TokenKind for TokenType{}
 #[test]
 fn dangling_block_end(){
     let tokens = &[
-        Token{
-            kind:TokenType::A,
-            literal: "A".to_string(),
-            location: Location { file: "".to_string(), line: 0, column: 0 }
-        },
-
-        Token{
-            kind:TokenType::B,
-            literal: "B".to_string(),
-            location: Location { file: "".to_string(), line: 1, column: 2 }
-        },
-
-        Token{
-            kind:TokenType::BlockEnd,
-            literal: "}".to_string(),
-            location: Location { file: "".to_string(), line: 1, column: 3 }
-        }
+        Token::new(
+            Location { file: "".to_string(), line: 0, column: 0 },
+            TokenType::A, "A".to_string()
+        ),
+
+        Token::new(
+            Location { file: "".to_string(), line: 1, column: 2 },
+            TokenType::B, "B".to_string()
+        ),
+
+        Token::new(
+            Location { file: "".to_string(), line: 1, column: 3 },
+            TokenType::BlockEnd, "}".to_string()
+        )
     ];
 
     let mut parser = Parser::new(tokens);
@@ -86,66 +83,56 @@ fn dangling_block_end(){
 fn block_parsing(){
 
     let tokens = &[
-       Token{
-            kind:TokenType::A,
-            literal: "A".to_string(),
-            location: Location { file: "".to_string(), line: 0, column: 0 }
-        },
-
-        Token{
-            kind:TokenType::B,
-            literal: "B".to_string(),
-            location: Location { file: "".to_string(), line: 0, column: 2 }
-        },
-
-        Token{
-            kind:TokenType::BlockBegin,
-            literal: "{".to_string(),
-            location: Location { file: "".to_string(), line: 0, column: 3 }
-        },
-
-        Token{
-            kind:TokenType::A,
-            literal: "A".to_string(),
-            location: Location { file: "".to_string(), line: 1, column: 0 }
-        },
-
-        Token{
-            kind:TokenType::B,
-            literal: "B".to_string(),
-            location: Location { file: "".to_string(), line: 1, column: 2 }
-        },
-
-        Token{
-            kind:TokenType::BlockBegin,
-            literal: "{".to_string(),
-            location: Location { file: "".to_string(), line: 1, column: 3 }
-        },
-
-        Token{
-            kind:TokenType::B,
-            literal: "B".to_string(),
-            location: Location { file: "".to_string(), line: 1, column: 4 }
-        },
-
-
-        Token{
-            kind:TokenType::BlockEnd,
-            literal: "}".to_string(),
-            location: Location { file: "".to_string(), line: 1, column: 5 }
-        },
-
-        Token{
-            kind:TokenType::A,
-            literal: "A".to_string(),
-            location: Location { file: "".to_string(), line: 2, column: 0 }
-        },
-
-        Token{
-            kind:TokenType::BlockEnd,
-            literal: "}".to_string(),
-            location: Location { file: "".to_string(), line: 3, column: 0 }
-        },
+       Token::new(
+            Location { file: "".to_string(), line: 0, column: 0 },
+            TokenType::A, "A".to_string()
+        ),
+
+        Token::new(
+            Location { file: "".to_string(), line: 0, column: 2 },
+            TokenType::B, "B".to_string()
+        ),
+
+        Token::new(
+            Location { file: "".to_string(), line: 0, column: 3 },
+            TokenType::BlockBegin, "{".to_string()
+        ),
+
+        Token::new(
+            Location { file: "".to_string(), line: 1, column: 0 },
+            TokenType::A, "A".to_string()
+        ),
+
+        Token::new(
+            Location { file: "".to_string(), line: 1, column: 2 },
+            TokenType::B, "B".to_string()
+        ),
+
+        Token::new(
+            Location { file: "".to_string(), line: 1, column: 3 },
+            TokenType::BlockBegin, "{".to_string()
+        ),
+
+        Token::new(
+            Location { file: "".to_string(), line: 1, column: 4 },
+            TokenType::B, "B".to_string()
+        ),
+
+
+        Token::new(
+            Location { file: "".to_string(), line: 1, column: 5 },
+            TokenType::BlockEnd, "}".to_string()
+        ),
+
+        Token::new(
+            Location { file: "".to_string(), line: 2, column: 0 },
+            TokenType::A, "A".to_string()
+        ),
+
+        Token::new(
+            Location { file: "".to_string(), line: 3, column: 0 },
+            TokenType::BlockEnd, "}".to_string()
+        ),
     ];
 
 
@@ -236,4 +223,766 @@ fn block_parsing(){
         }
     }
 
-}
\ No newline at end of file
+}
+#[test]
+fn preorder_iteration(){
+    let ast = AST{ kind: TokenType::BlockBegin, children: vec![
+        AST{ kind: TokenType::A, children: vec![] },
+        AST{ kind: TokenType::BlockBegin, children: vec![
+            AST{ kind: TokenType::B, children: vec![] },
+            AST{ kind: TokenType::BlockEnd, children: vec![] }
+        ] },
+        AST{ kind: TokenType::BlockEnd, children: vec![] }
+    ] };
+
+    let kinds = (&ast).into_iter().map(|node| node.kind).collect::<Vec<TokenType>>();
+
+    assert_eq!(kinds, vec![
+        TokenType::BlockBegin, TokenType::A,
+        TokenType::BlockBegin, TokenType::B, TokenType::BlockEnd,
+        TokenType::BlockEnd
+    ]);
+    assert_eq!(ast.iter().count(), 6);
+}
+
+#[test]
+fn display(){
+    let ast = AST{ kind: TokenType::BlockBegin, children: vec![
+        AST{ kind: TokenType::A, children: vec![] },
+        AST{ kind: TokenType::BlockBegin, children: vec![
+            AST{ kind: TokenType::B, children: vec![] }
+        ] }
+    ] };
+
+    assert_eq!(ast.to_string(), "BlockBegin(A BlockBegin(B))");
+    assert_eq!(AST{ kind: TokenType::A, children: vec![] }.to_string(), "A");
+}
+
+#[test]
+fn skipping(){
+    let tokens = [TokenType::A, TokenType::A, TokenType::B, TokenType::A].map(|kind| Token::new(
+        Location { file: "".to_string(), line: 0, column: 0 },
+        kind, format!("{kind:?}")
+    ));
+
+    let mut parser = Parser::new(&tokens);
+
+    assert_eq!(parser.skip_while(TokenType::A), 2);
+    assert_eq!(parser.skip_while(TokenType::A), 0);
+    assert!(parser.on_token(TokenType::B));
+
+    assert_eq!(parser.skip(1), 1);
+    assert_eq!(parser.skip(5), 1);
+    assert!(parser.finished());
+    assert_eq!(parser.skip(1), 0);
+}
+
+#[test]
+fn matching_delimiters(){
+    use TokenType::*;
+
+    // { A { B } } }
+    let tokens = [BlockBegin, A, BlockBegin, B, BlockEnd, BlockEnd, BlockEnd].map(|kind| Token::new(
+        Location { file: "".to_string(), line: 0, column: 0 },
+        kind, format!("{kind:?}")
+    ));
+
+    assert_eq!(find_matching(&tokens, BlockBegin, BlockEnd, 0), Ok(5));
+    assert_eq!(find_matching(&tokens, BlockBegin, BlockEnd, 2), Ok(4));
+    assert_eq!(find_matching(&tokens, BlockBegin, BlockEnd, 4), Ok(2));
+    assert_eq!(find_matching(&tokens, BlockBegin, BlockEnd, 5), Ok(0));
+    assert_eq!(find_matching(&tokens, BlockBegin, BlockEnd, 6), Err(Unmatched::Closer(6)));
+    assert_eq!(find_matching(&tokens[..4], BlockBegin, BlockEnd, 0), Err(Unmatched::Opener(0)));
+    assert_eq!(find_matching(&tokens, BlockBegin, BlockEnd, 1), Err(Unmatched::NotADelimiter(1)));
+    assert_eq!(find_matching(&tokens, BlockBegin, BlockEnd, 9), Err(Unmatched::NotADelimiter(9)));
+
+    // A B A B A A A : identical delimiters pair up without nesting
+    let tokens = [A, B, A, B, A, A, A].map(|kind| Token::new(
+        Location { file: "".to_string(), line: 0, column: 0 },
+        kind, format!("{kind:?}")
+    ));
+
+    assert_eq!(find_matching(&tokens, A, A, 0), Ok(2));
+    assert_eq!(find_matching(&tokens, A, A, 2), Ok(4));
+    assert_eq!(find_matching(&tokens, A, A, 5), Ok(6));
+    assert_eq!(find_matching(&tokens, A, A, 6), Err(Unmatched::Opener(6)));
+    assert_eq!(find_matching(&tokens, A, A, 1), Err(Unmatched::NotADelimiter(1)));
+}
+
+#[test]
+fn kind_sets(){
+    use TokenType::*;
+
+    let blocks = KindSet::of(&[BlockBegin, BlockEnd]);
+    let tokens = [A, BlockEnd].map(|kind| Token::new(
+        Location { file: "".to_string(), line: 0, column: 0 },
+        kind, format!("{kind:?}")
+    ));
+
+    assert_eq!(blocks.len(), 2);
+    assert!(blocks.contains(BlockEnd));
+    assert!(!blocks.contains(A));
+
+    let mut parser = Parser::new(&tokens);
+    assert!(!parser.on_any_of(&blocks));
+    parser.skip(1);
+    assert!(parser.on_any_of(&blocks));
+    parser.skip(1);
+    assert!(!parser.on_any_of(&blocks));
+
+    let mut set = [A, B].into_iter().collect::<KindSet<TokenType>>();
+    set.remove(A);
+    assert!(!set.contains(A));
+    assert!(set.contains(B));
+    assert!(KindSet::<TokenType>::new().is_empty());
+}
+
+#[test]
+fn error_reports(){
+    use crate::{Reportable, ReportStyle};
+
+    let location = Location { file: "src/tests/basic_math_sheet.txt".to_string(), line: 1, column: 2 };
+
+    let unexpected = ParsingError::UnexpectedToken { expected: Some(TokenType::BlockEnd), got: Some(TokenType::A), location: location.clone() };
+    let missing = ParsingError::<TokenType>::UnexpectedToken { expected: Some(TokenType::BlockEnd), got: None, location: location.clone() };
+    let no_tokens = ParsingError::<TokenType>::NoTokens;
+
+    assert_eq!(unexpected.message(), "Expected `BlockEnd` but found `A`");
+    assert_eq!(missing.message(), "Expected `BlockEnd` but found nothing");
+    assert_eq!(unexpected.report(), "Expected `BlockEnd` but found `A` at src/tests/basic_math_sheet.txt 1:2\n3 - 125\n  ^^^^^");
+    assert_eq!(unexpected.to_string(), unexpected.report());
+    assert_eq!(no_tokens.report(), "No tokens to parse");
+
+    let lexing = LexingError::new(Location { file: "virtual_file".to_string(), line: 0, column: 0 });
+    assert_eq!(lexing.report(), "Failed to parse token at virtual_file 0:0");
+
+    let relative = ReportStyle::new().with_root("src");
+    assert_eq!(unexpected.report_with(&relative), "Expected `BlockEnd` but found `A` at tests/basic_math_sheet.txt 1:2\n3 - 125\n  ^^^^^");
+    assert_eq!(lexing.report_with(&relative), "Failed to parse token at virtual_file 0:0");
+
+    let absolute = std::fs::canonicalize("src/tests/basic_math_sheet.txt").unwrap();
+    let linked = ReportStyle::new().with_root("src/tests").with_hyperlinks();
+    assert_eq!(linked.path("src/tests/basic_math_sheet.txt"), format!("\x1b]8;;file://{}\x1b\\basic_math_sheet.txt\x1b]8;;\x1b\\", absolute.display()));
+}
+
+#[test]
+fn folding_and_outline(){
+    use crate::parser::outline::*;
+
+    #[derive(Debug, Clone, PartialEq)]
+    enum Node{ Function(Span), Block(Span), Statement }
+
+    let span = |start_line, end_line| Span::new(
+        Location { file: "".to_string(), line: start_line, column: 0 },
+        Location { file: "".to_string(), line: end_line, column: 1 }
+    );
+
+    let forest = vec![
+        AST{ kind: Node::Function(span(0, 4)), children: vec![
+            AST{ kind: Node::Block(span(0, 4)), children: vec![
+                AST{ kind: Node::Statement, children: vec![] },
+                AST{ kind: Node::Function(span(2, 2)), children: vec![] }
+            ] }
+        ] },
+        AST{ kind: Node::Block(span(5, 5)), children: vec![] },
+        AST{ kind: Node::Block(span(6, 9)), children: vec![] }
+    ];
+
+    let ranges = folding_ranges(&forest, |kind| match kind {
+        Node::Function(s) | Node::Block(s) => Some(s.clone()),
+        Node::Statement => None
+    });
+
+    assert_eq!(ranges, vec![
+        FoldingRange{ start_line: 0, end_line: 4 },
+        FoldingRange{ start_line: 6, end_line: 9 }
+    ]);
+
+    let items = outline(&forest, &|kind| match kind {
+        Node::Function(s) => Some(s.clone()),
+        _ => None
+    });
+
+    assert_eq!(items.len(), 1);
+    assert_eq!(items[0].span, span(0, 4));
+    assert_eq!(items[0].children.len(), 1);
+    assert_eq!(items[0].children[0].node, &forest[0].children[0].children[1]);
+}
+
+#[test]
+fn completion(){
+    use crate::{parser::completion::*, source::Source};
+
+    // {A B
+    //  A}
+    let source = Source::new("", "{A B\n A}".to_string());
+    let tokens = [(TokenType::BlockBegin, 0, 0), (TokenType::A, 0, 1), (TokenType::B, 0, 3), (TokenType::A, 1, 1), (TokenType::BlockEnd, 1, 2)]
+        .map(|(kind, line, column)| Token::new(
+            Location { file: "".to_string(), line, column },
+            kind, if kind == TokenType::BlockBegin { "{".to_string() } else if kind == TokenType::BlockEnd { "}".to_string() } else { format!("{kind:?}") }
+        ));
+
+    let block_span = Span::new(tokens[0].location.clone(), tokens[4].span().end);
+    let forest = vec![
+        AST{ kind: Some(block_span), children: vec![
+            AST{ kind: None, children: vec![] }
+        ] }
+    ];
+
+    // Right after the B
+    let context = completion_context(&source, &tokens, &forest, 4, |span| span.clone()).unwrap();
+    assert_eq!(context.current, Some(&tokens[2]));
+    assert_eq!(context.previous_kinds(5), vec![TokenType::BlockBegin, TokenType::A]);
+    assert_eq!(context.innermost(), Some(&forest[0]));
+
+    // On the space before the second A
+    let context = completion_context(&source, &tokens, &forest, 5, |span| span.clone()).unwrap();
+    assert_eq!(context.current, None);
+    assert_eq!(context.previous_kinds(1), vec![TokenType::B]);
+
+    // After the block
+    let context = completion_context(&source, &tokens, &forest, 8, |span| span.clone()).unwrap();
+    assert_eq!(context.current, Some(&tokens[4]));
+    assert!(context.ancestors.is_empty());
+
+    assert!(completion_context(&source, &tokens, &forest, 20, |span| span.clone()).is_none());
+}
+
+#[test]
+fn event_parsing(){
+    use crate::parser::events::ParseEvent;
+    use TokenType::*;
+
+    let tokens = [A, B, B, BlockEnd].map(|kind| Token::new(
+        Location { file: "".to_string(), line: 0, column: 0 },
+        kind, format!("{kind:?}")
+    ));
+
+    let mut parser = Parser::new(&tokens)
+        .with(ParserNode{
+            regex: Regex::new().then(RegexElement::Item(A, Quantifier::Exactly(1))),
+            parser: Box::new(|_| Ok(AST{ kind: A, children: vec![] }))
+        })
+        .with(ParserNode{
+            regex: Regex::new().then(RegexElement::Item(B, Quantifier::OneOrMany)),
+            parser: Box::new(|_| Ok(AST{ kind: BlockBegin, children: vec![AST{ kind: B, children: vec![] }] }))
+        });
+
+    let mut events = vec![];
+    parser.parse_events(|e| events.push(e));
+
+    let loc = |column| Location { file: "".to_string(), line: 0, column };
+
+    assert_eq!(events, vec![
+        ParseEvent::EnterNode(A, Some(Span::new(loc(0), loc(1)))),
+        ParseEvent::Token(&tokens[0]),
+        ParseEvent::ExitNode,
+
+        ParseEvent::EnterNode(BlockBegin, Some(Span::new(loc(0), loc(1)))),
+        ParseEvent::Token(&tokens[1]),
+        ParseEvent::Token(&tokens[2]),
+        ParseEvent::EnterNode(B, None),
+        ParseEvent::ExitNode,
+        ParseEvent::ExitNode,
+
+        ParseEvent::Error(ParsingError::UnparsedSequence(loc(0)))
+    ]);
+}
+
+#[test]
+fn statement_splitting(){
+    use TokenType::*;
+
+    // Using B as the terminator: A B B { A B A } A B A
+    let tokens = [A, B, B, BlockBegin, A, B, A, BlockEnd, A, B, A].map(|kind| Token::new(
+        Location { file: "".to_string(), line: 0, column: 0 },
+        kind, format!("{kind:?}")
+    ));
+
+    let statements = tokens.split_statements(B, &[(BlockBegin, BlockEnd)]).collect::<Vec<&[Token<TokenType>]>>();
+    assert_eq!(statements, vec![&tokens[0..1], &tokens[3..9], &tokens[10..11]]);
+
+    let flat = tokens.split_statements(B, &[]).count();
+    assert_eq!(flat, 4);
+}
+
+#[test]
+fn parse_trace(){
+    use crate::parser::trace::*;
+    use TokenType::*;
+
+    let tokens = [B, A].map(|kind| Token::new(
+        Location { file: "".to_string(), line: 0, column: 0 },
+        kind, format!("{kind:?}")
+    ));
+
+    let mut parser = Parser::new(&tokens)
+        .with(ParserNode{
+            regex: Regex::new().then(RegexElement::Item(A, Quantifier::Exactly(1))),
+            parser: Box::new(|_| Ok(AST{ kind: A, children: vec![] }))
+        })
+        .with(ParserNode{
+            regex: Regex::new().then(RegexElement::Item(B, Quantifier::Exactly(1))),
+            parser: Box::new(|_| Ok(AST{ kind: B, children: vec![] }))
+        });
+
+    parser.start_trace();
+    while !parser.finished(){ parser.parse_with_node().unwrap(); }
+
+    let trace = parser.take_trace().unwrap();
+    assert_eq!(trace.entries, vec![
+        TraceEntry{ node: 0, start: 0, end: 0, outcome: TraceOutcome::Skipped },
+        TraceEntry{ node: 1, start: 0, end: 1, outcome: TraceOutcome::Parsed },
+        TraceEntry{ node: 0, start: 1, end: 2, outcome: TraceOutcome::Parsed }
+    ]);
+
+    let names = ["a", "\"b\""];
+    assert_eq!(
+        ParseTrace{ entries: trace.entries[1..2].to_vec() }.to_chrome_trace(|node| names[node].to_string()),
+        r#"[{"name":"\"b\"","cat":"parsed","ph":"X","ts":0,"dur":1,"pid":0,"tid":0,"args":{"node":1}}]"#
+    );
+    assert!(parser.take_trace().is_none());
+}
+
+#[test]
+fn round_trip_rendering(){
+    use crate::{parser::cst::*, source::Source};
+    use TokenType::*;
+
+    //   A  B\r\n{ B }  # trailing
+    let source = Source::new("", "  A  B\r\n{ B }  # trailing".to_string());
+    let tokens = [(A, 0, 2), (B, 0, 5), (BlockBegin, 1, 0), (B, 1, 2), (BlockEnd, 1, 4)]
+        .map(|(kind, line, column)| Token::new(
+            Location { file: "".to_string(), line, column },
+            kind, match kind { BlockBegin => "{".to_string(), BlockEnd => "}".to_string(), _ => format!("{kind:?}") }
+        ));
+
+    let mut parser = Parser::new(&tokens)
+        .with(ParserNode{
+            regex: Regex::new().then(RegexElement::Item(A, Quantifier::Exactly(1))).then(RegexElement::Item(B, Quantifier::Exactly(1))),
+            parser: Box::new(|_| Ok(AST{ kind: A, children: vec![AST{ kind: B, children: vec![] }] }))
+        });
+
+    let (forest, errors) = parser.parse_cst(&source).unwrap();
+
+    assert_eq!(errors.len(), 3);
+    assert_eq!(forest.len(), 5);
+    assert_eq!(forest[0].children.len(), 3);
+    assert_eq!(forest[0].children[1].kind, Syntax::Token(TriviaToken{ leading: "  ".to_string(), token: tokens[1].clone() }));
+    assert_eq!(forest[4].kind, Syntax::Trailing("  # trailing".to_string()));
+
+    assert_eq!(render(&forest), source.content());
+
+    let moved = tokens.clone().map(|mut t| { t.location.column += 1; t });
+    assert!(attach_trivia(&source, &moved).is_none());
+}
+
+#[test]
+fn optional_and_repeated_rules(){
+    use TokenType::*;
+
+    let tokens = [A, B, A, B, A, BlockEnd].map(|kind| Token::new(
+        Location { file: "".to_string(), line: 0, column: 0 },
+        kind, format!("{kind:?}")
+    ));
+
+    // A B
+    let pair = |parser:&mut Parser<TokenType>| {
+        for kind in [A, B]{
+            let token = parser.pop().map(|t| t.kind);
+            expect(token, kind, Location { file: "".to_string(), line: 0, column: 0 })?;
+        }
+        Ok(AST{ kind: A, children: vec![AST{ kind: B, children: vec![] }] })
+    };
+
+    let mut parser = Parser::new(&tokens);
+
+    assert_eq!(parser.many(pair).len(), 2);
+    assert_eq!(parser.peek().map(|t| t.kind), Some(A));
+
+    assert_eq!(parser.opt(pair), None);
+    assert_eq!(parser.peek().map(|t| t.kind), Some(A));
+
+    assert_eq!(parser.opt(|p| { p.skip(1); Ok(()) }), Some(()));
+    assert_eq!(parser.many(|_| Ok(())), vec![()]);
+    assert_eq!(parser.peek().map(|t| t.kind), Some(BlockEnd));
+}
+
+#[test]
+fn parsing_limits(){
+    use crate::limits::*;
+    use TokenType::*;
+
+    let tokens = [A, A, A, A].map(|kind| Token::new(
+        Location { file: "".to_string(), line: 0, column: 0 },
+        kind, format!("{kind:?}")
+    ));
+
+    // A nested list of A, recursing once per token
+    fn nested_list(parser:&mut Parser<TokenType>) -> ParsingResult<TokenType>{
+        expect(parser.pop().map(|t| t.kind), TokenType::A, Location { file: "".to_string(), line: 0, column: 0 })?;
+
+        let children = if parser.finished() { vec![] } else { vec![parser.nested(nested_list)?] };
+        Ok(AST{ kind: TokenType::A, children })
+    }
+
+    let mut parser = Parser::new(&tokens).with_limits(Limits::new().with_max_depth(4));
+    assert!(parser.nested(nested_list).is_ok());
+
+    let mut parser = Parser::new(&tokens).with_limits(Limits::new().with_max_depth(3));
+    assert_eq!(parser.nested(nested_list), Err(ParsingError::LimitExceeded{ limit: Limit::Depth(3), location: Some(tokens[3].location.clone()) }));
+
+    let mut parser = Parser::new(&tokens)
+        .with(ParserNode{
+            regex: Regex::new().then(RegexElement::Item(A, Quantifier::Exactly(1))),
+            parser: Box::new(|_| Ok(AST{ kind: A, children: vec![] }))
+        })
+        .with_limits(Limits::new().with_max_tokens(2).with_time_budget(std::time::Duration::from_secs(60)));
+
+    assert!(parser.parse_with_node().is_ok());
+    assert!(parser.parse_with_node().is_ok());
+    assert!(matches!(parser.parse_with_node(), Err(ParsingError::LimitExceeded{ limit: Limit::Tokens(2), .. })));
+}
+
+#[test]
+fn call_graph(){
+    use crate::parser::callgraph::*;
+
+    let node = |kind:(&'static str, &'static str), children| AST{ kind, children };
+    let leaf = |kind| node(kind, vec![]);
+
+    // def main { even() }    def even { odd() }    def odd { even() log() }
+    // def fact { fact() }    def unused { def inner { main() } }    main()
+    let forest = vec![
+        node(("def", "main"), vec![leaf(("call", "even"))]),
+        node(("def", "even"), vec![leaf(("call", "odd"))]),
+        node(("def", "odd"), vec![leaf(("call", "even")), leaf(("call", "log"))]),
+        node(("def", "fact"), vec![node(("block", ""), vec![leaf(("call", "fact"))])]),
+        node(("def", "unused"), vec![node(("def", "inner"), vec![leaf(("call", "main"))])]),
+        leaf(("call", "main"))
+    ];
+
+    let graph = call_graph(&forest,
+        |(kind, name)| (*kind == "def").then_some(*name),
+        |(kind, name)| (*kind == "call").then_some(*name)
+    );
+
+    assert_eq!(graph.functions, vec!["main", "even", "odd", "fact", "unused", "inner"]);
+    assert_eq!(graph.calls, vec![(0, 1), (1, 2), (2, 1), (3, 3), (5, 0)]);
+    assert_eq!(graph.top_level_calls, vec![0]);
+    assert_eq!(graph.unresolved, vec![(Some(2), "log")]);
+
+    assert_eq!(graph.callers(1).collect::<Vec<usize>>(), vec![0, 2]);
+    assert_eq!(graph.components(), vec![vec![1, 2], vec![0], vec![3], vec![4], vec![5]]);
+    assert_eq!(graph.recursive(), vec![vec![1, 2], vec![3]]);
+    assert_eq!(graph.unreachable(&[]), vec![3, 4, 5]);
+}
+
+#[test]
+fn cross_references(){
+    use crate::parser::xref::*;
+
+    let at = |file:&str, line, column| Location { file: file.to_string(), line, column };
+    let word = |file:&str, line, column, length| Span::new(at(file, line, column), at(file, line, column + length));
+    let node = |kind:(&'static str, &'static str, Span), children| AST{ kind, children };
+    let leaf = |kind| node(kind, vec![]);
+
+    // main.ng: def f { def x  x  y }  f  g
+    // lib.ng:  def g { f }
+    let main = vec![
+        node(("def", "f", word("main.ng", 0, 4, 1)), vec![
+            leaf(("def", "x", word("main.ng", 1, 4, 1))),
+            leaf(("ref", "x", word("main.ng", 2, 0, 1))),
+            leaf(("ref", "y", word("main.ng", 3, 0, 1)))
+        ]),
+        leaf(("ref", "f", word("main.ng", 4, 0, 1))),
+        leaf(("ref", "g", word("main.ng", 5, 0, 1))),
+        leaf(("ref", "x", word("main.ng", 6, 0, 1)))
+    ];
+    let lib = vec![node(("def", "g", word("lib.ng", 0, 4, 1)), vec![leaf(("ref", "f", word("lib.ng", 1, 0, 1)))])];
+
+    let index = xref_index([main.as_slice(), lib.as_slice()], |(kind, name, span)| match *kind {
+        "def" => Some(Occurrence::Definition(*name, span.clone())),
+        "ref" => Some(Occurrence::Reference(*name, span.clone())),
+        _ => None
+    });
+
+    assert_eq!(index.definitions.iter().map(|d| (d.name, d.parent)).collect::<Vec<_>>(), vec![("f", None), ("x", Some(0)), ("g", None)]);
+
+    // Go to definition, from references across files and from definitions themselves
+    assert_eq!(index.definition_at(&at("main.ng", 2, 0)), Some(1));
+    assert_eq!(index.definition_at(&at("main.ng", 5, 0)), Some(2));
+    assert_eq!(index.definition_at(&at("lib.ng", 1, 0)), Some(0));
+    assert_eq!(index.definition_at(&at("main.ng", 0, 4)), Some(0));
+    assert_eq!(index.definition_at(&at("main.ng", 0, 5)), None);
+
+    assert_eq!(index.references_of(0).cloned().collect::<Vec<Span>>(), vec![word("main.ng", 4, 0, 1), word("lib.ng", 1, 0, 1)]);
+
+    // x is only visible inside f
+    assert_eq!(index.unresolved().map(|r| r.span.start.line).collect::<Vec<usize>>(), vec![3, 6]);
+    assert_eq!(index.document_symbols("main.ng").collect::<Vec<usize>>(), vec![0, 1]);
+}
+
+#[test]
+fn kind_coverage(){
+    use crate::parser::coverage::*;
+    use TokenType::*;
+
+    let lexer = Lexer::new()
+        .with(LexerNode::new(Regex::new().then(RegexElement::Item('a', Quantifier::Exactly(1))), A))
+        .with(LexerNode::new(Regex::new().then(RegexElement::Item('b', Quantifier::Exactly(1))), B))
+        .with(LexerNode::new(Regex::new().then(RegexElement::Item('{', Quantifier::Exactly(1))), BlockBegin));
+
+    let parser = Parser::new(&[])
+        .with(ParserNode{
+            regex: Regex::new().then(RegexElement::Item(A, Quantifier::ZeroOrOne)).then(RegexElement::Group(vec![RegexElement::Item(BlockEnd, Quantifier::Exactly(1))], Quantifier::ZeroOrMany)),
+            parser: Box::new(|_| Ok(AST{ kind: A, children: vec![] }))
+        });
+
+    assert_eq!(coverage(&lexer, &parser, &[]), Coverage{ unconsumed: vec![B, BlockBegin], unproduced: vec![BlockEnd] });
+    assert_eq!(coverage(&lexer, &parser, &[B]), Coverage{ unconsumed: vec![BlockBegin], unproduced: vec![BlockEnd] });
+
+    let parser = parser.with(ParserNode{
+        regex: Regex::new().then(RegexElement::NoneOf(vec![RegexElement::Item(A, Quantifier::Exactly(1))], Quantifier::OneOrMany)),
+        parser: Box::new(|_| Ok(AST{ kind: B, children: vec![] }))
+    });
+
+    assert_eq!(coverage(&lexer, &parser, &[]).unconsumed, vec![]);
+    assert!(!coverage(&lexer, &parser, &[]).is_complete());
+}
+
+#[test]
+fn fingerprints(){
+    let leaf = |kind:(&'static str, usize)| AST{ kind, children: vec![] };
+
+    // The second value of the kinds stands for a position
+    let first = AST{ kind: ("add", 0), children: vec![leaf(("a", 0)), leaf(("b", 2))] };
+    let moved = AST{ kind: ("add", 5), children: vec![leaf(("a", 5)), leaf(("b", 7))] };
+    let swapped = AST{ kind: ("add", 0), children: vec![leaf(("b", 0)), leaf(("a", 2))] };
+
+    assert_eq!(first.fingerprint(), first.clone().fingerprint());
+    assert_ne!(first.fingerprint(), moved.fingerprint());
+    assert_eq!(first.fingerprint_by(|(name, _)| *name), moved.fingerprint_by(|(name, _)| *name));
+    assert_ne!(first.fingerprint_by(|(name, _)| *name), swapped.fingerprint_by(|(name, _)| *name));
+
+    let prints = first.fingerprints_by(|(name, _)| *name);
+    assert_eq!(prints.len(), 3);
+    assert_eq!(prints[0], first.fingerprint_by(|(name, _)| *name));
+    assert_eq!(prints[2], swapped.children[0].fingerprint_by(|(name, _)| *name));
+
+    // A leaf and the same leaf with a child differ
+    assert_ne!(leaf(("a", 0)).fingerprint(), AST{ kind: ("a", 0), children: vec![leaf(("a", 0))] }.fingerprint());
+}
+
+#[test]
+fn error_nodes(){
+    #[derive(Debug, Hash, Clone, Copy, PartialOrd, PartialEq, Eq)]
+    enum Kind{ A, B, Error }
+
+    impl Symbol for Kind{}
+    impl TokenKind for Kind{}
+    impl ErrorKind for Kind{
+        fn error() -> Self { Kind::Error }
+        fn is_error(&self) -> bool { *self == Kind::Error }
+    }
+
+    let tokens = [Kind::A, Kind::B, Kind::B, Kind::A, Kind::B].map(|kind| Token::new(
+        Location { file: "".to_string(), line: 0, column: 0 },
+        kind, format!("{kind:?}")
+    ));
+
+    let mut parser = Parser::new(&tokens).with(ParserNode{
+        regex: Regex::new().then(RegexElement::Item(Kind::A, Quantifier::Exactly(1))),
+        parser: Box::new(|_| Ok(AST{ kind: Kind::A, children: vec![] }))
+    });
+
+    let (forest, errors) = parser.parse_recovering();
+    assert_eq!(forest.iter().map(|ast| ast.kind).collect::<Vec<Kind>>(), vec![Kind::A, Kind::Error, Kind::A, Kind::Error]);
+    assert_eq!(errors, vec![ParsingError::UnparsedSequence(tokens[1].location.clone()); 2]);
+
+    let tree = AST{ kind: Kind::A, children: vec![
+        AST{ kind: Kind::B, children: vec![] },
+        AST{ kind: Kind::Error, children: vec![AST{ kind: Kind::B, children: vec![] }] },
+        AST::error()
+    ] };
+
+    assert!(tree.has_errors());
+    assert!(!tree.children[0].has_errors());
+    assert_eq!(tree.errors().count(), 2);
+    assert_eq!(tree.iter_valid().map(|ast| ast.kind).collect::<Vec<Kind>>(), vec![Kind::A, Kind::B]);
+}
+
+#[test]
+fn speculative_parsing(){
+    use TokenType::*;
+
+    let token = |kind| Token::new(
+        Location { file: "".to_string(), line: 0, column: 0 },
+        kind, format!("{kind:?}")
+    );
+    let tokens = [A, B, A].map(token);
+
+    let mut parser = Parser::new(&tokens)
+        .with(ParserNode{
+            regex: Regex::new().then(RegexElement::Item(A, Quantifier::Exactly(1))),
+            parser: Box::new(|_| Ok(AST{ kind: A, children: vec![] }))
+        });
+
+    parser.start_trace();
+    assert!(parser.parse_with_node().is_ok());
+
+    let snapshot = parser.snapshot();
+    assert!(parser.parse_with_node().is_err());
+    parser.skip(2);
+    assert!(parser.finished());
+
+    parser.restore(snapshot);
+    assert_eq!(parser.peek().map(|t| t.kind), Some(B));
+
+    assert_eq!(parser.speculate(|p| { p.skip(1); p.parse_with_node().map(|ast| ast.kind) }), Ok(A));
+    assert_eq!(parser.peek().map(|t| t.kind), Some(B));
+
+    // What if the B was an A
+    let edited = [A, A, A].map(token);
+    let parsed = parser.speculate_on(&edited, |p| {
+        let mut parsed = 0;
+        while p.parse_with_node().is_ok() { parsed += 1; }
+        parsed
+    });
+    assert_eq!(parsed, 3);
+
+    assert_eq!(parser.nodes.len(), 1);
+    assert_eq!(parser.peek().map(|t| t.kind), Some(B));
+    assert_eq!(parser.take_trace().unwrap().entries.len(), 1);
+}
+
+#[test]
+fn node_order(){
+    use TokenType::*;
+
+    let tokens = [A, B].map(|kind| Token::new(Location { file: "".to_string(), line: 0, column: 0 }, kind, format!("{kind:?}")));
+
+    // Both nodes apply to A, the parsed kind tells which one won
+    let node = |kind| ParserNode{
+        regex: Regex::new().then(RegexElement::Item(A, Quantifier::Exactly(1))).then(RegexElement::Item(B, Quantifier::ZeroOrOne)),
+        parser: Box::new(move |_| Ok(AST{ kind, children: vec![] }))
+    };
+
+    let mut parser = Parser::new(&tokens).with(node(BlockBegin)).with(node(BlockEnd));
+    assert_eq!(parser.speculate(|p| p.parse_with_node().map(|ast| ast.kind)), Ok(BlockBegin));
+
+    assert!(parser.move_node(1, 0));
+    assert!(!parser.move_node(0, 2));
+    assert_eq!(parser.speculate(|p| p.parse_with_node().map(|ast| ast.kind)), Ok(BlockEnd));
+
+    assert!(parser.remove_node(0).is_some());
+    assert!(parser.remove_node(1).is_none());
+    parser.insert_node(usize::MAX, node(B));
+    assert_eq!(parser.nodes.len(), 2);
+    assert_eq!(parser.parse_with_node().map(|ast| ast.kind), Ok(BlockBegin));
+}
+
+#[test]
+fn unclosed_blocks(){
+    use crate::Reportable;
+    use TokenType::*;
+
+    // { A { B { A } B
+    // {
+    let tokens = [BlockBegin, A, BlockBegin, B, BlockBegin, A, BlockEnd, B, BlockBegin].iter().enumerate().map(|(i, kind)| Token::new(
+        Location { file: "main".to_string(), line: i / 8, column: i % 8 },
+        *kind, format!("{kind:?}")
+    )).collect::<Vec<Token<TokenType>>>();
+
+    let location = |line, column| Location { file: "main".to_string(), line, column };
+
+    let error = Parser::new(&tokens[..8]).slice_block(BlockBegin, BlockEnd).unwrap_err();
+    assert_eq!(error, ParsingError::UnclosedBlock { start: location(0, 0), opener: location(0, 2), stopped: location(0, 7) });
+    assert_eq!(error.location(), Some(location(0, 2)));
+
+    // The innermost is the last one opened
+    let error = Parser::new(&tokens).slice_block(BlockBegin, BlockEnd).unwrap_err();
+    assert!(matches!(error, ParsingError::UnclosedBlock { opener, stopped, .. } if opener == location(1, 0) && stopped == opener));
+
+    // Without inner blocks
+    let error = Parser::new(&tokens[..2]).slice_block(BlockBegin, BlockEnd).unwrap_err();
+    assert!(matches!(error, ParsingError::UnclosedBlock { opener, .. } if opener == location(0, 0)));
+}
+
+#[test]
+fn prefix_parsing(){
+    use crate::parser::trace::*;
+    use TokenType::*;
+
+    // Statements A A B and A B terminated by BlockEnd, each one parsed by a node for its A and another one for the rest
+    let tokens = [A, A, B, BlockEnd, A, B, BlockEnd].map(|kind| Token::new(
+        Location { file: "".to_string(), line: 0, column: 0 },
+        kind, format!("{kind:?}")
+    ));
+
+    let head = ParserNode{
+        regex: Regex::new().then(RegexElement::Item(A, Quantifier::Exactly(1))),
+        parser: Box::new(|_| Ok(AST{ kind: A, children: vec![] }))
+    };
+
+    let (matched, remainder) = head.split(&tokens);
+    assert_eq!((matched, remainder), (&tokens[..1], &tokens[1..]));
+    assert!(head.parse_prefix(&tokens[2..]).is_none());
+
+    let mut parser = Parser::new(&tokens)
+        .with(head)
+        .with(ParserNode{
+            regex: Regex::new().then(RegexElement::Item(B, Quantifier::Exactly(1))),
+            parser: Box::new(|tokens| Ok(AST{ kind: B, children: vec![AST{ kind: tokens[0].kind, children: vec![] }] }))
+        });
+
+    parser.start_trace();
+
+    let mut forest = vec![];
+    for statement in tokens.split_statements(BlockEnd, &[]){
+        let mut rest = statement;
+
+        while !rest.is_empty(){
+            let (ast, remainder) = parser.parse_prefix(rest).unwrap();
+            forest.push(ast.kind);
+            rest = remainder;
+        }
+    }
+
+    assert_eq!(forest, vec![A, A, B, A, B]);
+
+    // The parser's own tokens are untouched and the positions are the ones of the whole tokens
+    assert_eq!(parser.peek().map(|t| t.kind), Some(A));
+    let parsed = parser.take_trace().unwrap().entries.into_iter()
+        .filter(|entry| entry.outcome == TraceOutcome::Parsed)
+        .map(|entry| (entry.node, entry.start, entry.end))
+        .collect::<Vec<(usize, usize, usize)>>();
+    assert_eq!(parsed, vec![(0, 0, 1), (0, 1, 2), (1, 2, 3), (0, 4, 5), (1, 5, 6)]);
+
+    assert!(matches!(parser.parse_prefix(&tokens[3..4]), Err(ParsingError::UnparsedSequence(_))));
+}
+
+#[test]
+fn constructors(){
+    let leaf = AST::leaf(TokenType::A);
+    assert_eq!(leaf, AST{ kind: TokenType::A, children: vec![] });
+
+    assert_eq!(AST::new(TokenType::BlockBegin, vec![leaf.clone()]), AST{ kind: TokenType::BlockBegin, children: vec![leaf] });
+}
+
+#[test]
+fn deep_trees(){
+    // On the 2 MiB stack the standard library gives to its threads
+    std::thread::Builder::new().stack_size(2 << 20).spawn(|| {
+        let chain = |leaf| (0..1_000_000).fold(AST::leaf(leaf), |ast, _| AST::new(1u8, vec![ast]));
+        let ast = chain(0);
+        let other = chain(2);
+
+        let copy = ast.clone();
+        assert_eq!(copy.iter().count(), 1_000_001);
+        assert!(copy == ast);
+        assert!(copy != other);
+
+        for ast in [ast, other, copy]{ ast.dismantle(); }
+    }).unwrap().join().unwrap();
+}
@@ -0,0 +1,92 @@
+use crate::{lexer::*, pipeline::*, regex::*, suite::Diagnostic};
+
+#[derive(PartialEq, PartialOrd, Eq, Hash, Copy, Clone, Debug)]
+enum TokenType{
+    Number,
+    Operator
+}
+
+impl Symbol for TokenType{}
+impl TokenKind for TokenType{}
+
+#[test]
+fn event_log(){
+    let lexer = Lexer::new()
+        .with(LexerNode::new(Regex::new().then(RegexElement::Set('0', '9', Quantifier::OneOrMany)), TokenType::Number))
+        .with(LexerNode::new(Regex::new().then(RegexElement::AnyOf(vec![RegexElement::Item('+', Quantifier::Exactly(1)), RegexElement::Item('-', Quantifier::Exactly(1))], Quantifier::Exactly(1))), TokenType::Operator));
+
+    let mut events = vec![];
+    let mut diagnostics = 0;
+    let mut log = EventLog::new()
+        .with_listener(|event| events.push(event.clone()))
+        .with_listener(|event| if let PipelineEvent::Diagnostic{ .. } = event { diagnostics += 1; });
+
+    log.stage("lex", |log| {
+        assert!(matches!(lexer.tokenize_file_logged("src/tests/basic_math_sheet.txt", log), LexingResult::Ok(_)));
+        assert!(matches!(lexer.tokenize_file_logged("src/tests/invalid.txt", log), LexingResult::Err(_)));
+    });
+
+    drop(log);
+
+    assert_eq!(events.first(), Some(&PipelineEvent::StageStarted("lex".to_string())));
+    assert!(matches!(events.last(), Some(PipelineEvent::StageFinished(stage, _)) if stage == "lex"));
+
+    assert_eq!(events[1], PipelineEvent::FileProcessed{ path: "src/tests/basic_math_sheet.txt".to_string(), diagnostics: 0 });
+    assert_eq!(events[events.len() - 2], PipelineEvent::FileProcessed{ path: "src/tests/invalid.txt".to_string(), diagnostics });
+
+    assert!(diagnostics > 0);
+    assert!(matches!(&events[2], PipelineEvent::Diagnostic{ location: Some(loc), .. } if loc.file == "src/tests/invalid.txt"));
+    assert_eq!(events.len(), diagnostics + 4);
+}
+
+#[test]
+fn batch_directory(){
+    let lexer = Lexer::new()
+        .with(LexerNode::new(Regex::new().then(RegexElement::Set('0', '9', Quantifier::OneOrMany)), TokenType::Number))
+        .with(LexerNode::new(Regex::new().then(RegexElement::Item('/', Quantifier::OneOrMany)), TokenType::Operator));
+
+    let pipeline = || Pipeline::new()
+        .with_stage(|source, artifact:&mut (usize, bool)| match lexer.tokenize_content(source.content().to_string(), source.path()) {
+            LexingResult::Ok(tokens) => { artifact.0 = tokens.len(); vec![] },
+            LexingResult::Err(errors) => errors.iter().map(Diagnostic::error).collect()
+        })
+        .with_stage(|_, artifact| { artifact.1 = true; vec![] });
+
+    // Sub-directories are visited, other extensions are skipped
+    let report = pipeline().run_dir("src/tests", "ng").unwrap();
+    let paths = report.files.iter().map(|file| file.path.replace('\\', "/")).collect::<Vec<String>>();
+    assert_eq!(paths, vec!["src/tests/suite/fail.ng", "src/tests/suite/pass.ng"]);
+
+    // The stages after a failing one are skipped
+    assert!(report.files.iter().all(|file| file.failed() && !file.artifact.1));
+    assert_eq!(report.error_count(), report.diagnostics().count());
+    assert_eq!(report.failed().count(), 2);
+
+    assert_eq!(pipeline().with_parallelism().run_dir("src/tests", "ng").unwrap(), report);
+
+    let mut events = vec![];
+    report.log(&mut EventLog::new().with_listener(|event| events.push(event.clone())));
+    assert_eq!(events.len(), report.error_count() + 2);
+
+    assert!(pipeline().run_dir("src/tests/missing", "ng").is_err());
+
+    // A file that can't be read fails alone, and links to directories aren't followed
+    let dir = std::env::temp_dir().join(format!("neoglot-batch-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::write(dir.join("a.ng"), "1 2").unwrap();
+    std::fs::write(dir.join("b.ng"), [b'1', 0xFF]).unwrap();
+
+    #[cfg(unix)]
+    std::os::unix::fs::symlink(&dir, dir.join("loop")).unwrap();
+
+    let report = pipeline().with_parallelism().run_dir(&dir, "ng").unwrap();
+    std::fs::remove_dir_all(&dir).unwrap();
+
+    assert_eq!(report.files.len(), 2);
+    assert_eq!(report.files[0].artifact, (2, true));
+    assert!(report.files[1].failed() && !report.files[1].artifact.1);
+
+    let unreadable = &report.files[1].diagnostics[0];
+    assert!(unreadable.message.starts_with("Could not read the file"));
+    assert_eq!(unreadable.location.as_ref().map(|loc| loc.file.as_str()), Some(report.files[1].path.as_str()));
+}
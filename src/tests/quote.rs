@@ -0,0 +1,43 @@
+use crate::{lexer::*, quote::*, regex::*};
+
+#[derive(PartialEq, PartialOrd, Eq, Hash, Copy, Clone, Debug)]
+enum TokenType{
+    UInt,
+    Plus,
+    End
+}
+
+impl Symbol for TokenType{}
+impl TokenKind for TokenType{}
+
+fn lexer() -> Lexer<TokenType>{
+    Lexer::new()
+        .with(LexerNode::new(Regex::new().then(RegexElement::Set('0', '9', Quantifier::OneOrMany)), TokenType::UInt))
+        .with(LexerNode::new(Regex::new().then(RegexElement::Item('+', Quantifier::Exactly(1))), TokenType::Plus))
+        .with_eof(TokenType::End)
+}
+
+#[test]
+fn quoting(){
+    let user = Token::new(Location{ file: "main".to_string(), line: 2, column: 4 }, TokenType::UInt, "7".to_string());
+
+    let tokens = Quote::new("macro")
+        .token(TokenType::UInt, "10")
+        .token(TokenType::Plus, "+")
+        .splice(std::slice::from_ref(&user))
+        .text(&lexer(), "+ 3").unwrap()
+        .build();
+
+    assert_eq!(tokens.iter().map(|t| t.kind).collect::<Vec<TokenType>>(), vec![TokenType::UInt, TokenType::Plus, TokenType::UInt, TokenType::Plus, TokenType::UInt]);
+    assert_eq!(tokens[1].location, Location::generated("macro", 3));
+    assert_eq!(tokens[4].location, Location::generated("macro", 7));
+    assert!(!tokens[2].location.is_generated());
+
+    let tokens = Quote::template(&lexer(), "macro", "{} + 1 + {}", &[std::slice::from_ref(&user), std::slice::from_ref(&user)]).unwrap().build();
+    assert_eq!(tokens.len(), 5);
+    assert_eq!(tokens[2].location, Location::generated("macro", 5));
+
+    assert_eq!(Quote::template(&lexer(), "macro", "1 # {}", &[&[user]]), Err(vec![
+        LexingError::new(Location::generated("macro", 2))
+    ]));
+}
@@ -1,5 +1,5 @@
 
-use crate::regex::{Quantifier, Regex, RegexElement};
+use crate::{limits::{Limit, Limits}, regex::{Boundaries, Chunk, ClassItem, Match, Quantifier, Regex, RegexElement, SymbolPredicate}};
 
 type ChrRegex = Regex<char>;
 
@@ -125,7 +125,9 @@ fn number() {
     assert_eq!(regex.r#match(candidate5), true);
     assert_eq!(regex.r#match(candidate6), true);
     assert_eq!(regex.r#match(candidate7), true);
-    assert_eq!(regex.r#match(candidate8), true);
+    // The sign needs digits after it
+    assert_eq!(regex.r#match(candidate8), false);
+    assert_eq!(regex.r#match(candidate8), regex.compile().r#match(candidate8));
 }
 
 #[test]
@@ -164,7 +166,7 @@ fn mail() {
             vec![RegexElement::AnyOf(vec![
                 RegexElement::Set('a', 'z', Quantifier::Exactly(1)),
                 RegexElement::Set('0', '9', Quantifier::Exactly(1)),
-            ])],
+            ], Quantifier::Exactly(1))],
             Quantifier::OneOrMany,
         ))
         .then(RegexElement::Group(
@@ -174,7 +176,7 @@ fn mail() {
                     vec![RegexElement::AnyOf(vec![
                         RegexElement::Set('a', 'z', Quantifier::Exactly(1)),
                         RegexElement::Set('0', '9', Quantifier::Exactly(1)),
-                    ])],
+                    ], Quantifier::Exactly(1))],
                     Quantifier::OneOrMany,
                 ),
             ],
@@ -256,3 +258,1301 @@ fn matched_symbols() {
     assert_eq!(regex.split_first(candidate6), result6);
     assert_eq!(regex.split_first(candidate7), result7);
 }
+
+#[test]
+fn fixed_width() {
+    let hex_digit = RegexElement::AnyOf(vec![
+        RegexElement::Set('0', '9', Quantifier::Exactly(1)),
+        RegexElement::Set('a', 'f', Quantifier::Exactly(1)),
+    ], Quantifier::Exactly(1));
+
+    let hash = ChrRegex::new().then(RegexElement::Group(vec![hex_digit], Quantifier::Exactly(64)));
+    let zeros = ChrRegex::new().then(RegexElement::Item('0', Quantifier::Exactly(64)));
+
+    let candidate1 = &"9f86d081884c7d659a2feaa0c55ad015a3bf4f1b2b0b822cd15d6c15b0f00a08".chars().collect::<Vec<char>>();
+    let candidate2 = &"9f86d081884c7d659a2feaa0c55ad015a3bf4f1b2b0b822cd15d6c15b0f00a0".chars().collect::<Vec<char>>();
+    let candidate3 = &"9f86d081884c7d659a2feaa0c55ad015a3bf4f1b2b0b822cd15d6c15b0f00a08ff".chars().collect::<Vec<char>>();
+    let candidate4 = &"0".repeat(64).chars().collect::<Vec<char>>();
+    let candidate5 = &"0".repeat(70).chars().collect::<Vec<char>>();
+
+
+    assert_eq!(hash.r#match(candidate1), true);
+    assert_eq!(hash.r#match(candidate2), false, "63 digits");
+    assert_eq!(hash.r#match(candidate3), false, "66 digits");
+    assert_eq!(zeros.r#match(candidate4), true);
+    assert_eq!(zeros.r#match(candidate5), false);
+    assert_eq!(zeros.split_first(candidate5), (&candidate5[..64], &candidate5[64..]));
+}
+
+#[test]
+fn limits() {
+    let nested = RegexElement::Group(vec![RegexElement::Group(vec![RegexElement::Item('a', Quantifier::OneOrMany)], Quantifier::Exactly(1))], Quantifier::Exactly(1));
+    let candidate = &"aaaa".chars().collect::<Vec<char>>();
+
+    let regex = ChrRegex::new().then(nested.clone()).with_limits(Limits::new().with_max_depth(3).with_max_tokens(4));
+    assert_eq!(regex.try_match(candidate), Ok(true));
+    assert_eq!(regex.try_split_first(&"aaaaa".chars().collect::<Vec<char>>()), Err(Limit::Tokens(4)));
+
+    let regex = ChrRegex::new().then(nested).with_limits(Limits::new().with_max_depth(2));
+    assert_eq!(regex.try_match(candidate), Err(Limit::Depth(2)));
+    assert_eq!(regex.r#match(candidate), false);
+    assert_eq!(regex.split_first(candidate), (&[][..], &candidate[..]));
+
+    // Each element of a sequence is matched one level deeper
+    let long = vec!['a'; 100_000];
+    let regex = long.iter().fold(ChrRegex::new(), |regex, c| regex.then(RegexElement::Item(*c, Quantifier::Exactly(1))));
+    assert_eq!(regex.try_match(&long), Err(Limit::Stack(crate::limits::DEFAULT_MAX_STACK)));
+    assert_eq!(regex.with_limits(Limits::new().with_max_stack(1 << 16)).try_match(&long), Err(Limit::Stack(65536)));
+
+    // Nested groups, on the 8 MiB stack of a main thread as dropping them recurses
+    std::thread::Builder::new().stack_size(8 << 20).spawn(|| {
+        let nested = (0..20_000).fold(RegexElement::Item('a', Quantifier::Exactly(1)), |e, _| RegexElement::Group(vec![e], Quantifier::Exactly(1)));
+        let regex = ChrRegex::new().then(nested);
+
+        assert_eq!(regex.try_match(&['a']), Err(Limit::Stack(crate::limits::DEFAULT_MAX_STACK)));
+        assert!(!regex.r#match(&['a']));
+    }).unwrap().join().unwrap();
+}
+
+#[test]
+fn compiled() {
+    let number = ChrRegex::new()
+        .then(RegexElement::Item('-', Quantifier::ZeroOrOne))
+        .then(RegexElement::Set('0', '9', Quantifier::OneOrMany))
+        .then(RegexElement::Group(vec![
+            RegexElement::Item('.', Quantifier::Exactly(1)),
+            RegexElement::Set('0', '9', Quantifier::OneOrMany)
+        ], Quantifier::ZeroOrOne));
+
+    let identifier = ChrRegex::new()
+        .then(RegexElement::AnyOf(vec![RegexElement::Set('a', 'z', Quantifier::Exactly(1)), RegexElement::Item('_', Quantifier::Exactly(1))], Quantifier::Exactly(1)))
+        .then(RegexElement::NoneOf(vec![RegexElement::Item(' ', Quantifier::Exactly(1)), RegexElement::Set('(', '+', Quantifier::Exactly(1))], Quantifier::ZeroOrMany));
+
+    // Both engines agree on patterns that never need to give symbols back
+    for regex in [&number, &identifier]{
+        let dfa = regex.compile();
+
+        for candidate in ["", "-", "12", "-0.5", "1.25", "abc", "_é9 x", "a(b", "ü", "-x"]{
+            let candidate = candidate.chars().collect::<Vec<char>>();
+
+            assert_eq!(dfa.r#match(&candidate), regex.r#match(&candidate), "{candidate:?}");
+            assert_eq!(dfa.split_first(&candidate), regex.split_first(&candidate), "{candidate:?}");
+        }
+    }
+
+    // a* a
+    let regex = ChrRegex::new()
+        .then(RegexElement::Item('a', Quantifier::ZeroOrMany))
+        .then(RegexElement::Item('a', Quantifier::Exactly(1)));
+    let candidate = &"aaab".chars().collect::<Vec<char>>();
+
+    assert_eq!(regex.split_first(candidate), (&candidate[..3], &candidate[3..]));
+    assert_eq!(regex.compile().split_first(candidate), (&candidate[..3], &candidate[3..]));
+    assert!(!regex.compile().r#match(&[]));
+
+    // (a)* a
+    let regex = ChrRegex::new()
+        .then(RegexElement::Group(vec![RegexElement::Item('a', Quantifier::Exactly(1))], Quantifier::ZeroOrMany))
+        .then(RegexElement::Item('a', Quantifier::Exactly(1)));
+
+    assert_eq!(regex.split_first(candidate), (&candidate[..3], &candidate[3..]));
+    assert_eq!(regex.compile().split_first(candidate), (&candidate[..3], &candidate[3..]));
+
+    // a|ab
+    let regex = ChrRegex::new().then(RegexElement::AnyOf(vec![
+        RegexElement::Item('a', Quantifier::Exactly(1)),
+        RegexElement::Group(vec![RegexElement::Item('a', Quantifier::Exactly(1)), RegexElement::Item('b', Quantifier::Exactly(1))], Quantifier::Exactly(1))
+    ], Quantifier::Exactly(1)));
+    let candidate = &"ab".chars().collect::<Vec<char>>();
+
+    assert_eq!(regex.split_first(candidate), (&candidate[..1], &candidate[1..]));
+    assert_eq!(regex.compile().split_first(candidate), (&candidate[..], &[][..]));
+
+    // Empty patterns and groups match as by backtracking
+    let empty = [
+        ChrRegex::new(),
+        crate::regex![item('a')].or(ChrRegex::new()),
+        ChrRegex::new().then(RegexElement::Group(vec![], Quantifier::OneOrMany)).then(RegexElement::Item('a', Quantifier::Exactly(1))),
+        ChrRegex::new().then(RegexElement::Group(vec![], Quantifier::ZeroOrOne)).then(RegexElement::Item('a', Quantifier::Exactly(1)))
+    ];
+    for regex in empty{
+        for candidate in [&[][..], &['a'][..]]{ assert_eq!(regex.compile().r#match(candidate), regex.r#match(candidate), "{regex:?} on {candidate:?}"); }
+    }
+
+    // Large automata aren't built
+    use crate::regex::dfa::{CompileError, MAX_STATES};
+
+    assert_eq!(crate::regex![group[item('a'){100}]{100}].try_compile().err(), Some(CompileError::States(MAX_STATES + 3)));
+    assert!(matches!(crate::regex![set('a'..='b')*, item('a'), set('a'..='b'){16}].try_compile(), Err(CompileError::States(states)) if states > MAX_STATES));
+    assert!(crate::regex![set('a'..='b')*, item('a'), set('a'..='b'){8}].try_compile().is_ok());
+
+    let predicates = (0..16).fold(ChrRegex::new(), |regex, i| regex.then(RegexElement::Predicate(SymbolPredicate::new(move |c:&char| (*c as usize).is_multiple_of(i + 2)), Quantifier::Exactly(1))));
+    let points = ('a'..='z').chain('A'..='Z').fold(predicates, |regex, c| regex.then(RegexElement::Item(c, Quantifier::ZeroOrOne)));
+    assert_eq!(points.try_compile().err(), Some(CompileError::Transitions(105 << 16)));
+}
+
+#[test]
+fn bounded_quantifiers() {
+    let hex = ChrRegex::new()
+        .then(RegexElement::Item('#', Quantifier::Exactly(1)))
+        .then(RegexElement::Set('0', '9', Quantifier::Between(2, 4)));
+    let long = ChrRegex::new().then(RegexElement::Item('a', Quantifier::AtLeast(3)));
+
+    let chars = |s:&str| s.chars().collect::<Vec<char>>();
+
+    assert_eq!(hex.r#match(&chars("#1")), false);
+    assert_eq!(hex.r#match(&chars("#12")), true);
+    assert_eq!(hex.r#match(&chars("#1234")), true);
+    assert_eq!(hex.r#match(&chars("#12345")), false);
+    assert_eq!(hex.split_first(&chars("#12345")).0, &chars("#1234")[..]);
+
+    assert_eq!(long.r#match(&chars("aa")), false);
+    assert_eq!(long.r#match(&chars("aaaaaa")), true);
+
+    let groups = ChrRegex::new().then(RegexElement::Group(vec![RegexElement::Item('a', Quantifier::Exactly(1)), RegexElement::Item('b', Quantifier::Exactly(1))], Quantifier::Between(1, 2)));
+    assert_eq!(groups.r#match(&chars("abab")), true);
+    assert_eq!(groups.r#match(&chars("")), false);
+    assert_eq!(groups.examples(4), vec!["ab", "abab"]);
+
+    for regex in [&hex, &long, &groups]{
+        let dfa = regex.compile();
+
+        for candidate in ["#1", "#12", "#1234", "#12345", "aa", "aaa", "ab", "abab", "ababab"]{
+            assert_eq!(dfa.r#match(&chars(candidate)), regex.r#match(&chars(candidate)), "{candidate}");
+        }
+    }
+
+    let impossible = ChrRegex::new().then(RegexElement::Item('a', Quantifier::Between(2, 1)));
+    assert_eq!(impossible.r#match(&chars("a")), false);
+    assert_eq!(impossible.compile().r#match(&chars("a")), false);
+}
+
+#[test]
+fn captures() {
+    let capture = |name:&str, e| RegexElement::Capture(name.to_string(), Box::new(e));
+    let chars = |s:&str| s.chars().collect::<Vec<char>>();
+
+    // [a-z]+(=([0-9]+))?
+    let regex = ChrRegex::new()
+        .then(capture("key", RegexElement::Set('a', 'z', Quantifier::OneOrMany)))
+        .then(RegexElement::Group(vec![
+            RegexElement::Item('=', Quantifier::Exactly(1)),
+            capture("value", RegexElement::Set('0', '9', Quantifier::OneOrMany))
+        ], Quantifier::ZeroOrOne));
+
+    let candidate = chars("size=12;");
+    let captures = regex.captures(&candidate).unwrap();
+    assert_eq!(captures.matched(), &candidate[..7]);
+    assert_eq!(captures.len(), 2);
+    assert_eq!(captures.name("key"), Some(&candidate[..4]));
+    assert_eq!(captures.get(1), Some(&candidate[5..7]));
+    assert_eq!(captures.name("other"), None);
+
+    let candidate = chars("size");
+    let captures = regex.captures(&candidate).unwrap();
+    assert_eq!(captures.iter().collect::<Vec<_>>(), vec![("key", Some(&candidate[..])), ("value", None)]);
+
+    assert_eq!(regex.captures(&chars("12")), None);
+
+    // The last repetition and the taken alternative are kept
+    let regex = ChrRegex::new()
+        .then(RegexElement::Group(vec![capture("letter", RegexElement::Set('a', 'z', Quantifier::Exactly(1)))], Quantifier::OneOrMany))
+        .then(RegexElement::AnyOf(vec![capture("digit", RegexElement::Set('0', '9', Quantifier::Exactly(1))), capture("dot", RegexElement::Item('.', Quantifier::Exactly(1)))], Quantifier::Exactly(1)));
+
+    let candidate = chars("abc.");
+    let captures = regex.captures(&candidate).unwrap();
+    assert_eq!(captures.get(0), Some(&candidate[2..3]));
+    assert_eq!(captures.name("digit"), None);
+    assert_eq!(captures.name("dot"), Some(&candidate[3..]));
+
+    assert!(regex.r#match(&candidate));
+    assert!(regex.compile().r#match(&candidate));
+}
+
+#[test]
+fn lazy_quantifiers(){
+    let chars = |s:&str| s.chars().collect::<Vec<char>>();
+
+    // /* everything up to the first */
+    let comment = ChrRegex::new()
+        .then(RegexElement::Item('/', Quantifier::Exactly(1)))
+        .then(RegexElement::Item('*', Quantifier::Exactly(1)))
+        .then(RegexElement::Set('\0', char::MAX, Quantifier::ZeroOrManyLazy))
+        .then(RegexElement::Item('*', Quantifier::Exactly(1)))
+        .then(RegexElement::Item('/', Quantifier::Exactly(1)));
+
+    let candidate = chars("/* a * b */ c */");
+    assert_eq!(comment.split_first(&candidate).0, &candidate[..11]);
+    assert!(comment.r#match(&chars("/**/")));
+    assert!(!comment.r#match(&chars("/* a")));
+
+    // A lazy element ending a sequence takes the fewest occurences
+    let regex = ChrRegex::new().then(RegexElement::Set('a', 'z', Quantifier::OneOrManyLazy));
+    assert_eq!(regex.split_first(&chars("abc")).0, &['a']);
+
+    let regex = ChrRegex::new().then(RegexElement::Item('a', Quantifier::ZeroOrOneLazy));
+    assert_eq!(regex.split_first(&chars("a")).0, &[] as &[char]);
+
+    // Lazy elements look at the rest of their group
+    let regex = ChrRegex::new()
+        .then(RegexElement::Group(vec![
+            RegexElement::Capture("key".to_string(), Box::new(RegexElement::Set('a', 'z', Quantifier::OneOrManyLazy))),
+            RegexElement::Item('s', Quantifier::Exactly(1))
+        ], Quantifier::Exactly(1)));
+
+    let candidate = chars("bas!");
+    assert_eq!(regex.captures(&candidate).unwrap().name("key"), Some(&candidate[..2]));
+    assert_eq!(regex.split_first(&candidate).0, &candidate[..3]);
+    assert!(regex.compile().r#match(&candidate[..3]));
+}
+
+#[test]
+fn backtracking(){
+    let chars = |s:&str| s.chars().collect::<Vec<char>>();
+
+    // [a-z]+ e
+    let regex = ChrRegex::new()
+        .then(RegexElement::Capture("stem".to_string(), Box::new(RegexElement::Set('a', 'z', Quantifier::OneOrMany))))
+        .then(RegexElement::Item('e', Quantifier::Exactly(1)));
+
+    let candidate = chars("same");
+    assert!(regex.r#match(&candidate));
+    assert_eq!(regex.captures(&candidate).unwrap().name("stem"), Some(&candidate[..3]));
+    assert!(!regex.r#match(&chars("e")));
+    assert!(!regex.r#match(&chars("sam")));
+
+    // The longest possible prefix is kept
+    let candidate = chars("eeee!");
+    assert_eq!(regex.split_first(&candidate).0, &candidate[..4]);
+
+    // Bounded quantifiers give back symbols down to their minimum, inside groups too
+    let regex = ChrRegex::new()
+        .then(RegexElement::Group(vec![
+            RegexElement::Set('0', '9', Quantifier::Between(2, 4)),
+            RegexElement::Set('0', '9', Quantifier::Exactly(2))
+        ], Quantifier::Exactly(1)))
+        .then(RegexElement::Item('.', Quantifier::ZeroOrOne));
+
+    assert!(regex.r#match(&chars("1234.")));
+    assert!(regex.r#match(&chars("123456")));
+    assert!(!regex.r#match(&chars("123")));
+
+    // Both engines agree once symbols can be given back
+    let regex = ChrRegex::new()
+        .then(RegexElement::NoneOf(vec![RegexElement::Item(' ', Quantifier::Exactly(1))], Quantifier::ZeroOrMany))
+        .then(RegexElement::Set('0', '9', Quantifier::AtLeast(2)));
+
+    for candidate in ["v12", "12", "v1", "a b 12", "123"]{
+        let candidate = chars(candidate);
+        assert_eq!(regex.split_first(&candidate), regex.compile().split_first(&candidate), "{candidate:?}");
+    }
+}
+
+#[test]
+fn alternation(){
+    let chars = |s:&str| s.chars().collect::<Vec<char>>();
+
+    let integer = ChrRegex::new().then(RegexElement::Set('0', '9', Quantifier::OneOrMany));
+    let float = ChrRegex::new()
+        .then(RegexElement::Set('0', '9', Quantifier::ZeroOrMany))
+        .then(RegexElement::Item('.', Quantifier::Exactly(1)))
+        .then(RegexElement::Capture("fraction".to_string(), Box::new(RegexElement::Set('0', '9', Quantifier::OneOrMany))));
+    let word = ChrRegex::new().then(RegexElement::Set('a', 'z', Quantifier::OneOrMany));
+
+    let regex = integer.or(float).or(word);
+
+    // The longest alternative wins
+    let candidate = chars("12.5+");
+    assert_eq!(regex.split_first(&candidate).0, &candidate[..4]);
+    assert_eq!(regex.split_first(&chars("12+")).0, &['1', '2']);
+    assert!(regex.r#match(&chars(".5")));
+    assert!(regex.r#match(&chars("abc")));
+    assert!(!regex.r#match(&chars("a1")));
+
+    // Captures come from the alternative that matched
+    assert_eq!(regex.captures(&candidate).unwrap().name("fraction"), Some(&candidate[3..4]));
+    assert!(regex.captures(&chars("12")).unwrap().is_empty());
+
+    // Elements added afterwards follow every alternative
+    let regex = regex.then(RegexElement::Item(';', Quantifier::Exactly(1)));
+    for (candidate, matched) in [("12;", true), ("1.5;", true), ("abc;", true), ("abc", false), (";", false)]{
+        let candidate = chars(candidate);
+        assert_eq!(regex.r#match(&candidate), matched, "{candidate:?}");
+        assert_eq!(regex.compile().r#match(&candidate), matched, "{candidate:?}");
+    }
+
+    let examples = regex.examples(16);
+    assert!(examples.iter().any(|e| e.contains('.')) && examples.iter().any(|e| e.starts_with('a')));
+    assert_eq!(regex.symbols().len(), 6);
+}
+
+#[test]
+fn group_backtracking(){
+    let chars = |s:&str| s.chars().collect::<Vec<char>>();
+    let item = |c:char| RegexElement::Item(c, Quantifier::Exactly(1));
+
+    // ([a-z]+) e
+    let regex = ChrRegex::new()
+        .then(RegexElement::Group(vec![RegexElement::Capture("stem".to_string(), Box::new(RegexElement::Set('a', 'z', Quantifier::OneOrMany)))], Quantifier::Exactly(1)))
+        .then(item('e'));
+
+    let candidate = chars("same");
+    assert!(regex.r#match(&candidate));
+    assert_eq!(regex.captures(&candidate).unwrap().name("stem"), Some(&candidate[..3]));
+
+    // (ab)* ab
+    let regex = ChrRegex::new()
+        .then(RegexElement::Group(vec![item('a'), item('b')], Quantifier::ZeroOrMany))
+        .then(item('a'))
+        .then(item('b'));
+
+    assert!(regex.r#match(&chars("ababab")));
+    assert!(regex.r#match(&chars("ab")));
+    assert!(!regex.r#match(&chars("aba")));
+
+    // (a|ab) c: the second alternative is taken when the first one leaves no c
+    let regex = ChrRegex::new()
+        .then(RegexElement::AnyOf(vec![item('a'), RegexElement::Group(vec![item('a'), item('b')], Quantifier::Exactly(1))], Quantifier::Exactly(1)))
+        .then(item('c'));
+
+    assert!(regex.r#match(&chars("ac")));
+    assert!(regex.r#match(&chars("abc")));
+    assert!(!regex.r#match(&chars("abbc")));
+
+    // (ab)? ab: a group over its limit gives back occurences
+    let regex = ChrRegex::new()
+        .then(RegexElement::Group(vec![RegexElement::Capture("first".to_string(), Box::new(item('a'))), item('b')], Quantifier::ZeroOrOne))
+        .then(item('a'))
+        .then(item('b'));
+
+    let candidate = chars("abab");
+    assert!(regex.r#match(&candidate));
+    assert_eq!(regex.captures(&candidate).unwrap().name("first"), Some(&candidate[..1]));
+    assert!(regex.r#match(&chars("ab")));
+    assert_eq!(regex.captures(&chars("ab")).unwrap().name("first"), None);
+
+    // Both engines agree on groups
+    let regex = ChrRegex::new()
+        .then(RegexElement::Group(vec![RegexElement::Set('a', 'z', Quantifier::OneOrMany), item(' ')], Quantifier::ZeroOrMany))
+        .then(RegexElement::Set('a', 'z', Quantifier::OneOrMany))
+        .then(item(' '));
+
+    for candidate in ["a b c ", "ab ", "a b", "ab cd ef gh "]{
+        let candidate = chars(candidate);
+        assert_eq!(regex.r#match(&candidate), regex.compile().r#match(&candidate), "{candidate:?}");
+        assert_eq!(regex.split_first(&candidate), regex.compile().split_first(&candidate), "{candidate:?}");
+    }
+}
+
+#[test]
+fn sub_pattern(){
+    let chars = |s:&str| s.chars().collect::<Vec<char>>();
+
+    let digit = ChrRegex::new().then(RegexElement::Set('0', '9', Quantifier::Exactly(1)));
+    let ident_start = ChrRegex::new()
+        .then(RegexElement::Set('a', 'z', Quantifier::Exactly(1)))
+        .or(ChrRegex::new().then(RegexElement::Item('_', Quantifier::Exactly(1))));
+
+    let ident = ChrRegex::new()
+        .then(RegexElement::SubPattern(ident_start.clone(), Quantifier::Exactly(1)))
+        .then(RegexElement::AnyOf(vec![
+            RegexElement::SubPattern(ident_start.clone(), Quantifier::Exactly(1)),
+            RegexElement::SubPattern(digit.clone(), Quantifier::Exactly(1))
+        ], Quantifier::Exactly(1)))
+        .then(RegexElement::SubPattern(ChrRegex::new().then(RegexElement::Capture("rest".to_string(), Box::new(RegexElement::Set('a', 'z', Quantifier::OneOrMany)))), Quantifier::ZeroOrOne));
+
+    assert!(ident.r#match(&chars("_1")));
+    assert!(ident.r#match(&chars("a_bc")));
+    assert!(!ident.r#match(&chars("1a")));
+    assert!(ident.may_accept(&'_'));
+    assert_eq!(ident.symbols(), vec![&'a', &'z', &'_', &'0', &'9']);
+
+    let candidate = chars("x2yz");
+    assert_eq!(ident.captures(&candidate).unwrap().name("rest"), Some(&candidate[2..]));
+
+    // The embedded regex keeps the longest of its alternatives
+    let keyword = ChrRegex::new()
+        .then(RegexElement::Item('i', Quantifier::Exactly(1)))
+        .or(ChrRegex::new().then(RegexElement::Item('i', Quantifier::Exactly(1))).then(RegexElement::Item('f', Quantifier::Exactly(1))));
+    let keywords = ChrRegex::new().then(RegexElement::SubPattern(keyword, Quantifier::OneOrMany));
+
+    let candidate = chars("ifiif!");
+    assert_eq!(keywords.split_first(&candidate).0, &candidate[..5]);
+
+    // Once compiled too
+    let number = ChrRegex::new().then(RegexElement::SubPattern(digit, Quantifier::AtLeast(2)));
+    for candidate in ["1", "12", "123", "1a", ""]{
+        let candidate = chars(candidate);
+        assert_eq!(number.r#match(&candidate), number.compile().r#match(&candidate), "{candidate:?}");
+        assert_eq!(number.split_first(&candidate), number.compile().split_first(&candidate), "{candidate:?}");
+    }
+
+    // An empty embedded regex matches nothing
+    let empty = ChrRegex::new().then(RegexElement::SubPattern(ChrRegex::new(), Quantifier::ZeroOrOne)).then(RegexElement::Item('a', Quantifier::Exactly(1)));
+    assert!(empty.r#match(&chars("a")));
+    assert!(empty.compile().r#match(&chars("a")));
+}
+
+#[test]
+fn boundaries(){
+    let chars = |s:&str| s.chars().collect::<Vec<char>>();
+
+    // [a-z]+ = [0-9]+
+    let assignment = ChrRegex::new()
+        .then(RegexElement::Set('a', 'z', Quantifier::OneOrMany))
+        .then(RegexElement::Item('=', Quantifier::Exactly(1)))
+        .then(RegexElement::Set('0', '9', Quantifier::OneOrMany));
+
+    let candidate = chars("ab=12;");
+    assert_eq!(assignment.split_at_match(&candidate, 0), (&candidate[..5], &candidate[5..]));
+    assert_eq!(assignment.split_at_match(&candidate, 1), (&candidate[1..5], &candidate[5..]));
+    assert_eq!(assignment.split_at_match(&candidate, 2), (&[][..], &candidate[2..]));
+    assert_eq!(assignment.split_at_match(&candidate, 9), (&[][..], &[][..]));
+
+    let (matched, rest, boundaries) = assignment.split_first_with_boundaries(&candidate);
+    assert_eq!((matched, rest), (&candidate[..5], &candidate[5..]));
+    assert_eq!(boundaries, Boundaries { ends: vec![2, 3, 5], failed: None });
+
+    let candidate = chars("ab;");
+    let (matched, _, boundaries) = assignment.split_first_with_boundaries(&candidate);
+    assert!(matched.is_empty());
+    assert_eq!(boundaries, Boundaries { ends: vec![2], failed: Some(1) });
+
+    // Elements giving back symbols end where the match needs them to
+    let stem = ChrRegex::new()
+        .then(RegexElement::Set('a', 'z', Quantifier::OneOrMany))
+        .then(RegexElement::Item('e', Quantifier::Exactly(1)));
+    assert_eq!(stem.split_first_with_boundaries(&chars("same")).2, Boundaries { ends: vec![3, 4], failed: None });
+
+    // The alternative going the furthest is reported
+    let either = stem.or(assignment);
+    assert_eq!(either.split_first_with_boundaries(&chars("a=")).2, Boundaries { ends: vec![1, 2], failed: Some(2) });
+    assert_eq!(ChrRegex::new().split_first_with_boundaries(&chars("a")).2, Boundaries { ends: vec![], failed: Some(0) });
+}
+
+#[test]
+fn predicates(){
+    let chars = |s:&str| s.chars().collect::<Vec<char>>();
+    let alphabetic = SymbolPredicate::new(|c:&char| c.is_alphabetic());
+
+    // \p{Alphabetic}+ [0-9]* but not 'x'
+    let ident = ChrRegex::new()
+        .then(RegexElement::Predicate(alphabetic.clone(), Quantifier::OneOrMany))
+        .then(RegexElement::Set('0', '9', Quantifier::ZeroOrMany))
+        .then(RegexElement::NoneOf(vec![RegexElement::Item('x', Quantifier::Exactly(1)), RegexElement::Predicate(alphabetic.clone(), Quantifier::Exactly(1))], Quantifier::ZeroOrOne));
+
+    assert!(ident.r#match(&chars("λx12")));
+    assert!(ident.r#match(&chars("été_")));
+    assert!(!ident.r#match(&chars("12")));
+    assert!(ident.may_accept(&'é'));
+    assert_eq!(ident.examples(1), vec!["a".to_string()]);
+
+    // Predicates are only equal to their clones
+    assert_eq!(alphabetic, alphabetic.clone());
+    assert_ne!(alphabetic, SymbolPredicate::new(|c:&char| c.is_alphabetic()));
+
+    // Both engines agree
+    let dfa = ident.compile();
+    for candidate in ["abc", "ab1", "a1x", "a1y", "a1_", "é9", "9", "", "x", "a1__"]{
+        let candidate = chars(candidate);
+        assert_eq!(ident.r#match(&candidate), dfa.r#match(&candidate), "{candidate:?}");
+        assert_eq!(ident.split_first(&candidate), dfa.split_first(&candidate), "{candidate:?}");
+    }
+}
+
+#[test]
+fn classes(){
+    let chars = |s:&str| s.chars().collect::<Vec<char>>();
+
+    // [a-zA-Z_][a-zA-Z0-9_]*
+    let word = vec![ClassItem::Range('a', 'z'), ClassItem::Range('A', 'Z'), ClassItem::Single('_')];
+    let ident = ChrRegex::new()
+        .then(RegexElement::Class(word.clone(), Quantifier::Exactly(1)))
+        .then(RegexElement::Class([word, vec![ClassItem::Range('0', '9')]].concat(), Quantifier::ZeroOrMany));
+
+    assert!(ident.r#match(&chars("_Foo42")));
+    assert!(ident.r#match(&chars("x")));
+    assert!(!ident.r#match(&chars("4x")));
+    assert!(!ident.r#match(&chars("a-b")));
+    assert!(ident.may_accept(&'Q'));
+    assert!(!ident.may_accept(&'-'));
+    assert_eq!(ident.symbols(), vec![&'a', &'z', &'A', &'Z', &'_', &'0', &'9']);
+    assert!(ident.examples(8).iter().all(|e| ident.r#match(&chars(e))));
+
+    let dfa = ident.compile();
+    for candidate in ["_Foo42", "x", "4x", "a-b", "Z9_", "`", "{", "@"]{
+        let candidate = chars(candidate);
+        assert_eq!(ident.split_first(&candidate), dfa.split_first(&candidate), "{candidate:?}");
+    }
+}
+
+#[test]
+fn find_all(){
+    let chars = |s:&str| s.chars().collect::<Vec<char>>();
+
+    // ab+
+    let regex = ChrRegex::new()
+        .then(RegexElement::Item('a', Quantifier::Exactly(1)))
+        .then(RegexElement::Item('b', Quantifier::OneOrMany));
+
+    let candidate = chars("abbxaabab");
+    let found = regex.find_all(&candidate).collect::<Vec<Match<char>>>();
+
+    assert_eq!(found, vec![
+        Match { start: 0, length: 3, symbols: &candidate[0..3] },
+        Match { start: 5, length: 2, symbols: &candidate[5..7] },
+        Match { start: 7, length: 2, symbols: &candidate[7..9] }
+    ]);
+    assert_eq!(found[1].end(), 7);
+
+    // Empty matches aren't occurences
+    let optional = ChrRegex::new().then(RegexElement::Item('a', Quantifier::ZeroOrMany));
+    assert_eq!(optional.find_all(&chars("baab")).map(|m| m.start).collect::<Vec<usize>>(), vec![1]);
+    assert_eq!(regex.find_all(&[]).count(), 0);
+
+    // The search stops at the limits
+    let limited = regex.clone().with_limits(Limits::new().with_max_tokens(4));
+    assert_eq!(limited.find_all(&candidate).count(), 0);
+}
+
+#[test]
+fn split_all(){
+    let chars = |s:&str| s.chars().collect::<Vec<char>>();
+    let number = ChrRegex::new().then(RegexElement::Set('0', '9', Quantifier::OneOrMany));
+
+    let candidate = chars("1+23--4");
+    let chunks = number.split_all(&candidate);
+
+    assert_eq!(chunks, vec![
+        Chunk::Matched(&candidate[0..1]),
+        Chunk::Unmatched(&candidate[1..2]),
+        Chunk::Matched(&candidate[2..4]),
+        Chunk::Unmatched(&candidate[4..6]),
+        Chunk::Matched(&candidate[6..7])
+    ]);
+    assert_eq!(chunks.iter().flat_map(|chunk| chunk.symbols()).collect::<String>(), "1+23--4");
+
+    let candidate = chars("ab");
+    assert_eq!(number.split_all(&candidate), vec![Chunk::Unmatched(&candidate[..])]);
+    assert!(number.split_all(&[]).is_empty());
+}
+
+#[test]
+fn regex_macro(){
+    use RegexElement::*;
+
+    let identifier = crate::regex![class['a'..='z', 'A'..='Z', '_'], class['a'..='z', '0'..='9', '_']*?];
+    assert_eq!(identifier, ChrRegex::new()
+        .then(Class(vec![ClassItem::Range('a', 'z'), ClassItem::Range('A', 'Z'), ClassItem::Single('_')], Quantifier::Exactly(1)))
+        .then(Class(vec![ClassItem::Range('a', 'z'), ClassItem::Range('0', '9'), ClassItem::Single('_')], Quantifier::ZeroOrManyLazy)));
+
+    let hex = crate::regex![item('0'), any_of[item('x'), item('X')], capture("digits", set('0'..='9'){1, 8}), none_of[item(';')]{2,}];
+    assert_eq!(hex, ChrRegex::new()
+        .then(Item('0', Quantifier::Exactly(1)))
+        .then(AnyOf(vec![Item('x', Quantifier::Exactly(1)), Item('X', Quantifier::Exactly(1))], Quantifier::Exactly(1)))
+        .then(Capture("digits".to_string(), Box::new(Set('0', '9', Quantifier::Between(1, 8)))))
+        .then(NoneOf(vec![Item(';', Quantifier::Exactly(1))], Quantifier::AtLeast(2))));
+
+    // Any symbol type, and elements built by hand
+    let bytes = crate::regex![{Item(b'#', Quantifier::OneOrMany)}, pattern(crate::regex![set(b'a'..=b'f')]){3}, predicate(|b:&u8| b.is_ascii_whitespace())??,];
+    assert!(bytes.r#match(b"##abc"));
+    assert!(!bytes.r#match(b"#ab"));
+    assert_eq!(bytes.split_first(b"##abc ").1, b" ", "lazy");
+}
+
+#[test]
+fn regex_algebra(){
+    use crate::regex::dfa::{CompileError, MAX_PREDICATES};
+
+    let identifier = crate::regex![class['a'..='z', '_'], class['a'..='z', '_', '0'..='9']*];
+    let keyword = crate::regex![item('i'), item('f')];
+    let number = crate::regex![set('0'..='9')+];
+
+    assert!(identifier.intersects(&keyword).unwrap() && keyword.intersects(&identifier).unwrap());
+    assert!(!identifier.intersects(&number).unwrap());
+    assert!(keyword.is_subset_of(&identifier).unwrap() && !identifier.is_subset_of(&keyword).unwrap());
+
+    // The same sequences written differently
+    assert!(number.is_equivalent(&crate::regex![set('0'..='9'), group[set('0'..='9')]*?]).unwrap());
+    assert!(number.is_equivalent(&crate::regex![class['0'..='4', '5'..='9']+]).unwrap());
+    assert!(!number.is_equivalent(&crate::regex![set('0'..='9')*]).unwrap());
+    assert!(ChrRegex::new().is_subset_of(&crate::regex![set('0'..='9')*]).unwrap());
+
+    // Nothing lies between adjacent symbols
+    assert!(!crate::regex![none_of[item('a'), item('b')]].intersects(&crate::regex![set('a'..='b')]).unwrap());
+    assert!(crate::regex![none_of[item('a'), item('c')]].intersects(&crate::regex![set('a'..='c')]).unwrap());
+    assert!(!crate::regex![none_of[item(9u8), item(10)]].intersects(&crate::regex![set(9..=10u8)]).unwrap());
+
+    // Predicates are known on the named symbols only
+    let alphabetic = crate::regex![predicate(|c:&char| c.is_alphabetic())+];
+    assert!(keyword.is_subset_of(&alphabetic).unwrap());
+    assert!(!crate::regex![item('7')].intersects(&alphabetic).unwrap());
+    assert!(crate::regex![none_of[item('7')]].intersects(&alphabetic).unwrap());
+
+    // Assertions and too many predicates can't be compared
+    let anchored = crate::regex![start(), item('i'), item('f')];
+    assert_eq!(anchored.intersects(&keyword), Err(CompileError::Assertion));
+    assert_eq!(keyword.is_subset_of(&crate::regex![item('i'), ahead(item('f')), item('f')]), Err(CompileError::Assertion));
+    assert_eq!(anchored.is_equivalent(&anchored), Err(CompileError::Assertion));
+
+    let many = (0..MAX_PREDICATES).fold(ChrRegex::new(), |regex, i| regex.then(RegexElement::Predicate(SymbolPredicate::new(move |c:&char| (*c as usize).is_multiple_of(i + 2)), Quantifier::Exactly(1))));
+    assert_eq!(many.intersects(&alphabetic), Err(CompileError::Predicates(MAX_PREDICATES + 1)));
+    assert_eq!(alphabetic.is_subset_of(&many), Err(CompileError::Predicates(MAX_PREDICATES + 1)));
+    assert_eq!(many.clone().then(RegexElement::Predicate(SymbolPredicate::new(|c:&char| c.is_numeric()), Quantifier::Exactly(1))).try_compile().err(), Some(CompileError::Predicates(MAX_PREDICATES + 1)));
+}
+
+#[test]
+fn streaming(){
+    let dfa = crate::regex![set('0'..='9')+, group[item('.'), set('0'..='9')+]?, group[item('e'), set('0'..='9')+]?].compile();
+
+    for candidate in ["", "1", "12.5e3", "12.5e", "12.", "x1", "7.e2", "3e4.5"]{
+        let chars = candidate.chars().collect::<Vec<char>>();
+        let mut symbols = chars.iter().copied().peekable();
+        let first = dfa.read_first(&mut symbols);
+
+        assert_eq!(first.symbols(), dfa.split_first(&chars).0, "{candidate}");
+        assert_eq!(first.read.len() + symbols.count(), chars.len(), "{candidate}");
+        assert_eq!(dfa.match_iter(candidate.chars()), dfa.r#match(&chars), "{candidate}");
+    }
+
+    // Splitting numbers out of a reader without buffering it
+    let reader = std::io::Cursor::new(b"42 3.5e1 7.");
+    let mut bytes = std::io::Read::bytes(reader).map(|b| b.unwrap()).peekable();
+    let number = crate::regex![set(b'0'..=b'9')+, group[item(b'.'), set(b'0'..=b'9')+]?, group[item(b'e'), set(b'0'..=b'9')+]?].compile();
+
+    let mut numbers = vec![];
+    while bytes.peek().is_some(){
+        let first = number.read_first(&mut bytes);
+        if first.read.is_empty() { bytes.next(); } else { numbers.push((first.symbols().to_vec(), first.excess().to_vec())); }
+    }
+
+    assert_eq!(numbers, vec![(b"42".to_vec(), vec![]), (b"3.5e1".to_vec(), vec![]), (b"7".to_vec(), b".".to_vec())]);
+}
+
+#[test]
+fn assertions(){
+    let chars = |text:&str| text.chars().collect::<Vec<char>>();
+    let word = |c:&char| c.is_alphanumeric();
+
+    // Without anchors a prefix matches
+    let digits = crate::regex![start(), set('0'..='9')+, end()];
+    assert!(digits.r#match(&chars("123")));
+    assert!(digits.split_first(&chars("12a")).0.is_empty());
+    assert!(digits.split_at_match(&chars("a12"), 1).0.is_empty(), "not at the start");
+
+    // Whole words only
+    let the = crate::regex![boundary(word), item('t'), item('h'), item('e'), boundary(word)];
+    let text = chars("then the, other the");
+    assert_eq!(the.find_all(&text).map(|m| m.start).collect::<Vec<usize>>(), vec![5, 16]);
+
+    // Groups and alternatives may hold them
+    let suffix = crate::regex![set('a'..='z')+, any_of[end(), item(';')]];
+    assert_eq!(suffix.split_first(&chars("ab")).0.len(), 2);
+    assert_eq!(suffix.split_first(&chars("ab;c")).0.len(), 3);
+    assert!(suffix.split_first(&chars("ab c")).0.is_empty());
+
+    let repeated = crate::regex![group[set('a'..='z')+, boundary(word), item(' ')?]+, end()];
+    assert!(repeated.r#match(&chars("ab cd")));
+    assert!(!repeated.r#match(&chars("ab cd!")));
+
+    // Alone they match nothing, as an empty pattern
+    assert!(crate::regex![end()].split_first(&chars("")).0.is_empty());
+    assert!(!ChrRegex::new().then(RegexElement::StartOfInput).may_accept(&'a'));
+    assert_eq!(crate::regex![boundary(word), set('a'..='b')].examples(4), vec!["a", "b"]);
+
+    // Symbols taking no memory are told apart by their index
+    #[derive(PartialEq, PartialOrd, Eq, Hash, Clone, Debug)]
+    enum Unit{ A }
+    impl crate::regex::Symbol for Unit{}
+
+    let all = Regex::new().then(RegexElement::Item(Unit::A, Quantifier::OneOrMany)).then(RegexElement::EndOfInput);
+    assert!(all.r#match(&[Unit::A, Unit::A]));
+    assert_eq!(all.split_first(&[Unit::A, Unit::A]).0.len(), 2);
+    assert!(Regex::new().then(RegexElement::StartOfInput).then(RegexElement::Item(Unit::A, Quantifier::Exactly(1))).split_at_match(&[Unit::A, Unit::A], 1).0.is_empty());
+}
+
+#[test]
+fn lookaround(){
+    let chars = |text:&str| text.chars().collect::<Vec<char>>();
+
+    // A slash that doesn't start a comment
+    let slash = crate::regex![item('/'), not_ahead(item('/'))];
+    assert_eq!(slash.split_first(&chars("/ 2")).0, &['/']);
+    assert!(slash.split_first(&chars("// 2")).0.is_empty());
+    assert_eq!(slash.split_first(&chars("/")).0, &['/']);
+
+    // Digits followed by a unit that isn't matched
+    let amount = crate::regex![set('0'..='9')+, ahead(group[item('k'), item('g')])];
+    assert_eq!(amount.split_first(&chars("12kg")).0, &['1', '2']);
+    assert!(amount.split_first(&chars("12g")).0.is_empty());
+
+    let stem = crate::regex![set('a'..='z')+, ahead(item('s'))];
+    assert_eq!(stem.split_first(&chars("cats")).0.len(), 3, "given back to the lookahead");
+
+    // The symbols before the start of a split are seen
+    let text = chars("a-b -c");
+    let negative = crate::regex![not_behind(set('a'..='z')), item('-'), set('a'..='z')];
+    assert_eq!(negative.find_all(&text).map(|m| m.start).collect::<Vec<usize>>(), vec![4]);
+
+    let suffix = crate::regex![behind(group[item('a'), item('-')]), set('a'..='z')+];
+    assert_eq!(suffix.split_at_match(&text, 2).0, &['b']);
+    assert!(suffix.split_at_match(&text, 5).0.is_empty());
+
+    // Only the starts the element may come from are tried, unless it has no most width
+    let long = vec!['a'; 20_000];
+    assert_eq!(crate::regex![behind(group[item('a'), item('a')?]), item('a')].find_all(&long).count(), 19_999);
+    assert_eq!(crate::regex![behind(group[item('b'), item('a')?]), item('a')].find_all(&long).count(), 0);
+    assert_eq!(crate::regex![not_behind(item('b')*), item('a')].find_all(&long[..2_000]).count(), 0);
+
+    // The elements inside are listed and nested
+    assert_eq!(slash.symbols(), vec![&'/']);
+    assert_eq!(crate::regex![ahead(item('x')), item('x')].with_limits(crate::limits::Limits::new().with_max_depth(1)).try_match(&['x']), Err(Limit::Depth(1)));
+}
+
+#[test]
+fn case_insensitive(){
+    let chars = |text:&str| text.chars().collect::<Vec<char>>();
+
+    let keyword = crate::regex![item('S'), item('e'), item('l'), item('e'), item('c'), item('t')]
+        .or(crate::regex![item('f'), item('r'), item('o'), item('m')])
+        .case_insensitive(true);
+
+    for candidate in ["SELECT", "select", "sElEcT", "FROM", "From"]{
+        assert!(keyword.r#match(&chars(candidate)), "{candidate}");
+        assert!(keyword.compile().r#match(&chars(candidate)), "{candidate}");
+    }
+    assert!(!keyword.r#match(&chars("selec")));
+
+    // The other cases of a range are merged into ranges, ranges without cases are kept
+    let identifier = crate::regex![set('a'..='z'), set('0'..='9')*].case_insensitive(true);
+    assert_eq!(identifier, crate::regex![class['a'..='z', 'A'..='Z'], set('0'..='9')*]);
+
+    let not_x = crate::regex![none_of[item('x')]+].case_insensitive(true);
+    assert!(not_x.r#match(&chars("abc")));
+    assert!(!not_x.r#match(&chars("aXc")));
+
+    let upper = ChrRegex::new().then(RegexElement::Predicate(SymbolPredicate::new(|c:&char| c.is_uppercase()), Quantifier::OneOrMany));
+    assert!(upper.clone().case_insensitive(true).r#match(&chars("aBc")));
+    assert!(!upper.clone().case_insensitive(true).r#match(&chars("a1")));
+
+    assert_eq!(upper.clone().case_insensitive(false), upper);
+}
+
+#[test]
+fn match_trace(){
+    use crate::regex::trace::*;
+
+    let chars = |text:&str| text.chars().collect::<Vec<char>>();
+    let number = crate::regex![set('0'..='9')+, group[item('.'), set('0'..='9')+]?];
+    let word = crate::regex![set('a'..='z')+];
+    let regex = number.clone().or(word);
+
+    // Symbols given back to the elements after
+    let trace = crate::regex![set('0'..='9')*, item('9')].trace(&chars("199"));
+    assert_eq!(trace.outcome, TraceOutcome::Matched);
+    assert_eq!(trace.steps.iter().map(|step| step.consumed).collect::<Vec<_>>(), vec![Some(2), Some(1)]);
+
+    // Every branch is attempted, the furthest failure is kept
+    let trace = regex.trace(&chars("12.x"));
+    assert_eq!(trace.outcome, TraceOutcome::Failed(2));
+    assert_eq!(trace.steps.iter().map(|step| (step.branch, step.start, step.consumed)).collect::<Vec<_>>(), vec![(0, 0, Some(2)), (0, 2, Some(0)), (1, 0, None)]);
+    assert_eq!(trace.failure(), None);
+    assert!(!regex.r#match(&chars("12.x")));
+
+    let trace = number.trace(&chars("x"));
+    assert_eq!(trace.failure().map(|step| step.start), Some(0));
+    assert_eq!(trace.to_string(), "branch 0\n  Set('0', '9', OneOrMany) at 0: no match\nfailed at 0");
+
+    assert_eq!(regex.trace(&chars("abc")).steps.last().map(|step| step.branch), Some(1));
+
+    let limited = number.with_limits(Limits::new().with_max_tokens(2));
+    assert_eq!(limited.trace(&chars("123")).outcome, TraceOutcome::Stopped(Limit::Tokens(2)));
+}
+
+#[test]
+fn dot_export(){
+    let identifier = crate::regex![class['a'..='z', '_'], class['a'..='z', '_', '0'..='9']*];
+    let dot = identifier.to_dot().unwrap();
+
+    assert!(dot.starts_with("digraph {\n    rankdir=LR;\n    start [shape=point];\n    0 [shape=circle];\n"));
+    assert!(dot.ends_with("}"));
+    assert!(dot.contains("0 -> 2 [label=\"'_' | ['a', 'z']\"];"));
+    assert!(dot.contains("2 [shape=doublecircle];"));
+    assert!(dot.contains("2 -> 3 [label=\"['0', '9'] | '_' | ['a', 'z']\"];"));
+
+    // The dead state is left out, quotes are escaped
+    assert_eq!(dot.matches("->").count(), 4);
+    assert!(crate::regex![item('"')].to_dot().unwrap().contains("[label=\"'\\\"'\"]"));
+
+    let predicate = ChrRegex::new().then(RegexElement::Predicate(SymbolPredicate::new(|c:&char| c.is_uppercase()), Quantifier::Exactly(1)));
+    assert!(predicate.to_dot().unwrap().contains("[label=\"(.., ..) p0\"];"));
+
+    // Patterns that can't be compiled aren't rendered
+    assert_eq!(crate::regex![item('a'), end()].to_dot(), Err(crate::regex::dfa::CompileError::Assertion));
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn serialization(){
+    let digits = crate::regex![set('0'..='9')+];
+    let regex = crate::regex![capture("sign", class['+', '-']?), pattern(digits), group[item('.'), none_of[item('e')]{1, 3}]*?]
+        .or(crate::regex![any_of[item('i'), item('n')]])
+        .with_limits(Limits::new().with_max_depth(8).with_time_budget(std::time::Duration::from_millis(5)));
+
+    let json = serde_json::to_string(&regex).unwrap();
+    assert_eq!(serde_json::from_str::<ChrRegex>(&json).unwrap(), regex);
+
+    let word = crate::regex![predicate(|c:&char| c.is_alphabetic())+];
+    assert!(serde_json::to_string(&word).is_err());
+}
+
+#[test]
+fn zero_width_repetitions(){
+    let chars = |text:&str| text.chars().collect::<Vec<char>>();
+
+    // Groups that may match no symbols repeat as long as they consume some, and match the occurences they need empty
+    let cases = [
+        (crate::regex![group[item('a')*, item('b')]+], "bb"),
+        (crate::regex![group[item('a')*]{3}], ""),
+        (crate::regex![group[item('a')*, item('b')?]{2}], ""),
+        (crate::regex![group[item('a')*]*, item('b')], "aab"),
+        (crate::regex![group[group[item('a')*]*]*], "aaa"),
+        (crate::regex![group[item('a')?]{2,5}], "a")
+    ];
+
+    for (regex, candidate) in cases{
+        assert!(regex.r#match(&chars(candidate)), "{candidate}");
+        assert!(regex.compile().r#match(&chars(candidate)), "{candidate}");
+    }
+
+    let limited = crate::regex![group[item('a'), item('b')?]*].with_limits(Limits::new().with_max_repetitions(3));
+    assert_eq!(limited.try_match(&chars("aba")), Ok(true));
+    assert_eq!(limited.try_match(&chars("aaaa")), Err(Limit::Repetitions(3)));
+
+    let ahead = crate::regex![ahead(group[item('a')]*), item('a')*].with_limits(Limits::new().with_max_repetitions(2));
+    assert_eq!(ahead.try_match(&chars("aaa")), Err(Limit::Repetitions(2)));
+}
+
+#[test]
+fn any_of_quantifier(){
+    let chars = |text:&str| text.chars().collect::<Vec<char>>();
+
+    let identifier = crate::regex![any_of[set('a'..='z'), item('_')], any_of[set('a'..='z'), set('0'..='9'), item('_')]*];
+    let hex = crate::regex![any_of[set('0'..='9'), set('a'..='f')]{2,4}];
+    let optional = crate::regex![item('-'), any_of[item('+'), item('-')]?, set('0'..='9')+];
+
+    let cases = [
+        (&identifier, "a", true),
+        (&identifier, "snake_case2", true),
+        (&identifier, "2a", false),
+        (&hex, "ff", true),
+        (&hex, "09af", true),
+        (&hex, "f", false),
+        (&hex, "0a1b2", false),
+        (&optional, "-+1", true),
+        (&optional, "-12", true),
+        (&optional, "-+-1", false)
+    ];
+
+    for (regex, candidate, expected) in cases{
+        assert_eq!(regex.r#match(&chars(candidate)), expected, "{candidate}");
+        assert_eq!(regex.compile().r#match(&chars(candidate)), expected, "{candidate}");
+    }
+
+    // The first valid alternative is taken for each occurence when splitting,
+    // a full match gives it back for the next ones when the end isn't reached
+    let first = crate::regex![any_of[item('a'), group[item('a'), item('b')]]+];
+    assert!(first.r#match(&chars("aaa")));
+    assert!(first.r#match(&chars("aab")));
+    assert_eq!(first.split_first(&chars("ab")), (&['a'][..], &['b'][..]));
+
+    let candidate = chars("1.2");
+    let captures = crate::regex![any_of[capture("digit", set('0'..='9')), item('.')]*].captures(&candidate).unwrap();
+    assert_eq!(captures.name("digit"), Some(&['2'][..]));
+}
+
+#[test]
+fn named_elements(){
+    let chars = |text:&str| text.chars().collect::<Vec<char>>();
+
+    let digits = ChrRegex::new().then(RegexElement::Set('0', '9', Quantifier::OneOrMany)).with_name("digits");
+    let float = crate::regex![pattern(digits.clone()), named("fraction", group[item('.'), set('0'..='9')+]?)].with_name("float literal");
+
+    // Names don't change what is matched
+    assert_eq!(float.name(), Some("float literal"));
+    assert!(float.r#match(&chars("12.5")));
+    assert!(float.compile().r#match(&chars("12")));
+    assert!(!float.r#match(&chars("1x")));
+
+    let trace = float.trace(&chars("x"));
+    assert_eq!(trace.expected(), Some("digits".to_string()));
+    assert_eq!(trace.to_string(), "branch 0\n  digits at 0: no match\nfailed at 0");
+
+    let captured = RegexElement::Capture("int".to_string(), Box::new(RegexElement::SubPattern(digits, Quantifier::Exactly(1))));
+    assert_eq!(captured.name(), Some("digits"));
+    assert_eq!(RegexElement::Item('a', Quantifier::Exactly(1)).describe(), "Item('a', Exactly(1))");
+}
+
+#[test]
+fn longest_prefix(){
+    let chars = |text:&str| text.chars().collect::<Vec<char>>();
+
+    let cases = [
+        (crate::regex![any_of[item('a'), group[item('a'), item('b')]]+], "ababx", (4, true)),
+        (crate::regex![group[item('a'), item('b')?]+, item('b')], "ababb", (5, true)),
+        (crate::regex![set('a'..='z')+, item('e')], "same!", (4, true)),
+        (crate::regex![item('a')*], "bbb", (0, true)),
+        (crate::regex![item('i'), item('f')], "in", (1, false)),
+        (crate::regex![item('x')], "", (0, false)),
+        (crate::regex![item('"'), none_of[item('"')]*, item('"')], "\"abc", (4, false)),
+        (crate::regex![item('i'), item('f')].or(crate::regex![item('i'), item('n'), item('t')]), "ini", (2, false)),
+        (crate::regex![item('a')].or(crate::regex![item('a'), item('b'), item('c')]), "abd", (1, true))
+    ];
+
+    for (regex, candidate, expected) in cases{
+        assert_eq!(regex.longest_prefix(&chars(candidate)), expected, "{candidate}");
+    }
+
+    let limited = crate::regex![item('a')*].with_limits(Limits::new().with_max_tokens(2));
+    assert_eq!(limited.try_longest_prefix(&chars("aaa")), Err(Limit::Tokens(2)));
+    assert_eq!(limited.longest_prefix(&chars("aaa")), (0, false));
+}
+
+#[test]
+fn generation(){
+    use crate::{fuzz::Rng, lexer::{Lexer, LexerNode, LexingResult, TokenKind}};
+
+    #[derive(PartialEq, PartialOrd, Eq, Hash, Copy, Clone, Debug)]
+    enum Kind{ Name, Number, Text }
+
+    impl crate::regex::Symbol for Kind{}
+    impl TokenKind for Kind{}
+
+    let universe = ('a'..='f').chain('0'..='9').chain(['_', '"', ' ', '.']).collect::<Vec<char>>();
+    let nodes = [
+        (crate::regex![class['a'..='z', '_'], class['a'..='z', '_', '0'..='9']*], Kind::Name),
+        (crate::regex![set('0'..='9')+, group[item('.'), set('0'..='9')+]?], Kind::Number),
+        (crate::regex![item('"'), none_of[item('"')]*, item('"')], Kind::Text)
+    ];
+    let lexer = nodes.iter().fold(Lexer::new(), |lexer, (regex, kind)| lexer.with(LexerNode::new(regex.clone(), *kind)));
+
+    // Everything generated for a node lexes into one token of that node
+    let mut rng = Rng::new(2024);
+    for (regex, kind) in &nodes{
+        for _ in 0..50{
+            let literal = regex.generate(&mut rng, &universe, 5).unwrap();
+            assert!(literal.len() <= 13, "{literal:?}");
+
+            let LexingResult::Ok(tokens) = lexer.tokenize_content(literal.iter().collect(), "generated") else { panic!("{literal:?} doesn't lex") };
+            assert!(tokens.len() == 1 && tokens[0].kind == *kind, "{literal:?}");
+        }
+    }
+
+    // The same seed gives the same symbols
+    let draw = |seed| (0..5).map(|_| nodes[2].0.generate(&mut Rng::new(seed), &universe, 3)).collect::<Vec<Option<Vec<char>>>>();
+    assert_eq!(draw(7), draw(7));
+
+    // Assertions are honored, and patterns that can't be matched from the universe give nothing
+    let boundary = crate::regex![item('a')*, ahead(item('b')), set('a'..='c')];
+    assert_eq!(boundary.generate(&mut rng, &['a', 'b'], 3).map(|symbols| symbols.last().copied()), Some(Some('b')));
+    assert_eq!(crate::regex![set('x'..='z')].generate(&mut rng, &universe, 3), None);
+    assert_eq!(ChrRegex::new().generate(&mut rng, &universe, 3), None);
+}
+
+#[test]
+fn derivatives(){
+    use crate::{fuzz::Rng, regex::Engine};
+
+    let regexes = [
+        crate::regex![any_of[item('a'), group[item('a'), item('b')]]+],
+        crate::regex![group[item('a'), item('b')?]+, item('b')],
+        crate::regex![set('a'..='c')+, item('c')],
+        crate::regex![item('a')*],
+        crate::regex![item('a'){2, 3}, item('b')?],
+        crate::regex![item('"'), none_of[item('"')]*, item('"')],
+        crate::regex![item('a'), item('b')].or(crate::regex![item('a'), item('b'), item('c')]),
+        crate::regex![group[item('a')*]*, item('b')]
+    ];
+
+    // The derivatives accept the same sequences as the compiled automaton and as backtracking
+    let mut rng = Rng::new(3528);
+    for regex in regexes{
+        let dfa = regex.compile();
+        let derivatives = regex.clone().with_engine(Engine::Derivatives);
+        assert_eq!(derivatives.engine(), Engine::Derivatives);
+
+        for _ in 0..200{
+            let candidate = (0..rng.below(8)).map(|_| *rng.pick(&['a', 'b', 'c', '"']).unwrap()).collect::<Vec<char>>();
+
+            assert_eq!(derivatives.r#match(&candidate), dfa.r#match(&candidate), "{regex:?} on {candidate:?}");
+            assert_eq!(derivatives.r#match(&candidate), regex.r#match(&candidate), "{regex:?} on {candidate:?}");
+            assert_eq!(derivatives.split_first(&candidate), regex.split_first(&candidate), "{regex:?} on {candidate:?}");
+            assert_eq!(regex.match_iter(candidate.iter().cloned()), dfa.r#match(&candidate), "{regex:?} on {candidate:?}");
+        }
+    }
+
+    // An empty pattern matches nothing, as by backtracking
+    assert!(!Regex::<char>::new().with_engine(Engine::Derivatives).r#match(&[]));
+
+    // No backtracking blow-up on nested quantifiers
+    let nested = crate::regex![group[item('a')+]+, item('b')].with_engine(Engine::Derivatives);
+    assert!(!nested.r#match(&['a'; 64]));
+
+    // Finds keep backtracking, as the prefix they keep depends on the order of the alternatives
+    assert_eq!(nested.find_all(&"aaac ab".chars().collect::<Vec<char>>()).map(|m| (m.start, m.length)).collect::<Vec<(usize, usize)>>(), vec![(5, 2)]);
+
+    // Assertions are still matched by backtracking
+    let anchored = Regex::new().then(RegexElement::StartOfInput).then(RegexElement::Item('a', Quantifier::OneOrMany)).with_engine(Engine::Derivatives);
+    assert_eq!(anchored.split_first(&['a', 'a', 'b']), (&['a', 'a'][..], &['b'][..]));
+    assert!(anchored.split_at_match(&['a', 'a'], 1).0.is_empty());
+    assert!(anchored.match_iter("aa".chars()));
+}
+
+#[test]
+fn display(){
+    let word = crate::regex![set('a'..='z')+].with_name("word");
+    let regex = crate::regex![
+        start(),
+        capture("sign", any_of[item('-'), item('+')]?),
+        named("digits", class['0'..='9', '_']{2,}),
+        none_of[item('"'), item('\\')]*?,
+        group[item('.'), set('0'..='9'){1, 3}]??,
+        pattern(word.clone().or(crate::regex![item('x')]))+,
+        not_ahead(item('e')),
+        behind(item('f')),
+        predicate(|c:&char| c.is_alphabetic())+?,
+        boundary(|c:&char| c.is_alphanumeric()),
+        end()
+    ];
+
+    assert_eq!(
+        regex.to_string(),
+        r#"^ (?<sign>('-' | '+')?) (?#digits)['0'-'9' '_']{2,} [^'"' '\\']*? ('.' ['0'-'9']{1,3})?? (['a'-'z']+ | 'x')+ (?!'e') (?<='f') \p+? \b $"#
+    );
+    assert_eq!(Regex::<char>::new().to_string(), "");
+
+    // Regexes can be cloned and compared
+    assert_eq!(word.clone(), word);
+    assert_ne!(word.clone().with_name("other"), word);
+}
+
+#[test]
+fn match_many(){
+    use crate::{fuzz::Rng, regex::Engine};
+
+    let regex = crate::regex![class['a'..='z', '_'], class['a'..='z', '_', '0'..='9']*];
+    let mut rng = Rng::new(3530);
+
+    let literals = (0..2000)
+        .map(|_| (0..rng.below(6)).map(|_| *rng.pick(&['a', 'z', '_', '0', '9', '-']).unwrap()).collect::<Vec<char>>())
+        .collect::<Vec<Vec<char>>>();
+    let candidates = literals.iter().map(Vec::as_slice).collect::<Vec<&[char]>>();
+
+    // Same results in the same order as one match at a time, whatever the engine
+    let expected = candidates.iter().map(|candidate| regex.r#match(candidate)).collect::<Vec<bool>>();
+    assert_eq!(regex.match_many(&candidates), expected);
+    assert_eq!(regex.clone().with_engine(Engine::Derivatives).match_many(&candidates), expected);
+
+    assert!(expected.contains(&true) && expected.contains(&false));
+    assert_eq!(regex.match_many(&[]), Vec::<bool>::new());
+}
+
+#[test]
+fn anchored_match(){
+    let chars = |text:&str| text.chars().collect::<Vec<char>>();
+    let once = Quantifier::Exactly(1);
+
+    // Lazy elements at the end take as many symbols as the full match needs
+    let cases = [
+        (Regex::new().then(RegexElement::Item('a', Quantifier::OneOrManyLazy)), "aaa", true),
+        (Regex::new().then(RegexElement::Item('a', Quantifier::ZeroOrManyLazy)), "aa", true),
+        (Regex::new().then(RegexElement::Item('a', Quantifier::ZeroOrOneLazy)), "a", true),
+        (Regex::new().then(RegexElement::Item('b', once)).then(RegexElement::Group(vec![RegexElement::Item('a', Quantifier::OneOrManyLazy)], once)), "baa", true),
+        (Regex::new().then(RegexElement::Item('a', Quantifier::OneOrManyLazy)), "aab", false),
+
+        // An alternative that is a prefix of the next one is given back
+        (Regex::new().then(RegexElement::AnyOf(vec![
+            RegexElement::Item('a', once),
+            RegexElement::Group(vec![RegexElement::Item('a', once), RegexElement::Item('b', once)], once)
+        ], once)), "ab", true),
+        (Regex::new().then(RegexElement::SubPattern(crate::regex![item('a')].or(crate::regex![item('a'), item('b')]), once)).then(RegexElement::Item('c', once)), "abc", true),
+
+        // An invalid occurence of an optional group doesn't consume anything
+        (Regex::new().then(RegexElement::Item('1', once)).then(RegexElement::Group(vec![RegexElement::Item('.', once), RegexElement::Item('2', once)], Quantifier::ZeroOrOne)), "1.", false),
+        (Regex::new().then(RegexElement::Set('0', '9', Quantifier::OneOrMany)).then(RegexElement::Group(vec![RegexElement::Item('.', once), RegexElement::Set('0', '9', Quantifier::OneOrMany)], Quantifier::ZeroOrOne)), "12.", false)
+    ];
+
+    for (regex, candidate, expected) in cases{
+        assert_eq!(regex.r#match(&chars(candidate)), expected, "{regex} on {candidate}");
+        assert_eq!(regex.compile().r#match(&chars(candidate)), expected, "{regex} on {candidate}");
+    }
+
+    // Splitting still takes the fewest occurences of a lazy element
+    let lazy = Regex::new().then(RegexElement::Item('a', Quantifier::OneOrManyLazy));
+    assert_eq!(lazy.split_first(&chars("aaa")).0, &['a']);
+}
+
+// Asserts that both engines give the same full matches on every sequence of symbols of the *alphabet*
+// up to a length, then on longer random ones
+fn engines_agree<T:crate::regex::Symbol>(regex:&Regex<T>, alphabet:&[T], rng:&mut crate::fuzz::Rng){
+    let derivatives = regex.clone().with_engine(crate::regex::Engine::Derivatives);
+    let check = |candidate:&[T]| {
+        assert_eq!(derivatives.r#match(candidate), regex.r#match(candidate), "{regex} on {candidate:?}");
+        assert_eq!(regex.match_iter(candidate.iter().cloned()), regex.r#match(candidate), "{regex} on {candidate:?}");
+    };
+
+    let mut sequences = vec![vec![]];
+    while sequences.len() * alphabet.len() <= 2048 && sequences[0].len() < 8{
+        sequences.iter().for_each(|candidate| check(candidate));
+        sequences = sequences.iter().flat_map(|candidate| alphabet.iter().map(move |symbol| [candidate.clone(), vec![symbol.clone()]].concat())).collect();
+    }
+    sequences.iter().for_each(|candidate| check(candidate));
+
+    for _ in 0..200{
+        let candidate = (0..rng.below(16)).map(|_| rng.pick(alphabet).unwrap().clone()).collect::<Vec<T>>();
+        check(&candidate);
+    }
+}
+
+#[test]
+fn engine_equivalence(){
+    use RegexElement::*;
+
+    let once = Quantifier::Exactly(1);
+    let word = |c:&char| c.is_alphanumeric();
+    let alphabetic = SymbolPredicate::new(|c:&char| c.is_alphabetic());
+
+    let number = crate::regex![set('0'..='9')+, group[item('.'), set('0'..='9')+]?];
+    let identifier = crate::regex![class['a'..='z', '_'], class['a'..='z', '_', '0'..='9']*];
+    let keyword = crate::regex![item('i'), item('f')];
+    let float = crate::regex![set('0'..='9')*, item('.'), capture("fraction", set('0'..='9')+)];
+    let ident_start = crate::regex![set('a'..='z')].or(crate::regex![item('_')]);
+    let assignment = crate::regex![set('a'..='z')+, item('='), set('0'..='9')+];
+    let stem = crate::regex![set('a'..='z')+, item('e')];
+
+    // The patterns of the tests above, with the symbols to build candidates from,
+    // the fixed widths being shortened for the candidates to reach them
+    let regexes = [
+        (crate::regex![item('@')?, item('>')+, item('i')*, item('.')], "@>i."),
+        (crate::regex![group[item('a')+, item('b')]], "ab"),
+        (crate::regex![group[item('a')+, item('b')]+], "ab1"),
+        (crate::regex![group[item('-')?, set('0'..='9')+]?, group[item('.'), set('0'..='9')+]?], "-.05"),
+        (crate::regex![set('a'..='z')+, group[item('_'), set('a'..='z')+]*], "a_z-"),
+        (crate::regex![
+            set('a'..='z'),
+            group[any_of[set('a'..='z'), set('0'..='9')]]+,
+            group[item('.'), group[any_of[set('a'..='z'), set('0'..='9')]]+]*,
+            item('@'), set('a'..='z')+, item('.'), set('a'..='z')+
+        ], "a0.@"),
+        (crate::regex![none_of[set('a'..='z'), set('A'..='Z')]+], "aZ-1"),
+        (crate::regex![item('-')?, set('0'..='9')+], "-0."),
+        (crate::regex![group[any_of[set('0'..='9'), set('a'..='f')]]{4}], "0fg"),
+        (crate::regex![item('0'){4}], "01"),
+        (crate::regex![item('-')?, set('0'..='9')+, group[item('.'), set('0'..='9')+]?], "-0.a"),
+        (crate::regex![any_of[set('a'..='z'), item('_')], none_of[item(' '), set('('..='+')]*], "a_ (é"),
+        (crate::regex![item('a')*, item('a')], "ab"),
+        (crate::regex![group[item('a')]*, item('a')], "ab"),
+        (crate::regex![any_of[item('a'), group[item('a'), item('b')]]], "ab"),
+        (crate::regex![item('#'), set('0'..='9'){2, 4}], "#1"),
+        (crate::regex![item('a'){3,}], "ab"),
+        (crate::regex![group[item('a'), item('b')]{1, 2}], "ab"),
+        (ChrRegex::new().then(Item('a', Quantifier::Between(2, 1))), "a"),
+        (crate::regex![capture("key", set('a'..='z')+), group[item('='), capture("value", set('0'..='9')+)]?], "a=1;"),
+        (crate::regex![group[capture("letter", set('a'..='z'))]+, any_of[capture("digit", set('0'..='9')), capture("dot", item('.'))]], "a1."),
+        (crate::regex![item('/'), item('*'), set('\0'..=char::MAX)*?, item('*'), item('/')], "/*a"),
+        (crate::regex![set('a'..='z')+?], "ab"),
+        (crate::regex![item('a')??], "ab"),
+        (crate::regex![group[capture("key", set('a'..='z')+?), item('s')]], "as!"),
+        (crate::regex![capture("stem", set('a'..='z')+), item('e')], "aez"),
+        (crate::regex![group[set('0'..='9'){2, 4}, set('0'..='9'){2}], item('.')?], "1."),
+        (crate::regex![none_of[item(' ')]*, set('0'..='9'){2,}], "v1 "),
+        (crate::regex![set('0'..='9')+].or(float.clone()).or(crate::regex![set('a'..='z')+]), "1.a+"),
+        (crate::regex![set('0'..='9')+].or(float).or(crate::regex![set('a'..='z')+]).then(Item(';', once)), "1.a;"),
+        (crate::regex![group[capture("stem", set('a'..='z')+)], item('e')], "aez"),
+        (crate::regex![group[item('a'), item('b')]*, item('a'), item('b')], "ab"),
+        (crate::regex![any_of[item('a'), group[item('a'), item('b')]], item('c')], "abc"),
+        (crate::regex![group[capture("first", item('a')), item('b')]?, item('a'), item('b')], "ab"),
+        (crate::regex![group[set('a'..='z')+, item(' ')]*, set('a'..='z')+, item(' ')], "ab "),
+        (crate::regex![
+            pattern(ident_start.clone()),
+            any_of[pattern(ident_start), pattern(crate::regex![set('0'..='9')])],
+            pattern(crate::regex![capture("rest", set('a'..='z')+)])?
+        ], "a_1"),
+        (crate::regex![pattern(crate::regex![item('i')].or(keyword.clone()))+], "if!"),
+        (crate::regex![pattern(crate::regex![set('0'..='9')]){2,}], "1a"),
+        (crate::regex![pattern(ChrRegex::new())?, item('a')], "ab"),
+        (assignment.clone(), "a=1;"),
+        (stem.clone(), "aez"),
+        (stem.or(assignment), "ae=1"),
+        (ChrRegex::new()
+            .then(Predicate(alphabetic.clone(), Quantifier::OneOrMany))
+            .then(Set('0', '9', Quantifier::ZeroOrMany))
+            .then(NoneOf(vec![Item('x', once), Predicate(alphabetic.clone(), once)], Quantifier::ZeroOrOne)), "λx1_"),
+        (crate::regex![class['a'..='z', 'A'..='Z', '_'], class['a'..='z', 'A'..='Z', '_', '0'..='9']*], "aZ_9-"),
+        (crate::regex![item('a'), item('b')+], "abx"),
+        (crate::regex![item('a')*], "ab"),
+        (crate::regex![set('0'..='9')+], "1+"),
+        (crate::regex![class['a'..='z', 'A'..='Z', '_'], class['a'..='z', '0'..='9', '_']*?], "aZ9_"),
+        (crate::regex![item('0'), any_of[item('x'), item('X')], capture("digits", set('0'..='9'){1, 8}), none_of[item(';')]{2,}], "0xX9;"),
+        (identifier.clone(), "a_0-"),
+        (keyword.clone(), "ifx"),
+        (crate::regex![set('0'..='9'), group[set('0'..='9')]*?], "0a"),
+        (crate::regex![class['0'..='4', '5'..='9']+], "09a"),
+        (crate::regex![set('0'..='9')*], "0a"),
+        (crate::regex![none_of[item('a'), item('c')]], "abc"),
+        (crate::regex![predicate(|c:&char| c.is_alphabetic())+], "a7"),
+        (crate::regex![none_of[item('7')]], "a7"),
+        (crate::regex![set('0'..='9')+, group[item('.'), set('0'..='9')+]?, group[item('e'), set('0'..='9')+]?], "1.e"),
+        (crate::regex![start(), set('0'..='9')+, end()], "1a"),
+        (crate::regex![boundary(word), item('t'), item('h'), item('e'), boundary(word)], "the "),
+        (crate::regex![set('a'..='z')+, any_of[end(), item(';')]], "a; "),
+        (crate::regex![group[set('a'..='z')+, boundary(word), item(' ')?]+, end()], "a !"),
+        (crate::regex![item('/'), not_ahead(item('/'))], "/ "),
+        (crate::regex![set('0'..='9')+, ahead(group[item('k'), item('g')])], "1kg"),
+        (crate::regex![not_behind(set('a'..='z')), item('-'), set('a'..='z')], "a- "),
+        (crate::regex![behind(group[item('a'), item('-')]), set('a'..='z')+], "a-b"),
+        (crate::regex![item('S'), item('e'), item('l')].or(crate::regex![item('f'), item('r')]).case_insensitive(true), "SeLFr"),
+        (crate::regex![set('a'..='z'), set('0'..='9')*].case_insensitive(true), "aZ0"),
+        (crate::regex![none_of[item('x')]+].case_insensitive(true), "aXx"),
+        (ChrRegex::new().then(Predicate(SymbolPredicate::new(|c:&char| c.is_uppercase()), Quantifier::OneOrMany)).case_insensitive(true), "aB1"),
+        (crate::regex![set('0'..='9')*, item('9')], "19"),
+        (number.clone().or(crate::regex![set('a'..='z')+]), "1.a"),
+        (crate::regex![item('"')], "\"a"),
+        (crate::regex![group[item('a')*, item('b')]+], "ab"),
+        (crate::regex![group[item('a')*]{3}], "ab"),
+        (crate::regex![group[item('a')*, item('b')?]{2}], "ab"),
+        (crate::regex![group[item('a')*]*, item('b')], "ab"),
+        (crate::regex![group[group[item('a')*]*]*], "ab"),
+        (crate::regex![group[item('a')?]{2,5}], "ab"),
+        (crate::regex![group[item('a'), item('b')?]*], "ab"),
+        (crate::regex![any_of[set('a'..='z'), item('_')], any_of[set('a'..='z'), set('0'..='9'), item('_')]*], "a_2-"),
+        (crate::regex![any_of[set('0'..='9'), set('a'..='f')]{2,4}], "0fg"),
+        (crate::regex![item('-'), any_of[item('+'), item('-')]?, set('0'..='9')+], "-+1"),
+        (crate::regex![any_of[item('a'), group[item('a'), item('b')]]+], "ab"),
+        (crate::regex![any_of[capture("digit", set('0'..='9')), item('.')]*], "1.a"),
+        (crate::regex![pattern(crate::regex![set('0'..='9')+].with_name("digits")), named("fraction", group[item('.'), set('0'..='9')+]?)], "1.x"),
+        (crate::regex![group[item('a'), item('b')?]+, item('b')], "ab"),
+        (crate::regex![set('a'..='z')+, item('e')], "ae!"),
+        (crate::regex![item('"'), none_of[item('"')]*, item('"')], "\"a"),
+        (crate::regex![item('i'), item('f')].or(crate::regex![item('i'), item('n'), item('t')]), "ifnt"),
+        (crate::regex![item('a')].or(crate::regex![item('a'), item('b'), item('c')]), "abc"),
+        (crate::regex![item('a')*, ahead(item('b')), set('a'..='c')], "abc"),
+        (crate::regex![set('a'..='c')+, item('c')], "abc"),
+        (crate::regex![item('a'){2, 3}, item('b')?], "ab"),
+        (crate::regex![item('a'), item('b')].or(crate::regex![item('a'), item('b'), item('c')]), "abc"),
+        (crate::regex![group[item('a')+]+, item('b')], "ab"),
+        (crate::regex![item('b'), group[item('a')+?]], "ab"),
+        (crate::regex![item('1'), group[item('.'), item('2')]?], "1.2"),
+        (crate::regex![pattern(crate::regex![item('a')].or(crate::regex![item('a'), item('b')])), item('c')], "abc"),
+        (ChrRegex::new(), "a")
+    ];
+
+    let mut rng = crate::fuzz::Rng::new(3528);
+    for (regex, alphabet) in &regexes{
+        engines_agree(regex, &alphabet.chars().collect::<Vec<char>>(), &mut rng);
+    }
+
+    // Over other symbols too
+    engines_agree(&crate::regex![{Item(b'#', Quantifier::OneOrMany)}, pattern(crate::regex![set(b'a'..=b'f')]){3}, predicate(|b:&u8| b.is_ascii_whitespace())??], b"#a ", &mut rng);
+}
+
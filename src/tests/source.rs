@@ -0,0 +1,103 @@
+use crate::{lexer::Location, source::*};
+
+fn location(line:usize, column:usize) -> Location{
+    Location{ file: "main.txt".to_string(), line, column }
+}
+
+#[test]
+fn offset_mapping(){
+    let source = Source::new("main.txt", "ab\r\nçd\n\nlast".to_string());
+
+    assert_eq!(source.line_count(), 4);
+    assert_eq!(source.line(0), Some("ab"));
+    assert_eq!(source.line(1), Some("çd"));
+    assert_eq!(source.line(2), Some(""));
+    assert_eq!(source.line(3), Some("last"));
+    assert_eq!(source.line(4), None);
+
+    assert_eq!(source.offset_to_location(0), Some(location(0, 0)));
+    assert_eq!(source.offset_to_location(4), Some(location(1, 0)));
+    assert_eq!(source.offset_to_location(5), None, "inside 'ç'");
+    assert_eq!(source.offset_to_location(6), Some(location(1, 1)));
+    assert_eq!(source.offset_to_location(8), Some(location(2, 0)));
+    assert_eq!(source.offset_to_location(13), Some(location(3, 4)));
+    assert_eq!(source.offset_to_location(14), None);
+
+    for offset in [0, 1, 4, 6, 7, 8, 9, 13]{
+        let location = source.offset_to_location(offset).unwrap();
+        assert_eq!(source.location_to_offset(&location), Some(offset));
+    }
+
+    assert_eq!(source.location_to_offset(&location(0, 10)), None);
+    assert_eq!(source.location_to_offset(&location(7, 0)), None);
+
+    // The line breaks aren't part of the lines, even CRLF
+    assert_eq!(source.location_to_offset(&location(0, 2)), Some(2));
+    assert_eq!(source.location_to_offset(&location(0, 3)), None);
+    assert_eq!(source.location_to_offset(&location(1, 3)), None);
+    assert_eq!(source.location_to_offset(&location(2, 1)), None);
+    assert_eq!(source.location_to_offset(&location(3, 5)), None);
+    assert_eq!(source.offset_to_location(2), Some(location(0, 2)));
+}
+
+#[test]
+fn manager(){
+    let mut manager = SourceManager::new();
+    manager.add(Source::new("main.txt", "a\nb".to_string()));
+
+    assert_eq!(manager.offset_to_location("main.txt", 2), Some(location(1, 0)));
+    assert_eq!(manager.location_to_offset(&location(1, 1)), Some(3));
+    assert_eq!(manager.offset_to_location("other.txt", 0), None);
+    assert!(manager.load("does/not/exist.txt").is_err());
+}
+
+#[test]
+fn line_endings(){
+    use crate::Reportable;
+
+    let content = "a\r\nb\rc\n\r\nd";
+    assert_eq!(lines(content).collect::<Vec<_>>(), vec!["a", "b", "c", "", "d"]);
+    assert_eq!(lines("a\r").collect::<Vec<_>>(), vec!["a"]);
+    assert_eq!(lines("").count(), 0);
+
+    let source = Source::new("main.txt", content.to_string());
+    assert_eq!(source.line_count(), 5);
+    assert_eq!(source.line(1), Some("b"));
+    assert_eq!(source.line(3), Some(""));
+    assert_eq!(source.offset_to_location(5), Some(location(2, 0)));
+    assert_eq!(source.location_to_offset(&location(4, 0)), Some(9));
+
+    assert_eq!(source.line_ending(), Some(LineEnding::CrLf));
+
+    let warning = source.check_line_endings().unwrap();
+    assert_eq!(warning, MixedLineEndings{ expected: LineEnding::CrLf, found: LineEnding::Cr, location: location(1, 1) });
+    assert_eq!(warning.message(), "Mixed line endings, CR found where CRLF was used before");
+
+    assert_eq!(Source::new("main.txt", "a\r\nb\r\n".to_string()).check_line_endings(), None);
+    assert_eq!(Source::new("main.txt", "a".to_string()).line_ending(), None);
+}
+
+#[test]
+fn reports_from_sources(){
+    use crate::{build_report_in, build_report_with, ReportStyle};
+
+    let style = ReportStyle::new();
+    let location = |file:&str, line| Location { file: file.to_string(), line, column: 1 };
+
+    // Sources unknown to the file system are rendered from the manager
+    let mut sources = SourceManager::new();
+    sources.add(Source::new("virtual.ng", String::from("let a\r\nlet b")));
+    assert_eq!(build_report_in("Oops", location("virtual.ng", 1), &style, &sources), "Oops at virtual.ng 1:1\nlet b\n ^^^^");
+    assert_eq!(build_report_in("Oops", location("virtual.ng", 2), &style, &sources), "Oops at virtual.ng 2:1");
+
+    // The others are read from the disk, up to the needed line
+    let path = std::env::temp_dir().join("neoglot_reports_from_sources.txt");
+    std::fs::write(&path, "first\rsecond\r\nthird\nfourth").unwrap();
+    let file = path.to_str().unwrap();
+
+    assert_eq!(build_report_in("Oops", location(file, 2), &style, &sources), format!("Oops at {file} 2:1\nthird\n ^^^^"));
+    assert_eq!(build_report_with("Oops", location(file, 1), &style), format!("Oops at {file} 1:1\nsecond\n ^^^^^"));
+    assert_eq!(build_report_with("Oops", location(file, 4), &style), format!("Oops at {file} 4:1"));
+
+    std::fs::remove_file(path).unwrap();
+}
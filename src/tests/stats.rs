@@ -0,0 +1,44 @@
+use crate::{lexer::*, parser::*, regex::*, stats::TransitionStats};
+
+#[derive(PartialEq, PartialOrd, Eq, Hash, Copy, Clone, Debug)]
+enum Kind{ Name, Number, Plus, Semicolon }
+
+impl Symbol for Kind{}
+impl TokenKind for Kind{}
+
+#[test]
+fn transition_stats(){
+    let lexer = Lexer::new()
+        .with(LexerNode::new(Regex::new().then(RegexElement::Set('a', 'z', Quantifier::OneOrMany)), Kind::Name))
+        .with(LexerNode::new(Regex::new().then(RegexElement::Set('0', '9', Quantifier::OneOrMany)), Kind::Number))
+        .with(LexerNode::new(Regex::new().then(RegexElement::Item('+', Quantifier::Exactly(1))), Kind::Plus))
+        .with(LexerNode::new(Regex::new().then(RegexElement::Item(';', Quantifier::Exactly(1))), Kind::Semicolon));
+
+    let operand = || RegexElement::AnyOf(vec![RegexElement::Item(Kind::Name, Quantifier::Exactly(1)), RegexElement::Item(Kind::Number, Quantifier::Exactly(1))], Quantifier::Exactly(1));
+    let node = |regex:Regex<Kind>| Box::new(ParserNode { regex, parser: Box::new(|_| Ok(AST { kind: Kind::Name, children: vec![] })) });
+    let nodes = vec![
+        node(Regex::new().then(operand()).then(RegexElement::Group(vec![RegexElement::Item(Kind::Plus, Quantifier::Exactly(1)), operand()], Quantifier::OneOrMany))),
+        node(Regex::new().then(RegexElement::Item(Kind::Semicolon, Quantifier::Exactly(1))))
+    ];
+
+    let mut stats = TransitionStats::new();
+    for content in ["a + 1; b + c + 2;", "+ x; 3"]{
+        let LexingResult::Ok(tokens) = lexer.tokenize_content(content.to_string(), "corpus") else { panic!("lexing failed") };
+        stats.add_parsed(&tokens, &nodes);
+    }
+
+    assert_eq!(stats.files(), 2);
+    assert_eq!(stats.kinds(), vec![(Kind::Name, 4), (Kind::Plus, 4), (Kind::Number, 3), (Kind::Semicolon, 3)]);
+    assert_eq!(stats.kind_count(Kind::Plus), 4);
+    assert_eq!(stats.count(Kind::Name, Kind::Plus), 3);
+    assert_eq!(stats.count(Kind::Semicolon, Kind::Plus), 0);
+    assert_eq!(stats.followers(Kind::Plus), vec![(Kind::Name, 2), (Kind::Number, 2)]);
+
+    // The pairs between two statements, or in tokens no node matches, aren't consumed by any node
+    assert_eq!(stats.rules(Kind::Name, Kind::Plus), vec![(0, 3)]);
+    assert_eq!(stats.rules(Kind::Plus, Kind::Number), vec![(0, 2)]);
+    assert_eq!(stats.rules(Kind::Plus, Kind::Name), vec![(0, 1)]);
+    assert_eq!(stats.rules(Kind::Number, Kind::Semicolon), vec![]);
+
+    assert!(stats.to_string().starts_with("Name -> Plus: 3 (node 0: 3)\nNumber -> Semicolon: 2\n"));
+}
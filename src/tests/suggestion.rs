@@ -0,0 +1,26 @@
+use crate::suggestion::*;
+
+#[test]
+fn distances(){
+    assert_eq!(levenshtein("", ""), 0);
+    assert_eq!(levenshtein("abc", ""), 3);
+    assert_eq!(levenshtein("", "abc"), 3);
+    assert_eq!(levenshtein("kitten", "sitting"), 3);
+    assert_eq!(levenshtein("flaw", "lawn"), 2);
+    assert_eq!(levenshtein("été", "ete"), 2);
+}
+
+#[test]
+fn suggestions(){
+    let keywords = ["while", "for", "if", "else", "return", "when"];
+
+    assert_eq!(suggest("whle", keywords), vec!["while"]);
+    assert_eq!(suggest("whn", keywords), vec!["when"]);
+    assert_eq!(suggest("retrun", keywords), vec!["return"]);
+    assert_eq!(suggest("iff", keywords), vec!["if"]);
+    assert_eq!(suggest("while", keywords), Vec::<&str>::new());
+    assert_eq!(suggest("loop", keywords), Vec::<&str>::new());
+
+    assert_eq!(did_you_mean("elze", keywords), Some("did you mean `else`?".to_string()));
+    assert_eq!(did_you_mean("xyz", keywords), None);
+}
@@ -0,0 +1,51 @@
+use crate::{lexer::*, regex::*, source::Source, suite::*, Reportable};
+
+#[derive(PartialEq, PartialOrd, Eq, Hash, Copy, Clone, Debug)]
+enum TokenType{ Number, Comment }
+
+impl Symbol for TokenType{}
+impl TokenKind for TokenType{}
+
+#[test]
+fn test_suite(){
+    let suite = TestSuite::new();
+
+    let expectations = suite.expectations("a\nb //~ ERROR Bad b\nc\n//~^^ WARNING\n//~\n//~^^^^^^ ERROR Above the file");
+    assert_eq!(expectations, vec![
+        Expectation{ line: 1, kind: "ERROR".to_string(), message: "Bad b".to_string() },
+        Expectation{ line: 1, kind: "WARNING".to_string(), message: String::new() }
+    ]);
+
+    assert_eq!(TestSuite::new().with_marker("#!").expectations("x #! ERROR x //~ WARNING")[0].kind, "ERROR");
+
+    let lexer = Lexer::new()
+        .with(LexerNode::new(Regex::new().then(RegexElement::Set('0', '9', Quantifier::OneOrMany)), TokenType::Number))
+        .with(LexerNode::new(Regex::new()
+            .then(RegexElement::Item('/', Quantifier::Exactly(2)))
+            .then(RegexElement::NoneOf(vec![RegexElement::Item('\n', Quantifier::Exactly(1))], Quantifier::ZeroOrMany)), TokenType::Comment));
+
+    let lex = |source:&Source| match lexer.tokenize_content(source.content().to_string(), source.path()) {
+        LexingResult::Ok(_) => vec![],
+        LexingResult::Err(errors) => errors.iter().map(Diagnostic::error).collect()
+    };
+
+    // A missing and an unexpected diagnostic
+    let source = Source::new("main.ng", "1 //~ ERROR Failed\nx //~ WARNING Failed".to_string());
+    let mismatches = suite.check(&source, lex(&source));
+
+    assert_eq!(mismatches.len(), 3);
+    assert!(matches!(&mismatches[0], Mismatch::Missing{ expectation, .. } if expectation.line == 0));
+    assert!(matches!(&mismatches[1], Mismatch::Missing{ expectation, .. } if expectation.kind == "WARNING"));
+    assert!(matches!(&mismatches[2], Mismatch::Unexpected(diagnostic) if diagnostic.location.as_ref().map(|l| l.line) == Some(1)));
+    assert_eq!(mismatches[0].message(), "Expected ERROR `Failed` was not emitted");
+
+    // Only the files with the extension are run, in path order
+    let report = suite.run("src/tests/suite", "ng", lex).unwrap();
+
+    assert_eq!(report.files.iter().map(|(path, _)| path.as_str()).collect::<Vec<&str>>(), vec!["src/tests/suite/fail.ng", "src/tests/suite/pass.ng"]);
+    assert!(!report.passed());
+    assert_eq!(report.failed().collect::<Vec<&str>>(), vec!["src/tests/suite/fail.ng"]);
+    assert_eq!(report.mismatches().count(), 2);
+
+    assert!(suite.run("src/tests/missing", "ng", |_| vec![]).is_err());
+}
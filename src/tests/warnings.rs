@@ -0,0 +1,64 @@
+use crate::{config::LanguageConfig, lexer::Location, pipeline::Pipeline, source::Source, suite::Diagnostic, warnings::*, Reportable};
+
+struct Lint(&'static str, usize);
+
+impl Reportable for Lint{
+    fn message(&self) -> String{ self.0.to_string() }
+    fn location(&self) -> Option<Location>{ Some(Location { file: "main.ng".to_string(), line: self.1, column: 0 }) }
+}
+
+#[test]
+fn warning_levels(){
+    let categories = WarningCategories::standard().with_category("shadowing", Level::Allow);
+
+    assert_eq!(categories.categories().collect::<Vec<&str>>(), vec!["deprecated", "shadowing", "style", "unused"]);
+    assert_eq!(categories.level("unused"), Some(Level::Warn));
+    assert_eq!(categories.level("shadowing"), Some(Level::Allow));
+    assert_eq!(categories.level("unknown"), None);
+
+    // Overrides win over the defaults, and wait for their category to be registered
+    let categories = categories.with_level("style", Level::Deny).with_level("later", Level::Deny);
+    assert_eq!(categories.level("style"), Some(Level::Deny));
+    assert_eq!(categories.level("later"), None);
+    assert_eq!(categories.clone().with_category("later", Level::Warn).level("later"), Some(Level::Deny));
+
+    let diagnostics = vec![
+        Diagnostic::warning(&Lint("Unused variable `x`", 1)).with_category("unused"),
+        Diagnostic::warning(&Lint("`y` shadows `y`", 2)).with_category("shadowing"),
+        Diagnostic::warning(&Lint("Missing space", 3)).with_category("style"),
+        Diagnostic::error(&Lint("Unknown name `z`", 4)),
+        Diagnostic::warning(&Lint("Custom", 5)).with_category("unknown")
+    ];
+
+    let applied = categories.apply_all(diagnostics.clone());
+    let kinds = applied.iter().map(|d| (d.kind.as_str(), d.message.as_str())).collect::<Vec<(&str, &str)>>();
+    assert_eq!(kinds, vec![
+        ("WARNING", "Unused variable `x`"),
+        ("ERROR", "Missing space"),
+        ("ERROR", "Unknown name `z`"),
+        ("WARNING", "Custom")
+    ]);
+
+    // The pipeline applies the levels to every stage, a denied warning skipping the next stages
+    let emitted = &diagnostics[..3];
+    let stages = |warnings:WarningCategories| Pipeline::new()
+        .with_stage(|_, ran:&mut Vec<usize>| { ran.push(0); emitted.to_vec() })
+        .with_stage(|_, ran| { ran.push(1); vec![] })
+        .with_warnings(warnings);
+    let source = Source::new("main.ng", String::new());
+
+    let denied = stages(categories.clone()).run_file(&source);
+    assert!(denied.failed());
+    assert_eq!((denied.artifact, denied.diagnostics.len()), (vec![0], 2));
+
+    // The user's configuration relaxes the style lints
+    let config = LanguageConfig::parse("warnings.style = allow\nwarnings.unused = deny\n", "lang.cfg").unwrap();
+    assert_eq!(config, LanguageConfig::new().with_warning_level("style", Level::Allow).with_warning_level("unused", Level::Deny));
+    assert_eq!(LanguageConfig::parse(&config.to_string(), "lang.cfg"), Ok(config.clone()));
+
+    let relaxed = stages(config.warning_categories(WarningCategories::standard())).run_file(&source);
+    assert_eq!(relaxed.diagnostics.iter().map(|d| (d.kind.as_str(), d.category.as_deref())).collect::<Vec<(&str, Option<&str>)>>(), vec![("ERROR", Some("unused")), ("WARNING", Some("shadowing"))]);
+
+    assert_eq!(LanguageConfig::parse("warnings.style = loud", "lang.cfg").unwrap_err().message(), "Invalid value `loud` for `warnings.style`");
+    assert_eq!(Level::parse(&Level::Deny.to_string()), Some(Level::Deny));
+}
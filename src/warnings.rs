@@ -0,0 +1,135 @@
+use std::{collections::BTreeMap, fmt::Display};
+
+use crate::suite::Diagnostic;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+/// What is done with the warnings of a category, see [WarningCategories]
+pub enum Level{
+    /// The warnings are dropped
+    Allow,
+
+    /// The warnings are reported as `WARNING`
+    Warn,
+
+    /// The warnings are reported as `ERROR`, failing the compilation
+    Deny
+}
+
+impl Level{
+    /// Reads a level written as by [Display], as `deny`
+    pub fn parse(text:&str) -> Option<Level>{
+        match text {
+            "allow" => Some(Level::Allow),
+            "warn" => Some(Level::Warn),
+            "deny" => Some(Level::Deny),
+            _ => None
+        }
+    }
+}
+
+impl Display for Level{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Level::Allow => "allow",
+            Level::Warn => "warn",
+            Level::Deny => "deny"
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// The registry of the warning categories of a language, with their default level and the levels chosen by the user
+/// 
+/// Every stage tags its warnings with a [category](Diagnostic::with_category), then the registry [applies](WarningCategories::apply)
+/// the levels to all of them the same way, as a [Pipeline](crate::pipeline::Pipeline) does for each of its stages
+/// 
+/// # Exemples
+/// ```rust
+/// use crate::neoglot_lib::{lexer::Location, suite::Diagnostic, warnings::*, Reportable};
+/// 
+/// struct Unused(Location);
+/// 
+/// impl Reportable for Unused{
+///     fn message(&self) -> String{ String::from("Unused variable") }
+///     fn location(&self) -> Option<Location>{ Some(self.0.clone()) }
+/// }
+/// 
+/// let categories = WarningCategories::standard().with_level("unused", Level::Deny);
+/// let warning = Diagnostic::warning(&Unused(Location::generated("doc", 0))).with_category("unused");
+/// 
+/// assert!(categories.apply(warning.clone()).is_some_and(|d| d.is_error()));
+/// assert_eq!(categories.with_level("unused", Level::Allow).apply(warning), None);
+/// ```
+pub struct WarningCategories{
+    /// The level of each category when the user didn't choose one
+    defaults: BTreeMap<String, Level>,
+
+    /// The levels chosen by the user, as in a [configuration](crate::config::LanguageConfig::warnings)
+    overrides: BTreeMap<String, Level>
+}
+
+impl Default for WarningCategories{
+    fn default() -> Self { Self::new() }
+}
+
+impl WarningCategories{
+    /// A registry without any category
+    pub fn new() -> Self{ WarningCategories { defaults: BTreeMap::new(), overrides: BTreeMap::new() } }
+
+    /// The categories most languages have, `unused`, `deprecated` and `style`, all warned about
+    pub fn standard() -> Self{
+        Self::new()
+            .with_category("unused", Level::Warn)
+            .with_category("deprecated", Level::Warn)
+            .with_category("style", Level::Warn)
+    }
+
+    /// Registers the category *name* with its *default* level, builder style
+    pub fn with_category(mut self, name:&str, default:Level) -> Self{
+        self.defaults.insert(name.to_string(), default);
+        self
+    }
+
+    /// Overrides the level of the category *name*, builder style
+    /// 
+    /// The levels of unregistered categories are kept until the category is registered
+    pub fn with_level(mut self, name:&str, level:Level) -> Self{
+        self.overrides.insert(name.to_string(), level);
+        self
+    }
+
+    /// Overrides the levels of the categories with the ones of *levels*, builder style
+    pub fn with_levels<'a>(self, levels:impl IntoIterator<Item = (&'a String, &'a Level)>) -> Self{
+        levels.into_iter().fold(self, |categories, (name, level)| categories.with_level(name, *level))
+    }
+
+    /// The registered categories, in name order
+    pub fn categories(&self) -> impl Iterator<Item = &str>{
+        self.defaults.keys().map(String::as_str)
+    }
+
+    /// The level of the category *name*, None if it isn't registered
+    pub fn level(&self, name:&str) -> Option<Level>{
+        let default = self.defaults.get(name)?;
+        Some(*self.overrides.get(name).unwrap_or(default))
+    }
+
+    /// Applies the level of its category to *diagnostic*: None if it's allowed,
+    /// otherwise the diagnostic as a `WARNING` or as an `ERROR` if it's denied
+    /// 
+    /// The diagnostics without a registered category are kept as they are
+    pub fn apply(&self, diagnostic:Diagnostic) -> Option<Diagnostic>{
+        let Some(level) = diagnostic.category.as_deref().and_then(|category| self.level(category)) else { return Some(diagnostic); };
+
+        match level {
+            Level::Allow => None,
+            Level::Warn => Some(Diagnostic { kind: String::from("WARNING"), ..diagnostic }),
+            Level::Deny => Some(Diagnostic { kind: String::from("ERROR"), ..diagnostic })
+        }
+    }
+
+    /// [Applies](WarningCategories::apply) the levels to every diagnostic, keeping the ones not allowed in order
+    pub fn apply_all(&self, diagnostics:impl IntoIterator<Item = Diagnostic>) -> Vec<Diagnostic>{
+        diagnostics.into_iter().filter_map(|diagnostic| self.apply(diagnostic)).collect()
+    }
+}